@@ -1,7 +1,15 @@
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Value, json};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use crate::agents::{anthropic::AnthropicAgent, ollama::OllamaAgent, openai::OpenAIAgent};
+use crate::agent_config::{DEFAULT_MAX_TOKENS, DEFAULT_REASONING_MAX_TOKENS};
+use crate::cooldown;
+use crate::games::engine::REASONING_FIELD;
+use crate::games::rng::SeededRng;
+use crate::agents::{
+    anthropic::AnthropicAgent, baseline::BaselineAgent, ollama::OllamaAgent, openai::OpenAIAgent, perturbed::PerturbedAgent,
+    replay_checked::ReplayCheckedAgent, scripted::ScriptedAgent, socket::SocketAgent,
+};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MoveRequest {
@@ -9,12 +17,90 @@ pub struct MoveRequest {
     pub game_id: String,
     pub state: Value,
     pub expected_move_schema: Value,
+    /// Every legal move in the position, in the shape of
+    /// `expected_move_schema`, for games that can list them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub legal_moves: Option<Value>,
+}
+
+/// System message every LLM-backed agent sends with a move request
+pub const SYSTEM_PROMPT: &str = "You are a game-playing AI. Respond ONLY with strict JSON matching the expected schema. Do not include any text outside JSON.";
+
+impl MoveRequest {
+    /// User message LLM-backed agents send for this request
+    pub fn user_prompt(&self) -> String {
+        let mut prompt = json!({
+            "turn_index": self.turn_index,
+            "game_id": self.game_id,
+            "state": self.state,
+            "expected_move_schema": self.expected_move_schema,
+        });
+        if let Some(legal_moves) = &self.legal_moves {
+            prompt["legal_moves"] = legal_moves.clone();
+        }
+        prompt.to_string()
+    }
+
+    /// Cap on tokens for the reply when the agent's config sets none:
+    /// larger when the move schema offers `reasoning`, so a model that
+    /// explains its move isn't cut off before the JSON closes
+    pub fn default_max_tokens(&self) -> u32 {
+        if self.expected_move_schema["properties"].get(REASONING_FIELD).is_some() {
+            DEFAULT_REASONING_MAX_TOKENS
+        } else {
+            DEFAULT_MAX_TOKENS
+        }
+    }
+}
+
+/// Tokens a provider reports for one request
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+}
+
+/// The model a provider reports actually answering a request, which can
+/// differ from the one requested when it resolves aliases or versions
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServedModel {
+    pub model: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_fingerprint: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MoveResponse {
     pub chosen_move: Value,
     pub diagnostics: Option<String>,
+    /// Perturbation the arena applied to the state this move answered;
+    /// set locally, never sent over the wire
+    #[serde(skip)]
+    pub perturbation: Option<String>,
+    /// Whether asking again with the same request gave the same move;
+    /// `None` unless the agent was replay-checked
+    #[serde(skip)]
+    pub replay_consistent: Option<bool>,
+    /// Tokens the request consumed, if the provider reports usage
+    #[serde(default)]
+    pub usage: Option<TokenUsage>,
+    /// Model the provider says served the request, if it reports one
+    #[serde(default)]
+    pub served_model: Option<ServedModel>,
+}
+
+impl MoveResponse {
+    /// A bare move with nothing else reported
+    pub fn new(chosen_move: Value) -> Self {
+        MoveResponse {
+            chosen_move,
+            diagnostics: None,
+            perturbation: None,
+            replay_consistent: None,
+            usage: None,
+            served_model: None,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -22,6 +108,11 @@ pub enum AgentError {
     InvalidRequest(String),
     InvalidResponse(String),
     Internal(String),
+    /// A failure that may clear up by itself, like a 5xx or a dropped
+    /// connection; `with_retry` re-sends the request
+    Transient(String),
+    /// The provider's rate limit is exhausted; `retry_after` is its reset window if it sent one
+    RateLimited { retry_after: Option<Duration> },
 }
 
 impl std::fmt::Display for AgentError {
@@ -30,42 +121,94 @@ impl std::fmt::Display for AgentError {
             AgentError::InvalidRequest(msg) => write!(f, "invalid request: {}", msg),
             AgentError::InvalidResponse(msg) => write!(f, "invalid response: {}", msg),
             AgentError::Internal(msg) => write!(f, "internal error: {}", msg),
+            AgentError::Transient(msg) => write!(f, "transient error: {}", msg),
+            AgentError::RateLimited { retry_after: Some(wait) } => {
+                write!(f, "rate limited (retry after {}s)", wait.as_secs())
+            }
+            AgentError::RateLimited { retry_after: None } => write!(f, "rate limited"),
         }
     }
 }
 
 impl std::error::Error for AgentError {}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub type AgentResult<T> = Result<T, AgentError>;
 
-    #[test]
-    fn test_agent_error_display() {
-        let err1 = AgentError::InvalidRequest("test request".to_string());
-        assert_eq!(err1.to_string(), "invalid request: test request");
+/// Tries per request when a provider fails transiently, the first included
+pub const DEFAULT_RETRY_ATTEMPTS: u32 = 3;
 
-        let err2 = AgentError::InvalidResponse("test response".to_string());
-        assert_eq!(err2.to_string(), "invalid response: test response");
+/// Wait before the first retry of a transient failure
+pub const DEFAULT_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
 
-        let err3 = AgentError::Internal("test internal".to_string());
-        assert_eq!(err3.to_string(), "internal error: test internal");
+/// How `with_retry` re-sends a request that failed transiently
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Tries in all, the first included; 1 never retries
+    pub attempts: u32,
+    /// Wait before the first retry, doubled for every later one
+    pub base_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            attempts: DEFAULT_RETRY_ATTEMPTS,
+            base_delay: DEFAULT_RETRY_BASE_DELAY,
+        }
     }
+}
 
-    #[test]
-    fn test_agent_error_error_trait() {
-        let err = AgentError::Internal("test".to_string());
-        // Just verify it implements Error trait
-        let _: &dyn std::error::Error = &err;
+impl RetryPolicy {
+    /// Wait before retry number `retry` (0-based): the base delay doubled
+    /// `retry` times, less a random part of up to half, so agents that failed
+    /// together don't all retry together
+    fn delay(&self, retry: u32, rng: &mut SeededRng) -> Duration {
+        let full = self.base_delay.saturating_mul(1 << retry.min(16));
+        let half = full / 2;
+        half + half.mul_f64(rng.next_below(1001) as f64 / 1000.0)
     }
 }
 
-pub type AgentResult<T> = Result<T, AgentError>;
+/// Run `request` to `model` at `provider`, re-sending it with exponential
+/// backoff while it fails with `AgentError::Transient`, up to
+/// `policy.attempts` tries. Any other error is returned at once. Retries are
+/// counted in the global cooldown scheduler's API retry report.
+pub async fn with_retry<T>(
+    policy: RetryPolicy,
+    provider: &str,
+    model: &str,
+    request: impl AsyncFn() -> AgentResult<T>,
+) -> AgentResult<T> {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().subsec_nanos();
+    let mut rng = SeededRng::new(nanos as u64);
+    let mut retry = 0;
+    loop {
+        match request().await {
+            Err(AgentError::Transient(msg)) if retry + 1 < policy.attempts => {
+                let wait = policy.delay(retry, &mut rng);
+                retry += 1;
+                cooldown::global().count_retry(provider, model);
+                tracing::warn!("Transient error: {}; retrying in {}ms ({}/{})", msg, wait.as_millis(), retry, policy.attempts - 1);
+                tokio::time::sleep(wait).await;
+            }
+            other => return other,
+        }
+    }
+}
 
 pub enum AIAgent {
     OpenAI(OpenAIAgent),
     Anthropic(AnthropicAgent),
     Ollama(OllamaAgent),
+    Socket(SocketAgent),
+    /// Any agent shown a deliberately perturbed state
+    Perturbed(PerturbedAgent),
+    /// Any agent whose every move is requested twice to check determinism
+    ReplayChecked(ReplayCheckedAgent),
+    /// Built-in opponent of known strength, for curricula
+    Baseline(BaselineAgent),
+    /// Plays a fixed list of moves, for deterministic tests
+    Scripted(ScriptedAgent),
 }
 
 impl AIAgent {
@@ -74,14 +217,169 @@ impl AIAgent {
             AIAgent::OpenAI(agent) => agent.name(),
             AIAgent::Anthropic(agent) => agent.name(),
             AIAgent::Ollama(agent) => agent.name(),
+            AIAgent::Socket(agent) => agent.name(),
+            AIAgent::Perturbed(agent) => agent.name(),
+            AIAgent::ReplayChecked(agent) => agent.name(),
+            AIAgent::Baseline(agent) => agent.name(),
+            AIAgent::Scripted(agent) => agent.name(),
+        }
+    }
+
+    /// System message sent with every move request, for agents backed by a
+    /// chat model
+    pub fn system_prompt(&self) -> Option<&str> {
+        match self {
+            AIAgent::OpenAI(agent) => Some(agent.system_prompt()),
+            AIAgent::Anthropic(agent) => Some(agent.system_prompt()),
+            AIAgent::Ollama(agent) => Some(agent.system_prompt()),
+            AIAgent::Perturbed(agent) => agent.system_prompt(),
+            AIAgent::ReplayChecked(agent) => agent.system_prompt(),
+            AIAgent::Socket(_) | AIAgent::Baseline(_) | AIAgent::Scripted(_) => None,
         }
     }
 
+    /// Tell the agent the name of the game it is about to play. Only
+    /// baseline agents use it, to know whose rules the board follows;
+    /// everything else an agent needs comes with each request.
+    pub fn join_game(&mut self, game: &str) {
+        match self {
+            AIAgent::Baseline(agent) => agent.join_game(game),
+            AIAgent::Perturbed(agent) => agent.join_game(game),
+            AIAgent::ReplayChecked(agent) => agent.join_game(game),
+            _ => {}
+        }
+    }
+
+    /// Ask the agent for a move. Rate-limited providers are paused and
+    /// retried through the shared cooldown scheduler.
     pub async fn execute_turn(&self, request: &MoveRequest) -> AgentResult<MoveResponse> {
+        let (provider, model) = match self {
+            AIAgent::OpenAI(agent) => (agent.provider(), agent.model()),
+            AIAgent::Anthropic(agent) => ("Anthropic".to_string(), agent.model()),
+            AIAgent::Ollama(agent) => (agent.provider(), agent.model()),
+            AIAgent::Socket(agent) => return agent.execute_turn(request).await,
+            AIAgent::Baseline(agent) => return agent.execute_turn(request).await,
+            AIAgent::Scripted(agent) => return agent.execute_turn(request).await,
+            // The wrapped agent goes through the scheduler itself
+            AIAgent::Perturbed(agent) => return agent.execute_turn(request).await,
+            AIAgent::ReplayChecked(agent) => return agent.execute_turn(request).await,
+        };
+        tracing::debug!("Requesting turn {} of {} from {} {}", request.turn_index, request.game_id, provider, model);
+        let response = cooldown::global()
+            .call(&provider, model, async || self.request_move(request).await)
+            .await;
+        match &response {
+            Ok(response) => tracing::debug!("{} {} answered {}", provider, model, response.chosen_move),
+            Err(e) => tracing::debug!("{} {} request failed: {}", provider, model, e),
+        }
+        response
+    }
+
+    async fn request_move(&self, request: &MoveRequest) -> AgentResult<MoveResponse> {
         match self {
             AIAgent::OpenAI(agent) => agent.execute_turn(request).await,
             AIAgent::Anthropic(agent) => agent.execute_turn(request).await,
             AIAgent::Ollama(agent) => agent.execute_turn(request).await,
+            AIAgent::Socket(agent) => agent.execute_turn(request).await,
+            AIAgent::Perturbed(agent) => agent.execute_turn(request).await,
+            AIAgent::ReplayChecked(agent) => agent.execute_turn(request).await,
+            AIAgent::Baseline(agent) => agent.execute_turn(request).await,
+            AIAgent::Scripted(agent) => agent.execute_turn(request).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_agent_error_display() {
+        let err1 = AgentError::InvalidRequest("test request".to_string());
+        assert_eq!(err1.to_string(), "invalid request: test request");
+
+        let err2 = AgentError::InvalidResponse("test response".to_string());
+        assert_eq!(err2.to_string(), "invalid response: test response");
+
+        let err3 = AgentError::Internal("test internal".to_string());
+        assert_eq!(err3.to_string(), "internal error: test internal");
+
+        let err4 = AgentError::RateLimited { retry_after: Some(Duration::from_secs(20)) };
+        assert_eq!(err4.to_string(), "rate limited (retry after 20s)");
+    }
+
+    #[tokio::test]
+    async fn test_with_retry_recovers_from_transient_errors() {
+        let calls = std::cell::Cell::new(0);
+        let policy = RetryPolicy { attempts: 3, base_delay: Duration::from_millis(1) };
+        let result = with_retry(policy, "OpenAI", "recovering-model", async || {
+            calls.set(calls.get() + 1);
+            match calls.get() {
+                1 | 2 => Err(AgentError::Transient("503 Service Unavailable".to_string())),
+                _ => Ok("move"),
+            }
+        })
+        .await;
+        assert_eq!(result.unwrap(), "move");
+        assert_eq!(calls.get(), 3);
+
+        // One try too few
+        calls.set(0);
+        let result = with_retry(RetryPolicy { attempts: 2, ..policy }, "OpenAI", "recovering-model", async || {
+            calls.set(calls.get() + 1);
+            Err::<(), _>(AgentError::Transient("503 Service Unavailable".to_string()))
+        })
+        .await;
+        assert!(matches!(result, Err(AgentError::Transient(_))));
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_with_retry_gives_up_on_other_errors() {
+        let calls = std::cell::Cell::new(0);
+        let result = with_retry(RetryPolicy::default(), "OpenAI", "unauthorized-model", async || {
+            calls.set(calls.get() + 1);
+            Err::<(), _>(AgentError::Internal("401 Unauthorized".to_string()))
+        })
+        .await;
+        assert!(matches!(result, Err(AgentError::Internal(_))));
+        assert_eq!(calls.get(), 1);
+        assert_eq!(cooldown::global().retry_counts().get("OpenAI/unauthorized-model"), None);
+    }
+
+    #[tokio::test]
+    async fn test_transient_retries_reach_the_retry_report() {
+        let policy = RetryPolicy { attempts: 3, base_delay: Duration::from_millis(1) };
+        let calls = std::cell::Cell::new(0);
+        let flaky = async || {
+            calls.set(calls.get() + 1);
+            match calls.get() % 3 {
+                0 => Ok(()),
+                _ => Err(AgentError::Transient("502 Bad Gateway".to_string())),
+            }
+        };
+        with_retry(policy, "Ollama", "flaky-5xx-model", &flaky).await.unwrap();
+        with_retry(policy, "Ollama", "flaky-5xx-model", &flaky).await.unwrap();
+
+        assert_eq!(cooldown::global().retry_counts().get("Ollama/flaky-5xx-model"), Some(&4));
+        let report = cooldown::global().retry_report().unwrap().to_string();
+        assert!(report.lines().any(|line| line.contains("Ollama/flaky-5xx-model") && line.contains(" 4 ")));
+    }
+
+    #[test]
+    fn test_retry_delay_doubles_with_jitter() {
+        let policy = RetryPolicy { attempts: 5, base_delay: Duration::from_millis(100) };
+        let mut rng = SeededRng::new(7);
+        for (retry, full) in [(0, 100), (1, 200), (2, 400)] {
+            let delay = policy.delay(retry, &mut rng);
+            assert!(delay >= Duration::from_millis(full / 2) && delay <= Duration::from_millis(full), "{:?}", delay);
         }
     }
+
+    #[test]
+    fn test_agent_error_error_trait() {
+        let err = AgentError::Internal("test".to_string());
+        // Just verify it implements Error trait
+        let _: &dyn std::error::Error = &err;
+    }
 }
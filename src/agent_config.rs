@@ -1,74 +1,382 @@
-use crate::agent::AIAgent;
-use crate::agents::{anthropic::AnthropicAgent, ollama::OllamaAgent, openai::OpenAIAgent};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+use std::time::Duration;
+
+use crate::agent::{AIAgent, DEFAULT_RETRY_ATTEMPTS, DEFAULT_RETRY_BASE_DELAY, RetryPolicy, SYSTEM_PROMPT};
+use crate::agents::baseline::{BaselineAgent, BaselineLevel};
+use crate::agents::{anthropic::AnthropicAgent, ollama::OllamaAgent, openai::OpenAIAgent, scripted::ScriptedAgent};
 use crate::secrets::SecretsManager;
 use clap::ValueEnum;
 use rig::prelude::*;
 use rig::providers::anthropic::{self, CLAUDE_3_7_SONNET};
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug, serde::Deserialize)]
+#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug, serde::Deserialize)]
 pub enum AgentKind {
+    #[default]
     OpenAI,
     Anthropic,
     Ollama,
+    /// Replays the moves in the JSON array file named by `model`; no network
+    Scripted,
+    /// Built-in alpha-beta search for boards of up to 3x3 (TicTacToe, small Gomoku);
+    /// `model` is a search depth, or `perfect` (or empty) to search to the end
+    Minimax,
 }
 
-#[derive(Clone, Debug, serde::Deserialize, clap::Args)]
+#[derive(Clone, Debug, Default, serde::Deserialize, clap::Args)]
 pub struct AIAgentConfig {
     pub model: String,
     pub temp: f32,
+    /// Sampling seed sent to OpenAI and Ollama; Anthropic has none and
+    /// ignores it with a warning
     pub seed: Option<u64>,
     #[arg(value_enum)]
     pub agent: AgentKind,
     /// Secret profile name to use for API keys (optional, falls back to environment variables)
     #[arg(long)]
     pub secret_profile: Option<String>,
+    /// Cap on tokens generated per move request (defaults to
+    /// `MoveRequest::default_max_tokens`)
+    #[arg(long)]
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    /// Per-turn temperatures overriding `temp`; see `scheduled_temperature`
+    #[arg(long, value_delimiter = ',')]
+    #[serde(default)]
+    pub temp_schedule: Option<Vec<f32>>,
+    /// Replaces the default system prompt sent with every move request
+    #[arg(long)]
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    /// File holding the system prompt, taking precedence over `system_prompt`.
+    /// Read by `load_system_prompt_file` when the config is loaded.
+    #[arg(long)]
+    #[serde(default)]
+    pub system_prompt_file: Option<PathBuf>,
+    /// Tries per move request when the provider fails transiently, e.g.
+    /// with a 5xx (defaults to `DEFAULT_RETRY_ATTEMPTS`)
+    #[arg(long)]
+    #[serde(default)]
+    pub retry_attempts: Option<u32>,
+    /// Milliseconds to wait before the first such retry, doubled for each
+    /// later one (defaults to `DEFAULT_RETRY_BASE_DELAY`)
+    #[arg(long)]
+    #[serde(default)]
+    pub retry_base_delay_ms: Option<u64>,
+    /// Nucleus sampling cutoff (Ollama only)
+    #[arg(long)]
+    #[serde(default)]
+    pub top_p: Option<f32>,
+    /// Sequences that end generation, e.g. `}` to stop right after the JSON
+    /// move (Ollama only)
+    #[arg(long)]
+    #[serde(default)]
+    pub stop: Option<Vec<String>>,
+}
+
+/// Warn only once per run that Anthropic ignores seeds
+static ANTHROPIC_SEED_WARNING: Once = Once::new();
+
+/// Anthropic model used when a config leaves `model` empty
+pub const DEFAULT_ANTHROPIC_MODEL: &str = CLAUDE_3_7_SONNET;
+
+/// Enough for a JSON move object with room to spare
+pub const DEFAULT_MAX_TOKENS: u32 = 256;
+
+/// Default cap when the move schema offers a `reasoning` field, leaving room
+/// for a few paragraphs of it ahead of the move
+pub const DEFAULT_REASONING_MAX_TOKENS: u32 = 1024;
+
+/// Temperature for game turn `turn_index` (1-based) under `schedule`: turn N
+/// uses entry N-1 and the last entry holds for every later turn. `None` when
+/// the schedule is empty.
+pub fn scheduled_temperature(schedule: &[f32], turn_index: u32) -> Option<f32> {
+    let idx = (turn_index.max(1) as usize - 1).min(schedule.len().checked_sub(1)?);
+    Some(schedule[idx])
+}
+
+/// Parse a temperature schedule written as `0.9;0.5;0` (or comma-separated)
+pub fn parse_temp_schedule(s: &str) -> Result<Vec<f32>, String> {
+    s.split([';', ','])
+        .map(|t| t.trim().parse().map_err(|e| format!("Invalid temperature '{}': {}", t.trim(), e)))
+        .collect()
+}
+
+/// Read a system prompt file, naming the file in the error
+pub fn read_system_prompt(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("Failed to read system prompt file {}: {}", path.display(), e))
+}
+
+impl AIAgentConfig {
+    /// Read `system_prompt_file` into `system_prompt`. Relative paths are
+    /// resolved against `base_dir`, normally the config file's directory.
+    pub fn load_system_prompt_file(&mut self, base_dir: &Path) -> Result<(), String> {
+        if let Some(file) = &self.system_prompt_file {
+            self.system_prompt = Some(read_system_prompt(&base_dir.join(file))?);
+        }
+        Ok(())
+    }
+
+    /// Samples at temperature 0 on every turn
+    pub fn is_greedy(&self) -> bool {
+        match &self.temp_schedule {
+            Some(schedule) => schedule.iter().all(|t| *t == 0.0),
+            None => self.temp == 0.0,
+        }
+    }
+
+    /// `model`, or `DEFAULT_ANTHROPIC_MODEL` when it is empty
+    pub fn anthropic_model(&self) -> &str {
+        match self.model.trim() {
+            "" => DEFAULT_ANTHROPIC_MODEL,
+            model => model,
+        }
+    }
+
+    /// Search a `Minimax` agent runs: `perfect` or empty searches to the end
+    /// of the game, a number looks that many plies ahead
+    pub fn minimax_level(&self) -> Result<BaselineLevel, String> {
+        match self.model.trim() {
+            "" | "perfect" => Ok(BaselineLevel::PerfectMinimax),
+            depth => depth
+                .parse()
+                .map(|depth| BaselineLevel::Minimax { depth })
+                .map_err(|_| format!("Invalid minimax model '{}': expected 'perfect' or a search depth", depth)),
+        }
+    }
+
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            attempts: self.retry_attempts.unwrap_or(DEFAULT_RETRY_ATTEMPTS).max(1),
+            base_delay: self.retry_base_delay_ms.map_or(DEFAULT_RETRY_BASE_DELAY, Duration::from_millis),
+        }
+    }
+
+    /// Name `build_agents` gives the agent built from this config for `seat`
+    /// (0-based), e.g. `OpenAI_1:gpt-4o-mini`. The seat tells self-play
+    /// agents apart.
+    pub fn agent_name(&self, seat: usize) -> String {
+        let model = match self.agent {
+            AgentKind::Anthropic => self.anthropic_model(),
+            // Scripted models are move files; the file name is enough
+            AgentKind::Scripted => Path::new(&self.model).file_name().and_then(|f| f.to_str()).unwrap_or(&self.model),
+            AgentKind::Minimax if self.model.trim().is_empty() => "perfect",
+            AgentKind::OpenAI | AgentKind::Ollama | AgentKind::Minimax => self.model.trim(),
+        };
+        format!("{:?}_{}:{}", self.agent, seat + 1, model)
+    }
 }
 
-pub fn build_agents(configs: Vec<AIAgentConfig>) -> Vec<AIAgent> {
+/// Kind and model of an agent named by `AIAgentConfig::agent_name`, e.g.
+/// `("OpenAI", "gpt-4o-mini")` for `OpenAI_1:gpt-4o-mini`
+pub fn split_agent_name(name: &str) -> Option<(&str, &str)> {
+    let (prefix, model) = name.split_once(':')?;
+    let (kind, seat) = prefix.rsplit_once('_')?;
+    seat.parse::<usize>().ok()?;
+    Some((kind, model))
+}
+
+pub fn build_agents(configs: Vec<AIAgentConfig>) -> Result<Vec<AIAgent>, String> {
     // Load secrets manager (will be empty if file doesn't exist, falls back to env vars)
-    let secrets_manager = SecretsManager::load().unwrap_or_else(|e| {
-        eprintln!("Warning: Could not load secrets file: {}. Falling back to environment variables.", e);
-        SecretsManager::load_from_path(std::path::Path::new("/dev/null")).unwrap()
-    });
+    build_agents_with(configs, &SecretsManager::load_or_empty())
+}
 
+/// Like `build_agents`, with already-loaded secrets. Fails on the first
+/// agent that can't be built, e.g. for want of an API key.
+pub fn build_agents_with(configs: Vec<AIAgentConfig>, secrets_manager: &SecretsManager) -> Result<Vec<AIAgent>, String> {
     configs
         .into_iter()
         .enumerate()
         .map(|(i, cfg)| {
             let secret_profile = cfg.secret_profile.as_deref();
-            match cfg.agent {
+            let max_tokens = cfg.max_tokens;
+            let temp_schedule = cfg.temp_schedule.clone().unwrap_or_default();
+            let system_prompt = cfg.system_prompt.as_deref().unwrap_or(SYSTEM_PROMPT);
+            let name = cfg.agent_name(i);
+            let retry = cfg.retry_policy();
+            let failed = |what: &str, e: &dyn std::fmt::Display| format!("{}: {}: {}", name, what, e);
+            let agent = match cfg.agent {
                 AgentKind::OpenAI => {
-                    let name = format!("OpenAI_{}", i + 1);
                     let api_key = secrets_manager
                         .resolve_openai_key(secret_profile)
-                        .expect("Failed to resolve OpenAI API key");
-                    AIAgent::OpenAI(OpenAIAgent::new(&name, &cfg.model, &api_key).expect("create openai agent"))
+                        .map_err(|e| failed("Failed to resolve OpenAI API key", &e))?;
+                    let mut agent = OpenAIAgent::new(&name, &cfg.model, &api_key)
+                        .map_err(|e| failed("Failed to create OpenAI agent", &e))?
+                        .with_max_tokens(max_tokens)
+                        .with_temperature(cfg.temp)
+                        .with_temp_schedule(temp_schedule)
+                        .with_seed(cfg.seed)
+                        .with_system_prompt(system_prompt)
+                        .with_retry_policy(retry);
+                    if let Some(base_url) = secrets_manager.resolve_openai_base_url(secret_profile) {
+                        agent = agent.with_base_url(base_url);
+                    }
+                    AIAgent::OpenAI(agent)
                 }
                 AgentKind::Anthropic => {
-                    let name = format!("Anthropic_{}", i + 1);
+                    if let Some(seed) = cfg.seed {
+                        ANTHROPIC_SEED_WARNING.call_once(|| {
+                            tracing::warn!("Anthropic has no seed parameter; seed {} is ignored", seed);
+                        });
+                    }
                     let key = secrets_manager
                         .resolve_anthropic_key(secret_profile)
-                        .expect("Failed to resolve Anthropic API key");
+                        .map_err(|e| failed("Failed to resolve Anthropic API key", &e))?;
                     let mdl = anthropic::Client::new(key.as_str());
                     let agent = mdl
-                        .agent(CLAUDE_3_7_SONNET)
-                        .preamble("Be precise and concise.")
+                        .agent(cfg.anthropic_model())
+                        .preamble(system_prompt)
                         .temperature(cfg.temp as f64)
                         .build();
-                    AIAgent::Anthropic(AnthropicAgent::new(&name, agent).expect("create anthropic agent"))
+                    AIAgent::Anthropic(
+                        AnthropicAgent::new(&name, agent)
+                            .map_err(|e| failed("Failed to create Anthropic agent", &e))?
+                            .with_max_tokens(max_tokens)
+                            .with_retry_policy(retry),
+                    )
                 }
                 AgentKind::Ollama => {
-                    let name = format!("Ollama_{}", i + 1);
                     let base_url = secrets_manager
                         .resolve_ollama_base_url(secret_profile)
-                        .expect("Failed to resolve Ollama base URL");
+                        .map_err(|e| failed("Failed to resolve Ollama base URL", &e))?;
                     AIAgent::Ollama(
                         OllamaAgent::new(&name, &cfg.model, &base_url, cfg.temp)
-                            .expect("create ollama agent"),
+                            .map_err(|e| failed("Failed to create Ollama agent", &e))?
+                            .with_max_tokens(max_tokens)
+                            .with_temp_schedule(temp_schedule)
+                            .with_seed(cfg.seed)
+                            .with_top_p(cfg.top_p)
+                            .with_stop(cfg.stop.clone().unwrap_or_default())
+                            .with_system_prompt(system_prompt)
+                            .with_retry_policy(retry),
                     )
                 }
-            }
+                AgentKind::Scripted => AIAgent::Scripted(
+                    ScriptedAgent::from_file(&name, Path::new(&cfg.model))
+                        .map_err(|e| failed("Failed to load scripted moves", &e))?,
+                ),
+                AgentKind::Minimax => {
+                    let level = cfg.minimax_level().map_err(|e| failed("Invalid minimax depth", &e))?;
+                    AIAgent::Baseline(BaselineAgent::new(level, cfg.seed.unwrap_or(0)).with_name(&name))
+                }
+            };
+            Ok(agent)
         })
         .collect()
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scheduled_temperature() {
+        let schedule = [0.9, 0.0];
+        assert_eq!(scheduled_temperature(&schedule, 1), Some(0.9));
+        assert_eq!(scheduled_temperature(&schedule, 2), Some(0.0));
+        assert_eq!(scheduled_temperature(&schedule, 7), Some(0.0));
+        assert_eq!(scheduled_temperature(&[], 1), None);
+    }
+
+    #[test]
+    fn test_parse_temp_schedule() {
+        assert_eq!(parse_temp_schedule("0.9;0.5, 0").unwrap(), vec![0.9, 0.5, 0.0]);
+        assert!(parse_temp_schedule("0.9;hot").is_err());
+    }
+
+    #[test]
+    fn test_load_system_prompt_file() {
+        let dir = std::env::temp_dir().join(format!("ai_arena_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("persona.txt"), "You are a cautious player.").unwrap();
+        let mut config = AIAgentConfig {
+            model: "gpt-4o-mini".to_string(),
+            temp: 0.0,
+            agent: AgentKind::OpenAI,
+            system_prompt: Some("inline".to_string()),
+            system_prompt_file: Some(PathBuf::from("persona.txt")),
+            ..Default::default()
+        };
+
+        config.load_system_prompt_file(&dir).unwrap();
+        assert_eq!(config.system_prompt.as_deref(), Some("You are a cautious player."));
+
+        config.system_prompt_file = Some(PathBuf::from("nope.txt"));
+        let err = config.load_system_prompt_file(&dir).unwrap_err();
+        assert_eq!(
+            err.split(": ").take(1).collect::<String>(),
+            format!("Failed to read system prompt file {}", dir.join("nope.txt").display())
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    fn anthropic_config(model: &str) -> AIAgentConfig {
+        AIAgentConfig {
+            model: model.to_string(),
+            temp: 0.0,
+            agent: AgentKind::Anthropic,
+            secret_profile: Some("test".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_anthropic_agent_uses_configured_model() {
+        let missing = std::env::temp_dir().join(format!("ai_arena_{}.toml", uuid::Uuid::new_v4()));
+        let mut secrets = SecretsManager::load_from_path(&missing).unwrap();
+        secrets.set_anthropic("test", "sk-ant-test");
+
+        let agents = build_agents_with(vec![anthropic_config("claude-3-5-haiku"), anthropic_config(" ")], &secrets).unwrap();
+        let models: Vec<&str> = agents
+            .iter()
+            .map(|agent| match agent {
+                AIAgent::Anthropic(agent) => agent.model(),
+                _ => panic!("expected an Anthropic agent"),
+            })
+            .collect();
+        assert_eq!(models, ["claude-3-5-haiku", DEFAULT_ANTHROPIC_MODEL]);
+    }
+
+    #[test]
+    fn test_self_play_agents_get_distinct_names() {
+        let missing = std::env::temp_dir().join(format!("ai_arena_{}.toml", uuid::Uuid::new_v4()));
+        let mut secrets = SecretsManager::load_from_path(&missing).unwrap();
+        secrets.set_anthropic("test", "sk-ant-test");
+
+        let config = anthropic_config("claude-3-5-haiku");
+        let agents = build_agents_with(vec![config.clone(), config], &secrets).unwrap();
+        let names: Vec<&str> = agents.iter().map(AIAgent::name).collect();
+        assert_eq!(names, ["Anthropic_1:claude-3-5-haiku", "Anthropic_2:claude-3-5-haiku"]);
+
+        let scripted = AIAgentConfig {
+            agent: AgentKind::Scripted,
+            ..anthropic_config("scripts/center_first.json")
+        };
+        assert_eq!(scripted.agent_name(0), "Scripted_1:center_first.json");
+    }
+
+    #[test]
+    fn test_openai_agent_uses_configured_system_prompt() {
+        let missing = std::env::temp_dir().join(format!("ai_arena_{}.toml", uuid::Uuid::new_v4()));
+        let mut secrets = SecretsManager::load_from_path(&missing).unwrap();
+        secrets.set_openai("test", "sk-test");
+
+        let config = |system_prompt: Option<&str>| AIAgentConfig {
+            model: "gpt-4o-mini".to_string(),
+            agent: AgentKind::OpenAI,
+            system_prompt: system_prompt.map(str::to_string),
+            ..anthropic_config("")
+        };
+        let agents = build_agents_with(vec![config(Some("You are a reckless player.")), config(None)], &secrets).unwrap();
+        let prompts: Vec<&str> = agents
+            .iter()
+            .map(|agent| match agent {
+                AIAgent::OpenAI(agent) => agent.system_prompt(),
+                _ => panic!("expected an OpenAI agent"),
+            })
+            .collect();
+        assert_eq!(prompts, ["You are a reckless player.", SYSTEM_PROMPT]);
+    }
+}
@@ -1,13 +1,17 @@
 use rig::{
     agent::Agent,
-    providers::anthropic::completion::CompletionModel,
+    completion::{AssistantContent, Completion, CompletionError, CompletionResponse},
+    providers::anthropic::completion::{CompletionModel, CompletionResponse as AnthropicResponse},
 };
+use serde_json::Value;
 
-use crate::agent::{AgentError, AgentResult, MoveRequest, MoveResponse};
+use crate::agent::{AgentError, AgentResult, MoveRequest, MoveResponse, RetryPolicy, ServedModel, TokenUsage, with_retry};
 
 pub struct AnthropicAgent {
     name: String,
     agent: Agent<CompletionModel>,
+    max_tokens: Option<u32>,
+    retry: RetryPolicy,
 }
 
 impl AnthropicAgent {
@@ -15,24 +19,238 @@ impl AnthropicAgent {
         &self.name
     }
 
+    pub fn model(&self) -> &str {
+        &self.agent.model.model
+    }
+
+    pub fn system_prompt(&self) -> &str {
+        &self.agent.preamble
+    }
+
     pub fn new(name: impl Into<String>, agent: Agent<CompletionModel>) -> Result<Self, AgentError> {
         Ok(Self {
             name: name.into(),
             agent,
+            max_tokens: None,
+            retry: RetryPolicy::default(),
         })
     }
 
+    /// Cap the completion length of each move request, or leave it to
+    /// `MoveRequest::default_max_tokens` if `None`
+    pub fn with_max_tokens(mut self, max_tokens: Option<u32>) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    /// How to retry requests that fail transiently
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
     pub async fn execute_turn(&self, request: &MoveRequest) -> AgentResult<MoveResponse> {
-        let _user_payload = serde_json::json!({
-            "turn_index": request.turn_index,
-            "game_id": request.game_id,
-            "state": request.state,
-            "expected_move_schema": request.expected_move_schema,
+        let max_tokens = self.max_tokens.unwrap_or_else(|| request.default_max_tokens());
+        let response = with_retry(self.retry, "Anthropic", self.model(), async || {
+            self.agent
+                .completion(request.user_prompt(), Vec::new())
+                .await
+                .map_err(map_error)?
+                .max_tokens(u64::from(max_tokens))
+                .send()
+                .await
+                .map_err(map_error)
+        })
+        .await?;
+
+        Self::move_response(response)
+    }
+
+    fn move_response(response: CompletionResponse<AnthropicResponse>) -> AgentResult<MoveResponse> {
+        let text: String = response
+            .choice
+            .iter()
+            .filter_map(|content| match content {
+                AssistantContent::Text(text) => Some(text.text.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        // Claude has no JSON mode and sometimes fences its answer in ```json
+        let json = text
+            .trim()
+            .trim_start_matches("```json")
+            .trim_start_matches("```")
+            .trim_end_matches("```")
+            .trim();
+        let chosen_move: Value = serde_json::from_str(json)
+            .map_err(|e| AgentError::InvalidResponse(format!("non-json: {} (raw: {})", e, text)))?;
+
+        Ok(MoveResponse {
+            chosen_move,
+            diagnostics: Some(text),
+            perturbation: None,
+            replay_consistent: None,
+            usage: Some(TokenUsage {
+                prompt_tokens: response.usage.input_tokens as u32,
+                completion_tokens: response.usage.output_tokens as u32,
+            }),
+            served_model: Some(ServedModel {
+                model: response.raw_response.model,
+                system_fingerprint: None,
+            }),
         })
-        .to_string();
-        Err(AgentError::Internal(format!(
-            "build system msg: {}",
-            "DOES NOT WORK"
-        )))
+    }
+}
+
+fn map_error(e: CompletionError) -> AgentError {
+    match e {
+        CompletionError::ProviderError(body) if body.contains("rate_limit_error") => {
+            AgentError::RateLimited { retry_after: None }
+        }
+        // 500 api_error and 529 overloaded_error
+        CompletionError::ProviderError(body) if body.contains("overloaded_error") || body.contains("api_error") => {
+            AgentError::Transient(format!("anthropic: {}", body))
+        }
+        e @ CompletionError::HttpError(_) => AgentError::Transient(format!("anthropic: {}", e)),
+        e => AgentError::Internal(format!("anthropic: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rig::client::CompletionClient;
+    use rig::providers::anthropic;
+    use serde_json::json;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    /// Serve one HTTP request with `body` and hand back the request body
+    async fn stub_server(status: &'static str, body: Value) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut stream = BufReader::new(stream);
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                stream.read_line(&mut line).await.unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':')
+                    && name.eq_ignore_ascii_case("content-length")
+                {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+            let mut request = vec![0; content_length];
+            stream.read_exact(&mut request).await.unwrap();
+
+            let body = body.to_string();
+            let reply = format!(
+                "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            stream.get_mut().write_all(reply.as_bytes()).await.unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        (addr, handle)
+    }
+
+    fn agent(base_url: &str) -> AnthropicAgent {
+        let client = anthropic::Client::builder("sk-ant-test").base_url(base_url).build().unwrap();
+        let agent = client.agent("claude-3-7-sonnet-latest").max_tokens(64).build();
+        AnthropicAgent::new("Anthropic_1", agent).unwrap()
+    }
+
+    fn request() -> MoveRequest {
+        MoveRequest {
+            turn_index: 1,
+            game_id: "ttt_test".to_string(),
+            state: json!({"board": [[null]]}),
+            expected_move_schema: json!({"type": "object"}),
+            legal_moves: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_turn_calls_the_model() {
+        let (base_url, server) = stub_server(
+            "200 OK",
+            json!({
+                "id": "msg_1",
+                "type": "message",
+                "role": "assistant",
+                "model": "claude-3-7-sonnet-20250219",
+                "content": [{"type": "text", "text": "```json\n{\"row\": 0, \"col\": 0}\n```"}],
+                "stop_reason": "end_turn",
+                "stop_sequence": null,
+                "usage": {"input_tokens": 42, "output_tokens": 9}
+            }),
+        )
+        .await;
+
+        let response = agent(&base_url).execute_turn(&request()).await.unwrap();
+        assert_eq!(response.chosen_move, json!({"row": 0, "col": 0}));
+        assert_eq!(response.usage, Some(TokenUsage { prompt_tokens: 42, completion_tokens: 9 }));
+        assert_eq!(response.served_model.unwrap().model, "claude-3-7-sonnet-20250219");
+        // The raw reply, fences and all
+        assert_eq!(response.diagnostics.as_deref(), Some("```json\n{\"row\": 0, \"col\": 0}\n```"));
+
+        // The same user payload the other providers send
+        let sent: Value = serde_json::from_str(&server.await.unwrap()).unwrap();
+        assert_eq!(sent["messages"][0]["content"][0]["text"], request().user_prompt());
+    }
+
+    #[tokio::test]
+    async fn test_non_json_reply_is_invalid_response() {
+        let (base_url, _server) = stub_server(
+            "200 OK",
+            json!({
+                "id": "msg_1",
+                "type": "message",
+                "role": "assistant",
+                "model": "claude-3-7-sonnet-20250219",
+                "content": [{"type": "text", "text": "I'll take the center."}],
+                "stop_reason": "end_turn",
+                "stop_sequence": null,
+                "usage": {"input_tokens": 42, "output_tokens": 9}
+            }),
+        )
+        .await;
+
+        let err = agent(&base_url).execute_turn(&request()).await.unwrap_err();
+        assert!(matches!(err, AgentError::InvalidResponse(_)), "{}", err);
+        assert!(err.to_string().ends_with("(raw: I'll take the center.)"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_is_reported() {
+        let (base_url, _server) = stub_server(
+            "429 Too Many Requests",
+            json!({"type": "error", "error": {"type": "rate_limit_error", "message": "slow down"}}),
+        )
+        .await;
+
+        let err = agent(&base_url).execute_turn(&request()).await.unwrap_err();
+        assert!(matches!(err, AgentError::RateLimited { .. }), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_overloaded_is_transient() {
+        let (base_url, _server) = stub_server(
+            "529 Overloaded",
+            json!({"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}}),
+        )
+        .await;
+
+        let no_retries = RetryPolicy { attempts: 1, ..Default::default() };
+        let err = agent(&base_url).with_retry_policy(no_retries).execute_turn(&request()).await.unwrap_err();
+        assert!(matches!(err, AgentError::Transient(_)), "{}", err);
     }
 }
@@ -0,0 +1,372 @@
+use std::cell::Cell;
+
+use serde_json::{Value, json};
+
+use crate::agent::{AgentError, AgentResult, MoveRequest, MoveResponse};
+use crate::games::engine::TurnBasedGame;
+use crate::games::gomoku::Gomoku;
+use crate::games::parsing::{empty_cells, parse_board, parse_current_player};
+use crate::games::rng::SeededRng;
+use crate::games::tic_tac_toe::TicTacToe;
+
+/// How strongly a baseline agent plays, weakest first
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BaselineLevel {
+    /// Any empty cell, uniformly
+    Random,
+    /// Empty cells weighted by how many winning lines run through them
+    WeightedRandom,
+    /// Alpha-beta search that looks `depth` plies ahead
+    Minimax { depth: u32 },
+    /// Alpha-beta search to the end of the game; only quick on small boards
+    PerfectMinimax,
+}
+
+impl BaselineLevel {
+    /// The default difficulty ladder for curricula
+    pub fn ladder() -> Vec<BaselineLevel> {
+        vec![
+            BaselineLevel::Random,
+            BaselineLevel::WeightedRandom,
+            BaselineLevel::Minimax { depth: 2 },
+            BaselineLevel::PerfectMinimax,
+        ]
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            BaselineLevel::Random => "random".to_string(),
+            BaselineLevel::WeightedRandom => "weighted-random".to_string(),
+            BaselineLevel::Minimax { depth } => format!("minimax(depth {})", depth),
+            BaselineLevel::PerfectMinimax => "perfect-minimax".to_string(),
+        }
+    }
+}
+
+/// A built-in TicTacToe or Gomoku opponent of known strength that needs no
+/// provider. Reads the `board` matrix, so the game must not use the
+/// text-only board representation.
+pub struct BaselineAgent {
+    name: String,
+    level: BaselineLevel,
+    /// Name of the game being played, set by `join_game`
+    game: Option<String>,
+    /// For the random levels
+    rng: Cell<SeededRng>,
+}
+
+impl BaselineAgent {
+    pub fn new(level: BaselineLevel, seed: u64) -> Self {
+        Self {
+            name: format!("baseline-{}", level.name()),
+            level,
+            game: None,
+            rng: Cell::new(SeededRng::new(seed)),
+        }
+    }
+
+    /// Report moves under `name` instead of the level's name
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Play `game` from now on; its name picks how the board is read
+    pub fn join_game(&mut self, game: &str) {
+        self.game = Some(game.to_string());
+    }
+
+    pub fn level(&self) -> BaselineLevel {
+        self.level
+    }
+
+    pub async fn execute_turn(&self, request: &MoveRequest) -> AgentResult<MoveResponse> {
+        let game = self.game.as_deref().unwrap_or("an unnamed game");
+        let seats = PLAYABLE_GAMES
+            .iter()
+            .find(|(name, _)| *name == game)
+            .map(|(_, seats)| *seats)
+            .ok_or_else(|| {
+                AgentError::InvalidRequest(format!("baseline agents only play TicTacToe and Gomoku, not {}", game))
+            })?;
+        let board = Board::from_state(&request.state, seats)?;
+        let (row, col) = self
+            .choose(&board)
+            .ok_or_else(|| AgentError::InvalidRequest("no empty cell to play".to_string()))?;
+
+        Ok(MoveResponse {
+            chosen_move: json!({"row": row, "col": col}),
+            diagnostics: Some(self.level.name()),
+            perturbation: None,
+            replay_consistent: None,
+            usage: None,
+            served_model: None,
+        })
+    }
+
+    fn choose(&self, board: &Board) -> Option<(usize, usize)> {
+        let empty = board.empty_cells();
+        match self.level {
+            BaselineLevel::Random => empty.get(self.next_below(empty.len())).copied(),
+            BaselineLevel::WeightedRandom => {
+                let weights: Vec<usize> = empty.iter().map(|&(r, c)| board.lines_through(r, c) + 1).collect();
+                let mut pick = self.next_below(weights.iter().sum());
+                empty.iter().zip(&weights).find_map(|(cell, weight)| {
+                    if pick < *weight {
+                        Some(*cell)
+                    } else {
+                        pick -= weight;
+                        None
+                    }
+                })
+            }
+            BaselineLevel::Minimax { depth } => board.best_move(depth),
+            BaselineLevel::PerfectMinimax => board.best_move(u32::MAX),
+        }
+    }
+
+    fn next_below(&self, bound: usize) -> usize {
+        let mut rng = self.rng.get();
+        let value = rng.next_below(bound);
+        self.rng.set(rng);
+        value
+    }
+}
+
+/// Names of the games baseline agents can read, with their seats
+const PLAYABLE_GAMES: [(&str, [&str; 2]); 2] = [("TicTacToe", TicTacToe::SEATS), ("Gomoku", Gomoku::SEATS)];
+
+/// TicTacToe or Gomoku position as the baseline agents see it, with each
+/// piece stored as the seat that played it
+#[derive(Clone, Debug)]
+struct Board {
+    cells: Vec<Vec<Option<usize>>>,
+    win_length: usize,
+    to_move: usize,
+}
+
+impl Board {
+    /// Read a game state whose pieces are named by `seats`
+    fn from_state(state: &Value, seats: [&str; 2]) -> AgentResult<Self> {
+        let seat_of = |symbol: &str| {
+            seats
+                .iter()
+                .position(|seat| *seat == symbol)
+                .ok_or_else(|| AgentError::InvalidRequest(format!("unknown player '{}'", symbol)))
+        };
+        let board = parse_board(state).map_err(AgentError::InvalidRequest)?;
+        let cells = board
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.as_deref().map(seat_of).transpose()).collect())
+            .collect::<AgentResult<_>>()?;
+        let to_move = seat_of(&parse_current_player(state).map_err(AgentError::InvalidRequest)?)?;
+        let win_length = state
+            .get("win_length")
+            .and_then(Value::as_u64)
+            .map_or(board.len(), |n| n as usize);
+        Ok(Board { cells, win_length, to_move })
+    }
+
+    fn empty_cells(&self) -> Vec<(usize, usize)> {
+        empty_cells(&self.cells)
+    }
+
+    fn get(&self, r: isize, c: isize) -> Option<Option<usize>> {
+        let row = self.cells.get(usize::try_from(r).ok()?)?;
+        row.get(usize::try_from(c).ok()?).copied()
+    }
+
+    /// Every window of `win_length` cells, as (start, direction)
+    fn windows(&self) -> impl Iterator<Item = ((isize, isize), (isize, isize))> + '_ {
+        let n = self.win_length as isize - 1;
+        (0..self.cells.len() as isize)
+            .flat_map(move |r| (0..self.cells[r as usize].len() as isize).map(move |c| (r, c)))
+            .flat_map(|start| [(0, 1), (1, 0), (1, 1), (1, -1)].map(|dir| (start, dir)))
+            .filter(move |&((r, c), (dr, dc))| self.get(r + dr * n, c + dc * n).is_some())
+    }
+
+    fn lines_through(&self, row: usize, col: usize) -> usize {
+        let n = self.win_length as isize;
+        self.windows()
+            .filter(|&((r, c), (dr, dc))| (0..n).any(|i| (r + dr * i, c + dc * i) == (row as isize, col as isize)))
+            .count()
+    }
+
+    fn winner(&self) -> Option<usize> {
+        let n = self.win_length as isize;
+        self.windows().find_map(|((r, c), (dr, dc))| {
+            let first = self.get(r, c)??;
+            (1..n).all(|i| self.get(r + dr * i, c + dc * i) == Some(Some(first))).then_some(first)
+        })
+    }
+
+    fn best_move(&self, depth: u32) -> Option<(usize, usize)> {
+        let mut board = self.clone();
+        let me = self.to_move;
+        let mut best: Option<((usize, usize), i32)> = None;
+        for (r, c) in self.empty_cells() {
+            board.cells[r][c] = Some(me);
+            let score = -board.negamax(1 - me, depth.saturating_sub(1), -i32::MAX, i32::MAX);
+            board.cells[r][c] = None;
+            if best.is_none_or(|(_, s)| score > s) {
+                best = Some(((r, c), score));
+            }
+        }
+        best.map(|(cell, _)| cell)
+    }
+
+    /// Score for `player` to move; quicker wins score higher
+    fn negamax(&mut self, player: usize, depth: u32, mut alpha: i32, beta: i32) -> i32 {
+        let empty = self.empty_cells();
+        if let Some(winner) = self.winner() {
+            let score = 1 + empty.len() as i32;
+            return if winner == player { score } else { -score };
+        }
+        if empty.is_empty() || depth == 0 {
+            return 0;
+        }
+        let mut best = -i32::MAX;
+        for (r, c) in empty {
+            self.cells[r][c] = Some(player);
+            let score = -self.negamax(1 - player, depth - 1, -beta, -alpha);
+            self.cells[r][c] = None;
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::AIAgent;
+    use crate::games::Game;
+
+    fn playing(game: &str, level: BaselineLevel, seed: u64) -> BaselineAgent {
+        let mut agent = BaselineAgent::new(level, seed);
+        agent.join_game(game);
+        agent
+    }
+
+    fn request(board: Value, current_player: &str) -> MoveRequest {
+        MoveRequest {
+            turn_index: 1,
+            game_id: "ttt_test".to_string(),
+            state: json!({"board": board, "current_player": current_player, "win_length": 3}),
+            expected_move_schema: json!({}),
+            legal_moves: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_minimax_wins_then_blocks() {
+        let agent = playing("TicTacToe", BaselineLevel::Minimax { depth: 2 }, 0);
+
+        let win = request(json!([["X", "X", null], ["O", "O", null], [null, null, null]]), "X");
+        let response = agent.execute_turn(&win).await.unwrap();
+        assert_eq!(response.chosen_move, json!({"row": 0, "col": 2}));
+
+        let block = request(json!([["X", "X", null], ["O", null, null], [null, null, null]]), "O");
+        let response = agent.execute_turn(&block).await.unwrap();
+        assert_eq!(response.chosen_move, json!({"row": 0, "col": 2}));
+    }
+
+    #[tokio::test]
+    async fn test_perfect_minimax_wins_then_blocks_on_gomoku() {
+        let agent = playing("Gomoku", BaselineLevel::PerfectMinimax, 0);
+
+        let win = request(
+            json!([["Black", "Black", null, null], ["White", "White", null, null], [null, null, null, null], [null, null, null, null]]),
+            "White",
+        );
+        let response = agent.execute_turn(&win).await.unwrap();
+        assert_eq!(response.chosen_move, json!({"row": 1, "col": 2}));
+
+        let block = request(
+            json!([["Black", null, null, null], [null, "Black", null, null], ["White", null, null, null], [null, null, null, null]]),
+            "White",
+        );
+        let response = agent.execute_turn(&block).await.unwrap();
+        assert_eq!(response.chosen_move, json!({"row": 2, "col": 2}));
+    }
+
+    #[tokio::test]
+    async fn test_other_games_are_rejected() {
+        let req = request(json!([[null, null], [null, null]]), "Red");
+        let agent = playing("ConnectFour", BaselineLevel::PerfectMinimax, 0);
+        let err = agent.execute_turn(&req).await.unwrap_err();
+        assert_eq!(err.to_string(), "invalid request: baseline agents only play TicTacToe and Gomoku, not ConnectFour");
+
+        // The game is known by name only, so an agent never told it can't play
+        let agent = BaselineAgent::new(BaselineLevel::PerfectMinimax, 0);
+        let err = agent.execute_turn(&request(json!([[null, null], [null, null]]), "X")).await.unwrap_err();
+        assert_eq!(err.to_string(), "invalid request: baseline agents only play TicTacToe and Gomoku, not an unnamed game");
+    }
+
+    #[tokio::test]
+    async fn test_random_levels_play_empty_cells() {
+        let board = json!([["X", "O", "X"], [null, "O", null], ["O", "X", "O"]]);
+        for level in [BaselineLevel::Random, BaselineLevel::WeightedRandom] {
+            for seed in 0..20 {
+                let agent = playing("TicTacToe", level, seed);
+                let chosen = agent.execute_turn(&request(board.clone(), "X")).await.unwrap().chosen_move;
+                assert!(chosen == json!({"row": 1, "col": 0}) || chosen == json!({"row": 1, "col": 2}));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_text_only_state_is_rejected() {
+        let agent = playing("TicTacToe", BaselineLevel::Random, 0);
+        let mut req = request(json!(null), "X");
+        req.state = json!({"board_text": "...", "current_player": "X"});
+        assert!(matches!(agent.execute_turn(&req).await, Err(AgentError::InvalidRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_malformed_boards_are_rejected() {
+        let agent = playing("TicTacToe", BaselineLevel::Random, 0);
+        let cases = [
+            (json!([["X", 1], [null, null]]), "O", "board cell (0, 1) must be null or a string, got 1"),
+            (json!([["X", null], [null]]), "O", "board row 1 has 1 cells, expected 2"),
+            (json!([["X", "Red"], [null, null]]), "O", "unknown player 'Red'"),
+            (json!([["X", null], [null, null]]), "Red", "unknown player 'Red'"),
+        ];
+        for (board, current_player, expected) in cases {
+            match agent.execute_turn(&request(board, current_player)).await {
+                Err(AgentError::InvalidRequest(e)) => assert_eq!(e, expected),
+                other => panic!("expected an invalid request, got {:?}", other.map(|r| r.chosen_move)),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_perfect_play_draws() {
+        let agents = vec![
+            AIAgent::Baseline(BaselineAgent::new(BaselineLevel::PerfectMinimax, 0)),
+            AIAgent::Baseline(BaselineAgent::new(BaselineLevel::PerfectMinimax, 1)),
+        ];
+        let result = Game::new("TicTacToe").unwrap().play_with_agents(agents).await;
+        assert_eq!(result.winner(), None);
+        assert!(result.stats().draw);
+    }
+
+    #[tokio::test]
+    async fn test_plays_gomoku_by_name() {
+        let agents = vec![
+            AIAgent::Baseline(BaselineAgent::new(BaselineLevel::Random, 0)),
+            AIAgent::Baseline(BaselineAgent::new(BaselineLevel::Random, 1)),
+        ];
+        let result = Game::new("Gomoku").unwrap().play_with_agents(agents).await;
+        assert_eq!(result.error(), None);
+        assert_eq!(result.stats().invalid_moves, 0);
+    }
+}
@@ -1,3 +1,8 @@
 pub mod anthropic;
+pub mod baseline;
 pub mod openai;
 pub mod ollama;
+pub mod perturbed;
+pub mod replay_checked;
+pub mod scripted;
+pub mod socket;
@@ -1,16 +1,28 @@
 use llm_connector::{
     LlmClient,
-    types::{ChatRequest, Message, Role},
+    error::LlmConnectorError,
+    types::{ChatRequest, ChatResponse, Message, Role},
 };
-use serde_json::{Value, json};
+use serde_json::Value;
 
-use crate::agent::{AgentError, AgentResult, MoveRequest, MoveResponse};
+use crate::agent::{AgentError, AgentResult, MoveRequest, MoveResponse, RetryPolicy, SYSTEM_PROMPT, ServedModel, with_retry};
+use crate::agent_config::scheduled_temperature;
+
+/// Where a local Ollama server listens unless told otherwise
+pub const DEFAULT_BASE_URL: &str = "http://localhost:11434";
 
 pub struct OllamaAgent {
     name: String,
     model: String,
     base_url: String,
     temperature: f32,
+    max_tokens: Option<u32>,
+    temp_schedule: Vec<f32>,
+    seed: Option<u64>,
+    top_p: Option<f32>,
+    stop: Vec<String>,
+    system_prompt: String,
+    retry: RetryPolicy,
     client: LlmClient,
 }
 
@@ -19,6 +31,22 @@ impl OllamaAgent {
         &self.name
     }
 
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Name rate limits and retries are tracked under, one per server
+    pub fn provider(&self) -> String {
+        match self.base_url.as_str() {
+            DEFAULT_BASE_URL => "Ollama".to_string(),
+            base_url => format!("Ollama @ {}", base_url),
+        }
+    }
+
     pub fn new(
         name: impl Into<String>,
         model: impl Into<String>,
@@ -34,56 +62,130 @@ impl OllamaAgent {
             model: model.into(),
             base_url,
             temperature,
+            max_tokens: None,
+            temp_schedule: Vec::new(),
+            seed: None,
+            top_p: None,
+            stop: Vec::new(),
+            system_prompt: SYSTEM_PROMPT.to_string(),
+            retry: RetryPolicy::default(),
             client,
         })
     }
 
-    pub async fn execute_turn(&self, request: &MoveRequest) -> AgentResult<MoveResponse> {
-        let system = "You are a game-playing AI. Respond ONLY with strict JSON matching the expected schema. Do not include any text outside JSON.";
-        let user = json!({
-            "turn_index": request.turn_index,
-            "game_id": request.game_id,
-            "state": request.state,
-            "expected_move_schema": request.expected_move_schema,
-        })
-        .to_string();
+    pub fn system_prompt(&self) -> &str {
+        &self.system_prompt
+    }
+
+    /// Cap the completion length of each move request (sent as `num_predict`), or leave it to
+    /// `MoveRequest::default_max_tokens` if `None`
+    pub fn with_max_tokens(mut self, max_tokens: Option<u32>) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    /// Per-turn temperatures that override the fixed temperature
+    pub fn with_temp_schedule(mut self, schedule: Vec<f32>) -> Self {
+        self.temp_schedule = schedule;
+        self
+    }
+
+    /// Sampling seed, set on every chat request
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Nucleus sampling cutoff, set on every chat request
+    pub fn with_top_p(mut self, top_p: Option<f32>) -> Self {
+        self.top_p = top_p;
+        self
+    }
+
+    /// Sequences that end generation, set on every chat request
+    pub fn with_stop(mut self, stop: Vec<String>) -> Self {
+        self.stop = stop;
+        self
+    }
+
+    /// Replace the default `SYSTEM_PROMPT`
+    pub fn with_system_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.system_prompt = prompt.into();
+        self
+    }
+
+    /// How to retry requests that fail transiently
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    fn build_chat_request(&self, request: &MoveRequest) -> ChatRequest {
+        let user = request.user_prompt();
 
         let messages = vec![
-            Message::text(Role::System, system),
+            Message::text(Role::System, &self.system_prompt),
             Message::text(Role::User, &user),
         ];
 
-        let chat_request = ChatRequest {
+        ChatRequest {
             model: self.model.clone(),
             messages,
-            temperature: Some(self.temperature),
+            temperature: Some(scheduled_temperature(&self.temp_schedule, request.turn_index).unwrap_or(self.temperature)),
+            max_tokens: Some(self.max_tokens.unwrap_or_else(|| request.default_max_tokens())),
+            seed: self.seed,
+            top_p: self.top_p,
+            stop: (!self.stop.is_empty()).then(|| self.stop.clone()),
             ..Default::default()
-        };
+        }
+    }
 
-        let response = self
-            .client
-            .chat(&chat_request)
-            .await
-            .map_err(|e| AgentError::Internal(format!("ollama chat request failed: {}", e)))?;
+    pub async fn execute_turn(&self, request: &MoveRequest) -> AgentResult<MoveResponse> {
+        let chat_request = self.build_chat_request(request);
 
-        // Extract content from response - llm-connector returns content as a String
-        let content = response.content;
+        let response = with_retry(self.retry, &self.provider(), &self.model, async || {
+            self.client.chat(&chat_request).await.map_err(map_error)
+        })
+        .await?;
 
-        // Parse the JSON response
-        let chosen_move: Value = serde_json::from_str(&content).map_err(|e| {
-            AgentError::InvalidResponse(format!("failed to parse JSON response: {}", e))
+        Self::move_response(response)
+    }
+
+    fn move_response(response: ChatResponse) -> AgentResult<MoveResponse> {
+        // llm-connector returns the first choice's content as a String
+        let chosen_move: Value = serde_json::from_str(&response.content).map_err(|e| {
+            AgentError::InvalidResponse(format!("failed to parse JSON response: {} (raw: {})", e, response.content))
         })?;
 
         Ok(MoveResponse {
             chosen_move,
-            diagnostics: None,
+            diagnostics: Some(response.content),
+            perturbation: None,
+            replay_consistent: None,
+            usage: None,
+            served_model: Some(ServedModel {
+                model: response.model,
+                system_fingerprint: response.system_fingerprint,
+            }),
         })
     }
 }
 
+fn map_error(e: LlmConnectorError) -> AgentError {
+    match e {
+        LlmConnectorError::RateLimitError(_) => AgentError::RateLimited { retry_after: None },
+        e @ (LlmConnectorError::NetworkError(_)
+        | LlmConnectorError::ConnectionError(_)
+        | LlmConnectorError::ServerError(_)
+        | LlmConnectorError::TimeoutError(_)) => AgentError::Transient(format!("ollama chat request failed: {}", e)),
+        e => AgentError::Internal(format!("ollama chat request failed: {}", e)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
 
     #[test]
     fn test_ollama_agent_creation() {
@@ -108,6 +210,82 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_each_server_is_its_own_provider() {
+        if let Ok(agent) = OllamaAgent::new("test", "llama3", DEFAULT_BASE_URL, 0.7) {
+            assert_eq!(agent.provider(), "Ollama");
+        }
+        if let Ok(agent) = OllamaAgent::new("test", "llama3", "http://gpu-box:11434", 0.7) {
+            assert_eq!(agent.provider(), "Ollama @ http://gpu-box:11434");
+        }
+    }
+
+    #[test]
+    fn test_chat_request_carries_max_tokens() {
+        if let Ok(agent) = OllamaAgent::new("test", "llama3", "http://localhost:11434", 0.7) {
+            let request = MoveRequest {
+                turn_index: 1,
+                game_id: "ttt_test".to_string(),
+                state: json!({}),
+                expected_move_schema: json!({}),
+                legal_moves: None,
+            };
+            let chat_request = agent.with_max_tokens(Some(32)).build_chat_request(&request);
+            assert_eq!(chat_request.max_tokens, Some(32));
+        }
+    }
+
+    #[test]
+    fn test_chat_request_follows_temp_schedule() {
+        if let Ok(agent) = OllamaAgent::new("test", "llama3", "http://localhost:11434", 0.7) {
+            let agent = agent.with_temp_schedule(vec![1.0, 0.0]);
+            let request = |turn_index| MoveRequest {
+                turn_index,
+                game_id: "ttt_test".to_string(),
+                state: json!({}),
+                expected_move_schema: json!({}),
+                legal_moves: None,
+            };
+            assert_eq!(agent.build_chat_request(&request(1)).temperature, Some(1.0));
+            assert_eq!(agent.build_chat_request(&request(2)).temperature, Some(0.0));
+        }
+    }
+
+    #[test]
+    fn test_chat_request_carries_seed() {
+        if let Ok(agent) = OllamaAgent::new("test", "llama3", "http://localhost:11434", 0.7) {
+            let request = MoveRequest {
+                turn_index: 1,
+                game_id: "ttt_test".to_string(),
+                state: json!({}),
+                expected_move_schema: json!({}),
+                legal_moves: None,
+            };
+            assert_eq!(agent.with_seed(Some(7)).build_chat_request(&request).seed, Some(7));
+        }
+    }
+
+    #[test]
+    fn test_chat_request_carries_sampling_options() {
+        if let Ok(agent) = OllamaAgent::new("test", "llama3", "http://localhost:11434", 0.7) {
+            let request = MoveRequest {
+                turn_index: 1,
+                game_id: "ttt_test".to_string(),
+                state: json!({}),
+                expected_move_schema: json!({}),
+                legal_moves: None,
+            };
+            let defaults = agent.build_chat_request(&request);
+            assert_eq!((defaults.top_p, defaults.stop), (None, None));
+
+            let agent = agent.with_max_tokens(Some(64)).with_top_p(Some(0.9)).with_stop(vec!["}".to_string()]);
+            let chat_request = agent.build_chat_request(&request);
+            assert_eq!(chat_request.max_tokens, Some(64));
+            assert_eq!(chat_request.top_p, Some(0.9));
+            assert_eq!(chat_request.stop, Some(vec!["}".to_string()]));
+        }
+    }
+
     #[test]
     fn test_ollama_agent_name() {
         // Test name method (doesn't require Ollama to be running)
@@ -115,4 +293,35 @@ mod tests {
             assert_eq!(agent.name(), "test_name");
         }
     }
+
+    #[test]
+    fn test_response_records_served_model() {
+        let response = ChatResponse {
+            model: "llama3:8b-instruct-q4_0".to_string(),
+            content: r#"{"column": 3}"#.to_string(),
+            ..Default::default()
+        };
+
+        let response = OllamaAgent::move_response(response).unwrap();
+        assert_eq!(response.chosen_move, json!({"column": 3}));
+        assert_eq!(response.served_model.unwrap().model, "llama3:8b-instruct-q4_0");
+    }
+
+    #[test]
+    fn test_response_keeps_raw_content() {
+        let response = ChatResponse {
+            content: "{\"row\": 1,\n \"col\": 2}".to_string(),
+            ..Default::default()
+        };
+        let response = OllamaAgent::move_response(response).unwrap();
+        assert_eq!(response.chosen_move, json!({"row": 1, "col": 2}));
+        assert_eq!(response.diagnostics.as_deref(), Some("{\"row\": 1,\n \"col\": 2}"));
+
+        let garbled = ChatResponse {
+            content: "row 1, col 2".to_string(),
+            ..Default::default()
+        };
+        let err = OllamaAgent::move_response(garbled).unwrap_err();
+        assert!(err.to_string().ends_with("(raw: row 1, col 2)"), "{}", err);
+    }
 }
@@ -1,18 +1,32 @@
 use async_openai::{
-    config::OpenAIConfig,
+    config::{Config, OPENAI_API_BASE, OpenAIConfig},
+    error::OpenAIError,
     Client,
     types::{
         ChatCompletionRequestMessage, ChatCompletionRequestSystemMessageArgs,
-        ChatCompletionRequestUserMessageArgs, CreateChatCompletionRequestArgs, ResponseFormat,
+        ChatCompletionRequestUserMessageArgs, CreateChatCompletionRequest,
+        CreateChatCompletionRequestArgs, CreateChatCompletionResponse, ResponseFormat, ResponseFormatJsonSchema,
     },
 };
-use serde_json::{Value, json};
+use serde_json::{Map, Value, json};
 
-use crate::agent::{AgentError, AgentResult, MoveRequest, MoveResponse};
+use crate::agent::{
+    AgentError, AgentResult, MoveRequest, MoveResponse, RetryPolicy, SYSTEM_PROMPT, ServedModel, TokenUsage, with_retry,
+};
+use crate::agent_config::scheduled_temperature;
+use crate::games::engine::REASONING_FIELD;
 
 pub struct OpenAIAgent {
     name: String,
     model: String,
+    max_tokens: Option<u32>,
+    /// `None` leaves sampling at the provider default
+    temperature: Option<f32>,
+    temp_schedule: Vec<f32>,
+    /// Sent as the request `seed` for best-effort reproducible sampling
+    seed: Option<u64>,
+    system_prompt: String,
+    retry: RetryPolicy,
     client: Client<OpenAIConfig>,
 }
 
@@ -21,6 +35,30 @@ impl OpenAIAgent {
         &self.name
     }
 
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// API base requests are sent to
+    pub fn base_url(&self) -> &str {
+        self.client.config().api_base()
+    }
+
+    /// Name rate limits and retries are tracked under. Each
+    /// OpenAI-compatible server gets its own, so one that throttles
+    /// doesn't pause the others.
+    pub fn provider(&self) -> String {
+        match self.base_url() {
+            OPENAI_API_BASE => "OpenAI".to_string(),
+            base_url => format!("OpenAI @ {}", base_url),
+        }
+    }
+
+    /// System message sent with every move request
+    pub fn system_prompt(&self) -> &str {
+        &self.system_prompt
+    }
+
     pub fn new(name: impl Into<String>, model: impl Into<String>, api_key: impl Into<String>) -> Result<Self, AgentError> {
         let api_key = api_key.into();
         // Create config with the API key directly - no environment variable manipulation needed
@@ -30,23 +68,65 @@ impl OpenAIAgent {
         Ok(Self {
             name: name.into(),
             model: model.into(),
+            max_tokens: None,
+            temperature: None,
+            temp_schedule: Vec::new(),
+            seed: None,
+            system_prompt: SYSTEM_PROMPT.to_string(),
+            retry: RetryPolicy::default(),
             client,
         })
     }
 
-    pub async fn execute_turn(&self, request: &MoveRequest) -> AgentResult<MoveResponse> {
-        let system = "You are a game-playing AI. Respond ONLY with strict JSON matching the expected schema. Do not include any text outside JSON.";
-        let user = json!({
-            "turn_index": request.turn_index,
-            "game_id": request.game_id,
-            "state": request.state,
-            "expected_move_schema": request.expected_move_schema,
-        })
-        .to_string();
+    /// Send requests to an OpenAI-compatible server, such as vLLM or LM
+    /// Studio, instead of api.openai.com
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        let config = self.client.config().clone().with_api_base(base_url);
+        self.client = Client::with_config(config);
+        self
+    }
+
+    /// Cap the completion length of each move request, or leave it to
+    /// `MoveRequest::default_max_tokens` if `None`
+    pub fn with_max_tokens(mut self, max_tokens: Option<u32>) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// Per-turn temperatures that override `with_temperature`
+    pub fn with_temp_schedule(mut self, schedule: Vec<f32>) -> Self {
+        self.temp_schedule = schedule;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Replace the default `SYSTEM_PROMPT`
+    pub fn with_system_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.system_prompt = prompt.into();
+        self
+    }
+
+    /// How to retry requests that fail transiently
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    fn build_request(&self, request: &MoveRequest) -> AgentResult<CreateChatCompletionRequest> {
+        let user = request.user_prompt();
 
         let messages: Vec<ChatCompletionRequestMessage> = vec![
             ChatCompletionRequestSystemMessageArgs::default()
-                .content(system)
+                .content(self.system_prompt.as_str())
                 .build()
                 .map_err(|e| AgentError::Internal(format!("build system msg: {}", e)))?
                 .into(),
@@ -57,33 +137,331 @@ impl OpenAIAgent {
                 .into(),
         ];
 
-        let req = CreateChatCompletionRequestArgs::default()
-            .model(&self.model)
+        let mut args = CreateChatCompletionRequestArgs::default();
+        args.model(&self.model)
             .messages(messages)
-            .response_format(ResponseFormat::JsonObject)
-            .build()
-            .map_err(|e| AgentError::Internal(format!("build chat req: {}", e)))?;
+            .response_format(self.response_format(&request.expected_move_schema))
+            .max_completion_tokens(self.max_tokens.unwrap_or_else(|| request.default_max_tokens()));
+        if let Some(temperature) = scheduled_temperature(&self.temp_schedule, request.turn_index).or(self.temperature) {
+            args.temperature(temperature);
+        }
+        if let Some(seed) = self.seed {
+            args.seed(seed as i64);
+        }
+        args.build()
+            .map_err(|e| AgentError::Internal(format!("build chat req: {}", e)))
+    }
+
+    /// The move schema as a structured-output format, strict when the schema
+    /// allows it; plain JSON mode for models from before structured outputs
+    /// `json_schema` for OpenAI models that support it. OpenAI-compatible
+    /// servers often reject it, so requests to any other base URL get
+    /// `json_object`.
+    fn response_format(&self, schema: &Value) -> ResponseFormat {
+        if self.base_url() != OPENAI_API_BASE
+            || !supports_json_schema(&self.model)
+            || schema.get("type") != Some(&Value::from("object"))
+        {
+            return ResponseFormat::JsonObject;
+        }
+        let strict = strict_schema(schema);
+        ResponseFormat::JsonSchema {
+            json_schema: ResponseFormatJsonSchema {
+                description: None,
+                name: "move".to_string(),
+                strict: Some(strict.is_some()),
+                schema: Some(strict.unwrap_or_else(|| schema.clone())),
+            },
+        }
+    }
+
+    pub async fn execute_turn(&self, request: &MoveRequest) -> AgentResult<MoveResponse> {
+        let req = self.build_request(request)?;
 
         // Use the client that was created with the API key during initialization
         // No environment variable manipulation needed - eliminates race conditions
-        let resp = self.client
-            .chat()
-            .create(req)
-            .await
-            .map_err(|e| AgentError::Internal(format!("openai: {}", e)))?;
+        let resp = with_retry(self.retry, &self.provider(), &self.model, async || {
+            self.client.chat().create(req.clone()).await.map_err(map_error)
+        })
+        .await?;
 
+        Self::move_response(resp)
+    }
+
+    fn move_response(resp: CreateChatCompletionResponse) -> AgentResult<MoveResponse> {
         let content = resp
             .choices
-            .get(0)
+            .first()
             .and_then(|c| c.message.content.as_deref())
             .ok_or_else(|| AgentError::InvalidResponse("missing content".into()))?;
 
         let chosen_move: Value = serde_json::from_str(content)
-            .map_err(|e| AgentError::InvalidResponse(format!("non-json: {}", e)))?;
+            .map_err(|e| AgentError::InvalidResponse(format!("non-json: {} (raw: {})", e, content)))?;
 
         Ok(MoveResponse {
             chosen_move,
-            diagnostics: None,
+            diagnostics: Some(content.to_string()),
+            perturbation: None,
+            replay_consistent: None,
+            usage: resp.usage.as_ref().map(|u| TokenUsage {
+                prompt_tokens: u.prompt_tokens,
+                completion_tokens: u.completion_tokens,
+            }),
+            served_model: Some(ServedModel {
+                model: resp.model,
+                system_fingerprint: resp.system_fingerprint,
+            }),
         })
     }
 }
+
+/// Whether `model` accepts a `json_schema` response format; older models
+/// only have JSON mode
+fn supports_json_schema(model: &str) -> bool {
+    let legacy = model.starts_with("gpt-3.5")
+        || model == "gpt-4"
+        || model.starts_with("gpt-4-")
+        || model == "gpt-4o-2024-05-13"
+        || model.starts_with("o1-mini")
+        || model.starts_with("o1-preview");
+    !legacy
+}
+
+/// `schema` with every object closed to extra properties, as strict mode
+/// requires. Strict mode has no optional properties, so the optional
+/// `reasoning` becomes a required one that may be null. `None` if some
+/// object has any other optional property.
+fn strict_schema(schema: &Value) -> Option<Value> {
+    match schema {
+        Value::Object(map) => {
+            let mut strict: Map<String, Value> =
+                map.iter().map(|(key, value)| Some((key.clone(), strict_schema(value)?))).collect::<Option<_>>()?;
+            if map.get("type") == Some(&Value::from("object")) {
+                let mut required = map.get("required").and_then(Value::as_array).cloned().unwrap_or_default();
+                if let Some(properties) = strict.get_mut("properties").and_then(Value::as_object_mut) {
+                    if let Some(reasoning) = properties.get_mut(REASONING_FIELD)
+                        && !required.contains(&Value::from(REASONING_FIELD))
+                    {
+                        reasoning["type"] = json!(["string", "null"]);
+                        required.push(Value::from(REASONING_FIELD));
+                    }
+                    if !properties.keys().all(|key| required.contains(&Value::from(key.as_str()))) {
+                        return None;
+                    }
+                    strict.insert("required".to_string(), Value::Array(required));
+                }
+                strict.insert("additionalProperties".to_string(), Value::Bool(false));
+            }
+            Some(Value::Object(strict))
+        }
+        Value::Array(items) => items.iter().map(strict_schema).collect::<Option<Vec<_>>>().map(Value::Array),
+        other => Some(other.clone()),
+    }
+}
+
+fn map_error(e: OpenAIError) -> AgentError {
+    match e {
+        OpenAIError::ApiError(api) if api.code.as_deref() == Some("rate_limit_exceeded") => {
+            AgentError::RateLimited { retry_after: None }
+        }
+        // 5xx bodies aren't JSON, so the client reports them with no error type
+        OpenAIError::ApiError(api) if api.r#type.is_none() => AgentError::Transient(format!("openai: {}", api.message)),
+        OpenAIError::Reqwest(e) => AgentError::Transient(format!("openai: {}", e)),
+        e => AgentError::Internal(format!("openai: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent_config::{DEFAULT_MAX_TOKENS, DEFAULT_REASONING_MAX_TOKENS};
+    use crate::games::Game;
+    use serde_json::json;
+
+    #[test]
+    fn test_request_carries_max_tokens() {
+        let agent = OpenAIAgent::new("test", "gpt-4o-mini", "sk-test").unwrap();
+        let request = MoveRequest {
+            turn_index: 1,
+            game_id: "ttt_test".to_string(),
+            state: json!({}),
+            expected_move_schema: json!({}),
+            legal_moves: None,
+        };
+
+        let req = agent.build_request(&request).unwrap();
+        assert_eq!(req.max_completion_tokens, Some(DEFAULT_MAX_TOKENS));
+
+        // Room for the reasoning every game's schema offers, unless capped
+        let reasoning = MoveRequest {
+            turn_index: 1,
+            game_id: "c4_test".to_string(),
+            state: json!({}),
+            expected_move_schema: Game::new("ConnectFour").unwrap().move_schema(),
+            legal_moves: None,
+        };
+        let req = agent.build_request(&reasoning).unwrap();
+        assert_eq!(req.max_completion_tokens, Some(DEFAULT_REASONING_MAX_TOKENS));
+
+        let agent = agent.with_max_tokens(Some(64));
+        assert_eq!(agent.build_request(&request).unwrap().max_completion_tokens, Some(64));
+        assert_eq!(agent.build_request(&reasoning).unwrap().max_completion_tokens, Some(64));
+    }
+
+    #[test]
+    fn test_request_carries_the_move_schema() {
+        let request = |expected_move_schema| MoveRequest {
+            turn_index: 1,
+            game_id: "ttt_test".to_string(),
+            state: json!({}),
+            expected_move_schema,
+            legal_moves: None,
+        };
+        let cell = json!({
+            "type": "object",
+            "properties": {"row": {"type": "integer"}, "col": {"type": "integer"}},
+            "required": ["row", "col"]
+        });
+        let agent = OpenAIAgent::new("test", "gpt-4o-mini", "sk-test").unwrap();
+
+        let req = serde_json::to_value(agent.build_request(&request(cell.clone())).unwrap()).unwrap();
+        let format = &req["response_format"];
+        assert_eq!(format["type"], "json_schema");
+        assert_eq!(format["json_schema"]["strict"], true);
+        assert_eq!(format["json_schema"]["schema"]["properties"], cell["properties"]);
+        assert_eq!(format["json_schema"]["schema"]["additionalProperties"], false);
+
+        // An optional field can't be strict, but the schema still goes along
+        let with_chain = json!({
+            "type": "object",
+            "properties": {"to": cell, "chain": {"type": "array", "items": cell}},
+            "required": ["to"]
+        });
+        let req = serde_json::to_value(agent.build_request(&request(with_chain.clone())).unwrap()).unwrap();
+        assert_eq!(req["response_format"]["json_schema"]["strict"], false);
+        assert_eq!(req["response_format"]["json_schema"]["schema"], with_chain);
+
+        // The optional reasoning every game offers is the exception: it turns
+        // into a required property that may be null
+        let schema = crate::games::Game::new("ConnectFour").unwrap().move_schema();
+        let req = serde_json::to_value(agent.build_request(&request(schema)).unwrap()).unwrap();
+        let strict = &req["response_format"]["json_schema"];
+        assert_eq!(strict["strict"], true);
+        assert_eq!(strict["schema"]["required"], json!(["column", "reasoning"]));
+        assert_eq!(strict["schema"]["properties"]["reasoning"]["type"], json!(["string", "null"]));
+
+        let legacy = OpenAIAgent::new("test", "gpt-3.5-turbo", "sk-test").unwrap();
+        let req = serde_json::to_value(legacy.build_request(&request(cell)).unwrap()).unwrap();
+        assert_eq!(req["response_format"], json!({"type": "json_object"}));
+    }
+
+    #[test]
+    fn test_base_url_overrides_the_api_base() {
+        let agent = OpenAIAgent::new("test", "gpt-4o-mini", "sk-test").unwrap();
+        assert_eq!(agent.base_url(), "https://api.openai.com/v1");
+        assert_eq!(agent.provider(), "OpenAI");
+
+        let agent = agent.with_base_url("http://localhost:8000/v1");
+        assert_eq!(agent.base_url(), "http://localhost:8000/v1");
+        // Its rate limits and retries are its own
+        assert_eq!(agent.provider(), "OpenAI @ http://localhost:8000/v1");
+        // The key survives the rebuilt client
+        assert_eq!(agent.client.config().headers()["authorization"], "Bearer sk-test");
+
+        // A compatible server may not take json_schema, so it isn't sent one
+        let request = MoveRequest {
+            turn_index: 1,
+            game_id: "ttt_test".to_string(),
+            state: json!({}),
+            expected_move_schema: crate::games::Game::new("TicTacToe").unwrap().move_schema(),
+            legal_moves: None,
+        };
+        let req = serde_json::to_value(agent.build_request(&request).unwrap()).unwrap();
+        assert_eq!(req["response_format"], json!({"type": "json_object"}));
+    }
+
+    #[test]
+    fn test_temp_schedule_sets_per_turn_temperature() {
+        let agent = OpenAIAgent::new("test", "gpt-4o-mini", "sk-test")
+            .unwrap()
+            .with_temperature(0.7)
+            .with_temp_schedule(vec![1.0, 0.0]);
+        let request = |turn_index| MoveRequest {
+            turn_index,
+            game_id: "ttt_test".to_string(),
+            state: json!({}),
+            expected_move_schema: json!({}),
+            legal_moves: None,
+        };
+
+        assert_eq!(agent.build_request(&request(1)).unwrap().temperature, Some(1.0));
+        assert_eq!(agent.build_request(&request(2)).unwrap().temperature, Some(0.0));
+
+        let fixed = OpenAIAgent::new("test", "gpt-4o-mini", "sk-test").unwrap().with_temperature(0.7);
+        assert_eq!(fixed.build_request(&request(2)).unwrap().temperature, Some(0.7));
+    }
+
+    #[test]
+    fn test_request_carries_seed() {
+        let request = MoveRequest {
+            turn_index: 1,
+            game_id: "ttt_test".to_string(),
+            state: json!({}),
+            expected_move_schema: json!({}),
+            legal_moves: None,
+        };
+
+        let unseeded = OpenAIAgent::new("test", "gpt-4o-mini", "sk-test").unwrap();
+        assert_eq!(unseeded.build_request(&request).unwrap().seed, None);
+
+        let seeded = OpenAIAgent::new("test", "gpt-4o-mini", "sk-test").unwrap().with_seed(Some(42));
+        assert_eq!(seeded.build_request(&request).unwrap().seed, Some(42));
+    }
+
+    #[test]
+    fn test_system_prompt_is_the_system_message() {
+        let agent = OpenAIAgent::new("test", "gpt-4o-mini", "sk-test")
+            .unwrap()
+            .with_system_prompt("You are a cautious player.");
+        let request = MoveRequest {
+            turn_index: 1,
+            game_id: "ttt_test".to_string(),
+            state: json!({}),
+            expected_move_schema: json!({}),
+            legal_moves: None,
+        };
+
+        let req = serde_json::to_value(agent.build_request(&request).unwrap()).unwrap();
+        assert_eq!(req["messages"][0]["role"], "system");
+        assert_eq!(req["messages"][0]["content"], "You are a cautious player.");
+    }
+
+    #[test]
+    fn test_response_records_served_model() {
+        // Asked for the "gpt-4o" alias; the provider reports the dated version
+        let resp: CreateChatCompletionResponse = serde_json::from_value(json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 0,
+            "model": "gpt-4o-2024-08-06",
+            "system_fingerprint": "fp_abc123",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "{\"row\": 1, \"col\": 1}"},
+                "finish_reason": "stop"
+            }]
+        }))
+        .unwrap();
+
+        let response = OpenAIAgent::move_response(resp).unwrap();
+        assert_eq!(response.chosen_move, json!({"row": 1, "col": 1}));
+        assert_eq!(response.diagnostics.as_deref(), Some("{\"row\": 1, \"col\": 1}"));
+        assert_eq!(
+            response.served_model,
+            Some(ServedModel {
+                model: "gpt-4o-2024-08-06".to_string(),
+                system_fingerprint: Some("fp_abc123".to_string()),
+            })
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use clap::ValueEnum;
+use serde_json::{Value, json};
+
+use crate::agent::{AIAgent, AgentResult, MoveRequest, MoveResponse};
+
+/// Ways the state shown to an agent can be degraded for robustness tests.
+/// The game itself always validates moves against the true state.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum PerturbationKind {
+    /// Drop the first of `current_player`, `turn_number` or `round` present
+    OmitField,
+    /// Add a `decoy` field that carries no game information
+    DecoyField,
+    /// Report the first empty board cell as occupied
+    MisreportCell,
+}
+
+const OMITTABLE_FIELDS: &[&str] = &["current_player", "turn_number", "round"];
+
+/// Apply `kind` to a copy of `state`. Returns the perturbed state and a
+/// description of what changed, or `None` if the state had nothing to perturb.
+pub fn perturb_state(state: &Value, kind: PerturbationKind) -> (Value, Option<String>) {
+    let mut perturbed = state.clone();
+    let Some(obj) = perturbed.as_object_mut() else {
+        return (perturbed, None);
+    };
+
+    let applied = match kind {
+        PerturbationKind::OmitField => OMITTABLE_FIELDS
+            .iter()
+            .find(|field| obj.remove(**field).is_some())
+            .map(|field| format!("omit-field({})", field)),
+        PerturbationKind::DecoyField => {
+            obj.insert("decoy".to_string(), json!("this field is noise; ignore it"));
+            Some("decoy-field(decoy)".to_string())
+        }
+        PerturbationKind::MisreportCell => {
+            // Pretend a piece of the player to move (or any piece) sits there
+            let symbol = obj
+                .get("current_player")
+                .cloned()
+                .unwrap_or_else(|| json!("?"));
+            obj.get_mut("board")
+                .and_then(Value::as_array_mut)
+                .and_then(|rows| {
+                    rows.iter_mut().enumerate().find_map(|(r, row)| {
+                        let cell = row
+                            .as_array_mut()?
+                            .iter_mut()
+                            .enumerate()
+                            .find(|(_, cell)| cell.is_null())?;
+                        *cell.1 = symbol.clone();
+                        Some(format!("misreport-cell({},{})", r, cell.0))
+                    })
+                })
+        }
+    };
+
+    (perturbed, applied)
+}
+
+/// Which agent is shown a perturbed state, and how
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Perturbation {
+    pub kind: PerturbationKind,
+    /// Index of the affected agent in the game's agent list
+    pub seat: usize,
+}
+
+impl Perturbation {
+    /// Wrap the agent in `seat`, leaving the others untouched
+    pub fn apply(&self, agents: Vec<AIAgent>) -> Vec<AIAgent> {
+        agents
+            .into_iter()
+            .enumerate()
+            .map(|(i, agent)| {
+                if i == self.seat {
+                    AIAgent::Perturbed(PerturbedAgent::new(agent, self.kind))
+                } else {
+                    agent
+                }
+            })
+            .collect()
+    }
+}
+
+/// Wraps an agent and perturbs every state it is shown
+pub struct PerturbedAgent {
+    inner: Box<AIAgent>,
+    kind: PerturbationKind,
+}
+
+impl PerturbedAgent {
+    pub fn new(inner: AIAgent, kind: PerturbationKind) -> Self {
+        Self {
+            inner: Box::new(inner),
+            kind,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    pub fn system_prompt(&self) -> Option<&str> {
+        self.inner.system_prompt()
+    }
+
+    pub fn join_game(&mut self, game: &str) {
+        self.inner.join_game(game);
+    }
+
+    pub async fn execute_turn(&self, request: &MoveRequest) -> AgentResult<MoveResponse> {
+        let (state, applied) = perturb_state(&request.state, self.kind);
+        let perturbed = MoveRequest {
+            turn_index: request.turn_index,
+            game_id: request.game_id.clone(),
+            state,
+            expected_move_schema: request.expected_move_schema.clone(),
+            legal_moves: request.legal_moves.clone(),
+        };
+
+        let mut response = Box::pin(self.inner.execute_turn(&perturbed)).await?;
+        response.perturbation = applied;
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> Value {
+        json!({
+            "board": [["X", null], [null, null]],
+            "current_player": "O",
+            "turn_number": 1,
+        })
+    }
+
+    #[test]
+    fn test_omit_field() {
+        let (perturbed, applied) = perturb_state(&state(), PerturbationKind::OmitField);
+        assert_eq!(applied.as_deref(), Some("omit-field(current_player)"));
+        assert!(perturbed.get("current_player").is_none());
+        assert_eq!(perturbed["board"], state()["board"]);
+    }
+
+    #[test]
+    fn test_decoy_field() {
+        let (perturbed, applied) = perturb_state(&state(), PerturbationKind::DecoyField);
+        assert_eq!(applied.as_deref(), Some("decoy-field(decoy)"));
+        let mut without_decoy = perturbed.clone();
+        without_decoy.as_object_mut().unwrap().remove("decoy");
+        assert_eq!(without_decoy, state());
+    }
+
+    #[test]
+    fn test_misreport_cell() {
+        let (perturbed, applied) = perturb_state(&state(), PerturbationKind::MisreportCell);
+        assert_eq!(applied.as_deref(), Some("misreport-cell(0,1)"));
+        assert_eq!(perturbed["board"], json!([["X", "O"], [null, null]]));
+    }
+
+    #[tokio::test]
+    async fn test_validation_uses_true_state() {
+        use crate::agents::socket::scripted_socket_agent;
+        use crate::games::Game;
+
+        let x = scripted_socket_agent(
+            "x",
+            vec![json!({"row": 0, "col": 0}), json!({"row": 1, "col": 0}), json!({"row": 2, "col": 0})],
+        )
+        .await;
+        // O is told (0, 1) is taken but plays there anyway; the true board has it free
+        let o = scripted_socket_agent("o", vec![json!({"row": 0, "col": 1}), json!({"row": 1, "col": 1})]).await;
+        let perturbation = Perturbation {
+            kind: PerturbationKind::MisreportCell,
+            seat: 1,
+        };
+
+        let agents = perturbation.apply(vec![AIAgent::Socket(x), AIAgent::Socket(o)]);
+        let result = Game::new("TicTacToe").unwrap().play_with_agents(agents).await;
+        let turns = &result.stats().turns;
+
+        assert_eq!(turns[0].perturbation, None);
+        assert_eq!(turns[1].perturbation.as_deref(), Some("misreport-cell(0,1)"));
+        assert!(turns[1].move_valid);
+        assert!(turns[1].state_before["board"][0][1].is_null());
+        assert_eq!(turns[3].perturbation.as_deref(), Some("misreport-cell(0,2)"));
+        assert_eq!(result.winner().map(String::as_str), Some("x (X)"));
+    }
+
+    #[test]
+    fn test_nothing_to_perturb() {
+        let (perturbed, applied) = perturb_state(&json!({"round": 1}), PerturbationKind::MisreportCell);
+        assert_eq!(applied, None);
+        assert_eq!(perturbed, json!({"round": 1}));
+    }
+}
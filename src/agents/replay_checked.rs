@@ -0,0 +1,81 @@
+use crate::agent::{AIAgent, AgentResult, MoveRequest, MoveResponse};
+
+/// Wraps a temperature-0 agent and asks it for every move twice with the
+/// identical request, flagging turns where the two answers differ. The game
+/// plays the first answer.
+pub struct ReplayCheckedAgent {
+    inner: Box<AIAgent>,
+}
+
+impl ReplayCheckedAgent {
+    pub fn new(inner: AIAgent) -> Self {
+        Self { inner: Box::new(inner) }
+    }
+
+    pub fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    pub fn system_prompt(&self) -> Option<&str> {
+        self.inner.system_prompt()
+    }
+
+    pub fn join_game(&mut self, game: &str) {
+        self.inner.join_game(game);
+    }
+
+    pub async fn execute_turn(&self, request: &MoveRequest) -> AgentResult<MoveResponse> {
+        let mut response = Box::pin(self.inner.execute_turn(request)).await?;
+        // A failed replay says nothing about determinism, so leave it unknown
+        if let Ok(replay) = Box::pin(self.inner.execute_turn(request)).await {
+            response.replay_consistent = Some(replay.chosen_move == response.chosen_move);
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::socket::scripted_socket_agent;
+    use crate::games::reliability::reliability_rows;
+    use crate::games::Game;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_differing_replay_is_reported() {
+        // X answers its first position two different ways, then steadily
+        let x = scripted_socket_agent(
+            "x",
+            vec![
+                json!({"row": 0, "col": 0}),
+                json!({"row": 2, "col": 2}),
+                json!({"row": 1, "col": 0}),
+                json!({"row": 1, "col": 0}),
+                json!({"row": 2, "col": 0}),
+                json!({"row": 2, "col": 0}),
+            ],
+        )
+        .await;
+        let o = scripted_socket_agent("o", vec![json!({"row": 0, "col": 1}), json!({"row": 1, "col": 1})]).await;
+
+        let agents = vec![
+            AIAgent::ReplayChecked(ReplayCheckedAgent::new(AIAgent::Socket(x))),
+            AIAgent::Socket(o),
+        ];
+        let result = Game::new("TicTacToe").unwrap().play_with_agents(agents).await;
+        let turns = &result.stats().turns;
+
+        assert_eq!(turns[0].replay_consistent, Some(false));
+        assert_eq!(turns[0].move_made, json!({"row": 0, "col": 0}));
+        assert_eq!(turns[1].replay_consistent, None);
+        assert_eq!(turns[2].replay_consistent, Some(true));
+        assert_eq!(result.winner().map(String::as_str), Some("x (X)"));
+
+        let rows = reliability_rows(std::slice::from_ref(&result));
+        let x_row = rows.iter().find(|r| r.agent == "x").unwrap();
+        assert_eq!((x_row.replayed_turns, x_row.replay_mismatches), (3, 1));
+        assert!((x_row.temp0_stability().unwrap() - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(rows.iter().find(|r| r.agent == "o").unwrap().temp0_stability(), None);
+    }
+}
@@ -0,0 +1,138 @@
+use std::cell::Cell;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::agent::{AgentError, AgentResult, MoveRequest, MoveResponse};
+
+/// Plays a fixed list of moves in order, whatever the position, and fails
+/// once they run out. Needs no network, so whole games can be played
+/// deterministically in tests.
+pub struct ScriptedAgent {
+    name: String,
+    moves: Vec<Value>,
+    /// Index of the next move to play
+    next: Cell<usize>,
+}
+
+impl ScriptedAgent {
+    pub fn new(name: impl Into<String>, moves: Vec<Value>) -> Self {
+        Self {
+            name: name.into(),
+            moves,
+            next: Cell::new(0),
+        }
+    }
+
+    /// Load the moves from a file holding a JSON array of moves
+    pub fn from_file(name: impl Into<String>, path: &Path) -> Result<Self, AgentError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| AgentError::InvalidRequest(format!("read {}: {}", path.display(), e)))?;
+        let moves = serde_json::from_str(&contents)
+            .map_err(|e| AgentError::InvalidRequest(format!("{} is not a JSON array of moves: {}", path.display(), e)))?;
+        Ok(Self::new(name, moves))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub async fn execute_turn(&self, _request: &MoveRequest) -> AgentResult<MoveResponse> {
+        let next = self.next.get();
+        let chosen_move = self
+            .moves
+            .get(next)
+            .cloned()
+            .ok_or_else(|| AgentError::Internal(format!("script exhausted after {} move(s)", self.moves.len())))?;
+        self.next.set(next + 1);
+        Ok(MoveResponse::new(chosen_move))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::AIAgent;
+    use crate::games::engine::TurnRules;
+    use crate::games::tic_tac_toe::{TicTacToe, TicTacToeConfig};
+    use serde_json::json;
+
+    fn cells(cells: &[(u32, u32)]) -> Vec<Value> {
+        cells.iter().map(|(row, col)| json!({"row": row, "col": col})).collect()
+    }
+
+    fn agents(x: &[(u32, u32)], o: &[(u32, u32)]) -> Vec<AIAgent> {
+        vec![
+            AIAgent::Scripted(ScriptedAgent::new("x", cells(x))),
+            AIAgent::Scripted(ScriptedAgent::new("o", cells(o))),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_moves_are_played_in_order_then_exhausted() {
+        let agent = ScriptedAgent::new("s", vec![json!({"row": 0, "col": 0}), json!({"row": 1, "col": 1})]);
+        let request = MoveRequest {
+            turn_index: 1,
+            game_id: "ttt_test".to_string(),
+            state: json!({}),
+            expected_move_schema: json!({}),
+            legal_moves: None,
+        };
+
+        assert_eq!(agent.execute_turn(&request).await.unwrap().chosen_move, json!({"row": 0, "col": 0}));
+        assert_eq!(agent.execute_turn(&request).await.unwrap().chosen_move, json!({"row": 1, "col": 1}));
+        let err = agent.execute_turn(&request).await.unwrap_err();
+        assert_eq!(err.to_string(), "internal error: script exhausted after 2 move(s)");
+    }
+
+    #[tokio::test]
+    async fn test_tic_tac_toe_win() {
+        let result = TicTacToe::new(TicTacToeConfig::default())
+            .play_game(agents(&[(0, 0), (1, 1), (2, 2)], &[(0, 1), (0, 2)]))
+            .await;
+        assert_eq!(result.winner.as_deref(), Some("x (X)"));
+        assert_eq!(result.stats.total_turns(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_tic_tac_toe_draw() {
+        let result = TicTacToe::new(TicTacToeConfig::default())
+            .play_game(agents(
+                &[(0, 0), (0, 2), (1, 0), (2, 1), (1, 2)],
+                &[(0, 1), (1, 1), (2, 0), (2, 2)],
+            ))
+            .await;
+        assert_eq!(result.winner, None);
+        assert!(result.stats.draw);
+    }
+
+    #[tokio::test]
+    async fn test_tic_tac_toe_forfeit() {
+        let config = TicTacToeConfig {
+            rules: TurnRules {
+                max_retries: Some(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        // O keeps playing the taken center
+        let result = TicTacToe::new(config)
+            .play_game(agents(&[(1, 1)], &[(1, 1), (1, 1)]))
+            .await;
+        assert_eq!(result.winner.as_deref(), Some("x (X) (opponent forfeited)"));
+        assert_eq!(result.stats.invalid_moves, 2);
+    }
+
+    #[tokio::test]
+    async fn test_from_file() {
+        let path = std::env::temp_dir().join(format!("ai_arena_moves_{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(&path, r#"[{"column": 3}]"#).unwrap();
+        let agent = ScriptedAgent::from_file("s", &path).unwrap();
+        std::fs::write(&path, r#"{"column": 3}"#).unwrap();
+        let err = ScriptedAgent::from_file("s", &path).err().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(agent.moves, vec![json!({"column": 3})]);
+        assert!(matches!(err, AgentError::InvalidRequest(_)), "{}", err);
+    }
+}
@@ -0,0 +1,215 @@
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+
+use crate::agent::{AgentError, AgentResult, MoveRequest, MoveResponse};
+
+/// Longest `MoveResponse` line accepted, newline included
+const MAX_RESPONSE_BYTES: u64 = 64 * 1024;
+
+/// An agent living in another process. Each turn the `MoveRequest` is written
+/// to the connection as one line of JSON and a single `MoveResponse` line is
+/// read back, so agents can be written in any language.
+pub struct SocketAgent {
+    name: String,
+    stream: Mutex<BufReader<TcpStream>>,
+}
+
+impl SocketAgent {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn new(name: impl Into<String>, stream: TcpStream) -> Self {
+        Self::from_reader(name, BufReader::new(stream))
+    }
+
+    /// Take over a connection something has already read from, keeping
+    /// whatever it buffered past its last line
+    pub fn from_reader(name: impl Into<String>, stream: BufReader<TcpStream>) -> Self {
+        Self {
+            name: name.into(),
+            stream: Mutex::new(stream),
+        }
+    }
+
+    pub async fn connect(name: impl Into<String>, addr: &str) -> Result<Self, AgentError> {
+        let stream = TcpStream::connect(addr)
+            .await
+            .map_err(|e| AgentError::Internal(format!("failed to connect to {}: {}", addr, e)))?;
+        Ok(Self::new(name, stream))
+    }
+
+    pub async fn execute_turn(&self, request: &MoveRequest) -> AgentResult<MoveResponse> {
+        let mut payload = serde_json::to_string(request)
+            .map_err(|e| AgentError::InvalidRequest(format!("serialize move request: {}", e)))?;
+        payload.push('\n');
+
+        let mut stream = self.stream.lock().await;
+        stream
+            .get_mut()
+            .write_all(payload.as_bytes())
+            .await
+            .map_err(|e| AgentError::Internal(format!("socket write failed: {}", e)))?;
+
+        let mut line = String::new();
+        let read = (&mut *stream)
+            .take(MAX_RESPONSE_BYTES)
+            .read_line(&mut line)
+            .await
+            .map_err(|e| AgentError::Internal(format!("socket read failed: {}", e)))?;
+        if read == 0 {
+            return Err(AgentError::Internal("socket closed by agent".into()));
+        }
+        if !line.ends_with('\n') && line.len() as u64 == MAX_RESPONSE_BYTES {
+            return Err(AgentError::InvalidResponse(format!(
+                "response is longer than {} bytes",
+                MAX_RESPONSE_BYTES
+            )));
+        }
+
+        serde_json::from_str(line.trim())
+            .map_err(|e| AgentError::InvalidResponse(format!("non-json: {}", e)))
+    }
+}
+
+/// A socket agent backed by an in-process peer that answers each request with
+/// the next scripted move and hangs up once the script runs out
+#[cfg(test)]
+pub(crate) async fn scripted_socket_agent(name: &str, moves: Vec<serde_json::Value>) -> SocketAgent {
+    let replies = moves
+        .into_iter()
+        .map(|chosen_move| serde_json::json!({"chosen_move": chosen_move}))
+        .collect();
+    scripted_socket_replies(name, replies).await
+}
+
+/// Like `scripted_socket_agent`, but each entry is a whole `MoveResponse`
+#[cfg(test)]
+pub(crate) async fn scripted_socket_replies(name: &str, replies: Vec<serde_json::Value>) -> SocketAgent {
+    scripted_peer(name, replies, true, std::time::Duration::ZERO).await
+}
+
+/// Like `scripted_socket_agent`, but once the script runs out the peer stays
+/// connected and never answers again
+#[cfg(test)]
+pub(crate) async fn stalling_socket_agent(name: &str, moves: Vec<serde_json::Value>) -> SocketAgent {
+    let replies = moves
+        .into_iter()
+        .map(|chosen_move| serde_json::json!({"chosen_move": chosen_move}))
+        .collect();
+    scripted_peer(name, replies, false, std::time::Duration::ZERO).await
+}
+
+/// Like `scripted_socket_agent`, but the peer waits `delay` before each reply
+#[cfg(test)]
+pub(crate) async fn delayed_socket_agent(name: &str, moves: Vec<serde_json::Value>, delay: std::time::Duration) -> SocketAgent {
+    let replies = moves
+        .into_iter()
+        .map(|chosen_move| serde_json::json!({"chosen_move": chosen_move}))
+        .collect();
+    scripted_peer(name, replies, true, delay).await
+}
+
+#[cfg(test)]
+async fn scripted_peer(name: &str, replies: Vec<serde_json::Value>, hang_up: bool, delay: std::time::Duration) -> SocketAgent {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut stream = BufReader::new(stream);
+        for reply in replies {
+            let mut line = String::new();
+            if stream.read_line(&mut line).await.unwrap_or(0) == 0 {
+                return;
+            }
+            tokio::time::sleep(delay).await;
+            let reply = format!("{}\n", reply);
+            if stream.get_mut().write_all(reply.as_bytes()).await.is_err() {
+                return;
+            }
+        }
+        if !hang_up {
+            std::future::pending::<()>().await;
+        }
+    });
+    SocketAgent::connect(name, &addr).await.unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tokio::net::TcpListener;
+
+    fn request() -> MoveRequest {
+        MoveRequest {
+            turn_index: 1,
+            game_id: "ttt_test".to_string(),
+            state: json!({"board": [[null]]}),
+            expected_move_schema: json!({"type": "object"}),
+            legal_moves: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_socket_agent_round_trip() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut stream = BufReader::new(stream);
+            let mut line = String::new();
+            stream.read_line(&mut line).await.unwrap();
+            let request: MoveRequest = serde_json::from_str(&line).unwrap();
+            let reply = json!({"chosen_move": {"row": 0, "col": 0}, "diagnostics": request.game_id});
+            stream
+                .get_mut()
+                .write_all(format!("{}\n", reply).as_bytes())
+                .await
+                .unwrap();
+        });
+
+        let agent = SocketAgent::connect("remote", &addr).await.unwrap();
+        let response = agent.execute_turn(&request()).await.unwrap();
+        server.await.unwrap();
+
+        assert_eq!(agent.name(), "remote");
+        assert_eq!(response.chosen_move, json!({"row": 0, "col": 0}));
+        assert_eq!(response.diagnostics.as_deref(), Some("ttt_test"));
+    }
+
+    #[tokio::test]
+    async fn test_socket_agent_closed_connection() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            drop(stream);
+        });
+
+        let agent = SocketAgent::connect("remote", &addr).await.unwrap();
+        server.await.unwrap();
+        let err = agent.execute_turn(&request()).await.unwrap_err();
+        assert!(matches!(err, AgentError::Internal(_)));
+    }
+
+    #[tokio::test]
+    async fn test_socket_agent_rejects_oversized_response() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            // Never sends a newline; the agent must stop reading anyway
+            let _ = stream.write_all(&vec![b'a'; 1 << 20]).await;
+        });
+
+        let agent = SocketAgent::connect("remote", &addr).await.unwrap();
+        let err = agent.execute_turn(&request()).await.unwrap_err();
+        assert!(matches!(err, AgentError::InvalidResponse(_)));
+        assert!(err.to_string().contains("longer than 65536 bytes"));
+        drop(agent);
+        server.await.unwrap();
+    }
+}
@@ -0,0 +1,152 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// One finished repetition of a CSV batch test case
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CompletedUnit {
+    /// Index of the test case among the batch's runnable rows
+    pub case: usize,
+    pub rep: u32,
+}
+
+/// Which units of a CSV batch have finished, saved as the batch runs so an
+/// interrupted batch can be resumed with `--resume`
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BatchProgress {
+    pub csv_path: String,
+    /// Number of runnable test cases when the batch started; a checkpoint
+    /// for a file whose rows have since changed is not resumed
+    pub cases: usize,
+    pub completed: Vec<CompletedUnit>,
+}
+
+impl BatchProgress {
+    /// Repetitions of `case` already played. Repetitions are recorded in
+    /// order, so these are the first ones.
+    pub fn completed_reps(&self, case: usize) -> u32 {
+        self.completed.iter().filter(|unit| unit.case == case).count() as u32
+    }
+}
+
+/// Directory holding batch checkpoints, one file per CSV path
+pub fn default_progress_dir() -> PathBuf {
+    if let Ok(state_dir) = std::env::var("XDG_STATE_HOME") {
+        PathBuf::from(state_dir).join("ai_arena").join("progress")
+    } else if let Some(home) = dirs::home_dir() {
+        home.join(".local").join("state").join("ai_arena").join("progress")
+    } else {
+        PathBuf::from(".ai_arena").join("progress")
+    }
+}
+
+/// Checkpoint file for the batch in `csv_path`, named by a hash of the
+/// canonical path so unrelated batches don't collide
+pub fn progress_path(dir: &Path, csv_path: &str) -> PathBuf {
+    let canonical = fs::canonicalize(csv_path).unwrap_or_else(|_| PathBuf::from(csv_path));
+    dir.join(format!("{:016x}.json", fnv1a(canonical.to_string_lossy().as_bytes())))
+}
+
+/// FNV-1a, which unlike `DefaultHasher` is stable across Rust releases
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| (hash ^ u64::from(b)).wrapping_mul(0x100000001b3))
+}
+
+/// A batch checkpoint file, shared by everything recording into it
+#[derive(Clone, Debug)]
+pub struct BatchCheckpoint {
+    path: PathBuf,
+    progress: Arc<Mutex<BatchProgress>>,
+}
+
+impl BatchCheckpoint {
+    /// Start a checkpoint at `path` for `cases` test cases of `csv_path`.
+    /// With `resume`, units recorded there by an earlier run of the same
+    /// batch are kept; otherwise the batch starts from zero.
+    pub fn open(path: PathBuf, csv_path: &str, cases: usize, resume: bool) -> Result<Self, String> {
+        let fresh = BatchProgress {
+            csv_path: csv_path.to_string(),
+            cases,
+            completed: Vec::new(),
+        };
+        let progress = if resume && path.exists() {
+            let contents = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            let saved: BatchProgress = serde_json::from_str(&contents)
+                .map_err(|e| format!("Invalid batch progress in {}: {}", path.display(), e))?;
+            if saved.cases == cases {
+                saved
+            } else {
+                tracing::warn!(
+                    "Not resuming {}: its checkpoint was written for {} test case(s), it now has {}",
+                    csv_path, saved.cases, cases
+                );
+                fresh
+            }
+        } else {
+            fresh
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let checkpoint = BatchCheckpoint {
+            path,
+            progress: Arc::new(Mutex::new(progress)),
+        };
+        checkpoint.save()?;
+        Ok(checkpoint)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn completed_reps(&self, case: usize) -> u32 {
+        self.progress.lock().unwrap().completed_reps(case)
+    }
+
+    /// Record that `rep` of `case` finished and save the checkpoint
+    pub fn record(&self, case: usize, rep: u32) -> Result<(), String> {
+        self.progress.lock().unwrap().completed.push(CompletedUnit { case, rep });
+        self.save()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let contents = serde_json::to_string_pretty(&*self.progress.lock().unwrap())
+            .map_err(|e| format!("Failed to serialize batch progress: {}", e))?;
+        fs::write(&self.path, contents).map_err(|e| format!("Failed to write {}: {}", self.path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_path_is_per_csv_file() {
+        let dir = Path::new("progress");
+        let one = progress_path(dir, "batches/one.csv");
+        assert_eq!(one, progress_path(dir, "batches/one.csv"));
+        assert_ne!(one, progress_path(dir, "batches/two.csv"));
+        assert_eq!(one.parent(), Some(dir));
+    }
+
+    #[test]
+    fn test_resume_keeps_recorded_units_only_when_asked() {
+        let path = std::env::temp_dir().join(format!("ai_arena_progress_{}.json", uuid::Uuid::new_v4()));
+        let checkpoint = BatchCheckpoint::open(path.clone(), "cases.csv", 2, false).unwrap();
+        checkpoint.record(0, 0).unwrap();
+        checkpoint.record(0, 1).unwrap();
+        checkpoint.record(1, 0).unwrap();
+
+        let resumed = BatchCheckpoint::open(path.clone(), "cases.csv", 2, true).unwrap();
+        assert_eq!((resumed.completed_reps(0), resumed.completed_reps(1)), (2, 1));
+
+        // The file gained a row since the checkpoint was written
+        let changed = BatchCheckpoint::open(path.clone(), "cases.csv", 3, true).unwrap();
+        assert_eq!(changed.completed_reps(0), 0);
+
+        let restarted = BatchCheckpoint::open(path.clone(), "cases.csv", 3, false).unwrap();
+        assert_eq!(restarted.completed_reps(0), 0);
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,93 @@
+use crate::agent::TokenUsage;
+use crate::games::TestResult;
+
+/// Flat per-1k-token prices used to estimate what a game cost
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Pricing {
+    pub prompt_per_1k: f64,
+    pub completion_per_1k: f64,
+}
+
+impl Pricing {
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        usage.prompt_tokens as f64 / 1000.0 * self.prompt_per_1k
+            + usage.completion_tokens as f64 / 1000.0 * self.completion_per_1k
+    }
+
+    /// Estimated cost of every turn in `result` that reported usage
+    pub fn game_cost(&self, result: &TestResult) -> f64 {
+        result
+            .stats()
+            .turns
+            .iter()
+            .filter_map(|turn| turn.usage.as_ref())
+            .map(|usage| self.cost(usage))
+            .sum()
+    }
+}
+
+/// Spending cap for a batch
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Budget {
+    pub limit_usd: f64,
+    pub pricing: Pricing,
+}
+
+impl Budget {
+    /// Whether another game fits, assuming it costs as much as the average
+    /// game so far
+    pub fn allows_next(&self, spent: f64, games: u32) -> bool {
+        let projected = if games == 0 { 0.0 } else { spent / games as f64 };
+        spent + projected <= self.limit_usd
+    }
+
+    /// How many of the next `wanted` games fit, each assumed to cost as much
+    /// as the average game so far
+    pub fn games_allowed(&self, spent: f64, games: u32, wanted: u32) -> u32 {
+        let average = if games == 0 { 0.0 } else { spent / games as f64 };
+        (0..wanted)
+            .take_while(|&started| self.allows_next(spent + average * started as f64, games + started))
+            .count() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cost_uses_per_1k_rates() {
+        let pricing = Pricing {
+            prompt_per_1k: 0.5,
+            completion_per_1k: 2.0,
+        };
+        let usage = TokenUsage {
+            prompt_tokens: 2000,
+            completion_tokens: 500,
+        };
+        assert!((pricing.cost(&usage) - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_budget_projects_next_game() {
+        let budget = Budget {
+            limit_usd: 1.0,
+            pricing: Pricing::default(),
+        };
+        assert!(budget.allows_next(0.0, 0));
+        assert!(budget.allows_next(0.5, 2));
+        assert!(!budget.allows_next(0.8, 2));
+        assert!(!budget.allows_next(1.2, 4));
+    }
+
+    #[test]
+    fn test_games_allowed_caps_a_wave() {
+        let budget = Budget {
+            limit_usd: 1.0,
+            pricing: Pricing::default(),
+        };
+        assert_eq!(budget.games_allowed(0.0, 0, 4), 4);
+        assert_eq!(budget.games_allowed(0.5, 2, 4), 2);
+        assert_eq!(budget.games_allowed(0.8, 2, 4), 0);
+    }
+}
@@ -0,0 +1,269 @@
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tabled::{Table, Tabled, settings::{Alignment, Modify, Style, object::Rows}};
+
+use crate::agent::{AgentError, AgentResult};
+
+/// Cooldown used when a provider rate-limits us without saying for how long
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// Consecutive rate limits tolerated for one request before giving up
+pub const MAX_COOLDOWNS_PER_REQUEST: u32 = 5;
+
+/// Time source for the scheduler, so tests can run without real waiting
+pub trait Clock: Send + Sync {
+    fn now(&self) -> SystemTime;
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()> + Send;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()> + Send {
+        tokio::time::sleep(duration)
+    }
+}
+
+/// Pauses requests to a provider after it reports rate-limit exhaustion and
+/// resumes them once the provider's reset window has passed
+pub struct ProviderCooldowns<C: Clock> {
+    clock: C,
+    default_cooldown: Mutex<Duration>,
+    resume_at: Mutex<HashMap<String, SystemTime>>,
+    /// API retries spent per `provider/model`
+    retries: Mutex<BTreeMap<String, u32>>,
+}
+
+static GLOBAL: LazyLock<ProviderCooldowns<SystemClock>> =
+    LazyLock::new(|| ProviderCooldowns::new(SystemClock, DEFAULT_COOLDOWN));
+
+/// Process-wide scheduler shared by every agent, so one game hitting a cap
+/// pauses that provider for the rest of the batch too
+pub fn global() -> &'static ProviderCooldowns<SystemClock> {
+    &GLOBAL
+}
+
+impl<C: Clock> ProviderCooldowns<C> {
+    pub fn new(clock: C, default_cooldown: Duration) -> Self {
+        Self {
+            clock,
+            default_cooldown: Mutex::new(default_cooldown),
+            resume_at: Mutex::new(HashMap::new()),
+            retries: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn set_default_cooldown(&self, cooldown: Duration) {
+        *self.default_cooldown.lock().unwrap() = cooldown;
+    }
+
+    /// Record that `provider` is exhausted; returns when it may be used again
+    pub fn rate_limited(&self, provider: &str, retry_after: Option<Duration>) -> SystemTime {
+        let wait = retry_after.unwrap_or(*self.default_cooldown.lock().unwrap());
+        let until = self.clock.now() + wait;
+        let mut resume_at = self.resume_at.lock().unwrap();
+        let entry = resume_at.entry(provider.to_string()).or_insert(until);
+        *entry = (*entry).max(until);
+        *entry
+    }
+
+    /// Sleep until `provider` is out of its cooldown, if it is in one
+    pub async fn wait_until_ready(&self, provider: &str) {
+        let until = self.resume_at.lock().unwrap().get(provider).copied();
+        let Some(until) = until else {
+            return;
+        };
+        if let Ok(remaining) = until.duration_since(self.clock.now())
+            && !remaining.is_zero()
+        {
+            tracing::info!("{} rate limit exhausted, cooling down until {} UTC", provider, format_hh_mm(until));
+            self.clock.sleep(remaining).await;
+            tracing::info!("Resuming {} requests", provider);
+        }
+        self.resume_at.lock().unwrap().remove(provider);
+    }
+
+    /// Run `request` against `model` at `provider`, waiting out and retrying
+    /// rate limits. Every retry is counted towards `retry_counts`, as are
+    /// `agent::with_retry`'s retries of transient failures.
+    pub async fn call<T>(
+        &self,
+        provider: &str,
+        model: &str,
+        request: impl AsyncFn() -> AgentResult<T>,
+    ) -> AgentResult<T> {
+        let mut cooldowns = 0;
+        loop {
+            self.wait_until_ready(provider).await;
+            match request().await {
+                Err(AgentError::RateLimited { retry_after }) if cooldowns < MAX_COOLDOWNS_PER_REQUEST => {
+                    cooldowns += 1;
+                    self.count_retry(provider, model);
+                    self.rate_limited(provider, retry_after);
+                }
+                other => return other,
+            }
+        }
+    }
+}
+
+impl<C: Clock> ProviderCooldowns<C> {
+    /// Count one API retry of a request to `model` at `provider`
+    pub fn count_retry(&self, provider: &str, model: &str) {
+        *self.retries.lock().unwrap().entry(format!("{}/{}", provider, model)).or_default() += 1;
+    }
+
+    /// API retries so far, keyed by `provider/model`
+    pub fn retry_counts(&self) -> BTreeMap<String, u32> {
+        self.retries.lock().unwrap().clone()
+    }
+
+    /// Table of API retries per provider/model, or `None` if there were none
+    pub fn retry_report(&self) -> Option<Table> {
+        #[derive(Tabled)]
+        struct RetryRow {
+            #[tabled(rename = "Provider/Model")]
+            endpoint: String,
+            #[tabled(rename = "API Retries")]
+            retries: u32,
+        }
+
+        let counts = self.retry_counts();
+        if counts.is_empty() {
+            return None;
+        }
+        let rows = counts.into_iter().map(|(endpoint, retries)| RetryRow { endpoint, retries });
+        let mut table = Table::new(rows);
+        table
+            .with(Style::rounded())
+            .with(Modify::new(Rows::new(1..)).with(Alignment::left()));
+        Some(table)
+    }
+}
+
+fn format_hh_mm(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()) % 86_400;
+    format!("{:02}:{:02}", secs / 3600, (secs % 3600) / 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::AgentError;
+    use std::cell::Cell;
+
+    /// Clock whose sleeps return immediately but advance `now`
+    struct MockClock {
+        now: Mutex<SystemTime>,
+        slept: Mutex<Vec<Duration>>,
+    }
+
+    impl MockClock {
+        fn new() -> Self {
+            Self {
+                now: Mutex::new(UNIX_EPOCH + Duration::from_secs(3600)),
+                slept: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    impl Clock for MockClock {
+        fn now(&self) -> SystemTime {
+            *self.now.lock().unwrap()
+        }
+
+        fn sleep(&self, duration: Duration) -> impl Future<Output = ()> + Send {
+            *self.now.lock().unwrap() += duration;
+            self.slept.lock().unwrap().push(duration);
+            std::future::ready(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_waits_retry_after_before_resuming_provider() {
+        let cooldowns = ProviderCooldowns::new(MockClock::new(), DEFAULT_COOLDOWN);
+        cooldowns.rate_limited("OpenAI", Some(Duration::from_secs(30)));
+
+        cooldowns.wait_until_ready("Ollama").await;
+        assert!(cooldowns.clock.slept.lock().unwrap().is_empty());
+
+        cooldowns.wait_until_ready("OpenAI").await;
+        assert_eq!(*cooldowns.clock.slept.lock().unwrap(), vec![Duration::from_secs(30)]);
+
+        // Cooldown is cleared once served
+        cooldowns.wait_until_ready("OpenAI").await;
+        assert_eq!(cooldowns.clock.slept.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_call_retries_after_rate_limit() {
+        let cooldowns = ProviderCooldowns::new(MockClock::new(), Duration::from_secs(45));
+        let attempts = Cell::new(0);
+
+        let result = cooldowns
+            .call("OpenAI", "gpt-4o-mini", async || {
+                attempts.set(attempts.get() + 1);
+                if attempts.get() == 1 {
+                    Err(AgentError::RateLimited { retry_after: None })
+                } else {
+                    Ok("move")
+                }
+            })
+            .await;
+
+        assert_eq!(result.unwrap(), "move");
+        assert_eq!(attempts.get(), 2);
+        assert_eq!(*cooldowns.clock.slept.lock().unwrap(), vec![Duration::from_secs(45)]);
+    }
+
+    #[tokio::test]
+    async fn test_call_gives_up_after_repeated_rate_limits() {
+        let cooldowns = ProviderCooldowns::new(MockClock::new(), DEFAULT_COOLDOWN);
+
+        let result: AgentResult<()> = cooldowns
+            .call("OpenAI", "gpt-4o-mini", async || Err(AgentError::RateLimited { retry_after: None }))
+            .await;
+
+        assert!(matches!(result, Err(AgentError::RateLimited { .. })));
+        assert_eq!(cooldowns.clock.slept.lock().unwrap().len(), MAX_COOLDOWNS_PER_REQUEST as usize);
+    }
+
+    #[tokio::test]
+    async fn test_retries_are_counted_per_provider_and_model() {
+        let cooldowns = ProviderCooldowns::new(MockClock::new(), Duration::from_secs(1));
+        let flaky = |failures: u32| {
+            let attempts = Cell::new(0);
+            async move || {
+                attempts.set(attempts.get() + 1);
+                if attempts.get() <= failures {
+                    Err(AgentError::RateLimited { retry_after: None })
+                } else {
+                    Ok(())
+                }
+            }
+        };
+
+        cooldowns.call("OpenAI", "gpt-4o-mini", flaky(2)).await.unwrap();
+        cooldowns.call("OpenAI", "gpt-4o-mini", flaky(1)).await.unwrap();
+        cooldowns.call("Ollama", "llama3", flaky(0)).await.unwrap();
+        cooldowns.call("OpenAI", "gpt-4o", flaky(1)).await.unwrap();
+
+        let counts = cooldowns.retry_counts();
+        assert_eq!(counts.get("OpenAI/gpt-4o-mini"), Some(&3));
+        assert_eq!(counts.get("OpenAI/gpt-4o"), Some(&1));
+        assert_eq!(counts.get("Ollama/llama3"), None);
+        assert!(cooldowns.retry_report().is_some());
+    }
+
+    #[test]
+    fn test_format_hh_mm() {
+        assert_eq!(format_hh_mm(UNIX_EPOCH + Duration::from_secs(13 * 3600 + 5 * 60 + 59)), "13:05");
+    }
+}
@@ -1,29 +1,165 @@
 use csv::ReaderBuilder;
 use std::fs::File;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use crate::agent_config::{AIAgentConfig, AgentKind};
-use crate::games::{Game, TestResult, print_game_stats};
+use crate::agent::AIAgent;
+use crate::agent_config::{AIAgentConfig, AgentKind, build_agents_with, parse_temp_schedule, read_system_prompt};
+use crate::agents::perturbed::Perturbation;
+use crate::agents::replay_checked::ReplayCheckedAgent;
+use crate::batch_progress::{BatchCheckpoint, default_progress_dir, progress_path};
+use crate::cooldown;
+use crate::budget::{Budget, Pricing};
+use crate::rating::{DEFAULT_K_FACTOR, EloTracker, outcome_from_points};
+use crate::tournament::entrant_label;
+use crate::decathlon::game_points;
+use crate::games::reliability::{clean_rate_report, reliability_report};
+use crate::games::simultaneous::bounded_join;
+use crate::games::transcript::TranscriptWriter;
+use crate::games::aggregate::AggregateStats;
+use crate::games::{BoardLimits, Game, GameParams, TestResult, print_game_stats};
+use crate::progress_bar::BatchProgressBar;
+use crate::result_sink::{BatchSummary, ResultSink, SinkDispatcher};
+use crate::schedule::{ScheduleStrategy, schedule_order};
+use crate::secrets::{ModelDefaults, SecretsCache, SecretsManager};
+use crate::stopping::{AdaptiveStop, Z_95, wilson_interval};
 
-#[derive(Debug, Clone)]
+/// Knobs for a batch run that apply to every test case
+#[derive(Debug, Clone, Default)]
+pub struct BatchOptions {
+    /// Print the full stats tables for every game, not just single-rep cases
+    pub verbose: bool,
+    /// Reuse results of an earlier identical, deterministic test case instead of replaying it
+    pub dedupe: bool,
+    /// Check each agent can produce a parseable move before playing a game
+    pub probe_format: bool,
+    /// Repeat each case until the first agent's win-rate interval is narrow
+    /// enough, treating `repetitions` as a minimum
+    pub adaptive_stop: Option<AdaptiveStop>,
+    /// Replay a drawn game with the agents' seats swapped, up to this many times
+    pub rematch_on_draw: Option<u32>,
+    /// Offset each agent's seed by the repetition index, so repetitions of a
+    /// seeded row are independent samples rather than replays
+    pub seed_sweep: bool,
+    /// Show one agent a deliberately perturbed state
+    pub perturb: Option<Perturbation>,
+    /// Order in which test cases are started
+    pub schedule: ScheduleStrategy,
+    /// Ask temperature-0 agents for every move twice and report how often
+    /// the answers differ
+    pub check_temp0: bool,
+    /// Largest boards a test case may configure
+    pub board_limits: BoardLimits,
+    /// Re-read the secrets file before a game if it changed since the last one
+    pub reload_secrets: bool,
+    /// Stop starting games once the estimated spend would exceed this
+    pub budget: Option<Budget>,
+    /// Per-1k-token rates for the cost column in player summaries
+    pub pricing: Option<Pricing>,
+    /// Only run test cases for this game
+    pub only_game: Option<String>,
+    /// K-factor for the Elo ratings printed after the batch (default `DEFAULT_K_FACTOR`)
+    pub elo_k_factor: Option<f64>,
+    /// Repetitions of a test case played at once; 0 and 1 both play them one
+    /// at a time. Adaptive stopping and the budget are checked between waves.
+    pub concurrency: u32,
+    /// Log every turn to this file as it is played
+    pub transcript: Option<TranscriptWriter>,
+    /// Show the board after every turn in printed turn tables
+    pub show_boards: bool,
+    /// Print only the batch's final results, not each game and test case
+    pub quiet: bool,
+    /// Draw a progress bar on stderr while a CSV batch runs, printing only
+    /// the batch's final results as with `quiet`
+    pub progress: bool,
+    /// Skip the repetitions an earlier run of the same CSV batch finished
+    pub resume: bool,
+    /// Start no more games once one ends in an error
+    pub fail_fast: bool,
+    /// Record each finished repetition here, skipping those already recorded;
+    /// CSV batches use a file in `default_progress_dir` when this is unset
+    pub checkpoint: Option<BatchCheckpoint>,
+}
+
+/// `agents` with each seed offset by `rep`, as played in that repetition of a
+/// `--seed-sweep` batch; agents without a seed are unchanged
+pub fn sweep_seeds(agents: &[AIAgentConfig], rep: u32) -> Vec<AIAgentConfig> {
+    agents
+        .iter()
+        .map(|agent| AIAgentConfig {
+            seed: agent.seed.map(|seed| seed.wrapping_add(rep as u64)),
+            ..agent.clone()
+        })
+        .collect()
+}
+
+/// The game that settled a repetition, after any rematches
+pub struct Rematched {
+    pub result: TestResult,
+    /// Number of rematches played after draws
+    pub rematches: u32,
+    /// The agents were in swapped seats for `result`
+    pub swapped: bool,
+}
+
+/// Play `game`, and while it ends in a draw replay it (up to `max_rematches`
+/// times) with the agents' seats swapped, aiming for a decisive result
+pub async fn play_until_decisive(
+    game: &Game,
+    agents: &[AIAgentConfig],
+    max_rematches: u32,
+    play: &impl AsyncFn(&Game, Vec<AIAgentConfig>) -> TestResult,
+) -> Rematched {
+    let mut seating = agents.to_vec();
+    let mut result = play(game, seating.clone()).await;
+    let mut rematches = 0;
+    while rematches < max_rematches && result.error().is_none() && result.stats().draw {
+        rematches += 1;
+        seating.reverse();
+        tracing::info!("Draw; rematch {} of {} with seats swapped", rematches, max_rematches);
+        result = play(game, seating.clone()).await;
+    }
+    Rematched {
+        result,
+        rematches,
+        swapped: rematches % 2 == 1,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct CsvTestCase {
     pub game_name: String,
+    /// Board size, rounds and so on from the row, over the game's defaults
+    pub game_params: GameParams,
     pub agent_one_kind: AgentKind,
     pub agent_one_model: String,
     pub agent_one_temp: f32,
     pub agent_one_seed: u64,
     pub agent_one_secret_profile: Option<String>,
+    pub agent_one_max_tokens: Option<u32>,
+    pub agent_one_temp_schedule: Option<Vec<f32>>,
+    pub agent_one_system_prompt: Option<String>,
+    pub agent_one_system_prompt_file: Option<PathBuf>,
     pub agent_two_kind: AgentKind,
     pub agent_two_model: String,
     pub agent_two_temp: f32,
     pub agent_two_seed: u64,
     pub agent_two_secret_profile: Option<String>,
+    pub agent_two_max_tokens: Option<u32>,
+    pub agent_two_temp_schedule: Option<Vec<f32>>,
+    pub agent_two_system_prompt: Option<String>,
+    pub agent_two_system_prompt_file: Option<PathBuf>,
     pub repetitions: u32,
     pub description: String,
 }
 
 impl CsvTestCase {
-    fn from_record(record: csv::StringRecord, headers: &csv::StringRecord) -> Result<Self, String> {
+    /// Parse one row; a row that omits an agent's model or temperature takes
+    /// it from that provider's entry in `defaults`
+    fn from_record(
+        record: csv::StringRecord,
+        headers: &csv::StringRecord,
+        defaults: &ModelDefaults,
+    ) -> Result<Self, String> {
         let get_field = |name: &str| -> Result<String, String> {
             let idx = headers
                 .iter()
@@ -38,7 +174,9 @@ impl CsvTestCase {
                 "OPENAI" => Ok(AgentKind::OpenAI),
                 "ANTHROPIC" => Ok(AgentKind::Anthropic),
                 "OLLAMA" => Ok(AgentKind::Ollama),
-                _ => Err(format!("Invalid agent kind: {}. Must be OpenAI, Anthropic, or Ollama", value)),
+                "SCRIPTED" => Ok(AgentKind::Scripted),
+                "MINIMAX" => Ok(AgentKind::Minimax),
+                _ => Err(format!("Invalid agent kind: {}. Must be OpenAI, Anthropic, Ollama, Scripted, or Minimax", value)),
             }
         };
 
@@ -72,23 +210,122 @@ impl CsvTestCase {
                 })
         };
 
+        let parse_optional_u32 = |name: &str| -> Result<Option<u32>, String> {
+            get_optional_field(name)
+                .map(|v| v.parse().map_err(|e| format!("Invalid {}: {}", name, e)))
+                .transpose()
+        };
+
+        let parse_optional_schedule = |name: &str| -> Result<Option<Vec<f32>>, String> {
+            get_optional_field(name)
+                .map(|v| parse_temp_schedule(&v).map_err(|e| format!("Invalid {}: {}", name, e)))
+                .transpose()
+        };
+
+        // Row value wins, then the provider default, then the built-in fallback
+        let resolve_model = |field: &str, kind: AgentKind| -> Result<String, String> {
+            match get_optional_field(field) {
+                Some(model) => Ok(model),
+                None => defaults.for_kind(kind).model.clone().ok_or_else(|| {
+                    format!("Missing required field: {} (no default model configured for {:?})", field, kind)
+                }),
+            }
+        };
+        let resolve_temp = |field: &str, kind: AgentKind| -> f32 {
+            parse_f32(field)
+                .ok()
+                .or(defaults.for_kind(kind).temp)
+                .unwrap_or(0.7)
+        };
+
+        let agent_one_kind = parse_agent_kind("agent_one_kind")?;
+        let agent_two_kind = parse_agent_kind("agent_two_kind")?;
+
+        let game_name = get_field("game_name")?;
+        let game_params = GameParams {
+            board_size: parse_optional_u32("board_size")?,
+            win_length: parse_optional_u32("win_length")?,
+            rows: parse_optional_u32("rows")?,
+            cols: parse_optional_u32("cols")?,
+            rounds: parse_optional_u32("rounds")?,
+        };
+        if let Some(game) = Game::new(&game_name) {
+            game.with_params(&game_params)?;
+        }
+
         Ok(CsvTestCase {
-            game_name: get_field("game_name")?,
-            agent_one_kind: parse_agent_kind("agent_one_kind")?,
-            agent_one_model: get_field("agent_one_model")?,
-            agent_one_temp: parse_f32("agent_one_temp").unwrap_or(0.7),
+            game_name,
+            game_params,
+            agent_one_kind,
+            agent_one_model: resolve_model("agent_one_model", agent_one_kind)?,
+            agent_one_temp: resolve_temp("agent_one_temp", agent_one_kind),
             agent_one_seed: parse_u64("agent_one_seed").unwrap_or(0),
             agent_one_secret_profile: get_optional_field("agent_one_secret_profile"),
-            agent_two_kind: parse_agent_kind("agent_two_kind")?,
-            agent_two_model: get_field("agent_two_model")?,
-            agent_two_temp: parse_f32("agent_two_temp").unwrap_or(0.7),
+            agent_one_max_tokens: parse_optional_u32("agent_one_max_tokens")?,
+            agent_one_temp_schedule: parse_optional_schedule("agent_one_temp_schedule")?,
+            agent_one_system_prompt: get_optional_field("agent_one_system_prompt"),
+            agent_one_system_prompt_file: get_optional_field("agent_one_system_prompt_file").map(PathBuf::from),
+            agent_two_kind,
+            agent_two_model: resolve_model("agent_two_model", agent_two_kind)?,
+            agent_two_temp: resolve_temp("agent_two_temp", agent_two_kind),
             agent_two_seed: parse_u64("agent_two_seed").unwrap_or(0),
             agent_two_secret_profile: get_optional_field("agent_two_secret_profile"),
+            agent_two_max_tokens: parse_optional_u32("agent_two_max_tokens")?,
+            agent_two_temp_schedule: parse_optional_schedule("agent_two_temp_schedule")?,
+            agent_two_system_prompt: get_optional_field("agent_two_system_prompt"),
+            agent_two_system_prompt_file: get_optional_field("agent_two_system_prompt_file").map(PathBuf::from),
             repetitions: parse_u32("repetitions").unwrap_or(1),
             description: get_field("description").unwrap_or_else(|_| "".to_string()),
         })
     }
 
+    /// Read each agent's `system_prompt_file`, relative to `base_dir`, into
+    /// its system prompt
+    fn load_system_prompt_files(&mut self, base_dir: &Path) -> Result<(), String> {
+        for (file, prompt) in [
+            (&self.agent_one_system_prompt_file, &mut self.agent_one_system_prompt),
+            (&self.agent_two_system_prompt_file, &mut self.agent_two_system_prompt),
+        ] {
+            if let Some(file) = file {
+                *prompt = Some(read_system_prompt(&base_dir.join(file))?);
+            }
+        }
+        Ok(())
+    }
+
+    /// The row's game, configured by its `game_params`
+    pub fn game(&self) -> Result<Game, String> {
+        let game = Game::try_from(self.game_name.as_str())?.with_params(&self.game_params)?;
+        game.check_agents(&self.to_agent_configs())?;
+        Ok(game)
+    }
+
+    /// The row's description, or its game and models when it has none
+    pub fn summary(&self) -> String {
+        if self.description.is_empty() {
+            format!("{}: {} vs {}", self.game_name, self.agent_one_model, self.agent_two_model)
+        } else {
+            self.description.clone()
+        }
+    }
+
+    /// Same game and agent setup, ignoring the free-form description
+    pub fn same_setup(&self, other: &CsvTestCase) -> bool {
+        CsvTestCase {
+            description: String::new(),
+            ..self.clone()
+        } == CsvTestCase {
+            description: String::new(),
+            ..other.clone()
+        }
+    }
+
+    /// Both agents sample at temperature 0, so replaying the case should
+    /// reproduce the same games
+    pub fn is_deterministic(&self) -> bool {
+        self.to_agent_configs().iter().all(AIAgentConfig::is_greedy)
+    }
+
     pub fn to_agent_configs(&self) -> Vec<AIAgentConfig> {
         vec![
             AIAgentConfig {
@@ -97,6 +334,11 @@ impl CsvTestCase {
                 seed: Some(self.agent_one_seed),
                 agent: self.agent_one_kind,
                 secret_profile: self.agent_one_secret_profile.clone(),
+                max_tokens: self.agent_one_max_tokens,
+                temp_schedule: self.agent_one_temp_schedule.clone(),
+                system_prompt: self.agent_one_system_prompt.clone(),
+                system_prompt_file: self.agent_one_system_prompt_file.clone(),
+                ..Default::default()
             },
             AIAgentConfig {
                 model: self.agent_two_model.clone(),
@@ -104,12 +346,32 @@ impl CsvTestCase {
                 seed: Some(self.agent_two_seed),
                 agent: self.agent_two_kind,
                 secret_profile: self.agent_two_secret_profile.clone(),
+                max_tokens: self.agent_two_max_tokens,
+                temp_schedule: self.agent_two_temp_schedule.clone(),
+                system_prompt: self.agent_two_system_prompt.clone(),
+                system_prompt_file: self.agent_two_system_prompt_file.clone(),
+                ..Default::default()
             },
         ]
     }
 }
 
-pub fn read_csv_file<P: AsRef<Path>>(path: P) -> Result<Vec<CsvTestCase>, String> {
+pub fn read_csv_file<P: AsRef<Path>>(
+    path: P,
+    defaults: &ModelDefaults,
+) -> Result<Vec<CsvTestCase>, String> {
+    read_csv_rows(path, defaults)?
+        .into_iter()
+        .map(|(row, parsed)| parsed.map_err(|e| format!("Error parsing row {}: {}", row, e)))
+        .collect()
+}
+
+/// A row number as in the file (the header is row 1) and the test case
+/// parsed from it, or the problem that stopped it parsing
+type CsvRow = (usize, Result<CsvTestCase, String>);
+
+fn read_csv_rows<P: AsRef<Path>>(path: P, defaults: &ModelDefaults) -> Result<Vec<CsvRow>, String> {
+    let base_dir = path.as_ref().parent().unwrap_or(Path::new("")).to_path_buf();
     let file = File::open(path).map_err(|e| format!("Failed to open CSV file: {}", e))?;
     let mut reader = ReaderBuilder::new()
         .has_headers(true)
@@ -121,85 +383,569 @@ pub fn read_csv_file<P: AsRef<Path>>(path: P) -> Result<Vec<CsvTestCase>, String
         .map_err(|e| format!("Failed to read CSV headers: {}", e))?
         .clone();
 
-    let mut test_cases = Vec::new();
+    let mut rows = Vec::new();
     for (row_num, result) in reader.records().enumerate() {
         let record = result.map_err(|e| format!("Failed to read CSV row {}: {}", row_num + 2, e))?;
-        match CsvTestCase::from_record(record, &headers) {
-            Ok(test_case) => test_cases.push(test_case),
-            Err(e) => return Err(format!("Error parsing row {}: {}", row_num + 2, e)),
+        let parsed = CsvTestCase::from_record(record, &headers, defaults)
+            .and_then(|mut test_case| test_case.load_system_prompt_files(&base_dir).map(|()| test_case));
+        rows.push((row_num + 2, parsed));
+    }
+
+    Ok(rows)
+}
+
+/// Problems that would stop rows of a batch file from playing, one line
+/// each with its row number: rows that don't parse, unknown games or game
+/// parameters, and secret profiles missing from `secrets`. No agent is
+/// contacted. An empty list means the file is ready to run.
+pub fn check_csv_file<P: AsRef<Path>>(path: P, secrets: &SecretsManager) -> Result<Vec<String>, String> {
+    let rows = read_csv_rows(path, secrets.model_defaults())?;
+    if rows.is_empty() {
+        return Ok(vec!["no test cases found".to_string()]);
+    }
+    let mut problems = Vec::new();
+    for (row, parsed) in rows {
+        let test_case = match parsed {
+            Ok(test_case) => test_case,
+            Err(e) => {
+                problems.push(format!("row {}: {}", row, e));
+                continue;
+            }
+        };
+        if let Err(e) = test_case.game() {
+            problems.push(format!("row {}: {}", row, e));
+        }
+        for config in test_case.to_agent_configs() {
+            let Some(profile) = config.secret_profile.as_deref() else {
+                continue;
+            };
+            let found = match config.agent {
+                AgentKind::OpenAI => secrets.get_openai(profile).map(|_| ()),
+                AgentKind::Anthropic => secrets.get_anthropic(profile).map(|_| ()),
+                AgentKind::Ollama => secrets.get_ollama(profile).map(|_| ()),
+                AgentKind::Scripted | AgentKind::Minimax => Ok(()),
+            };
+            if let Err(e) = found {
+                problems.push(format!("row {}: {}", row, e));
+            }
         }
     }
+    Ok(problems)
+}
 
-    Ok(test_cases)
+/// The rows that parsed and name a playable game; every other row is
+/// reported and skipped rather than ending the batch
+fn runnable_rows(rows: Vec<CsvRow>) -> Vec<CsvTestCase> {
+    rows.into_iter()
+        .filter_map(|(row, parsed)| match parsed.and_then(|test_case| test_case.game().map(|_| test_case)) {
+            Ok(test_case) => Some(test_case),
+            Err(e) => {
+                tracing::warn!("Skipping row {}: {}", row, e);
+                None
+            }
+        })
+        .collect()
 }
 
-pub async fn run_csv_batch(csv_path: &str, verbose: bool) -> Result<(), String> {
-    let test_cases = read_csv_file(csv_path)?;
-    
-    println!("\n{}", "=".repeat(80));
-    println!("CSV BATCH RUN");
-    println!("Found {} test case(s) in CSV file", test_cases.len());
-    println!("{}", "=".repeat(80));
+pub async fn run_csv_batch(
+    csv_path: &str,
+    options: &BatchOptions,
+    sinks: &[Box<dyn ResultSink>],
+) -> Result<BatchSummary, String> {
+    let secrets = SecretsCache::load_default(options.reload_secrets);
+    let rows = read_csv_rows(csv_path, secrets.current().model_defaults())?;
+    if rows.is_empty() {
+        return Err(format!("no test cases found in {}", csv_path));
+    }
+    let test_cases = runnable_rows(rows);
+    if test_cases.is_empty() {
+        return Err(format!("no runnable test cases in {}", csv_path));
+    }
+    let checkpoint = match &options.checkpoint {
+        Some(checkpoint) => checkpoint.clone(),
+        None => {
+            let path = progress_path(&default_progress_dir(), csv_path);
+            BatchCheckpoint::open(path, csv_path, test_cases.len(), options.resume)?
+        }
+    };
+    tracing::info!("Saving progress to {}", checkpoint.path().display());
+    let options = BatchOptions {
+        checkpoint: Some(checkpoint),
+        ..options.clone()
+    };
+    run_test_cases(&test_cases, &options, sinks, async |game: &Game, agents| {
+        play_configured(game, agents, &options, &secrets).await
+    })
+    .await
+}
 
-    let mut total_games = 0;
-    let mut completed_games = 0;
+/// Build the agents and play one game, honoring the per-game options
+pub async fn play_configured(
+    game: &Game,
+    configs: Vec<AIAgentConfig>,
+    options: &BatchOptions,
+    secrets: &SecretsCache,
+) -> TestResult {
+    if let Err(e) = game.validate(&options.board_limits) {
+        return game.failed(e);
+    }
+    let greedy: Vec<bool> = configs.iter().map(AIAgentConfig::is_greedy).collect();
+    // An agent that can't be built fails this game, not the whole batch
+    let mut agents = match build_agents_with(configs, &secrets.current()) {
+        Ok(agents) => agents,
+        Err(e) => return game.failed(format!("Failed to start: {}", e)),
+    };
+    if options.check_temp0 {
+        agents = agents
+            .into_iter()
+            .zip(greedy)
+            .map(|(agent, greedy)| if greedy { AIAgent::ReplayChecked(ReplayCheckedAgent::new(agent)) } else { agent })
+            .collect();
+    }
+    if let Some(perturbation) = options.perturb {
+        agents = perturbation.apply(agents);
+    }
+    if options.probe_format {
+        game.play_with_probe(agents, options.transcript.as_ref()).await
+    } else {
+        game.play_recorded(agents, options.transcript.as_ref(), None).await
+    }
+}
 
-    for (idx, test_case) in test_cases.iter().enumerate() {
-        println!("\n[Test Case {} of {}]", idx + 1, test_cases.len());
-        if !test_case.description.is_empty() {
-            println!("Description: {}", test_case.description);
+/// The cases that play `game`, which must be a known game name
+pub fn select_game(test_cases: &[CsvTestCase], game: &str) -> Result<Vec<CsvTestCase>, String> {
+    if Game::new(game).is_none() {
+        return Err(format!("Unknown game for --only-game: {} (expected one of {})", game, Game::list().join(", ")));
+    }
+    let selected: Vec<CsvTestCase> = test_cases.iter().filter(|c| c.game_name == game).cloned().collect();
+    if selected.is_empty() {
+        return Err(format!("no {} test cases to run", game));
+    }
+    Ok(selected)
+}
+
+/// Run already-parsed test cases, using `play` to play each individual game
+pub async fn run_test_cases(
+    test_cases: &[CsvTestCase],
+    options: &BatchOptions,
+    sinks: &[Box<dyn ResultSink>],
+    play: impl AsyncFn(&Game, Vec<AIAgentConfig>) -> TestResult,
+) -> Result<BatchSummary, String> {
+    let selected;
+    // Each case's index among all of them, which is how checkpoints name it
+    let case_ids: Vec<usize>;
+    let test_cases = match options.only_game.as_deref() {
+        Some(game) => {
+            selected = select_game(test_cases, game)?;
+            tracing::info!("Running only {}: {} of {} test case(s)", game, selected.len(), test_cases.len());
+            case_ids = (0..test_cases.len()).filter(|&i| test_cases[i].game_name == game).collect();
+            &selected[..]
         }
-        println!("Game: {}", test_case.game_name);
-        println!("Repetitions: {}", test_case.repetitions);
-        println!("Agents: {} ({}) vs {} ({})", 
-            test_case.agent_one_model, 
-            format!("{:?}", test_case.agent_one_kind),
+        None => {
+            case_ids = (0..test_cases.len()).collect();
+            test_cases
+        }
+    };
+
+    tracing::info!("CSV batch run: {} test case(s)", test_cases.len());
+
+    let mut dispatcher = SinkDispatcher::new(sinks);
+    // Results of deterministic cases that later identical rows may reuse,
+    // with each game's points in agent order
+    let mut cache: Vec<(usize, Vec<TestResult>, Vec<[f64; 2]>)> = Vec::new();
+    let mut elo = EloTracker::new(options.elo_k_factor.unwrap_or(DEFAULT_K_FACTOR));
+    let mut all_results: Vec<TestResult> = Vec::new();
+    // Estimated spend across every game played, rematches included
+    let spent = std::cell::Cell::new(0.0);
+    let games_played = std::cell::Cell::new(0);
+    let play = async |game: &Game, agents: Vec<AIAgentConfig>| {
+        let result = play(game, agents).await;
+        if let Some(budget) = options.budget {
+            spent.set(spent.get() + budget.pricing.game_cost(&result));
+        }
+        games_played.set(games_played.get() + 1);
+        result
+    };
+    let mut budget_reached = false;
+    let mut failed_fast = false;
+    let mut bar = options.progress.then(|| {
+        let planned = (0..test_cases.len()).map(|idx| u64::from(planned_games(&test_cases[idx], case_ids[idx], options)));
+        BatchProgressBar::start(planned.sum())
+    });
+
+    for idx in schedule_order(test_cases, options.schedule) {
+        let test_case = &test_cases[idx];
+        tracing::info!(
+            "Test case {} of {}: {} x{}, {} ({:?}) vs {} ({:?})",
+            idx + 1,
+            test_cases.len(),
+            test_case.game_name,
+            test_case.repetitions,
+            test_case.agent_one_model,
+            test_case.agent_one_kind,
             test_case.agent_two_model,
-            format!("{:?}", test_case.agent_two_kind));
+            test_case.agent_two_kind
+        );
+        if !test_case.description.is_empty() {
+            tracing::info!("Description: {}", test_case.description);
+        }
+        let case_id = case_ids[idx];
+        let planned = u64::from(planned_games(test_case, case_id, options));
+        if let Some(bar) = &mut bar {
+            bar.start_case(&format!("[{}/{}] {}", idx + 1, test_cases.len(), test_case.summary()));
+        }
 
-        let game = Game::from(test_case.game_name.as_str());
+        let game = match test_case.game() {
+            Ok(game) => game,
+            Err(e) => {
+                tracing::warn!("Skipping test case {}: {}", idx + 1, e);
+                if let Some(bar) = &mut bar {
+                    bar.games_skipped(planned);
+                }
+                continue;
+            }
+        };
+        let checkpoint = options.checkpoint.as_ref();
+        let done = checkpoint.map_or(0, |c| c.completed_reps(case_id));
+        if done >= test_case.repetitions {
+            tracing::info!("Already completed in an earlier run; skipping");
+            if let Some(bar) = &mut bar {
+                bar.games_skipped(planned);
+            }
+            continue;
+        }
+        if done > 0 {
+            tracing::info!("Resuming after {} repetition(s) completed in an earlier run", done);
+        }
+        let record = |rep: u32| checkpoint.map_or(Ok(()), |c| c.record(case_id, rep));
+        let cacheable = options.dedupe && done == 0 && test_case.is_deterministic();
         let agents = test_case.to_agent_configs();
+        let labels: Vec<String> = agents.iter().map(entrant_label).collect();
+        let mut rate = |by_agent: [f64; 2]| {
+            if let Some(outcome) = outcome_from_points(by_agent) {
+                elo.record_game(&labels[0], &labels[1], outcome);
+            }
+        };
+
+        if cacheable
+            && let Some((original, results, scores)) = cache
+                .iter()
+                .find(|(original, _, _)| test_cases[*original].same_setup(test_case))
+        {
+            tracing::info!(
+                "Identical to test case {}; reusing its {} result(s) instead of replaying",
+                original + 1,
+                results.len()
+            );
+            for (rep, (result, by_agent)) in results.iter().zip(scores).enumerate() {
+                report_game(&game, test_case.repetitions, result, options);
+                dispatcher.game_complete(result);
+                rate(*by_agent);
+                record(rep as u32)?;
+            }
+            if let Some(bar) = &mut bar {
+                bar.games_reused(results.len() as u64);
+                bar.games_skipped(planned.saturating_sub(results.len() as u64));
+            }
+            report_aggregate(results, options);
+            all_results.extend(results.iter().cloned());
+            if options.fail_fast && let Some(e) = results.iter().find_map(TestResult::error) {
+                stop_after_failure(e);
+                break;
+            }
+            continue;
+        }
 
-        for rep in 0..test_case.repetitions {
-            total_games += 1;
-            
-            if test_case.repetitions > 1 {
-                println!("\n--- Repetition {} of {} ---", rep + 1, test_case.repetitions);
+        let mut results = Vec::new();
+        let mut scores = Vec::new();
+        // Seeds each repetition was played with, for --seed-sweep
+        let mut swept_seeds = Vec::new();
+        // First agent's score so far, for adaptive stopping
+        let mut points = 0.0;
+        let concurrency = options.concurrency.max(1);
+        loop {
+            let rep = done + results.len() as u32;
+            let remaining = match options.adaptive_stop {
+                Some(stop) if stop.should_stop(points, rep, test_case.repetitions) => break,
+                Some(stop) => stop.max_games.saturating_sub(rep),
+                None => test_case.repetitions.saturating_sub(rep),
+            };
+            if remaining == 0 || failed_fast {
+                break;
+            }
+            let mut wave = remaining.min(concurrency);
+            if let Some(budget) = options.budget {
+                wave = budget.games_allowed(spent.get(), games_played.get(), wave);
+                if wave == 0 {
+                    tracing::warn!(
+                        "Budget of ${:.2} would be exceeded (spent ~${:.2} over {} game(s)); not starting more games",
+                        budget.limit_usd,
+                        spent.get(),
+                        games_played.get()
+                    );
+                    budget_reached = true;
+                    break;
+                }
             }
 
-            match game.play_game(agents.clone()).await {
-                result => {
-                    completed_games += 1;
-                    if verbose || test_case.repetitions == 1 {
-                        print_game_stats(game.name(), &result);
-                    } else {
-                        // Brief summary for multiple repetitions
-                        let winner = match &result {
-                            TestResult::TicTacToe(r) => r.winner.as_ref(),
-                            TestResult::RockPaperScissors(r) => r.winner.as_ref(),
-                            TestResult::ConnectFour(r) => r.winner.as_ref(),
-                        };
-                        println!("  Result: {}", 
-                            winner.map(|w| format!("Winner: {}", w))
-                                .unwrap_or_else(|| "Draw".to_string()));
+            // Play the wave concurrently, then report it in repetition order
+            let played = bounded_join(rep..rep + wave, concurrency as usize, async |rep| {
+                let (seated, seating) = game.seat_agents(agents.clone(), rep as u64);
+                let seated = if options.seed_sweep { sweep_seeds(&seated, rep) } else { seated };
+                // Seat of the first agent; flips when a rematch swapped seats
+                let first_seat = seating.iter().position(|&i| i == 0).unwrap_or(0);
+                let rematched =
+                    play_until_decisive(&game, &seated, options.rematch_on_draw.unwrap_or(0), &play).await;
+                (rep, first_seat, rematched)
+            })
+            .await;
+
+            for (rep, first_seat, Rematched { result, rematches, swapped }) in played {
+                match options.adaptive_stop {
+                    Some(stop) => tracing::info!("Repetition {} (adaptive, at most {})", rep + 1, stop.max_games),
+                    None if test_case.repetitions > 1 => {
+                        tracing::info!("Repetition {} of {}", rep + 1, test_case.repetitions);
                     }
+                    None => {}
+                }
+                if options.seed_sweep {
+                    let seeds = seed_list(&sweep_seeds(&agents, rep));
+                    tracing::info!("Seeds: {}", seeds);
+                    swept_seeds.push(seeds);
+                }
+                if rematches > 0 {
+                    tracing::info!("Settled after {} rematch(es)", rematches);
+                }
+                report_game(&game, test_case.repetitions, &result, options);
+                dispatcher.game_complete(&result);
+                let scored = game_points(&result);
+                let one = first_seat ^ usize::from(swapped);
+                let by_agent = [scored[one], scored[1 - one]];
+                points += by_agent[0];
+                rate(by_agent);
+                scores.push(by_agent);
+                if options.fail_fast && !failed_fast && let Some(e) = result.error() {
+                    stop_after_failure(e);
+                    failed_fast = true;
+                }
+                results.push(result);
+                record(rep)?;
+                if let Some(bar) = &mut bar {
+                    bar.game_played();
+                }
+            }
+        }
+        if let Some(bar) = &mut bar {
+            bar.games_skipped(planned.saturating_sub(results.len() as u64));
+        }
+        if options.adaptive_stop.is_some() && !quiet_tables(options) {
+            let ci = wilson_interval(points, results.len() as u32, Z_95);
+            println!(
+                "Adaptive stop after {} game(s): agent one win rate {:.2}, 95% CI [{:.2}, {:.2}] (width {:.2})",
+                results.len(),
+                points / results.len().max(1) as f64,
+                ci.lower,
+                ci.upper,
+                ci.width()
+            );
+        }
+        if swept_seeds.len() > 1 && !quiet_tables(options) {
+            println!("Seeds by repetition:");
+            for (rep, seeds) in swept_seeds.iter().enumerate() {
+                println!("  {}: {}", done as usize + rep + 1, seeds);
+            }
+        }
+        report_aggregate(&results, options);
+        all_results.extend(results.iter().cloned());
+        if budget_reached || failed_fast {
+            break;
+        }
+        if cacheable {
+            cache.push((idx, results, scores));
+        }
+    }
+
+    drop(bar);
+    let summary = dispatcher.finish();
+    report_batch(&summary, &all_results, &elo);
+    Ok(summary)
+}
+
+/// Games a test case may still play: its remaining repetitions, or with
+/// adaptive stopping as many as it could stretch to
+fn planned_games(test_case: &CsvTestCase, case_id: usize, options: &BatchOptions) -> u32 {
+    let planned = match options.adaptive_stop {
+        Some(stop) => stop.max_games,
+        None => test_case.repetitions,
+    };
+    let done = options.checkpoint.as_ref().map_or(0, |c| c.completed_reps(case_id));
+    planned.saturating_sub(done)
+}
+
+/// A test case from a JSON batch file, carrying the game's full
+/// configuration rather than a name and board parameters
+#[derive(Debug, Clone)]
+pub struct ConfiguredCase {
+    pub game: Game,
+    pub description: String,
+    pub agents: Vec<AIAgentConfig>,
+    pub repetitions: u32,
+}
+
+/// Play configured cases one game at a time, seating and reporting each game
+/// as a CSV batch does. Options that replay or reschedule games (dedupe,
+/// adaptive stopping, rematches, concurrency, budget, schedule) are CSV-only.
+pub async fn run_configured_cases(
+    cases: &[ConfiguredCase],
+    options: &BatchOptions,
+    sinks: &[Box<dyn ResultSink>],
+    play: impl AsyncFn(&Game, Vec<AIAgentConfig>) -> TestResult,
+) -> Result<BatchSummary, String> {
+    let selected: Vec<&ConfiguredCase> = match options.only_game.as_deref() {
+        Some(game) => {
+            if Game::new(game).is_none() {
+                return Err(format!("Unknown game for --only-game: {}", game));
+            }
+            let selected: Vec<_> = cases.iter().filter(|case| case.game.name() == game).collect();
+            if selected.is_empty() {
+                return Err(format!("no {} test cases to run", game));
+            }
+            tracing::info!("Running only {}: {} of {} test case(s)", game, selected.len(), cases.len());
+            selected
+        }
+        None => cases.iter().collect(),
+    };
+
+    tracing::info!("JSON batch run: {} test case(s)", selected.len());
+
+    let mut dispatcher = SinkDispatcher::new(sinks);
+    let mut elo = EloTracker::new(options.elo_k_factor.unwrap_or(DEFAULT_K_FACTOR));
+    let mut all_results = Vec::new();
+    let mut failed_fast = false;
+    for (idx, case) in selected.iter().enumerate() {
+        let labels: Vec<String> = case.agents.iter().map(entrant_label).collect();
+        tracing::info!(
+            "Test case {} of {}: {} x{}, {}",
+            idx + 1,
+            selected.len(),
+            case.game.name(),
+            case.repetitions,
+            labels.join(" vs ")
+        );
+        if !case.description.is_empty() {
+            tracing::info!("Description: {}", case.description);
+        }
+
+        let mut results = Vec::new();
+        for rep in 0..case.repetitions {
+            if case.repetitions > 1 {
+                tracing::info!("Repetition {} of {}", rep + 1, case.repetitions);
+            }
+            let (seated, seating) = case.game.seat_agents(case.agents.clone(), rep as u64);
+            let result = play(&case.game, seated).await;
+            report_game(&case.game, case.repetitions, &result, options);
+            dispatcher.game_complete(&result);
+            // Rate the two agents by their seats in this game; Elo only
+            // rates head-to-head games
+            if let [one, two] = labels.as_slice() {
+                let scored = game_points(&result);
+                let first_seat = seating.iter().position(|&i| i == 0).unwrap_or(0);
+                if let Some(outcome) = outcome_from_points([scored[first_seat], scored[1 - first_seat]]) {
+                    elo.record_game(one, two, outcome);
                 }
             }
+            if options.fail_fast && let Some(e) = result.error() {
+                stop_after_failure(e);
+                failed_fast = true;
+            }
+            results.push(result);
+            if failed_fast {
+                break;
+            }
+        }
+        report_aggregate(&results, options);
+        all_results.extend(results);
+        if failed_fast {
+            break;
         }
     }
 
+    let summary = dispatcher.finish();
+    report_batch(&summary, &all_results, &elo);
+    Ok(summary)
+}
+
+/// Say why a `fail_fast` batch starts no more games
+fn stop_after_failure(error: &str) {
+    tracing::error!("A game ended in an error ({}); not starting more games", error);
+}
+
+/// Totals, reliability and ratings printed once a batch finishes
+fn report_batch(summary: &BatchSummary, all_results: &[TestResult], elo: &EloTracker) {
     println!("\n{}", "=".repeat(80));
     println!("BATCH RUN COMPLETE");
-    println!("Total games: {}", total_games);
-    println!("Completed: {}", completed_games);
+    println!("Total games: {}", summary.total_games);
+    println!("Completed: {}", summary.completed_games);
     println!("{}", "=".repeat(80));
 
-    Ok(())
+    if !all_results.is_empty() {
+        println!("\n📈 AGENT RELIABILITY");
+        println!("{}", reliability_report(all_results));
+        println!("\n🧼 CLEAN GAMES (no invalid moves)");
+        println!("{}", clean_rate_report(all_results));
+    }
+    if !elo.is_empty() {
+        println!("\n♟️  ELO RATINGS");
+        println!("{}", elo.table());
+    }
+    if let Some(retries) = cooldown::global().retry_report() {
+        println!("\n🔁 API RETRIES");
+        println!("{}", retries);
+    }
+}
+
+/// Per-game and per-case tables are left out, for `quiet` or because the
+/// progress bar stands in for them
+fn quiet_tables(options: &BatchOptions) -> bool {
+    options.quiet || options.progress
+}
+
+fn report_game(game: &Game, repetitions: u32, result: &TestResult, options: &BatchOptions) {
+    if quiet_tables(options) {
+        return;
+    }
+    if options.verbose || repetitions == 1 {
+        print_game_stats(game.name(), result, options.pricing, options.show_boards);
+    } else {
+        // Brief summary for multiple repetitions
+        println!("  Result: {}", 
+            result.winner().map(|w| format!("Winner: {}", w))
+                .unwrap_or_else(|| "Draw".to_string()));
+    }
+}
+
+/// Each agent's seed, in agent order, e.g. "42 vs 7"
+fn seed_list(agents: &[AIAgentConfig]) -> String {
+    agents
+        .iter()
+        .map(|agent| agent.seed.map_or_else(|| "none".to_string(), |seed| seed.to_string()))
+        .collect::<Vec<_>>()
+        .join(" vs ")
+}
+
+/// Totals across a test case's repetitions; nothing for a single game
+fn report_aggregate(results: &[TestResult], options: &BatchOptions) {
+    if results.len() > 1 && !quiet_tables(options) {
+        println!("\n📊 SUMMARY OVER {} GAMES", results.len());
+        println!("{}", AggregateStats::from_results(results));
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::result_sink::EXIT_GAMES_ERRORED;
+    use crate::secrets::ProviderDefaults;
 
     fn create_test_headers() -> csv::StringRecord {
         csv::StringRecord::from(vec![
@@ -238,7 +984,7 @@ mod tests {
             "Test game",
         ]);
 
-        let result = CsvTestCase::from_record(record, &headers);
+        let result = CsvTestCase::from_record(record, &headers, &ModelDefaults::default());
         assert!(result.is_ok());
         let test_case = result.unwrap();
         
@@ -255,6 +1001,34 @@ mod tests {
         assert_eq!(test_case.description, "Test game");
     }
 
+    #[test]
+    fn test_csv_test_case_from_record_provider_defaults() {
+        let defaults = ModelDefaults {
+            openai: ProviderDefaults {
+                model: Some("gpt-4o".to_string()),
+                temp: Some(0.2),
+            },
+            ..Default::default()
+        };
+        let headers = create_test_headers();
+        let record = csv::StringRecord::from(vec![
+            "TicTacToe", "OpenAI", "", "", "", "", "OpenAI", "gpt-4o-mini", "0.9", "", "", "", "",
+        ]);
+
+        let test_case = CsvTestCase::from_record(record, &headers, &defaults).unwrap();
+        assert_eq!(test_case.agent_one_model, "gpt-4o");
+        assert_eq!(test_case.agent_one_temp, 0.2);
+        assert_eq!(test_case.agent_two_model, "gpt-4o-mini");
+        assert_eq!(test_case.agent_two_temp, 0.9);
+
+        // No default for Ollama, so its model is still required
+        let record = csv::StringRecord::from(vec![
+            "TicTacToe", "OpenAI", "", "", "", "", "Ollama", "", "", "", "", "", "",
+        ]);
+        let err = CsvTestCase::from_record(record, &headers, &defaults).unwrap_err();
+        assert!(err.contains("agent_two_model"));
+    }
+
     #[test]
     fn test_csv_test_case_from_record_full() {
         let headers = create_test_headers();
@@ -274,7 +1048,7 @@ mod tests {
             "Full test",
         ]);
 
-        let result = CsvTestCase::from_record(record, &headers);
+        let result = CsvTestCase::from_record(record, &headers, &ModelDefaults::default());
         assert!(result.is_ok());
         let test_case = result.unwrap();
         
@@ -293,6 +1067,27 @@ mod tests {
         assert_eq!(test_case.description, "Full test");
     }
 
+    #[test]
+    fn test_csv_test_case_from_record_game_params() {
+        let headers = csv::StringRecord::from(vec![
+            "game_name", "agent_one_kind", "agent_one_model", "agent_two_kind", "agent_two_model", "rows", "cols", "win_length",
+        ]);
+        let record = csv::StringRecord::from(vec!["ConnectFour", "OpenAI", "gpt-4o-mini", "Ollama", "llama3", "5", "8", ""]);
+
+        let test_case = CsvTestCase::from_record(record, &headers, &ModelDefaults::default()).unwrap();
+        assert_eq!(test_case.game_params.rows, Some(5));
+        assert_eq!(test_case.game_params.win_length, None);
+        let Game::ConnectFour(config) = test_case.game().unwrap() else {
+            panic!("expected ConnectFour");
+        };
+        assert_eq!((config.rows, config.cols, config.win_length), (5, 8, 4));
+
+        // TicTacToe has a board_size, not rows and cols
+        let record = csv::StringRecord::from(vec!["TicTacToe", "OpenAI", "gpt-4o-mini", "Ollama", "llama3", "5", "", ""]);
+        let err = CsvTestCase::from_record(record, &headers, &ModelDefaults::default()).unwrap_err();
+        assert_eq!(err, "TicTacToe has no rows setting");
+    }
+
     #[test]
     fn test_csv_test_case_from_record_case_insensitive_agent_kind() {
         let headers = create_test_headers();
@@ -312,7 +1107,7 @@ mod tests {
             "",
         ]);
 
-        let result = CsvTestCase::from_record(record, &headers);
+        let result = CsvTestCase::from_record(record, &headers, &ModelDefaults::default());
         assert!(result.is_ok());
         let test_case = result.unwrap();
         assert_eq!(test_case.agent_one_kind, AgentKind::OpenAI);
@@ -338,7 +1133,7 @@ mod tests {
             "",
         ]);
 
-        let result = CsvTestCase::from_record(record, &headers);
+        let result = CsvTestCase::from_record(record, &headers, &ModelDefaults::default());
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("Invalid agent kind"));
     }
@@ -348,7 +1143,7 @@ mod tests {
         let headers = csv::StringRecord::from(vec!["game_name", "agent_one_kind"]);
         let record = csv::StringRecord::from(vec!["TicTacToe", "OpenAI"]);
 
-        let result = CsvTestCase::from_record(record, &headers);
+        let result = CsvTestCase::from_record(record, &headers, &ModelDefaults::default());
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("Missing required field"));
     }
@@ -357,16 +1152,25 @@ mod tests {
     fn test_csv_test_case_to_agent_configs() {
         let test_case = CsvTestCase {
             game_name: "TicTacToe".to_string(),
+            game_params: GameParams::default(),
             agent_one_kind: AgentKind::OpenAI,
             agent_one_model: "gpt-4o-mini".to_string(),
             agent_one_temp: 0.7,
             agent_one_seed: 42,
             agent_one_secret_profile: Some("profile1".to_string()),
+            agent_one_max_tokens: None,
+            agent_one_temp_schedule: None,
+            agent_one_system_prompt: None,
+            agent_one_system_prompt_file: None,
             agent_two_kind: AgentKind::Ollama,
             agent_two_model: "llama3".to_string(),
             agent_two_temp: 0.8,
             agent_two_seed: 43,
             agent_two_secret_profile: None,
+            agent_two_max_tokens: None,
+            agent_two_temp_schedule: None,
+            agent_two_system_prompt: None,
+            agent_two_system_prompt_file: None,
             repetitions: 1,
             description: "Test".to_string(),
         };
@@ -386,5 +1190,602 @@ mod tests {
         assert_eq!(configs[1].agent, AgentKind::Ollama);
         assert_eq!(configs[1].secret_profile, None);
     }
-}
 
+    fn deterministic_case(description: &str) -> CsvTestCase {
+        CsvTestCase {
+            game_name: "TicTacToe".to_string(),
+            game_params: GameParams::default(),
+            agent_one_kind: AgentKind::OpenAI,
+            agent_one_model: "gpt-4o-mini".to_string(),
+            agent_one_temp: 0.0,
+            agent_one_seed: 1,
+            agent_one_secret_profile: None,
+            agent_one_max_tokens: None,
+            agent_one_temp_schedule: None,
+            agent_one_system_prompt: None,
+            agent_one_system_prompt_file: None,
+            agent_two_kind: AgentKind::Ollama,
+            agent_two_model: "llama3".to_string(),
+            agent_two_temp: 0.0,
+            agent_two_seed: 2,
+            agent_two_secret_profile: None,
+            agent_two_max_tokens: None,
+            agent_two_temp_schedule: None,
+            agent_two_system_prompt: None,
+            agent_two_system_prompt_file: None,
+            repetitions: 1,
+            description: description.to_string(),
+        }
+    }
+
+    struct CountingSink {
+        games: std::sync::Arc<std::sync::Mutex<u32>>,
+    }
+
+    impl ResultSink for CountingSink {
+        fn on_game_complete(&self, _result: &TestResult) {
+            *self.games.lock().unwrap() += 1;
+        }
+
+        fn on_batch_complete(&self, _summary: &BatchSummary) {}
+    }
+
+    fn dedupe() -> BatchOptions {
+        BatchOptions { dedupe: true, ..Default::default() }
+    }
+
+    /// Runs the cases with a stub player, returning (games played, games reported)
+    async fn run_counting(cases: &[CsvTestCase], options: BatchOptions) -> (u32, u32) {
+        let reported = std::sync::Arc::new(std::sync::Mutex::new(0));
+        let sinks: Vec<Box<dyn ResultSink>> = vec![Box::new(CountingSink {
+            games: reported.clone(),
+        })];
+        let played = std::cell::Cell::new(0);
+
+        run_test_cases(cases, &options, &sinks, async |_game: &Game, _agents| {
+            played.set(played.get() + 1);
+            TestResult::TicTacToe(crate::games::TicTacToeResult {
+                winner: Some("OpenAI_1 (X)".to_string()),
+                stats: crate::games::GameStats::new(),
+                error: None,
+            })
+        })
+        .await
+        .unwrap();
+
+        let reported = *reported.lock().unwrap();
+        (played.get(), reported)
+    }
+
+    #[tokio::test]
+    async fn test_batch_stops_once_budget_would_be_exceeded() {
+        let mut case = deterministic_case("pricey");
+        case.repetitions = 5;
+        let options = BatchOptions {
+            budget: Some(Budget {
+                limit_usd: 2.5,
+                pricing: crate::budget::Pricing {
+                    prompt_per_1k: 1.0,
+                    completion_per_1k: 0.0,
+                },
+            }),
+            ..BatchOptions::default()
+        };
+        let played = std::cell::Cell::new(0);
+
+        // Every game reports 1000 prompt tokens, i.e. $1.00
+        let summary = run_test_cases(&[case.clone(), case], &options, &[], async |_game: &Game, _agents| {
+            played.set(played.get() + 1);
+            let mut stats = crate::games::GameStats::new();
+            stats.add_turn(crate::games::stats::TurnStats {
+                turn_number: 1,
+                player: "OpenAI_1".to_string(),
+                move_made: serde_json::json!({}),
+                time_taken_ms: 1,
+                api_latency_ms: 1,
+                move_valid: true,
+                state_before: serde_json::json!({}),
+                state_after: serde_json::json!({}),
+                usage: Some(crate::agent::TokenUsage {
+                    prompt_tokens: 1000,
+                    completion_tokens: 10,
+                }),
+                ..Default::default()
+            });
+            TestResult::TicTacToe(crate::games::TicTacToeResult {
+                winner: None,
+                stats,
+                error: None,
+            })
+        })
+        .await
+        .unwrap();
+
+        // $1 + $1 spent; a third game would reach $3
+        assert_eq!(played.get(), 2);
+        assert_eq!(summary.total_games, 2);
+    }
+
+    #[test]
+    fn test_same_setup_ignores_description() {
+        assert!(deterministic_case("a").same_setup(&deterministic_case("b")));
+
+        let mut other = deterministic_case("a");
+        other.agent_two_seed = 3;
+        assert!(!deterministic_case("a").same_setup(&other));
+    }
+
+    #[tokio::test]
+    async fn test_dedupe_reuses_identical_deterministic_case() {
+        let cases = vec![deterministic_case("first"), deterministic_case("second")];
+
+        assert_eq!(run_counting(&cases, dedupe()).await, (1, 2));
+        assert_eq!(run_counting(&cases, BatchOptions::default()).await, (2, 2));
+    }
+
+    #[tokio::test]
+    async fn test_dedupe_replays_non_deterministic_case() {
+        let mut case = deterministic_case("warm");
+        case.agent_one_temp = 0.7;
+        let cases = vec![case.clone(), case];
+
+        assert_eq!(run_counting(&cases, dedupe()).await, (2, 2));
+    }
+
+    #[tokio::test]
+    async fn test_adaptive_stop_ends_when_interval_is_narrow() {
+        let options = BatchOptions {
+            adaptive_stop: Some(AdaptiveStop { max_width: 0.2, max_games: 100 }),
+            ..Default::default()
+        };
+        // The stub player always wins for agent one, so the interval is
+        // narrow enough after 16 games
+        assert_eq!(run_counting(&[deterministic_case("lopsided")], options).await, (16, 16));
+    }
+
+    fn outcome(winner: Option<&str>) -> TestResult {
+        TestResult::TicTacToe(crate::games::TicTacToeResult {
+            winner: winner.map(|w| w.to_string()),
+            stats: crate::games::GameStats {
+                draw: winner.is_none(),
+                ..crate::games::GameStats::new()
+            },
+            error: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_draw_triggers_seat_swapped_rematch() {
+        let case = deterministic_case("drawish");
+        let seatings = std::cell::RefCell::new(Vec::new());
+
+        let play = async |_: &Game, agents: Vec<AIAgentConfig>| {
+            seatings.borrow_mut().push(agents[0].model.clone());
+            if seatings.borrow().len() == 1 { outcome(None) } else { outcome(Some("Ollama_1 (X)")) }
+        };
+        let rematched = play_until_decisive(&Game::new("TicTacToe").unwrap(), &case.to_agent_configs(), 3, &play).await;
+
+        assert_eq!(*seatings.borrow(), vec!["gpt-4o-mini", "llama3"]);
+        assert_eq!(rematched.rematches, 1);
+        assert!(rematched.swapped);
+        assert_eq!(rematched.result.winner_seat(), Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_decisive_game_is_not_rematched() {
+        let case = deterministic_case("decisive");
+        let calls = std::cell::Cell::new(0);
+
+        let play = async |_: &Game, _| {
+            calls.set(calls.get() + 1);
+            outcome(Some("OpenAI_1 (X)"))
+        };
+        let rematched = play_until_decisive(&Game::new("TicTacToe").unwrap(), &case.to_agent_configs(), 3, &play).await;
+
+        assert_eq!(calls.get(), 1);
+        assert_eq!(rematched.rematches, 0);
+        assert!(!rematched.swapped);
+    }
+
+    #[tokio::test]
+    async fn test_rematches_are_capped() {
+        let case = deterministic_case("stubborn");
+        let calls = std::cell::Cell::new(0);
+
+        let play = async |_: &Game, _| {
+            calls.set(calls.get() + 1);
+            outcome(None)
+        };
+        let rematched = play_until_decisive(&Game::new("TicTacToe").unwrap(), &case.to_agent_configs(), 2, &play).await;
+
+        assert_eq!(calls.get(), 3);
+        assert_eq!(rematched.rematches, 2);
+        assert!(!rematched.swapped);
+    }
+
+    #[tokio::test]
+    async fn test_empty_csv_is_an_error() {
+        let dir = std::env::temp_dir().join(format!("ai_arena_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let headers_only = dir.join("headers_only.csv");
+        std::fs::write(&headers_only, "game_name,agent_one_kind,agent_one_model,agent_two_kind,agent_two_model\n").unwrap();
+        let empty = dir.join("empty.csv");
+        std::fs::write(&empty, "").unwrap();
+
+        for path in [&headers_only, &empty] {
+            let path = path.to_str().unwrap();
+            let err = run_csv_batch(path, &BatchOptions::default(), &[]).await.unwrap_err();
+            assert_eq!(err, format!("no test cases found in {}", path));
+        }
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bad_rows_are_skipped_not_fatal() {
+        let dir = std::env::temp_dir().join(format!("ai_arena_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let csv = dir.join("batch.csv");
+        std::fs::write(
+            &csv,
+            "game_name,agent_one_kind,agent_one_model,agent_two_kind,agent_two_model,board_size\n\
+             TicTacToe,OpenAI,gpt-4o,OpenAI,gpt-4o,\n\
+             TickTacToe,OpenAI,gpt-4o,OpenAI,gpt-4o,\n\
+             Gomoku,Gemini,x,OpenAI,gpt-4o,\n\
+             ConnectFour,OpenAI,gpt-4o,OpenAI,gpt-4o,\n",
+        )
+        .unwrap();
+        let cases = runnable_rows(read_csv_rows(&csv, &ModelDefaults::default()).unwrap());
+        let names: Vec<&str> = cases.iter().map(|c| c.game_name.as_str()).collect();
+        assert_eq!(names, ["TicTacToe", "ConnectFour"]);
+
+        let all_bad = dir.join("all_bad.csv");
+        std::fs::write(
+            &all_bad,
+            "game_name,agent_one_kind,agent_one_model,agent_two_kind,agent_two_model\n\
+             Chess,OpenAI,gpt-4o,OpenAI,gpt-4o\n",
+        )
+        .unwrap();
+        let path = all_bad.to_str().unwrap();
+        let err = run_csv_batch(path, &BatchOptions::default(), &[]).await.unwrap_err();
+        assert_eq!(err, format!("no runnable test cases in {}", path));
+
+        // Cases built in code are skipped the same way
+        let mut unknown = deterministic_case("chess");
+        unknown.game_name = "Chess".to_string();
+        let cases = vec![unknown, deterministic_case("ttt")];
+        let summary = run_test_cases(&cases, &BatchOptions::default(), &[], async |game: &Game, _| {
+            game.failed("stub".to_string())
+        })
+        .await
+        .unwrap();
+        assert_eq!(summary.total_games, 1);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_system_prompt_file_is_relative_to_csv() {
+        let dir = std::env::temp_dir().join(format!("ai_arena_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("prompts")).unwrap();
+        std::fs::write(dir.join("prompts/terse.txt"), "Play fast. Answer in JSON.").unwrap();
+        let header = "game_name,agent_one_kind,agent_one_model,agent_two_kind,agent_two_model,agent_one_system_prompt,agent_one_system_prompt_file\n";
+        let csv = dir.join("cases.csv");
+        std::fs::write(&csv, format!("{}TicTacToe,OpenAI,gpt-4o,Ollama,llama3,inline,prompts/terse.txt\n", header)).unwrap();
+
+        let cases = read_csv_file(&csv, &ModelDefaults::default()).unwrap();
+        let configs = cases[0].to_agent_configs();
+        assert_eq!(configs[0].system_prompt.as_deref(), Some("Play fast. Answer in JSON."));
+        assert_eq!(configs[1].system_prompt, None);
+
+        std::fs::write(&csv, format!("{}TicTacToe,OpenAI,gpt-4o,Ollama,llama3,,prompts/missing.txt\n", header)).unwrap();
+        let err = read_csv_file(&csv, &ModelDefaults::default()).unwrap_err();
+        assert!(err.starts_with("Error parsing row 2: Failed to read system prompt file"), "{}", err);
+        assert!(err.contains("missing.txt"), "{}", err);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_reports_every_problem_by_row() {
+        let dir = std::env::temp_dir().join(format!("ai_arena_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut secrets = SecretsManager::load_from_path(&dir.join("secrets.toml")).unwrap();
+        secrets.set_openai("profile1", "sk-test");
+        let csv = dir.join("cases.csv");
+        std::fs::write(
+            &csv,
+            "game_name,agent_one_kind,agent_one_model,agent_one_secret_profile,agent_two_kind,agent_two_model,agent_two_secret_profile,board_size\n\
+             TicTacToe,OpenAI,gpt-4o,profile1,Ollama,llama3,,\n\
+             TickTacToe,OpenAI,gpt-4o,,Ollama,llama3,,\n\
+             TicTacToe,Gemini,gemini,,Ollama,llama3,,\n\
+             TicTacToe,OpenAI,gpt-4o,profile3,Anthropic,claude,profile1,\n\
+             Gomoku,OpenAI,gpt-4o,,Ollama,llama3,,x\n",
+        )
+        .unwrap();
+
+        let problems = check_csv_file(&csv, &secrets).unwrap();
+        assert_eq!(
+            problems,
+            vec![
+                format!("row 3: Unknown game name: TickTacToe (expected one of {})", Game::list().join(", ")),
+                "row 4: Invalid agent kind: Gemini. Must be OpenAI, Anthropic, Ollama, Scripted, or Minimax".to_string(),
+                "row 5: Secret not found: OpenAI profile 'profile3' not found".to_string(),
+                "row 5: Secret not found: Anthropic profile 'profile1' not found".to_string(),
+                "row 6: Invalid board_size: invalid digit found in string".to_string(),
+            ]
+        );
+
+        std::fs::write(&csv, "game_name,agent_one_kind,agent_one_model,agent_two_kind,agent_two_model\n").unwrap();
+        assert_eq!(check_csv_file(&csv, &secrets).unwrap(), vec!["no test cases found".to_string()]);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_only_game_runs_matching_rows() {
+        let mut connect_four = deterministic_case("c4");
+        connect_four.game_name = "ConnectFour".to_string();
+        let cases = vec![deterministic_case("ttt"), connect_four.clone(), deterministic_case("ttt again"), connect_four];
+        let options = BatchOptions {
+            only_game: Some("ConnectFour".to_string()),
+            ..BatchOptions::default()
+        };
+
+        let played = std::cell::RefCell::new(Vec::new());
+        let summary = run_test_cases(&cases, &options, &[], async |game: &Game, _| {
+            played.borrow_mut().push(game.name().to_string());
+            game.failed("stub".to_string())
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(*played.borrow(), ["ConnectFour", "ConnectFour"]);
+        assert_eq!(summary.total_games, 2);
+
+        let unknown = BatchOptions {
+            only_game: Some("Chess".to_string()),
+            ..BatchOptions::default()
+        };
+        let err = run_test_cases(&cases, &unknown, &[], async |game: &Game, _| game.failed("stub".to_string()))
+            .await
+            .unwrap_err();
+        assert!(err.starts_with("Unknown game for --only-game: Chess (expected one of TicTacToe, "));
+    }
+
+    #[tokio::test]
+    async fn test_configured_cases_play_their_full_game_config() {
+        let big_board = crate::games::TicTacToeConfig {
+            board_size: 5,
+            ..Default::default()
+        };
+        let cases = vec![
+            ConfiguredCase {
+                game: Game::TicTacToe(big_board.clone()),
+                description: "5x5".to_string(),
+                agents: deterministic_case("").to_agent_configs(),
+                repetitions: 2,
+            },
+            ConfiguredCase {
+                game: Game::new("Nim").unwrap(),
+                description: String::new(),
+                agents: deterministic_case("").to_agent_configs(),
+                repetitions: 1,
+            },
+        ];
+
+        let played = std::cell::RefCell::new(Vec::new());
+        let summary = run_configured_cases(&cases, &BatchOptions::default(), &[], async |game: &Game, agents| {
+            played.borrow_mut().push((game.clone(), agents.len()));
+            game.failed("stub".to_string())
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            *played.borrow(),
+            [(Game::TicTacToe(big_board.clone()), 2), (Game::TicTacToe(big_board), 2), (Game::new("Nim").unwrap(), 2)]
+        );
+        assert_eq!(summary.total_games, 3);
+    }
+
+    struct OrderSink {
+        durations: std::sync::Arc<std::sync::Mutex<Vec<u64>>>,
+    }
+
+    impl ResultSink for OrderSink {
+        fn on_game_complete(&self, result: &TestResult) {
+            self.durations.lock().unwrap().push(result.stats().total_duration_ms);
+        }
+
+        fn on_batch_complete(&self, _summary: &BatchSummary) {}
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_repetitions_report_in_order() {
+        let mut case = deterministic_case("parallel");
+        case.repetitions = 5;
+        let options = BatchOptions { concurrency: 3, ..Default::default() };
+        let reported = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sinks: Vec<Box<dyn ResultSink>> = vec![Box::new(OrderSink { durations: reported.clone() })];
+
+        let started = std::cell::Cell::new(0u64);
+        let in_flight = std::cell::Cell::new(0);
+        let peak = std::cell::Cell::new(0);
+        run_test_cases(&[case], &options, &sinks, async |_game: &Game, _agents| {
+            let n = started.get();
+            started.set(n + 1);
+            in_flight.set(in_flight.get() + 1);
+            peak.set(peak.get().max(in_flight.get()));
+            // Earlier games take longer, so each wave finishes out of order
+            for _ in 0..(5 - n) {
+                tokio::task::yield_now().await;
+            }
+            in_flight.set(in_flight.get() - 1);
+            TestResult::TicTacToe(crate::games::TicTacToeResult {
+                winner: None,
+                stats: crate::games::GameStats { total_duration_ms: n, ..crate::games::GameStats::new() },
+                error: None,
+            })
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(*reported.lock().unwrap(), [0, 1, 2, 3, 4]);
+        assert_eq!(peak.get(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_seed_sweep_offsets_seeds_by_repetition() {
+        let mut case = deterministic_case("sweep");
+        case.repetitions = 3;
+        let seeds_played = |options: BatchOptions| {
+            let case = case.clone();
+            async move {
+                let seen = std::cell::RefCell::new(Vec::new());
+                run_test_cases(&[case], &options, &[], async |_game: &Game, agents: Vec<AIAgentConfig>| {
+                    let mut seeds: Vec<_> = agents.iter().map(|a| (a.model.clone(), a.seed)).collect();
+                    seeds.sort();
+                    seen.borrow_mut().push(seeds);
+                    TestResult::TicTacToe(crate::games::TicTacToeResult {
+                        winner: None,
+                        stats: crate::games::GameStats::new(),
+                        error: None,
+                    })
+                })
+                .await
+                .unwrap();
+                seen.into_inner()
+            }
+        };
+
+        let swept = seeds_played(BatchOptions { seed_sweep: true, ..Default::default() }).await;
+        let expected: Vec<_> = (0..3)
+            .map(|rep| vec![("gpt-4o-mini".to_string(), Some(1 + rep)), ("llama3".to_string(), Some(2 + rep))])
+            .collect();
+        assert_eq!(swept, expected);
+
+        let fixed = seeds_played(BatchOptions::default()).await;
+        assert_eq!(fixed, vec![expected[0].clone(); 3]);
+    }
+
+    #[tokio::test]
+    async fn test_resume_skips_recorded_repetitions() {
+        let mut first = deterministic_case("first");
+        first.repetitions = 3;
+        let mut second = deterministic_case("second");
+        second.repetitions = 2;
+        let cases = [first, second];
+        let path = std::env::temp_dir().join(format!("ai_arena_progress_{}.json", uuid::Uuid::new_v4()));
+
+        // An earlier run finished the first case and one game of the second
+        let interrupted = BatchCheckpoint::open(path.clone(), "cases.csv", 2, false).unwrap();
+        for (case, rep) in [(0, 0), (0, 1), (0, 2), (1, 0)] {
+            interrupted.record(case, rep).unwrap();
+        }
+
+        let checkpoint = BatchCheckpoint::open(path.clone(), "cases.csv", 2, true).unwrap();
+        let options = BatchOptions { checkpoint: Some(checkpoint.clone()), ..Default::default() };
+        assert_eq!(run_counting(&cases, options).await, (1, 1));
+        assert_eq!(checkpoint.completed_reps(1), 2);
+
+        // Everything is recorded now, so resuming again plays nothing
+        let options = BatchOptions {
+            checkpoint: Some(BatchCheckpoint::open(path.clone(), "cases.csv", 2, true).unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(run_counting(&cases, options).await, (0, 0));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fail_fast_stops_after_first_errored_game() {
+        let mut first = deterministic_case("first");
+        first.repetitions = 4;
+        let cases = [first, deterministic_case("second")];
+        let run = async |fail_fast| {
+            let played = std::cell::Cell::new(0);
+            let options = BatchOptions { fail_fast, ..Default::default() };
+            let summary = run_test_cases(&cases, &options, &[], async |_game: &Game, _agents| {
+                played.set(played.get() + 1);
+                // The second game fails, as with a rejected API key
+                TestResult::TicTacToe(crate::games::TicTacToeResult {
+                    winner: None,
+                    stats: crate::games::GameStats::new(),
+                    error: (played.get() == 2).then(|| "401 Unauthorized".to_string()),
+                })
+            })
+            .await
+            .unwrap();
+            (played.get(), summary.exit_code())
+        };
+
+        assert_eq!(run(true).await, (2, EXIT_GAMES_ERRORED));
+        assert_eq!(run(false).await, (5, EXIT_GAMES_ERRORED));
+    }
+
+    #[tokio::test]
+    async fn test_progress_bar_plans_each_case_games() {
+        let mut case = deterministic_case("first");
+        case.repetitions = 4;
+        assert_eq!(planned_games(&case, 0, &BatchOptions::default()), 4);
+        let adaptive = BatchOptions {
+            adaptive_stop: Some(AdaptiveStop { max_width: 0.1, max_games: 30 }),
+            ..Default::default()
+        };
+        assert_eq!(planned_games(&case, 0, &adaptive), 30);
+
+        let options = BatchOptions { progress: true, ..Default::default() };
+        let cases = [case, deterministic_case("second")];
+        let summary = run_test_cases(&cases, &options, &[], async |_game: &Game, _agents| {
+            TestResult::TicTacToe(crate::games::TicTacToeResult {
+                winner: None,
+                stats: crate::games::GameStats::new(),
+                error: None,
+            })
+        })
+        .await
+        .unwrap();
+        assert_eq!(summary.completed_games, 5);
+    }
+
+    #[tokio::test]
+    async fn test_unresolvable_key_fails_its_game_not_the_batch() {
+        // The key would resolve from the environment instead
+        if std::env::var_os("OPENAI_API_KEY").is_some() {
+            return;
+        }
+        let missing = std::env::temp_dir().join(format!("ai_arena_{}.toml", uuid::Uuid::new_v4()));
+        let secrets = SecretsCache::new(&missing, false);
+        let minimax = |case: CsvTestCase| CsvTestCase {
+            agent_two_kind: AgentKind::Minimax,
+            agent_two_model: "perfect".to_string(),
+            ..case
+        };
+        let mistyped = minimax(CsvTestCase {
+            agent_one_secret_profile: Some("mistyped".to_string()),
+            ..deterministic_case("mistyped profile")
+        });
+        let fine = minimax(CsvTestCase {
+            agent_one_kind: AgentKind::Minimax,
+            agent_one_model: "perfect".to_string(),
+            ..deterministic_case("fine")
+        });
+
+        let options = BatchOptions::default();
+        let results = std::cell::RefCell::new(Vec::new());
+        let summary = run_test_cases(&[mistyped, fine], &options, &[], async |game: &Game, agents| {
+            let result = play_configured(game, agents, &options, &secrets).await;
+            results.borrow_mut().push(result.clone());
+            result
+        })
+        .await
+        .unwrap();
+
+        assert_eq!((summary.total_games, summary.errored_games), (2, 1));
+        let results = results.into_inner();
+        let error = results[0].error().unwrap();
+        assert!(error.starts_with("Failed to start: OpenAI_1:gpt-4o-mini: Failed to resolve OpenAI API key"), "{}", error);
+        assert!(results[1].error().is_none() && results[1].stats().draw);
+    }
+}
@@ -0,0 +1,144 @@
+use tabled::{Table, Tabled, settings::{Alignment, Modify, Style, object::Rows}};
+
+use crate::agents::baseline::BaselineLevel;
+use crate::decathlon::game_points;
+use crate::games::TestResult;
+
+/// Average points per game (see `game_points`) a model needs against a
+/// level to move on; drawing every game is enough
+pub const DEFAULT_PASS_SCORE: f64 = 0.5;
+
+/// How the model fared against one level
+#[derive(Clone, Debug, PartialEq)]
+pub struct LevelResult {
+    pub level: BaselineLevel,
+    pub games: u32,
+    /// Average points per game for the model, in `[0, 1]`
+    pub points: f64,
+    pub cleared: bool,
+}
+
+/// A model's run up the difficulty ladder, stopping at the first level it
+/// fails to clear
+#[derive(Clone, Debug, PartialEq)]
+pub struct CurriculumReport {
+    pub levels: Vec<LevelResult>,
+}
+
+impl CurriculumReport {
+    /// Hardest level cleared, `None` if the model failed the first one
+    pub fn ceiling(&self) -> Option<BaselineLevel> {
+        self.levels.iter().take_while(|l| l.cleared).last().map(|l| l.level)
+    }
+
+    /// Level at which the model stopped winning, `None` if it cleared them all
+    pub fn stopped_at(&self) -> Option<BaselineLevel> {
+        self.levels.iter().find(|l| !l.cleared).map(|l| l.level)
+    }
+
+    pub fn table(&self) -> Table {
+        #[derive(Tabled)]
+        struct LevelRow {
+            #[tabled(rename = "Level")]
+            level: String,
+            #[tabled(rename = "Games")]
+            games: u32,
+            #[tabled(rename = "Points")]
+            points: String,
+            #[tabled(rename = "Cleared")]
+            cleared: &'static str,
+        }
+
+        let mut table = Table::new(self.levels.iter().map(|l| LevelRow {
+            level: l.level.name(),
+            games: l.games,
+            points: format!("{:.2}", l.points),
+            cleared: if l.cleared { "✓" } else { "✗" },
+        }));
+        table
+            .with(Style::rounded())
+            .with(Modify::new(Rows::new(1..)).with(Alignment::left()));
+        table
+    }
+}
+
+/// Play the model against each level in turn, `games_per_level` games apiece,
+/// until it averages less than `pass_score`. `play` runs one game with the
+/// model in the first seat.
+pub async fn run_curriculum(
+    levels: &[BaselineLevel],
+    games_per_level: u32,
+    pass_score: f64,
+    play: impl AsyncFn(BaselineLevel) -> TestResult,
+) -> CurriculumReport {
+    let mut report = CurriculumReport { levels: Vec::new() };
+    for &level in levels {
+        println!("\n📈 Curriculum level: {}", level.name());
+        let mut total = 0.0;
+        for _ in 0..games_per_level {
+            total += game_points(&play(level).await)[0];
+        }
+        let points = if games_per_level > 0 { total / games_per_level as f64 } else { 0.0 };
+        let cleared = points >= pass_score;
+        report.levels.push(LevelResult {
+            level,
+            games: games_per_level,
+            points,
+            cleared,
+        });
+        if !cleared {
+            break;
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::{GameStats, TicTacToeResult};
+    use std::cell::RefCell;
+
+    fn result(winner: Option<&str>) -> TestResult {
+        TestResult::TicTacToe(TicTacToeResult {
+            winner: winner.map(str::to_string),
+            stats: GameStats::new(),
+            error: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_curriculum_stops_at_first_failed_level() {
+        let played = RefCell::new(Vec::new());
+        let report = run_curriculum(&BaselineLevel::ladder(), 2, DEFAULT_PASS_SCORE, async |level| {
+            played.borrow_mut().push(level);
+            match level {
+                BaselineLevel::Random | BaselineLevel::WeightedRandom => result(Some("model (X)")),
+                _ => result(Some("baseline (O)")),
+            }
+        })
+        .await;
+
+        let depth_two = BaselineLevel::Minimax { depth: 2 };
+        assert_eq!(report.ceiling(), Some(BaselineLevel::WeightedRandom));
+        assert_eq!(report.stopped_at(), Some(depth_two));
+        assert_eq!(report.levels.len(), 3);
+        assert_eq!(report.levels[2].points, 0.0);
+        assert!(!played.borrow().contains(&BaselineLevel::PerfectMinimax));
+    }
+
+    #[tokio::test]
+    async fn test_draws_clear_a_level() {
+        let draw = || {
+            TestResult::TicTacToe(TicTacToeResult {
+                winner: None,
+                stats: GameStats { draw: true, ..GameStats::new() },
+                error: None,
+            })
+        };
+        let report = run_curriculum(&BaselineLevel::ladder(), 1, DEFAULT_PASS_SCORE, async |_| draw()).await;
+
+        assert_eq!(report.stopped_at(), None);
+        assert_eq!(report.ceiling(), Some(BaselineLevel::PerfectMinimax));
+    }
+}
@@ -0,0 +1,229 @@
+use tabled::{Table, Tabled, settings::{Alignment, Modify, Style, object::Rows}};
+
+use crate::agent_config::AIAgentConfig;
+use crate::games::{Game, TestResult};
+
+/// Points for one game, the same for every game type so events are comparable:
+/// 1 for a win, 0.5 each for a draw, 0 for a loss or a game that never finished
+pub fn game_points(result: &TestResult) -> [f64; 2] {
+    match result.winner_seat() {
+        Some(0) => [1.0, 0.0],
+        Some(_) => [0.0, 1.0],
+        None if result.error().is_none() && result.stats().draw => [0.5, 0.5],
+        None => [0.0, 0.0],
+    }
+}
+
+/// Every repetition of one game in the decathlon
+pub struct DecathlonEvent {
+    pub game: String,
+    pub results: Vec<TestResult>,
+    /// Average points per game for each agent, in `[0, 1]`
+    pub points: [f64; 2],
+}
+
+/// The same two agents played across a list of games
+pub struct Decathlon {
+    pub agents: [String; 2],
+    pub events: Vec<DecathlonEvent>,
+}
+
+impl Decathlon {
+    /// Overall score per agent: the sum of normalized event points
+    pub fn totals(&self) -> [f64; 2] {
+        self.events.iter().fold([0.0, 0.0], |acc, event| {
+            [acc[0] + event.points[0], acc[1] + event.points[1]]
+        })
+    }
+
+    /// Name of the agent with more overall points, `None` on a tie
+    pub fn leader(&self) -> Option<&str> {
+        let [one, two] = self.totals();
+        if one > two {
+            Some(&self.agents[0])
+        } else if two > one {
+            Some(&self.agents[1])
+        } else {
+            None
+        }
+    }
+
+    pub fn print_scorecard(&self) {
+        #[derive(Tabled)]
+        struct ScoreRow {
+            #[tabled(rename = "Event")]
+            event: String,
+            #[tabled(rename = "Games")]
+            games: String,
+            #[tabled(rename = "Agent 1")]
+            one: String,
+            #[tabled(rename = "Agent 2")]
+            two: String,
+        }
+
+        let mut rows: Vec<ScoreRow> = self
+            .events
+            .iter()
+            .map(|event| ScoreRow {
+                event: event.game.clone(),
+                games: event.results.len().to_string(),
+                one: format!("{:.2}", event.points[0]),
+                two: format!("{:.2}", event.points[1]),
+            })
+            .collect();
+        let [one, two] = self.totals();
+        rows.push(ScoreRow {
+            event: "OVERALL".to_string(),
+            games: self.events.iter().map(|e| e.results.len()).sum::<usize>().to_string(),
+            one: format!("{:.2}", one),
+            two: format!("{:.2}", two),
+        });
+
+        println!("\n{}", "=".repeat(80));
+        println!("DECATHLON SCORECARD");
+        println!("Agent 1: {}", self.agents[0]);
+        println!("Agent 2: {}", self.agents[1]);
+        println!("{}", "=".repeat(80));
+
+        let mut table = Table::new(rows);
+        table
+            .with(Style::rounded())
+            .with(Modify::new(Rows::new(1..)).with(Alignment::left()));
+        println!("{}", table);
+
+        match self.leader() {
+            Some(leader) => println!("🏆 Overall: {}", leader),
+            None => println!("🤝 Overall: Tied"),
+        }
+    }
+}
+
+/// Play the same two agents through each game in `games`, `repetitions`
+/// times apiece, using `play` for every individual game
+pub async fn run_decathlon(
+    games: &[Game],
+    agents: Vec<AIAgentConfig>,
+    repetitions: u32,
+    play: impl AsyncFn(&Game, Vec<AIAgentConfig>) -> TestResult,
+) -> Result<Decathlon, String> {
+    let [one, two] = agents.as_slice() else {
+        return Err(format!("A decathlon needs exactly 2 agents, got {}", agents.len()));
+    };
+    let labels = [
+        format!("{} ({:?})", one.model, one.agent),
+        format!("{} ({:?})", two.model, two.agent),
+    ];
+
+    let mut events = Vec::with_capacity(games.len());
+    for game in games {
+        println!("\n🎯 Decathlon event: {}", game.name());
+        let mut results = Vec::with_capacity(repetitions as usize);
+        let mut points = [0.0, 0.0];
+        for rep in 0..repetitions {
+            let (seated, seating) = game.seat_agents(agents.clone(), rep as u64);
+            let result = play(game, seated).await;
+            let scored = game_points(&result);
+            for (seat, agent) in seating.into_iter().enumerate() {
+                points[agent] += scored[seat];
+            }
+            results.push(result);
+        }
+        if repetitions > 0 {
+            points = [points[0] / repetitions as f64, points[1] / repetitions as f64];
+        }
+        events.push(DecathlonEvent {
+            game: game.name().to_string(),
+            results,
+            points,
+        });
+    }
+
+    Ok(Decathlon { agents: labels, events })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent_config::AgentKind;
+    use crate::games::{GameStats, RockPaperScissorsResult, TicTacToeResult};
+
+    fn agent(model: &str) -> AIAgentConfig {
+        AIAgentConfig {
+            model: model.to_string(),
+            temp: 0.0,
+            agent: AgentKind::OpenAI,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_two_game_decathlon() {
+        let games = vec![Game::new("TicTacToe").unwrap(), Game::new("RockPaperScissors").unwrap()];
+
+        let decathlon = run_decathlon(&games, vec![agent("a"), agent("b")], 2, async |game: &Game, _| match game {
+            Game::TicTacToe(_) => TestResult::TicTacToe(TicTacToeResult {
+                winner: Some("OpenAI_1 (X)".to_string()),
+                stats: GameStats::new(),
+                error: None,
+            }),
+            _ => TestResult::RockPaperScissors(RockPaperScissorsResult {
+                winner: None,
+                stats: GameStats { draw: true, ..GameStats::new() },
+                error: None,
+            }),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(decathlon.agents, ["a (OpenAI)".to_string(), "b (OpenAI)".to_string()]);
+        assert_eq!(decathlon.events.len(), 2);
+        assert_eq!(decathlon.events[0].game, "TicTacToe");
+        assert_eq!(decathlon.events[0].results.len(), 2);
+        assert_eq!(decathlon.events[0].points, [1.0, 0.0]);
+        assert_eq!(decathlon.events[1].points, [0.5, 0.5]);
+        assert_eq!(decathlon.totals(), [1.5, 0.5]);
+        assert_eq!(decathlon.leader(), Some("a (OpenAI)"));
+    }
+
+    #[tokio::test]
+    async fn test_reversed_order_credits_the_seated_agent() {
+        let reversed = Game::TicTacToe(crate::games::TicTacToeConfig {
+            order: crate::games::PlayerOrder::ReverseOrderInList,
+            ..Default::default()
+        });
+
+        let decathlon = run_decathlon(&[reversed], vec![agent("a"), agent("b")], 1, async |_: &Game, seated| {
+            // X always wins; "b" is seated as X
+            assert_eq!(seated[0].model, "b");
+            TestResult::TicTacToe(TicTacToeResult {
+                winner: Some("OpenAI_1 (X)".to_string()),
+                stats: GameStats::new(),
+                error: None,
+            })
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(decathlon.events[0].points, [0.0, 1.0]);
+        assert_eq!(decathlon.leader(), Some("b (OpenAI)"));
+    }
+
+    #[tokio::test]
+    async fn test_decathlon_requires_two_agents() {
+        let result = run_decathlon(&[Game::new("TicTacToe").unwrap()], vec![agent("a")], 1, async |_: &Game, _| {
+            unreachable!("no game should be played")
+        })
+        .await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_game_points_errored_game_scores_nothing() {
+        let result = TestResult::TicTacToe(TicTacToeResult {
+            winner: None,
+            stats: GameStats::new(),
+            error: Some("boom".to_string()),
+        });
+        assert_eq!(game_points(&result), [0.0, 0.0]);
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use super::game::TestResult;
+use super::stats::FORFEIT_NOTE;
+
+/// Totals over every repetition of one test case
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize)]
+pub struct AggregateStats {
+    pub games: u32,
+    /// Wins per agent; agents that never won are listed with 0
+    pub wins: BTreeMap<String, u32>,
+    pub draws: u32,
+    /// Wins by seat, first mover first, whichever agent sat there
+    pub seat_wins: [u32; 2],
+    /// Games that ended in an error and count as neither a win nor a draw
+    pub errors: u32,
+    pub turns: u32,
+    pub invalid_moves: u32,
+    pub total_turn_time_ms: u64,
+    /// Turns taken by each agent
+    pub agent_turns: BTreeMap<String, u32>,
+    /// Invalid moves made by each agent; agents without any are left out
+    pub agent_invalid_moves: BTreeMap<String, u32>,
+}
+
+impl AggregateStats {
+    pub fn from_results(results: &[TestResult]) -> Self {
+        results.iter().fold(Self::default(), |mut agg, result| {
+            agg.add(result);
+            agg
+        })
+    }
+
+    pub fn add(&mut self, result: &TestResult) {
+        let stats = result.stats();
+        self.games += 1;
+        for turn in &stats.turns {
+            self.wins.entry(turn.player.clone()).or_insert(0);
+            self.total_turn_time_ms += turn.time_taken_ms;
+            *self.agent_turns.entry(turn.player.clone()).or_insert(0) += 1;
+            if !turn.move_valid {
+                *self.agent_invalid_moves.entry(turn.player.clone()).or_insert(0) += 1;
+            }
+        }
+        self.turns += stats.total_turns();
+        self.invalid_moves += stats.invalid_moves;
+
+        if let Some(winner) = result.winner() {
+            // Winners are recorded as "<agent> (<seat>)"; the agent the game
+            // lists in that seat takes precedence, in case it was renamed
+            let winner = winner.strip_suffix(FORFEIT_NOTE).unwrap_or(winner);
+            let agent = match result.winner_seat().and_then(|seat| stats.seats.get(seat)) {
+                Some(agent) => agent.as_str(),
+                None => winner.rsplit_once(" (").map_or(winner, |(agent, _)| agent),
+            };
+            *self.wins.entry(agent.to_string()).or_insert(0) += 1;
+            // Only the first two seats are tallied
+            if let Some(wins) = result.winner_seat().and_then(|seat| self.seat_wins.get_mut(seat)) {
+                *wins += 1;
+            }
+        } else if result.error().is_some() {
+            self.errors += 1;
+        } else if stats.draw {
+            self.draws += 1;
+        }
+    }
+
+    pub fn average_turns(&self) -> f64 {
+        ratio(self.turns as u64, self.games as u64)
+    }
+
+    /// Mean over every turn of every game, not a mean of per-game averages
+    pub fn average_turn_time_ms(&self) -> f64 {
+        ratio(self.total_turn_time_ms, self.turns as u64)
+    }
+
+    /// Share of games won from each seat, then drawn
+    pub fn seat_rates(&self) -> [f64; 3] {
+        let games = self.games as u64;
+        [
+            ratio(self.seat_wins[0] as u64, games),
+            ratio(self.seat_wins[1] as u64, games),
+            ratio(self.draws as u64, games),
+        ]
+    }
+
+    pub fn invalid_move_rate(&self) -> f64 {
+        ratio(self.invalid_moves as u64, self.turns as u64)
+    }
+
+    /// Share of `agent`'s turns that were invalid moves
+    pub fn agent_invalid_move_rate(&self, agent: &str) -> f64 {
+        ratio(
+            self.agent_invalid_moves.get(agent).copied().unwrap_or(0) as u64,
+            self.agent_turns.get(agent).copied().unwrap_or(0) as u64,
+        )
+    }
+}
+
+fn ratio(part: u64, whole: u64) -> f64 {
+    if whole == 0 { 0.0 } else { part as f64 / whole as f64 }
+}
+
+impl fmt::Display for AggregateStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let wins: Vec<String> = self.wins.iter().map(|(agent, wins)| format!("{} {}", agent, wins)).collect();
+        writeln!(f, "Games: {}", self.games)?;
+        writeln!(f, "Wins: {}", if wins.is_empty() { "-".to_string() } else { wins.join(", ") })?;
+        writeln!(f, "Draws: {}", self.draws)?;
+        let [first, second, draws] = self.seat_rates();
+        writeln!(
+            f,
+            "By seat: 1st {} ({:.1}%), 2nd {} ({:.1}%), draw {} ({:.1}%)",
+            self.seat_wins[0],
+            first * 100.0,
+            self.seat_wins[1],
+            second * 100.0,
+            self.draws,
+            draws * 100.0
+        )?;
+        if self.errors > 0 {
+            writeln!(f, "Errors: {}", self.errors)?;
+        }
+        writeln!(f, "Avg turns: {:.1}", self.average_turns())?;
+        writeln!(f, "Avg turn time: {:.2} ms", self.average_turn_time_ms())?;
+        write!(
+            f,
+            "Invalid moves: {} of {} turns ({:.1}%)",
+            self.invalid_moves,
+            self.turns,
+            self.invalid_move_rate() * 100.0
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::stats::{GameStats, TurnStats};
+    use crate::games::TicTacToeResult;
+    use serde_json::json;
+
+    fn turn(player: &str, time_taken_ms: u64, move_valid: bool) -> TurnStats {
+        TurnStats {
+            player: player.to_string(),
+            move_made: json!({}),
+            time_taken_ms,
+            api_latency_ms: time_taken_ms,
+            move_valid,
+            error_message: (!move_valid).then(|| "Invalid move: taken".to_string()),
+            state_before: json!({}),
+            state_after: json!({}),
+            ..Default::default()
+        }
+    }
+
+    fn game(winner: Option<&str>, error: Option<&str>, turns: Vec<TurnStats>) -> TestResult {
+        let mut stats = GameStats::new();
+        for t in turns {
+            stats.add_turn(t);
+        }
+        stats.draw = winner.is_none() && error.is_none();
+        TestResult::TicTacToe(TicTacToeResult {
+            winner: winner.map(|w| w.to_string()),
+            stats,
+            error: error.map(|e| e.to_string()),
+        })
+    }
+
+    #[test]
+    fn test_folds_repetitions() {
+        let results = vec![
+            game(Some("a (X)"), None, vec![turn("a", 100, true), turn("b", 300, false), turn("a", 200, true)]),
+            // Seats swapped, and b won by forfeit
+            game(Some("b (X) (opponent forfeited)"), None, vec![turn("b", 100, true), turn("a", 100, false)]),
+            game(None, None, vec![turn("a", 100, true), turn("b", 100, true), turn("a", 100, true)]),
+            game(None, Some("agent failed"), vec![turn("c", 0, false)]),
+        ];
+
+        let agg = AggregateStats::from_results(&results);
+        assert_eq!(agg.games, 4);
+        assert_eq!(agg.wins, BTreeMap::from([("a".to_string(), 1), ("b".to_string(), 1), ("c".to_string(), 0)]));
+        assert_eq!(agg.draws, 1);
+        // a won from the first seat, b from the first seat after the swap
+        assert_eq!(agg.seat_wins, [2, 0]);
+        assert_eq!(agg.seat_rates(), [0.5, 0.0, 0.25]);
+        assert!(agg.to_string().contains("By seat: 1st 2 (50.0%), 2nd 0 (0.0%), draw 1 (25.0%)"));
+        assert_eq!(agg.errors, 1);
+        assert_eq!(agg.turns, 9);
+        assert_eq!(agg.average_turns(), 2.25);
+        assert_eq!(agg.average_turn_time_ms(), 1100.0 / 9.0);
+        assert_eq!(agg.invalid_moves, 3);
+        assert_eq!(agg.invalid_move_rate(), 3.0 / 9.0);
+        assert_eq!(agg.agent_invalid_move_rate("a"), 1.0 / 5.0);
+        assert_eq!(agg.agent_invalid_move_rate("b"), 1.0 / 3.0);
+        assert_eq!(agg.agent_invalid_move_rate("nobody"), 0.0);
+        assert!(agg.to_string().contains("Wins: a 1, b 1, c 0"));
+    }
+
+    #[test]
+    fn test_wins_go_to_the_agent_listed_in_the_seat() {
+        let mut result = game(Some("OpenAI_2:gpt-4o (O)"), None, vec![turn("gpt-4o (OpenAI)", 100, true)]);
+        result.stats_mut().seats = vec!["llama3 (Ollama)".to_string(), "gpt-4o (OpenAI)".to_string()];
+        let agg = AggregateStats::from_results(&[result]);
+        assert_eq!(agg.wins, BTreeMap::from([("gpt-4o (OpenAI)".to_string(), 1)]));
+    }
+
+    #[test]
+    fn test_empty() {
+        let agg = AggregateStats::from_results(&[]);
+        assert_eq!(agg.average_turns(), 0.0);
+        assert_eq!(agg.average_turn_time_ms(), 0.0);
+        assert!(agg.to_string().contains("Wins: -"));
+    }
+}
@@ -0,0 +1,760 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::agent::AIAgent;
+use crate::games::display::{BoardRepresentation, render_board};
+use crate::games::engine::{
+    self, GameEngine, TurnBasedGame, TurnRules, with_reasoning,
+};
+use crate::games::judge::Outcome;
+use crate::games::parsing::{looks_like_state, missing_field_error, u32_field};
+use crate::games::stats::GameStats;
+use crate::games::transcript::TranscriptWriter;
+
+/// Checkers is always played on an 8x8 board
+pub const BOARD_SIZE: u32 = 8;
+
+/// Explains the board symbols to agents
+const LEGEND: &str = "b/w are Black/White men, B/W kings. Pieces sit on squares where row + col is odd. \
+Black men move towards row 0, White men towards row 7, kings either way.";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CheckersConfig {
+    /// A player who can capture must, and must keep jumping while the
+    /// capturing piece can
+    #[serde(default)]
+    pub forced_capture: bool,
+    /// Valid moves, counting both players, after which the game is drawn
+    pub max_moves: u32,
+    /// Whether agents see the board as a matrix, ASCII text, or both
+    #[serde(default)]
+    pub board_representation: BoardRepresentation,
+    /// `max_retries`, `move_timeout_ms` and `max_invalid_moves`, set at the
+    /// top level of the config
+    #[serde(flatten)]
+    pub rules: TurnRules,
+}
+
+impl Default for CheckersConfig {
+    fn default() -> Self {
+        CheckersConfig {
+            forced_capture: false,
+            max_moves: 200,
+            board_representation: BoardRepresentation::default(),
+            rules: TurnRules::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CheckersState {
+    pub board: Vec<Vec<Option<Piece>>>,
+    pub current_player: Player,
+    pub turn_number: u32,
+    /// Valid moves played so far, for the `max_moves` draw
+    pub moves_played: u32,
+    pub game_over: bool,
+    pub winner: Option<Player>,
+}
+
+/// Black moves first and always belongs to the first agent
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Player {
+    Black,
+    White,
+}
+
+impl Player {
+    fn as_str(self) -> &'static str {
+        match self {
+            Player::Black => "Black",
+            Player::White => "White",
+        }
+    }
+
+    fn other(&self) -> Player {
+        match self {
+            Player::Black => Player::White,
+            Player::White => Player::Black,
+        }
+    }
+
+    /// Row direction this player's men move in
+    fn forward(self) -> i32 {
+        match self {
+            Player::Black => -1,
+            Player::White => 1,
+        }
+    }
+
+    /// Row where this player's men are crowned
+    fn crowning_row(self) -> u32 {
+        match self {
+            Player::Black => 0,
+            Player::White => BOARD_SIZE - 1,
+        }
+    }
+}
+
+impl std::fmt::Display for Player {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Piece {
+    pub player: Player,
+    pub king: bool,
+}
+
+impl Piece {
+    fn symbol(self) -> &'static str {
+        match (self.player, self.king) {
+            (Player::Black, false) => "b",
+            (Player::Black, true) => "B",
+            (Player::White, false) => "w",
+            (Player::White, true) => "W",
+        }
+    }
+
+    /// Diagonal directions this piece may move or jump in
+    fn directions(self) -> Vec<(i32, i32)> {
+        if self.king {
+            vec![(-1, -1), (-1, 1), (1, -1), (1, 1)]
+        } else {
+            let dr = self.player.forward();
+            vec![(dr, -1), (dr, 1)]
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Square {
+    pub row: u32,
+    pub col: u32,
+}
+
+impl Square {
+    /// The square `steps` away in direction (dr, dc), if on the board
+    fn offset(self, (dr, dc): (i32, i32), steps: i32) -> Option<Square> {
+        let row = self.row as i32 + dr * steps;
+        let col = self.col as i32 + dc * steps;
+        let on_board = |v: i32| (0..BOARD_SIZE as i32).contains(&v);
+        (on_board(row) && on_board(col)).then_some(Square {
+            row: row as u32,
+            col: col as u32,
+        })
+    }
+}
+
+impl std::fmt::Display for Square {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.row, self.col)
+    }
+}
+
+/// A piece moved from `from` to `to`, then on through `chain` when
+/// continuing a multi-jump capture
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckersMove {
+    pub from: Square,
+    pub to: Square,
+    pub chain: Vec<Square>,
+}
+
+impl CheckersMove {
+    /// Every square the piece stands on, in order
+    fn path(&self) -> Vec<Square> {
+        [self.from, self.to].into_iter().chain(self.chain.iter().copied()).collect()
+    }
+}
+
+type Board = Vec<Vec<Option<Piece>>>;
+
+fn at(board: &Board, square: Square) -> Option<Piece> {
+    board[square.row as usize][square.col as usize]
+}
+
+fn set(board: &mut Board, square: Square, piece: Option<Piece>) {
+    board[square.row as usize][square.col as usize] = piece;
+}
+
+/// Whether `piece` on `from` can jump an opponent piece not already in
+/// `captured`
+fn can_jump(board: &Board, from: Square, piece: Piece, captured: &[Square]) -> bool {
+    piece.directions().into_iter().any(|dir| {
+        let (Some(over), Some(land)) = (from.offset(dir, 1), from.offset(dir, 2)) else {
+            return false;
+        };
+        at(board, over).is_some_and(|p| p.player != piece.player)
+            && !captured.contains(&over)
+            && at(board, land).is_none()
+    })
+}
+
+#[derive(Debug)]
+pub struct Checkers {
+    config: CheckersConfig,
+    state: CheckersState,
+    game_id: String,
+    /// Where each turn is logged as it is played
+    transcript: Option<TranscriptWriter>,
+}
+
+impl Checkers {
+    pub fn new(config: CheckersConfig) -> Self {
+        let board = (0..BOARD_SIZE)
+            .map(|row| {
+                (0..BOARD_SIZE)
+                    .map(|col| {
+                        let player = match row {
+                            _ if (row + col) % 2 == 0 => return None,
+                            0..3 => Player::White,
+                            5.. => Player::Black,
+                            _ => return None,
+                        };
+                        Some(Piece { player, king: false })
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            config,
+            state: CheckersState {
+                board,
+                current_player: Player::Black,
+                turn_number: 0,
+                moves_played: 0,
+                game_over: false,
+                winner: None,
+            },
+            game_id: format!("checkers_{}", &uuid::Uuid::new_v4().to_string()[..8]),
+            transcript: None,
+        }
+    }
+
+    /// Log every turn to `transcript` as the game is played
+    pub fn with_transcript(mut self, transcript: Option<TranscriptWriter>) -> Self {
+        self.transcript = transcript;
+        self
+    }
+
+    pub async fn play_game(mut self, agents: Vec<AIAgent>) -> CheckersResult {
+        let transcript = self.transcript.take();
+        match engine::play(&mut self, &agents, transcript.as_ref()).await {
+            Ok(stats) => CheckersResult {
+                winner: stats.winner.clone(),
+                stats,
+                error: None,
+            },
+            Err(aborted) => CheckersResult {
+                winner: None,
+                stats: aborted.stats,
+                error: Some(aborted.error),
+            },
+        }
+    }
+
+    /// Read a `{"row", "col"}` object, naming `field` in errors
+    fn square(value: &Value, field: &str) -> Result<Square, String> {
+        Ok(Square {
+            row: u32_field(value, "row").map_err(|e| format!("'{}': {}", field, e))?,
+            col: u32_field(value, "col").map_err(|e| format!("'{}': {}", field, e))?,
+        })
+    }
+
+    fn parse_move(move_data: &Value) -> Result<CheckersMove, String> {
+        let square_field = |field: &str| match move_data.get(field) {
+            Some(square) if !looks_like_state(move_data) => Self::square(square, field),
+            _ => Err(missing_field_error(move_data, field)),
+        };
+        let chain = match move_data.get("chain") {
+            None | Some(Value::Null) => Vec::new(),
+            Some(Value::Array(squares)) => squares
+                .iter()
+                .map(|square| Self::square(square, "chain"))
+                .collect::<Result<_, _>>()?,
+            Some(other) => return Err(format!("'chain' must be an array of squares, got {}", other)),
+        };
+        Ok(CheckersMove {
+            from: square_field("from")?,
+            to: square_field("to")?,
+            chain,
+        })
+    }
+
+    /// Whether `player` has a capture anywhere on the board
+    fn any_capture(&self, player: Player) -> bool {
+        self.pieces(player)
+            .any(|(square, piece)| can_jump(&self.state.board, square, piece, &[]))
+    }
+
+    /// Whether `player` has any legal move at all
+    fn has_any_move(&self, player: Player) -> bool {
+        self.pieces(player).any(|(square, piece)| {
+            can_jump(&self.state.board, square, piece, &[])
+                || piece
+                    .directions()
+                    .into_iter()
+                    .any(|dir| square.offset(dir, 1).is_some_and(|to| at(&self.state.board, to).is_none()))
+        })
+    }
+
+    fn pieces(&self, player: Player) -> impl Iterator<Item = (Square, Piece)> + '_ {
+        self.state.board.iter().enumerate().flat_map(move |(row, cells)| {
+            cells.iter().enumerate().filter_map(move |(col, cell)| {
+                cell.filter(|p| p.player == player).map(|p| {
+                    let square = Square {
+                        row: row as u32,
+                        col: col as u32,
+                    };
+                    (square, p)
+                })
+            })
+        })
+    }
+
+    /// Check `mv` step by step for the player to move, returning the board
+    /// after it is played, or why it is illegal
+    fn play_out(&self, mv: &CheckersMove) -> Result<Board, String> {
+        let path = mv.path();
+        if let Some(off) = path.iter().find(|s| s.row >= BOARD_SIZE || s.col >= BOARD_SIZE) {
+            return Err(format!("Square {} is off the board", off));
+        }
+        let player = self.state.current_player;
+        let piece = at(&self.state.board, mv.from)
+            .filter(|p| p.player == player)
+            .ok_or_else(|| format!("No {} piece at {}", player, mv.from))?;
+
+        let mut board = self.state.board.clone();
+        set(&mut board, mv.from, None);
+        let mut captured = Vec::new();
+        for step in path.windows(2) {
+            let (from, to) = (step[0], step[1]);
+            let dr = to.row as i32 - from.row as i32;
+            let dc = to.col as i32 - from.col as i32;
+            if dr == 0 || dr.abs() != dc.abs() {
+                return Err(format!("Move from {} to {} is not diagonal", from, to));
+            }
+            if dr.abs() > 2 {
+                return Err(format!("Move from {} to {} is more than one square, or one jump", from, to));
+            }
+            if !piece.king && dr.signum() != player.forward() {
+                return Err(format!("Move from {} to {} goes backwards; only kings can", from, to));
+            }
+            if at(&board, to).is_some() {
+                return Err(format!("Square {} is occupied", to));
+            }
+            if dr.abs() == 1 {
+                if path.len() > 2 {
+                    return Err(format!("Move from {} to {} is not a jump; only captures can be chained", from, to));
+                }
+                continue;
+            }
+            let over = Square {
+                row: (from.row + to.row) / 2,
+                col: (from.col + to.col) / 2,
+            };
+            if !at(&board, over).is_some_and(|p| p.player != player) || captured.contains(&over) {
+                return Err(format!("Jump from {} to {} doesn't capture an opponent piece", from, to));
+            }
+            captured.push(over);
+        }
+
+        if self.config.forced_capture {
+            let last = *path.last().expect("path has at least two squares");
+            if captured.is_empty() && self.any_capture(player) {
+                return Err("A capture is available, and captures are forced".to_string());
+            }
+            let crowned = !piece.king && last.row == player.crowning_row();
+            if !captured.is_empty() && !crowned && can_jump(&board, last, piece, &captured) {
+                return Err(format!("The capture can continue from {}, and captures are forced", last));
+            }
+        }
+
+        for square in &captured {
+            set(&mut board, *square, None);
+        }
+        let last = *path.last().expect("path has at least two squares");
+        let king = piece.king || last.row == player.crowning_row();
+        set(&mut board, last, Some(Piece { king, ..piece }));
+        Ok(board)
+    }
+
+    fn state_to_json(&self) -> Value {
+        let board: Vec<Vec<Option<String>>> = self
+            .state
+            .board
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| cell.map(|p| p.symbol().to_string()))
+                    .collect()
+            })
+            .collect();
+
+        let mut state = json!({
+            "current_player": self.state.current_player.to_string(),
+            "turn_number": self.state.turn_number,
+            "moves_played": self.state.moves_played,
+            "max_moves": self.config.max_moves,
+            "game_over": self.state.game_over,
+            "winner": self.state.winner.map(|p| p.to_string()),
+            "forced_capture": self.config.forced_capture,
+            "legend": LEGEND,
+        });
+
+        let representation = self.config.board_representation;
+        if representation.includes_text() {
+            state["board_text"] = json!(render_board(&board));
+        }
+        if representation.includes_matrix() {
+            state["board"] = json!(board);
+        }
+        state
+    }
+}
+
+impl GameEngine for Checkers {
+    type Move = CheckersMove;
+
+    fn game_id(&self) -> &str {
+        &self.game_id
+    }
+
+    fn move_schema(&self) -> Value {
+        let square = |description: &str| {
+            json!({
+                "type": "object",
+                "properties": {
+                    "row": {"type": "integer", "minimum": 0, "maximum": BOARD_SIZE - 1},
+                    "col": {"type": "integer", "minimum": 0, "maximum": BOARD_SIZE - 1}
+                },
+                "required": ["row", "col"],
+                "description": description
+            })
+        };
+        with_reasoning(json!({
+            "type": "object",
+            "properties": {
+                "from": square("Square of the piece to move"),
+                "to": square("Square it moves or jumps to"),
+                "chain": {
+                    "type": "array",
+                    "items": square("Next landing square"),
+                    "description": "Further landing squares when one capture is followed by more jumps"
+                }
+            },
+            "required": ["from", "to"]
+        }))
+    }
+
+    fn state_json(&self) -> Value {
+        self.state_to_json()
+    }
+
+    fn parse_move(&self, move_data: &Value) -> Result<CheckersMove, String> {
+        Checkers::parse_move(move_data)
+    }
+}
+
+impl TurnBasedGame for Checkers {
+    const SEATS: [&'static str; 2] = ["Black", "White"];
+
+    fn rules(&self) -> TurnRules {
+        self.config.rules
+    }
+
+    /// Room for `max_moves` valid moves with as many invalid ones
+    fn max_turns(&self) -> u32 {
+        self.config.max_moves * 2
+    }
+
+    fn turn_number(&self) -> u32 {
+        self.state.turn_number
+    }
+
+    fn set_turn_number(&mut self, turn_number: u32) {
+        self.state.turn_number = turn_number;
+    }
+
+    fn current_seat(&self) -> usize {
+        match self.state.current_player {
+            Player::Black => 0,
+            Player::White => 1,
+        }
+    }
+
+    fn next_player(&mut self) {
+        self.state.current_player = self.state.current_player.other();
+    }
+
+    fn notation(&self, mv: &CheckersMove) -> String {
+        mv.path().iter().map(|sq| format!("{},{}", sq.row, sq.col)).collect::<Vec<_>>().join("-")
+    }
+
+    fn apply_move(&mut self, mv: CheckersMove) -> Result<(), String> {
+        self.state.board = self.play_out(&mv)?;
+        self.state.moves_played += 1;
+        Ok(())
+    }
+
+    /// A player left without pieces or moves loses
+    fn is_over(&self) -> Option<Outcome> {
+        if !self.has_any_move(self.state.current_player.other()) {
+            Some(Outcome::Winner(self.current_seat()))
+        } else if self.state.moves_played >= self.config.max_moves {
+            Some(Outcome::Draw)
+        } else {
+            None
+        }
+    }
+
+    fn finish(&mut self, winner: Option<usize>) {
+        self.state.game_over = true;
+        self.state.winner = winner.map(|seat| [Player::Black, Player::White][seat]);
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CheckersResult {
+    pub winner: Option<String>,
+    pub stats: GameStats,
+    pub error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sq(row: u32, col: u32) -> Square {
+        Square { row, col }
+    }
+
+    fn step(from: (u32, u32), to: (u32, u32)) -> CheckersMove {
+        CheckersMove {
+            from: sq(from.0, from.1),
+            to: sq(to.0, to.1),
+            chain: Vec::new(),
+        }
+    }
+
+    /// An empty board with the given pieces, Black to move
+    fn with_pieces(config: CheckersConfig, pieces: &[((u32, u32), Player, bool)]) -> Checkers {
+        let mut game = Checkers::new(config);
+        game.state.board = vec![vec![None; BOARD_SIZE as usize]; BOARD_SIZE as usize];
+        for &((row, col), player, king) in pieces {
+            set(&mut game.state.board, sq(row, col), Some(Piece { player, king }));
+        }
+        game
+    }
+
+    #[test]
+    fn test_opening_position() {
+        let game = Checkers::new(CheckersConfig::default());
+        assert_eq!(game.pieces(Player::Black).count(), 12);
+        assert_eq!(game.pieces(Player::White).count(), 12);
+        assert_eq!(at(&game.state.board, sq(5, 0)).unwrap().player, Player::Black);
+        assert_eq!(at(&game.state.board, sq(0, 1)).unwrap().player, Player::White);
+        assert_eq!(game.state.current_player, Player::Black);
+        assert!(game.game_id.starts_with("checkers_"));
+    }
+
+    #[test]
+    fn test_legal_simple_move() {
+        let mut game = Checkers::new(CheckersConfig::default());
+        assert!(game.apply_move(step((5, 0), (4, 1))).is_ok());
+        assert!(at(&game.state.board, sq(5, 0)).is_none());
+        assert_eq!(at(&game.state.board, sq(4, 1)).unwrap().player, Player::Black);
+        assert_eq!(game.state.moves_played, 1);
+    }
+
+    #[test]
+    fn test_legal_capture() {
+        let mut game = with_pieces(
+            CheckersConfig::default(),
+            &[((5, 2), Player::Black, false), ((4, 3), Player::White, false), ((0, 7), Player::White, false)],
+        );
+        assert!(game.apply_move(step((5, 2), (3, 4))).is_ok());
+        assert!(at(&game.state.board, sq(4, 3)).is_none());
+        assert_eq!(at(&game.state.board, sq(3, 4)).unwrap().player, Player::Black);
+        assert_eq!(game.is_over(), None);
+    }
+
+    #[test]
+    fn test_capture_chain_and_crowning() {
+        let mut game = with_pieces(
+            CheckersConfig::default(),
+            &[((4, 1), Player::Black, false), ((3, 2), Player::White, false), ((1, 4), Player::White, false)],
+        );
+        let mv = CheckersMove {
+            from: sq(4, 1),
+            to: sq(2, 3),
+            chain: vec![sq(0, 5)],
+        };
+        assert!(game.apply_move(mv).is_ok());
+        assert_eq!(at(&game.state.board, sq(0, 5)), Some(Piece { player: Player::Black, king: true }));
+        assert_eq!(game.pieces(Player::White).count(), 0);
+        assert_eq!(game.is_over(), Some(Outcome::Winner(0)));
+    }
+
+    #[test]
+    fn test_rejects_non_diagonal_and_long_moves() {
+        let mut game = Checkers::new(CheckersConfig::default());
+        assert_eq!(
+            game.apply_move(step((5, 0), (4, 0))).unwrap_err(),
+            "Move from (5, 0) to (4, 0) is not diagonal"
+        );
+        assert_eq!(
+            game.apply_move(step((5, 2), (5, 4))).unwrap_err(),
+            "Move from (5, 2) to (5, 4) is not diagonal"
+        );
+        assert!(game.apply_move(step((5, 2), (2, 5))).unwrap_err().contains("more than one square"));
+    }
+
+    #[test]
+    fn test_rejects_occupied_square() {
+        let mut game = Checkers::new(CheckersConfig::default());
+        assert_eq!(game.apply_move(step((6, 1), (5, 2))).unwrap_err(), "Square (5, 2) is occupied");
+    }
+
+    #[test]
+    fn test_rejects_jump_without_capture() {
+        let mut game = with_pieces(
+            CheckersConfig::default(),
+            &[((5, 2), Player::Black, false), ((4, 1), Player::Black, false)],
+        );
+        // Over an empty square, then over its own piece
+        assert_eq!(
+            game.apply_move(step((5, 2), (3, 4))).unwrap_err(),
+            "Jump from (5, 2) to (3, 4) doesn't capture an opponent piece"
+        );
+        assert!(game.apply_move(step((5, 2), (3, 0))).is_err());
+    }
+
+    #[test]
+    fn test_rejects_backwards_men_and_other_players_pieces() {
+        let mut game = with_pieces(
+            CheckersConfig::default(),
+            &[((4, 3), Player::Black, false), ((2, 3), Player::White, false), ((4, 5), Player::Black, true)],
+        );
+        assert!(game.apply_move(step((4, 3), (5, 4))).unwrap_err().contains("backwards"));
+        assert_eq!(game.apply_move(step((2, 3), (3, 4))).unwrap_err(), "No Black piece at (2, 3)");
+        assert_eq!(game.apply_move(step((9, 0), (8, 1))).unwrap_err(), "Square (9, 0) is off the board");
+        // Kings may move backwards
+        assert!(game.apply_move(step((4, 5), (5, 6))).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_simple_moves_in_a_chain() {
+        let mut game = Checkers::new(CheckersConfig::default());
+        let mv = CheckersMove {
+            from: sq(5, 0),
+            to: sq(4, 1),
+            chain: vec![sq(3, 2)],
+        };
+        assert!(game.apply_move(mv).unwrap_err().contains("only captures can be chained"));
+    }
+
+    #[test]
+    fn test_forced_capture() {
+        let pieces = [
+            ((5, 2), Player::Black, false),
+            ((4, 3), Player::White, false),
+            ((2, 5), Player::White, false),
+            ((6, 7), Player::Black, false),
+        ];
+        let forced = CheckersConfig {
+            forced_capture: true,
+            ..Default::default()
+        };
+
+        let mut game = with_pieces(forced.clone(), &pieces);
+        assert_eq!(
+            game.apply_move(step((6, 7), (5, 6))).unwrap_err(),
+            "A capture is available, and captures are forced"
+        );
+        assert_eq!(
+            game.apply_move(step((5, 2), (3, 4))).unwrap_err(),
+            "The capture can continue from (3, 4), and captures are forced"
+        );
+        let full = CheckersMove {
+            from: sq(5, 2),
+            to: sq(3, 4),
+            chain: vec![sq(1, 6)],
+        };
+        assert!(game.apply_move(full).is_ok());
+
+        // Without the rule, both shortcuts are fine
+        let mut game = with_pieces(CheckersConfig::default(), &pieces);
+        assert!(game.apply_move(step((6, 7), (5, 6))).is_ok());
+        assert!(game.apply_move(step((5, 2), (3, 4))).is_ok());
+    }
+
+    #[test]
+    fn test_blocked_player_loses_and_max_moves_draws() {
+        // White's only man is stuck behind Black's pieces
+        let game = with_pieces(
+            CheckersConfig::default(),
+            &[((6, 1), Player::White, false), ((7, 0), Player::Black, false), ((7, 2), Player::Black, false)],
+        );
+        assert_eq!(game.is_over(), Some(Outcome::Winner(0)));
+
+        let mut game = Checkers::new(CheckersConfig {
+            max_moves: 1,
+            ..Default::default()
+        });
+        game.apply_move(step((5, 0), (4, 1))).unwrap();
+        assert_eq!(game.is_over(), Some(Outcome::Draw));
+    }
+
+    #[test]
+    fn test_parse_move() {
+        let mv = Checkers::parse_move(&json!({
+            "from": {"row": 5, "col": 0},
+            "to": {"row": "3", "col": 2.0},
+            "chain": [{"row": 1, "col": 4}]
+        }))
+        .unwrap();
+        assert_eq!(mv.path(), vec![sq(5, 0), sq(3, 2), sq(1, 4)]);
+
+        assert!(Checkers::parse_move(&json!({"from": {"row": 5, "col": 0}})).unwrap_err().contains("'to'"));
+        assert!(Checkers::parse_move(&json!({"from": {"row": 5}, "to": {"row": 4, "col": 1}}))
+            .unwrap_err()
+            .starts_with("'from': Missing or invalid 'col' field"));
+        assert!(Checkers::parse_move(&json!({"from": {"row": 5, "col": 0}, "to": {"row": 4, "col": 1}, "chain": 3})).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_attempts_are_recorded() {
+        use crate::agents::scripted::ScriptedAgent;
+
+        let config = CheckersConfig {
+            rules: TurnRules {
+                max_retries: Some(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let black = vec![
+            json!({"from": {"row": 5, "col": 0}, "to": {"row": 4, "col": 0}}),
+            json!({"from": {"row": 5, "col": 0}, "to": {"row": 4, "col": 1}}),
+        ];
+        // White keeps moving straight ahead and forfeits
+        let white = vec![json!({"from": {"row": 2, "col": 1}, "to": {"row": 3, "col": 1}}); 2];
+        let agents = vec![
+            AIAgent::Scripted(ScriptedAgent::new("black", black)),
+            AIAgent::Scripted(ScriptedAgent::new("white", white)),
+        ];
+        let result = Checkers::new(config).play_game(agents).await;
+
+        assert_eq!(result.winner.as_deref(), Some("black (Black) (opponent forfeited)"));
+        assert_eq!(result.stats.invalid_moves, 3);
+        let first = &result.stats.turns[0];
+        assert!(!first.move_valid);
+        assert_eq!(first.error_message.as_deref(), Some("Move from (5, 0) to (4, 0) is not diagonal"));
+        assert!(result.stats.turns[1].move_valid);
+    }
+}
@@ -1,15 +1,31 @@
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::time::Instant;
 
-use crate::agent::{AIAgent, MoveRequest, MoveResponse};
-use crate::games::stats::{GameStats, TurnStats};
+use crate::agent::AIAgent;
+use crate::games::parsing::u32_field;
+use crate::games::display::{BoardRepresentation, render_board};
+use crate::games::engine::{
+    self, GameEngine, TurnBasedGame, TurnRules, with_reasoning,
+};
+use crate::games::judge::Outcome;
+use crate::games::stats::GameStats;
+use crate::games::transcript::TranscriptWriter;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ConnectFourConfig {
     pub rows: u32,
     pub cols: u32,
     pub win_length: u32,
+    /// Color that moves first; the first agent always plays Red
+    #[serde(default)]
+    pub first_player: Player,
+    /// Whether agents see the board as a matrix, ASCII text, or both
+    #[serde(default)]
+    pub board_representation: BoardRepresentation,
+    /// `max_retries`, `move_timeout_ms` and `max_invalid_moves`, set at the
+    /// top level of the config
+    #[serde(flatten)]
+    pub rules: TurnRules,
 }
 
 impl Default for ConnectFourConfig {
@@ -18,6 +34,9 @@ impl Default for ConnectFourConfig {
             rows: 6,
             cols: 7,
             win_length: 4,
+            first_player: Player::Red,
+            board_representation: BoardRepresentation::default(),
+            rules: TurnRules::default(),
         }
     }
 }
@@ -31,24 +50,21 @@ pub struct ConnectFourState {
     pub winner: Option<Player>,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Player {
+    #[default]
     Red,
     Yellow,
 }
 
 impl Player {
-    fn as_str(&self) -> &str {
+    fn as_str(self) -> &'static str {
         match self {
             Player::Red => "Red",
             Player::Yellow => "Yellow",
         }
     }
 
-    fn to_string(&self) -> String {
-        self.as_str().to_string()
-    }
-
     fn other(&self) -> Player {
         match self {
             Player::Red => Player::Yellow,
@@ -57,11 +73,22 @@ impl Player {
     }
 }
 
+impl std::fmt::Display for Player {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug)]
 pub struct ConnectFour {
     config: ConnectFourConfig,
     state: ConnectFourState,
-    stats: GameStats,
     game_id: String,
+    /// Where each turn is logged as it is played
+    transcript: Option<TranscriptWriter>,
+    /// Row and column of the last piece dropped; only lines through it can
+    /// have just been completed
+    last_drop: Option<(usize, usize)>,
 }
 
 impl ConnectFour {
@@ -69,171 +96,47 @@ impl ConnectFour {
         let rows = config.rows as usize;
         let cols = config.cols as usize;
         let board = vec![vec![None; cols]; rows];
+        let first_player = config.first_player;
         
         Self {
             config,
             state: ConnectFourState {
                 board,
-                current_player: Player::Red,
+                current_player: first_player,
                 turn_number: 0,
                 game_over: false,
                 winner: None,
             },
-            stats: GameStats::new(),
-            game_id: format!("c4_{}", uuid::Uuid::new_v4().to_string()[..8].to_string()),
+            game_id: format!("c4_{}", &uuid::Uuid::new_v4().to_string()[..8]),
+            transcript: None,
+            last_drop: None,
         }
     }
 
-    pub async fn play_game(mut self, agents: Vec<AIAgent>) -> ConnectFourResult {
-        let start_time = Instant::now();
-        
-        // Ensure we have exactly 2 agents
-        if agents.len() != 2 {
-            return ConnectFourResult {
-                winner: None,
-                stats: self.stats,
-                error: Some(format!("Expected 2 agents, got {}", agents.len())),
-            };
-        }
-
-        let player_red_agent = &agents[0];
-        let player_yellow_agent = &agents[1];
-        
-        // Map players to agents
-        let agent_map: Vec<(&AIAgent, Player)> = vec![
-            (player_red_agent, Player::Red),
-            (player_yellow_agent, Player::Yellow),
-        ];
-
-        let max_turns = self.config.rows * self.config.cols;
-        
-        while !self.state.game_over && self.state.turn_number < max_turns {
-            let current_agent_idx = match self.state.current_player {
-                Player::Red => 0,
-                Player::Yellow => 1,
-            };
-            
-            let (agent, player) = &agent_map[current_agent_idx];
-            
-            // Execute turn
-            match self.execute_turn(agent, *player).await {
-                Ok(()) => {
-                    // Check for win condition
-                    if self.check_win() {
-                        self.state.game_over = true;
-                        self.state.winner = Some(self.state.current_player);
-                        self.stats.winner = Some(format!("{} ({})", agent.name(), self.state.current_player.as_str()));
-                        break;
-                    }
-                    
-                    // Check for draw (board full)
-                    if self.state.turn_number >= max_turns {
-                        self.state.game_over = true;
-                        self.stats.draw = true;
-                        break;
-                    }
-                    
-                    // Switch player
-                    self.state.current_player = self.state.current_player.other();
-                }
-                Err(e) => {
-                    // Invalid move - game continues but stats are tracked
-                    eprintln!("Turn error: {}", e);
-                }
-            }
-        }
-
-        let total_duration = start_time.elapsed();
-        self.stats.total_duration_ms = total_duration.as_millis() as u64;
-
-        ConnectFourResult {
-            winner: self.stats.winner.clone(),
-            stats: self.stats,
-            error: None,
-        }
+    /// Log every turn to `transcript` as the game is played
+    pub fn with_transcript(mut self, transcript: Option<TranscriptWriter>) -> Self {
+        self.transcript = transcript;
+        self
     }
 
-    async fn execute_turn(&mut self, agent: &AIAgent, player: Player) -> Result<(), String> {
-        let turn_start = Instant::now();
-        self.state.turn_number += 1;
-
-        // Create game state JSON
-        let state_json = self.state_to_json();
-        let state_before = state_json.clone();
-
-        // Create move schema
-        let move_schema = json!({
-            "type": "object",
-            "properties": {
-                "column": {
-                    "type": "integer",
-                    "minimum": 0,
-                    "maximum": self.config.cols - 1,
-                    "description": "Column index (0-indexed) where to drop the piece"
-                }
+    pub async fn play_game(mut self, agents: Vec<AIAgent>) -> ConnectFourResult {
+        let transcript = self.transcript.take();
+        match engine::play(&mut self, &agents, transcript.as_ref()).await {
+            Ok(stats) => ConnectFourResult {
+                winner: stats.winner.clone(),
+                stats,
+                error: None,
+            },
+            Err(aborted) => ConnectFourResult {
+                winner: None,
+                stats: aborted.stats,
+                error: Some(aborted.error),
             },
-            "required": ["column"]
-        });
-
-        // Create move request
-        let move_request = MoveRequest {
-            turn_index: self.state.turn_number,
-            game_id: self.game_id.clone(),
-            state: state_json,
-            expected_move_schema: move_schema,
-        };
-
-        // Get move from agent
-        let move_response: MoveResponse = agent
-            .execute_turn(&move_request)
-            .await
-            .map_err(|e| format!("Agent error: {}", e))?;
-
-        let time_taken = turn_start.elapsed();
-
-        // Parse move
-        let move_data = move_response.chosen_move;
-        let column = move_data
-            .get("column")
-            .and_then(|v| v.as_u64())
-            .ok_or_else(|| "Missing or invalid 'column' field".to_string())? as u32;
-
-        // Validate and apply move
-        let move_valid = self.is_valid_move(column);
-        let error_message = if !move_valid {
-            Some(format!("Invalid move: column={} (column full or out of bounds)", column))
-        } else {
-            None
-        };
-
-        // Apply move if valid
-        let state_after = if move_valid {
-            self.drop_piece(column, player);
-            self.state_to_json()
-        } else {
-            state_before.clone()
-        };
-
-        // Record turn stats
-        let turn_stats = TurnStats {
-            turn_number: self.state.turn_number,
-            player: agent.name().to_string(),
-            move_made: move_data.clone(),
-            time_taken_ms: time_taken.as_millis() as u64,
-            move_valid,
-            error_message: error_message.clone(),
-            state_before,
-            state_after,
-            diagnostics: move_response.diagnostics,
-        };
-
-        self.stats.add_turn(turn_stats);
-
-        if !move_valid {
-            return Err(error_message.unwrap_or_else(|| "Invalid move".to_string()));
         }
+    }
 
-        Ok(())
+    fn parse_move(move_data: &Value) -> Result<u32, String> {
+        u32_field(move_data, "column")
     }
 
     fn is_valid_move(&self, column: u32) -> bool {
@@ -244,7 +147,25 @@ impl ConnectFour {
         self.state.board[0][column as usize].is_none()
     }
 
-    fn drop_piece(&mut self, column: u32, player: Player) {
+    /// Columns a piece can still be dropped into, left to right
+    pub fn legal_columns(&self) -> Vec<u32> {
+        (0..self.config.cols).filter(|&column| self.is_valid_move(column)).collect()
+    }
+
+    /// Every column filled to the top
+    fn is_board_full(&self) -> bool {
+        self.legal_columns().is_empty()
+    }
+
+    /// Pieces the board holds once full, which is also the most valid
+    /// moves a game can have
+    fn full_board_pieces(&self) -> u32 {
+        self.config.rows * self.config.cols
+    }
+
+    /// Drop a piece into `column`, returning the row it lands in, or `None`
+    /// if the column is full
+    fn drop_piece(&mut self, column: u32, player: Player) -> Option<usize> {
         let col = column as usize;
         let rows = self.config.rows as usize;
         
@@ -252,12 +173,46 @@ impl ConnectFour {
         for row in (0..rows).rev() {
             if self.state.board[row][col].is_none() {
                 self.state.board[row][col] = Some(player);
-                return;
+                self.last_drop = Some((row, col));
+                return Some(row);
             }
         }
+        None
     }
 
+    /// Whether the current player has a line. Only lines through the last
+    /// piece dropped can be new, so just those are checked; a board set up
+    /// without a drop is scanned in full.
     fn check_win(&self) -> bool {
+        match self.last_drop {
+            Some((row, col)) => self.wins_through(row, col),
+            None => self.scan_for_win(),
+        }
+    }
+
+    /// Whether a line of the current player's runs through `(row, col)`,
+    /// looking at most `win_length - 1` cells each way
+    fn wins_through(&self, row: usize, col: usize) -> bool {
+        let player = Some(self.state.current_player);
+        let win_length = self.config.win_length as usize;
+        let piece_at = |r: isize, c: isize| {
+            let r = usize::try_from(r).ok()?;
+            let c = usize::try_from(c).ok()?;
+            *self.state.board.get(r)?.get(c)?
+        };
+        let run = |dr: isize, dc: isize| {
+            (1..win_length as isize)
+                .take_while(|&i| piece_at(row as isize + dr * i, col as isize + dc * i) == player)
+                .count()
+        };
+        self.state.board[row][col] == player
+            && [(0, 1), (1, 0), (1, 1), (1, -1)]
+                .into_iter()
+                .any(|(dr, dc)| 1 + run(dr, dc) + run(-dr, -dc) >= win_length)
+    }
+
+    /// Whether the current player has a line anywhere on the board
+    fn scan_for_win(&self) -> bool {
         let rows = self.config.rows as usize;
         let cols = self.config.cols as usize;
         let win_length = self.config.win_length as usize;
@@ -346,8 +301,7 @@ impl ConnectFour {
             })
             .collect();
 
-        json!({
-            "board": board,
+        let mut state = json!({
             "current_player": self.state.current_player.to_string(),
             "turn_number": self.state.turn_number,
             "game_over": self.state.game_over,
@@ -355,7 +309,118 @@ impl ConnectFour {
             "rows": self.config.rows,
             "cols": self.config.cols,
             "win_length": self.config.win_length,
-        })
+            "legal_columns": self.legal_columns(),
+        });
+
+        let representation = self.config.board_representation;
+        if representation.includes_text() {
+            state["board_text"] = json!(render_board(&board));
+        }
+        if representation.includes_matrix() {
+            state["board"] = json!(board);
+        }
+        state
+    }
+}
+
+impl GameEngine for ConnectFour {
+    type Move = u32;
+
+    fn game_id(&self) -> &str {
+        &self.game_id
+    }
+
+    fn move_schema(&self) -> Value {
+        with_reasoning(json!({
+            "type": "object",
+            "properties": {
+                "column": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "maximum": self.config.cols - 1,
+                    "description": "Column index (0-indexed) where to drop the piece"
+                }
+            },
+            "required": ["column"]
+        }))
+    }
+
+    fn state_json(&self) -> Value {
+        self.state_to_json()
+    }
+
+    fn parse_move(&self, move_data: &Value) -> Result<u32, String> {
+        ConnectFour::parse_move(move_data)
+    }
+
+    /// Columns that aren't full yet
+    fn legal_moves(&self) -> Option<Value> {
+        let columns: Vec<Value> = self
+            .legal_columns()
+            .into_iter()
+            .map(|column| json!({"column": column}))
+            .collect();
+        Some(json!(columns))
+    }
+}
+
+impl TurnBasedGame for ConnectFour {
+    const SEATS: [&'static str; 2] = ["Red", "Yellow"];
+
+    fn rules(&self) -> TurnRules {
+        self.config.rules
+    }
+
+    fn max_turns(&self) -> u32 {
+        self.full_board_pieces()
+    }
+
+    fn turn_number(&self) -> u32 {
+        self.state.turn_number
+    }
+
+    fn set_turn_number(&mut self, turn_number: u32) {
+        self.state.turn_number = turn_number;
+    }
+
+    fn current_seat(&self) -> usize {
+        match self.state.current_player {
+            Player::Red => 0,
+            Player::Yellow => 1,
+        }
+    }
+
+    fn next_player(&mut self) {
+        self.state.current_player = self.state.current_player.other();
+    }
+
+    fn notation(&self, column: &u32) -> String {
+        column.to_string()
+    }
+
+    fn apply_move(&mut self, column: u32) -> Result<(), String> {
+        if !self.is_valid_move(column) {
+            return Err(format!("Invalid move: column={} (column full or out of bounds)", column));
+        }
+        self.drop_piece(column, self.state.current_player);
+        Ok(())
+    }
+
+    fn is_over(&self) -> Option<Outcome> {
+        if self.check_win() {
+            Some(Outcome::Winner(self.current_seat()))
+        } else if self.is_board_full() {
+            // Invalid moves also advance turn_number, so count pieces on the
+            // board rather than turns
+            Some(Outcome::Draw)
+        } else {
+            None
+        }
+    }
+
+    fn finish(&mut self, winner: Option<usize>) {
+        self.state.game_over = true;
+        self.state.winner = winner.map(|seat| [Player::Red, Player::Yellow][seat]);
     }
 }
 
@@ -370,6 +435,190 @@ pub struct ConnectFourResult {
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn test_too_many_invalid_moves_forfeit() {
+        use crate::agent::AIAgent;
+        use crate::agents::scripted::ScriptedAgent;
+
+        // Red's moves are fine; Yellow keeps dropping into a missing column
+        let agents = vec![
+            AIAgent::Scripted(ScriptedAgent::new("red", vec![json!({"column": 0}); 3])),
+            AIAgent::Scripted(ScriptedAgent::new("yellow", vec![json!({"column": 9}); 10])),
+        ];
+        let config = ConnectFourConfig {
+            rules: TurnRules {
+                max_invalid_moves: Some(2),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = ConnectFour::new(config).play_game(agents).await;
+
+        assert_eq!(result.winner.as_deref(), Some("red (Red) (opponent forfeited)"));
+        assert_eq!(result.stats.invalid_moves, 3);
+        assert_eq!(result.stats.turns.len(), 4);
+        assert_eq!(result.stats.move_list, ["Red:0"]);
+    }
+
+    #[tokio::test]
+    async fn test_reasoning_is_recorded_but_not_parsed() {
+        use crate::agent::AIAgent;
+        use crate::agents::scripted::ScriptedAgent;
+
+        let game = ConnectFour::new(ConnectFourConfig::default());
+        assert_eq!(game.move_schema()["properties"]["reasoning"]["type"], "string");
+        assert_eq!(game.move_schema()["required"], json!(["column"]));
+
+        // Red explains every drop, once as a non-string; Yellow never does
+        let red = vec![
+            json!({"column": 3, "reasoning": "Take the centre"}),
+            json!({"column": 3, "reasoning": "Stack the centre"}),
+            json!({"column": 3, "reasoning": {"threat": "vertical"}}),
+            json!({"column": 3, "reasoning": null}),
+        ];
+        let agents = vec![
+            AIAgent::Scripted(ScriptedAgent::new("red", red)),
+            AIAgent::Scripted(ScriptedAgent::new("yellow", vec![json!({"column": 0}); 3])),
+        ];
+        let result = game.play_game(agents).await;
+
+        assert_eq!(result.winner.as_deref(), Some("red (Red)"));
+        assert_eq!(result.stats.invalid_moves, 0);
+        let turns = &result.stats.turns;
+        assert_eq!(turns[0].move_made, json!({"column": 3}));
+        assert_eq!(turns[0].reasoning.as_deref(), Some("Take the centre"));
+        assert_eq!(turns[1].reasoning, None);
+        assert_eq!(turns[4].reasoning.as_deref(), Some(r#"{"threat":"vertical"}"#));
+        assert_eq!(turns[6].reasoning, None);
+
+        let saved = serde_json::to_value(&turns[0]).unwrap();
+        assert_eq!(saved["reasoning"], "Take the centre");
+        assert!(serde_json::to_value(&turns[1]).unwrap().get("reasoning").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_retries_forfeit_the_game() {
+        use crate::agents::socket::scripted_socket_agent;
+        use crate::agent::AIAgent;
+
+        let red = scripted_socket_agent("red", vec![json!({"column": 3})]).await;
+        let yellow = scripted_socket_agent("yellow", vec![json!({"column": 9}); 2]).await;
+        let config = ConnectFourConfig {
+            rules: TurnRules {
+                max_retries: Some(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let result = ConnectFour::new(config)
+            .play_game(vec![AIAgent::Socket(red), AIAgent::Socket(yellow)])
+            .await;
+
+        let retry: Value = serde_json::from_str(result.stats.turns[2].prompt.as_deref().unwrap()).unwrap();
+        assert!(retry["state"]["last_error"].as_str().unwrap().contains("column=9"));
+        assert_eq!(result.winner.as_deref(), Some("red (Red) (opponent forfeited)"));
+    }
+
+    fn small_board() -> ConnectFourConfig {
+        ConnectFourConfig {
+            rows: 2,
+            cols: 3,
+            win_length: 3,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_legal_moves_skip_full_columns() {
+        let mut game = ConnectFour::new(small_board());
+        assert_eq!(game.legal_moves(), Some(json!([{"column": 0}, {"column": 1}, {"column": 2}])));
+        game.drop_piece(1, Player::Red);
+        game.drop_piece(1, Player::Yellow);
+        game.drop_piece(2, Player::Red);
+        assert_eq!(game.legal_moves(), Some(json!([{"column": 0}, {"column": 2}])));
+    }
+
+    #[test]
+    fn test_legal_columns_on_an_empty_board() {
+        let game = ConnectFour::new(small_board());
+        assert_eq!(game.legal_columns(), [0, 1, 2]);
+        assert_eq!(game.state_to_json()["legal_columns"], json!([0, 1, 2]));
+    }
+
+    #[test]
+    fn test_legal_columns_skip_full_columns() {
+        let mut game = ConnectFour::new(small_board());
+        game.drop_piece(0, Player::Red);
+        game.drop_piece(1, Player::Yellow);
+        game.drop_piece(1, Player::Red);
+        assert_eq!(game.legal_columns(), [0, 2]);
+        assert_eq!(game.state_to_json()["legal_columns"], json!([0, 2]));
+        assert!(!game.is_board_full());
+    }
+
+    #[test]
+    fn test_legal_columns_on_a_full_board() {
+        let mut game = ConnectFour::new(small_board());
+        for (column, player) in [(0, Player::Red), (1, Player::Yellow), (2, Player::Red)] {
+            game.drop_piece(column, player);
+            game.drop_piece(column, player.other());
+        }
+        assert!(game.legal_columns().is_empty());
+        assert_eq!(game.state_to_json()["legal_columns"], json!([]));
+        assert!(game.is_board_full());
+        assert_eq!(game.full_board_pieces(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_full_board_without_a_line_is_a_draw() {
+        use crate::agents::socket::scripted_socket_agent;
+        use crate::agent::AIAgent;
+
+        // Bottom row R Y R, top row Y R Y
+        let red = scripted_socket_agent("red", [0, 2, 1].map(|c| json!({"column": c})).to_vec()).await;
+        let yellow = scripted_socket_agent("yellow", [1, 0, 2].map(|c| json!({"column": c})).to_vec()).await;
+
+        let result = ConnectFour::new(small_board())
+            .play_game(vec![AIAgent::Socket(red), AIAgent::Socket(yellow)])
+            .await;
+
+        assert_eq!(result.winner, None);
+        assert!(result.stats.draw);
+    }
+
+    #[tokio::test]
+    async fn test_turn_cap_with_room_left_is_not_a_draw() {
+        use crate::agents::socket::scripted_socket_agent;
+        use crate::agent::AIAgent;
+
+        // Yellow's invalid move uses up a turn, so the cap hits with two cells empty
+        let red = scripted_socket_agent("red", [0, 1].map(|c| json!({"column": c})).to_vec()).await;
+        let yellow = scripted_socket_agent("yellow", [9, 0].map(|c| json!({"column": c})).to_vec()).await;
+
+        let result = ConnectFour::new(small_board())
+            .play_game(vec![AIAgent::Socket(red), AIAgent::Socket(yellow)])
+            .await;
+
+        assert_eq!(result.stats.turns.len(), 4);
+        assert!(!result.stats.draw);
+    }
+
+    #[test]
+    fn test_board_representation_text_only() {
+        let mut game = ConnectFour::new(ConnectFourConfig {
+            rows: 2,
+            cols: 3,
+            board_representation: BoardRepresentation::Text,
+            ..Default::default()
+        });
+        game.drop_piece(1, Player::Yellow);
+
+        let state = game.state_to_json();
+        assert!(state.get("board").is_none());
+        assert_eq!(state["board_text"], "  0 1 2\n0 . . .\n1 . Y .");
+    }
+
     #[test]
     fn test_player_as_str() {
         assert_eq!(Player::Red.as_str(), "Red");
@@ -397,7 +646,7 @@ mod tests {
         assert_eq!(game.state.board[0].len(), 7);
         assert_eq!(game.state.current_player, Player::Red);
         assert_eq!(game.state.turn_number, 0);
-        assert_eq!(game.state.game_over, false);
+        assert!(!game.state.game_over);
         assert_eq!(game.state.winner, None);
     }
 
@@ -407,6 +656,7 @@ mod tests {
             rows: 8,
             cols: 10,
             win_length: 5,
+            ..Default::default()
         };
         let game = ConnectFour::new(config);
         
@@ -543,6 +793,7 @@ mod tests {
             rows: 6,
             cols: 7,
             win_length: 5,
+            ..Default::default()
         };
         let mut game = ConnectFour::new(config);
         
@@ -557,6 +808,36 @@ mod tests {
         assert!(game.check_win());
     }
 
+    #[test]
+    fn test_large_board_edge_column_win() {
+        let config = ConnectFourConfig {
+            rows: 200,
+            cols: 200,
+            win_length: 8,
+            ..Default::default()
+        };
+        let mut game = ConnectFour::new(config);
+        let edge = 199;
+
+        // Fill the bottom row alternately, so neither player has a line,
+        // checking after every drop as a game would
+        for col in 0..edge {
+            let player = if col % 2 == 0 { Player::Red } else { Player::Yellow };
+            game.state.current_player = player;
+            assert_eq!(game.drop_piece(col, player), Some(199));
+            assert!(!game.check_win());
+        }
+
+        // Red stacks the edge column; only the eighth piece wins
+        game.state.current_player = Player::Red;
+        for height in 1..=8 {
+            assert_eq!(game.drop_piece(edge, Player::Red), Some(200 - height));
+            assert_eq!(game.check_win(), height == 8, "after {} piece(s)", height);
+        }
+        game.state.current_player = Player::Yellow;
+        assert!(!game.check_win());
+    }
+
     #[test]
     fn test_state_to_json() {
         let config = ConnectFourConfig::default();
@@ -572,6 +853,36 @@ mod tests {
         assert_eq!(json["win_length"], 4);
     }
 
+    #[test]
+    fn test_state_to_json_parses_as_board() {
+        let mut game = ConnectFour::new(ConnectFourConfig::default());
+        game.drop_piece(4, Player::Yellow);
+
+        let board = crate::games::parsing::parse_board(&game.state_to_json()).unwrap();
+        assert_eq!(crate::games::parsing::board_dimensions(&board), (6, 7));
+        assert_eq!(board[5][4].as_deref(), Some("Yellow"));
+    }
+
+    #[tokio::test]
+    async fn test_first_player_yellow_agent_takes_first_turn() {
+        use crate::agents::socket::scripted_socket_agent;
+
+        let red = scripted_socket_agent("red", vec![json!({"column": 0}); 3]).await;
+        let yellow = scripted_socket_agent("yellow", vec![json!({"column": 6}); 4]).await;
+        let config = ConnectFourConfig {
+            first_player: Player::Yellow,
+            ..Default::default()
+        };
+
+        let result = ConnectFour::new(config)
+            .play_game(vec![AIAgent::Socket(red), AIAgent::Socket(yellow)])
+            .await;
+
+        assert_eq!(result.stats.turns[0].player, "yellow");
+        assert_eq!(result.stats.turns[1].player, "red");
+        assert_eq!(result.winner.as_deref(), Some("yellow (Yellow)"));
+    }
+
     #[test]
     fn test_config_default() {
         let config = ConnectFourConfig::default();
@@ -579,5 +890,104 @@ mod tests {
         assert_eq!(config.cols, 7);
         assert_eq!(config.win_length, 4);
     }
-}
 
+    mod properties {
+        use super::*;
+        use crate::games::parsing::coerce_u32;
+        use crate::games::test_support::{arb_board, arb_json, line_start, plant_line, reference_has_line, DIRECTIONS};
+        use proptest::prelude::*;
+
+        fn arb_cell() -> impl Strategy<Value = Option<Player>> + Clone {
+            prop_oneof![Just(None), Just(Some(Player::Red)), Just(Some(Player::Yellow))]
+        }
+
+        fn arb_player() -> impl Strategy<Value = Player> {
+            prop_oneof![Just(Player::Red), Just(Player::Yellow)]
+        }
+
+        fn arb_game() -> impl Strategy<Value = ConnectFour> {
+            (4u32..9, 4u32..9)
+                .prop_flat_map(|(rows, cols)| {
+                    (
+                        Just(rows),
+                        Just(cols),
+                        2..=rows.min(cols),
+                        arb_board(rows as usize, cols as usize, arb_cell()),
+                    )
+                })
+                .prop_map(|(rows, cols, win_length, board)| {
+                    let mut game = ConnectFour::new(ConnectFourConfig { rows, cols, win_length, ..Default::default() });
+                    game.state.board = board;
+                    game
+                })
+        }
+
+        proptest! {
+            #[test]
+            fn parse_move_never_panics(value in arb_json()) {
+                if let Ok(column) = ConnectFour::parse_move(&value) {
+                    prop_assert_eq!(coerce_u32(&value["column"]), Some(column));
+                }
+            }
+
+            #[test]
+            fn parse_move_accepts_well_formed(column in any::<u32>()) {
+                prop_assert_eq!(ConnectFour::parse_move(&json!({"column": column})), Ok(column));
+            }
+
+            #[test]
+            fn check_win_matches_reference(mut game in arb_game(), player in arb_player()) {
+                game.state.current_player = player;
+                let expected = reference_has_line(&game.state.board, &player, game.config.win_length as usize);
+                prop_assert_eq!(game.check_win(), expected);
+            }
+
+            #[test]
+            fn planted_line_wins_through_each_of_its_cells(
+                mut game in arb_game(),
+                player in arb_player(),
+                seed in (any::<usize>(), any::<usize>()),
+                dir in 0usize..4,
+                along in any::<prop::sample::Index>(),
+            ) {
+                let win_length = game.config.win_length as usize;
+                let (rows, cols) = (game.state.board.len(), game.state.board[0].len());
+                let (dr, dc) = DIRECTIONS[dir];
+                let start = line_start(rows, cols, (dr, dc), win_length, seed);
+                prop_assert!(plant_line(&mut game.state.board, &player, start, (dr, dc), win_length));
+                game.state.current_player = player;
+                let i = along.index(win_length) as isize;
+                let cell = ((start.0 as isize + dr * i) as usize, (start.1 as isize + dc * i) as usize);
+                prop_assert!(game.wins_through(cell.0, cell.1));
+            }
+
+            #[test]
+            fn wins_through_finds_only_real_lines(
+                mut game in arb_game(),
+                player in arb_player(),
+                cell in (any::<prop::sample::Index>(), any::<prop::sample::Index>()),
+            ) {
+                game.state.current_player = player;
+                let (row, col) = (cell.0.index(game.state.board.len()), cell.1.index(game.state.board[0].len()));
+                if game.wins_through(row, col) {
+                    prop_assert!(reference_has_line(&game.state.board, &player, game.config.win_length as usize));
+                }
+            }
+
+            #[test]
+            fn planted_line_always_wins(
+                mut game in arb_game(),
+                player in arb_player(),
+                seed in (any::<usize>(), any::<usize>()),
+                dir in 0usize..4,
+            ) {
+                let win_length = game.config.win_length as usize;
+                let (rows, cols) = (game.state.board.len(), game.state.board[0].len());
+                let start = line_start(rows, cols, DIRECTIONS[dir], win_length, seed);
+                prop_assert!(plant_line(&mut game.state.board, &player, start, DIRECTIONS[dir], win_length));
+                game.state.current_player = player;
+                prop_assert!(game.check_win());
+            }
+        }
+    }
+}
@@ -1,11 +1,80 @@
-use tabled::{Table, Tabled, settings::{Style, Alignment, Modify, object::Rows}};
+use tabled::{Table, Tabled, settings::{Style, Alignment, Disable, Modify, location::ByColumnName, object::Rows}};
 use serde_json::Value;
+use std::io::Write;
 
+use super::parsing::parse_board;
 use super::stats::GameStats;
+use crate::agent::TokenUsage;
+use crate::budget::Pricing;
 use super::game::TestResult;
 
-/// Display game statistics in a formatted table
-pub fn print_game_stats(game_name: &str, result: &TestResult) {
+/// How a board is laid out in the state sent to agents
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum BoardRepresentation {
+    /// `board` as a nested array of rows
+    #[default]
+    Matrix,
+    /// `board_text` as an ASCII grid
+    Text,
+    /// Both `board` and `board_text`
+    Both,
+}
+
+impl BoardRepresentation {
+    pub fn includes_matrix(self) -> bool {
+        matches!(self, BoardRepresentation::Matrix | BoardRepresentation::Both)
+    }
+
+    pub fn includes_text(self) -> bool {
+        matches!(self, BoardRepresentation::Text | BoardRepresentation::Both)
+    }
+}
+
+/// Render a board as an ASCII grid with row/column indices; empty cells are
+/// `.` and pieces use the first letter of their symbol (e.g. `R` for Red)
+pub fn render_board(board: &[Vec<Option<String>>]) -> String {
+    let cols = board.first().map_or(0, |row| row.len());
+    let row_label_width = board.len().saturating_sub(1).to_string().len();
+    let cell_width = cols.saturating_sub(1).to_string().len();
+
+    let mut lines = Vec::with_capacity(board.len() + 1);
+    let header: Vec<String> = (0..cols).map(|c| format!("{:>w$}", c, w = cell_width)).collect();
+    lines.push(format!("{:w$} {}", "", header.join(" "), w = row_label_width));
+
+    for (r, row) in board.iter().enumerate() {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|cell| {
+                let symbol = cell.as_deref().and_then(|s| s.chars().next()).unwrap_or('.');
+                format!("{:>w$}", symbol, w = cell_width)
+            })
+            .collect();
+        lines.push(format!("{:>w$} {}", r, cells.join(" "), w = row_label_width));
+    }
+    lines.join("\n")
+}
+
+/// A game state's board as an ASCII grid (see `render_board`), falling back
+/// to its `board_text`. `None` for states with no board, such as Rock Paper
+/// Scissors.
+pub fn render_state_board(state: &Value) -> Option<String> {
+    match parse_board(state) {
+        Ok(board) => Some(render_board(&board)),
+        Err(_) => state.get("board_text")?.as_str().map(str::to_string),
+    }
+}
+
+/// Write a game's full result, turn by turn, as one line of JSON. Field
+/// names are those of `TestResult`, as in results files.
+pub fn write_game_json(result: &TestResult, mut writer: impl Write) -> Result<(), String> {
+    serde_json::to_writer(&mut writer, result).map_err(|e| format!("Failed to serialize result: {}", e))?;
+    writeln!(writer).map_err(|e| format!("Failed to write result: {}", e))
+}
+
+/// Display game statistics in a formatted table. With `pricing`, the player
+/// summary includes each player's estimated cost, and with `show_boards` the
+/// turn table shows the board after every turn.
+pub fn print_game_stats(game_name: &str, result: &TestResult, pricing: Option<Pricing>, show_boards: bool) {
     println!("\n{}", "=".repeat(80));
     println!("GAME RESULTS: {}", game_name);
     println!("{}", "=".repeat(80));
@@ -13,18 +82,43 @@ pub fn print_game_stats(game_name: &str, result: &TestResult) {
     match result {
         TestResult::TicTacToe(result) => {
             print_game_summary(&result.stats, result.error.as_deref());
-            print_turn_table(&result.stats);
-            print_player_summary(&result.stats);
+            print_turn_table(&result.stats, show_boards);
+            print_player_summary(&result.stats, pricing);
         }
         TestResult::RockPaperScissors(result) => {
             print_game_summary(&result.stats, result.error.as_deref());
-            print_turn_table(&result.stats);
-            print_player_summary(&result.stats);
+            print_turn_table(&result.stats, show_boards);
+            print_player_summary(&result.stats, pricing);
         }
         TestResult::ConnectFour(result) => {
             print_game_summary(&result.stats, result.error.as_deref());
-            print_turn_table(&result.stats);
-            print_player_summary(&result.stats);
+            print_turn_table(&result.stats, show_boards);
+            print_player_summary(&result.stats, pricing);
+        }
+        TestResult::Gomoku(result) => {
+            print_game_summary(&result.stats, result.error.as_deref());
+            print_turn_table(&result.stats, show_boards);
+            print_player_summary(&result.stats, pricing);
+        }
+        TestResult::Checkers(result) => {
+            print_game_summary(&result.stats, result.error.as_deref());
+            print_turn_table(&result.stats, show_boards);
+            print_player_summary(&result.stats, pricing);
+        }
+        TestResult::Nim(result) => {
+            print_game_summary(&result.stats, result.error.as_deref());
+            print_turn_table(&result.stats, show_boards);
+            print_player_summary(&result.stats, pricing);
+        }
+        TestResult::Reversi(result) => {
+            print_game_summary(&result.stats, result.error.as_deref());
+            print_turn_table(&result.stats, show_boards);
+            print_player_summary(&result.stats, pricing);
+        }
+        TestResult::Custom(result) => {
+            print_game_summary(&result.stats, result.error.as_deref());
+            print_turn_table(&result.stats, show_boards);
+            print_player_summary(&result.stats, pricing);
         }
     }
 
@@ -49,7 +143,46 @@ fn print_game_summary(stats: &GameStats, error: Option<&str>) {
     println!("⏱️  Total Duration: {:.2}s", stats.total_duration_ms as f64 / 1000.0);
     println!("🔄 Total Turns: {}", stats.total_turns());
     println!("⚡ Average Turn Time: {:.2}ms", stats.average_turn_time_ms());
+    for line in latency_lines(stats) {
+        println!("{}", line);
+    }
     println!("❌ Invalid Moves: {}", stats.invalid_moves);
+    if !stats.move_list.is_empty() {
+        println!("📝 Moves: {}", stats.move_list.join(" "));
+    }
+    if let (Some(prompt), Some(completion)) = (stats.total_prompt_tokens, stats.total_completion_tokens) {
+        println!("🪙 Tokens: {} prompt / {} completion", prompt, completion);
+    }
+}
+
+/// Buckets in the turn-time histogram
+const LATENCY_BUCKETS: usize = 5;
+
+/// Widest bar in the turn-time histogram
+const LATENCY_BAR_WIDTH: usize = 30;
+
+/// Turn-time percentiles and a histogram of turn times, or nothing for a
+/// game without turns
+fn latency_lines(stats: &GameStats) -> Vec<String> {
+    let turns = stats.turns.len();
+    if turns == 0 {
+        return Vec::new();
+    }
+    let [p50, p90, p99] = [50.0, 90.0, 99.0].map(|p| stats.latency_percentile(p));
+    let mut lines = vec![format!("📈 Turn Time p50 / p90 / p99: {} / {} / {} ms", p50, p90, p99)];
+    if turns < 10 {
+        lines[0].push_str(&format!(" (only {} turn(s))", turns));
+    }
+    let histogram = stats.latency_histogram(LATENCY_BUCKETS);
+    if histogram.len() > 1 {
+        let most = histogram.iter().map(|&(_, _, count)| count).max().unwrap_or(1) as usize;
+        let range_width = histogram.iter().map(|(low, high, _)| format!("{}-{}", low, high).len()).max().unwrap_or(0);
+        for (low, high, count) in histogram {
+            let bar = "█".repeat((count as usize * LATENCY_BAR_WIDTH).div_ceil(most)) + if count > 0 { " " } else { "" };
+            lines.push(format!("   {:>width$} ms {}{}", format!("{}-{}", low, high), bar, count, width = range_width));
+        }
+    }
+    lines
 }
 
 #[derive(Tabled)]
@@ -66,9 +199,11 @@ struct TurnRow {
     valid: String,
     #[tabled(rename = "Error")]
     error: String,
+    #[tabled(rename = "Board")]
+    board: String,
 }
 
-fn print_turn_table(stats: &GameStats) {
+fn print_turn_table(stats: &GameStats, show_boards: bool) {
     if stats.turns.is_empty() {
         return;
     }
@@ -86,18 +221,24 @@ fn print_turn_table(stats: &GameStats) {
             error: turn.error_message.as_ref()
                 .map(|e| e.chars().take(30).collect::<String>())
                 .unwrap_or_else(|| "-".to_string()),
+            board: render_state_board(&turn.state_after).unwrap_or_default(),
         }
     }).collect();
+    // Games without a board, like Rock Paper Scissors, leave the column empty
+    let show_boards = show_boards && rows.iter().any(|row| !row.board.is_empty());
 
     let mut table = Table::new(rows);
     table
         .with(Style::rounded())
         .with(Modify::new(Rows::new(1..)).with(Alignment::left()));
+    if !show_boards {
+        table.with(Disable::column(ByColumnName::new("Board")));
+    }
 
     println!("{}", table);
 }
 
-fn print_player_summary(stats: &GameStats) {
+fn print_player_summary(stats: &GameStats, pricing: Option<Pricing>) {
     if stats.turns.is_empty() {
         return;
     }
@@ -116,7 +257,9 @@ fn print_player_summary(stats: &GameStats) {
             valid_moves: 0,
             invalid_moves: 0,
             total_time_ms: 0,
+            total_api_ms: 0,
             avg_time_ms: 0.0,
+            usage: None,
         });
 
         player_stat.total_turns += 1;
@@ -126,6 +269,12 @@ fn print_player_summary(stats: &GameStats) {
             player_stat.invalid_moves += 1;
         }
         player_stat.total_time_ms += turn.time_taken_ms;
+        player_stat.total_api_ms += turn.api_latency_ms;
+        if let Some(usage) = turn.usage {
+            let total = player_stat.usage.get_or_insert_with(TokenUsage::default);
+            total.prompt_tokens += usage.prompt_tokens;
+            total.completion_tokens += usage.completion_tokens;
+        }
     }
 
     // Calculate averages
@@ -150,6 +299,12 @@ fn print_player_summary(stats: &GameStats) {
         total_time: String,
         #[tabled(rename = "Avg Time (ms)")]
         avg_time: String,
+        #[tabled(rename = "API Time (ms)")]
+        api_time: String,
+        #[tabled(rename = "Tokens (in/out)")]
+        tokens: String,
+        #[tabled(rename = "Cost (USD)")]
+        cost: String,
     }
 
     let mut player_rows: Vec<PlayerRow> = player_stats.values().map(|stat| {
@@ -160,6 +315,16 @@ fn print_player_summary(stats: &GameStats) {
             invalid_moves: stat.invalid_moves.to_string(),
             total_time: stat.total_time_ms.to_string(),
             avg_time: format!("{:.2}", stat.avg_time_ms),
+            api_time: stat.total_api_ms.to_string(),
+            tokens: stat
+                .usage
+                .map(|u| format!("{}/{}", u.prompt_tokens, u.completion_tokens))
+                .unwrap_or_else(|| "-".to_string()),
+            cost: stat
+                .usage
+                .zip(pricing)
+                .map(|(usage, pricing)| format!("{:.4}", pricing.cost(&usage)))
+                .unwrap_or_else(|| "-".to_string()),
         }
     }).collect();
 
@@ -180,7 +345,11 @@ struct PlayerStats {
     valid_moves: u32,
     invalid_moves: u32,
     total_time_ms: u64,
+    /// Part of `total_time_ms` spent waiting on the agent
+    total_api_ms: u64,
     avg_time_ms: f64,
+    /// `None` if the player's provider never reported usage
+    usage: Option<TokenUsage>,
 }
 
 fn format_move(move_value: &Value) -> String {
@@ -202,3 +371,49 @@ fn format_move(move_value: &Value) -> String {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_state_board() {
+        let state = json!({"board": [["Red", null], [null, "Yellow"]], "current_player": "Red"});
+        assert_eq!(render_state_board(&state).unwrap(), "  0 1\n0 R .\n1 . Y");
+
+        let text_only = json!({"board_text": "  0\n0 X"});
+        assert_eq!(render_state_board(&text_only).unwrap(), "  0\n0 X");
+
+        let rps = json!({"round": 1, "scores": [0, 0]});
+        assert_eq!(render_state_board(&rps), None);
+    }
+
+    #[test]
+    fn test_latency_lines() {
+        let mut stats = GameStats::new();
+        assert!(latency_lines(&stats).is_empty());
+        for (turn_number, time_taken_ms) in [(1, 100), (2, 120), (3, 900)] {
+            stats.add_turn(crate::games::stats::TurnStats {
+                turn_number,
+                player: "p".to_string(),
+                move_made: json!({}),
+                time_taken_ms,
+                api_latency_ms: time_taken_ms,
+                move_valid: true,
+                state_before: json!({}),
+                state_after: json!({}),
+                ..Default::default()
+            });
+        }
+        let bar = |n| "█".repeat(n);
+        assert_eq!(
+            latency_lines(&stats),
+            [
+                "📈 Turn Time p50 / p90 / p99: 120 / 900 / 900 ms (only 3 turn(s))".to_string(),
+                format!("   100-366 ms {} 2", bar(30)),
+                "   367-633 ms 0".to_string(),
+                format!("   634-900 ms {} 1", bar(15)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,458 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::time::Instant;
+
+use crate::agent::{AIAgent, AgentError, MoveRequest, MoveResponse};
+use crate::games::judge::Outcome;
+use crate::games::stats::{FORFEIT_NOTE, GameStats, TurnStats};
+use crate::games::timeout::request_move;
+use crate::games::transcript::TranscriptWriter;
+
+/// What every game exposes to agents: its state, the moves it accepts, and
+/// how to read one
+pub trait GameEngine {
+    /// A move parsed from an agent's response
+    type Move;
+
+    /// Fewest agents a game can be played with
+    const MIN_PLAYERS: usize = 2;
+    /// Most agents a game can be played with
+    const MAX_PLAYERS: usize = 2;
+
+    fn game_id(&self) -> &str;
+
+    /// JSON schema sent with every move request
+    fn move_schema(&self) -> Value;
+
+    /// The state agents are shown
+    fn state_json(&self) -> Value;
+
+    /// Read a move out of an agent's response, without checking it is legal
+    fn parse_move(&self, move_data: &Value) -> Result<Self::Move, String>;
+
+    /// Every move the player to move may make, sent with move requests.
+    /// `None` for games that don't list them.
+    fn legal_moves(&self) -> Option<Value> {
+        None
+    }
+
+    /// Whether a probe response parses as a move for this game
+    fn probe_check(&self, move_data: &Value) -> Result<(), String> {
+        self.parse_move(move_data).map(|_| ())
+    }
+}
+
+/// Move property where an agent may explain its choice
+pub const REASONING_FIELD: &str = "reasoning";
+
+/// `schema` with an optional free-text `reasoning` property added, which
+/// every game's move schema offers
+pub fn with_reasoning(mut schema: Value) -> Value {
+    if let Some(properties) = schema["properties"].as_object_mut() {
+        properties.insert(
+            REASONING_FIELD.to_string(),
+            json!({
+                "type": "string",
+                "description": "Optional: why you chose this move. Recorded, never checked"
+            }),
+        );
+    }
+    schema
+}
+
+/// Take the `reasoning` an agent sent off its move, so parsing and
+/// validation never see it. Reasoning that isn't a string is kept as JSON.
+pub fn take_reasoning(move_data: &mut Value) -> Option<String> {
+    match move_data.as_object_mut()?.remove(REASONING_FIELD)? {
+        Value::Null => None,
+        Value::String(text) => Some(text),
+        other => Some(other.to_string()),
+    }
+}
+
+/// Check a game taking `min..=max` players can seat `players` agents
+pub fn check_player_count(players: usize, min: usize, max: usize) -> Result<(), String> {
+    if (min..=max).contains(&players) {
+        Ok(())
+    } else if min == max {
+        Err(format!("Expected {} agents, got {}", min, players))
+    } else {
+        Err(format!("Expected {} to {} agents, got {}", min, max, players))
+    }
+}
+
+/// Invalid moves a player may make in one game by default
+pub const DEFAULT_MAX_INVALID_MOVES: u32 = 3;
+
+/// Serde default for `TurnRules::max_invalid_moves`
+pub fn default_max_invalid_moves() -> Option<u32> {
+    Some(DEFAULT_MAX_INVALID_MOVES)
+}
+
+/// Per-game settings for `play`'s turn loop, flattened into every game
+/// config
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TurnRules {
+    /// Times a player is re-asked, with the reason in `last_error`, after an
+    /// invalid move before forfeiting; unset keeps re-asking without feedback
+    #[serde(default)]
+    pub max_retries: Option<u32>,
+    /// Longest to wait for one move; a move that doesn't arrive in time is
+    /// recorded as invalid
+    #[serde(default)]
+    pub move_timeout_ms: Option<u64>,
+    /// Invalid moves a player may make in one game, retried or not; one
+    /// more forfeits it. Unset allows any number.
+    #[serde(default = "default_max_invalid_moves")]
+    pub max_invalid_moves: Option<u32>,
+}
+
+impl Default for TurnRules {
+    fn default() -> Self {
+        TurnRules {
+            max_retries: None,
+            move_timeout_ms: None,
+            max_invalid_moves: default_max_invalid_moves(),
+        }
+    }
+}
+
+/// A two-player game where players alternate moves on a shared position,
+/// so `play` can run the turn loop for it
+pub trait TurnBasedGame: GameEngine {
+    /// Seat names in agent order, as used in recorded winners
+    const SEATS: [&'static str; 2];
+
+    fn rules(&self) -> TurnRules;
+
+    /// Turns, valid or not, after which the game is abandoned
+    fn max_turns(&self) -> u32;
+
+    fn turn_number(&self) -> u32;
+
+    fn set_turn_number(&mut self, turn_number: u32);
+
+    /// Seat of the player to move
+    fn current_seat(&self) -> usize;
+
+    /// Pass the move to the other player
+    fn next_player(&mut self);
+
+    /// Short form of a move for `GameStats::move_list`, e.g. `1,1`
+    fn notation(&self, mv: &Self::Move) -> String;
+
+    /// Check and play a move for the player to move, or say why it's illegal
+    fn apply_move(&mut self, mv: Self::Move) -> Result<(), String>;
+
+    /// Outcome once the last move ended the game
+    fn is_over(&self) -> Option<Outcome>;
+
+    /// Mark the game over in the state, `winner` being a seat
+    fn finish(&mut self, winner: Option<usize>);
+
+    /// Request for the current position, used to check an agent's output
+    /// format before committing to a whole game
+    fn probe_request(&self) -> MoveRequest {
+        MoveRequest {
+            turn_index: self.turn_number() + 1,
+            game_id: self.game_id().to_string(),
+            state: self.state_json(),
+            expected_move_schema: self.move_schema(),
+            legal_moves: self.legal_moves(),
+        }
+    }
+}
+
+/// A game `play` stopped before it finished, with the turns played so far
+#[derive(Debug)]
+pub struct Aborted {
+    pub error: String,
+    pub stats: GameStats,
+}
+
+/// Why a turn produced no valid move
+#[derive(Debug)]
+enum TurnError {
+    /// The agent failed to answer at all, e.g. a rejected API key; asking
+    /// again would fail the same way
+    Agent(AgentError),
+    /// The answer was unreadable, illegal or late; recorded as an invalid move
+    Invalid(String),
+}
+
+/// Play `engine` to the end with `agents` in seat order, recording every turn,
+/// also to `transcript` as it is played. Invalid moves are re-asked. A player
+/// forfeits on exhausting its `max_retries`, or on going over
+/// `max_invalid_moves` in the game. An agent error aborts the game.
+pub async fn play<E: TurnBasedGame>(
+    engine: &mut E,
+    agents: &[AIAgent],
+    transcript: Option<&TranscriptWriter>,
+) -> Result<GameStats, Aborted> {
+    if let Err(error) = check_player_count(agents.len(), E::MIN_PLAYERS, E::MAX_PLAYERS) {
+        return Err(Aborted {
+            error,
+            stats: GameStats::new(),
+        });
+    }
+
+    let start_time = Instant::now();
+    let rules = engine.rules();
+    let mut stats = GameStats {
+        game_id: Some(engine.game_id().to_string()),
+        seats: agents.iter().map(|agent| agent.name().to_string()).collect(),
+        ..GameStats::new()
+    };
+    // Why the current player's last move was rejected, sent with the retry
+    let mut last_error: Option<String> = None;
+    let mut retries = 0;
+    // Invalid moves so far per seat, for `max_invalid_moves`
+    let mut invalid_moves = [0u32; 2];
+
+    while engine.turn_number() < engine.max_turns() {
+        let seat = engine.current_seat();
+        let agent = &agents[seat];
+
+        match execute_turn(engine, agent, last_error.as_deref(), rules, &mut stats, transcript).await {
+            Ok(()) => {
+                retries = 0;
+                last_error = None;
+
+                match engine.is_over() {
+                    Some(Outcome::Winner(winner)) => {
+                        engine.finish(Some(winner));
+                        stats.winner = Some(format!("{} ({})", agents[winner].name(), E::SEATS[winner]));
+                        break;
+                    }
+                    Some(Outcome::Draw) => {
+                        engine.finish(None);
+                        stats.draw = true;
+                        break;
+                    }
+                    None => engine.next_player(),
+                }
+            }
+            Err(TurnError::Agent(e)) => {
+                stats.total_duration_ms = start_time.elapsed().as_millis() as u64;
+                return Err(Aborted {
+                    error: format!("{} failed: {}", agent.name(), e),
+                    stats,
+                });
+            }
+            Err(TurnError::Invalid(e)) => {
+                // Invalid move - game continues but stats are tracked
+                tracing::warn!("Turn error: {}", e);
+                invalid_moves[seat] += 1;
+                let over_limit = rules.max_invalid_moves.is_some_and(|max| invalid_moves[seat] > max);
+                let out_of_retries = rules.max_retries.is_some_and(|max| retries >= max);
+                if over_limit || out_of_retries {
+                    if over_limit {
+                        tracing::warn!("{} forfeits after {} invalid moves", agent.name(), invalid_moves[seat]);
+                    } else {
+                        tracing::warn!("{} forfeits after {} retries", agent.name(), retries);
+                    }
+                    let opponent = 1 - seat;
+                    engine.finish(Some(opponent));
+                    stats.winner = Some(format!("{} ({}){}", agents[opponent].name(), E::SEATS[opponent], FORFEIT_NOTE));
+                    break;
+                }
+                if rules.max_retries.is_none() {
+                    continue;
+                }
+                // Re-send the same turn, telling the agent what went wrong
+                retries += 1;
+                engine.set_turn_number(engine.turn_number() - 1);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    stats.total_duration_ms = start_time.elapsed().as_millis() as u64;
+    Ok(stats)
+}
+
+async fn execute_turn<E: TurnBasedGame>(
+    engine: &mut E,
+    agent: &AIAgent,
+    last_error: Option<&str>,
+    rules: TurnRules,
+    stats: &mut GameStats,
+    transcript: Option<&TranscriptWriter>,
+) -> Result<(), TurnError> {
+    let turn_start = Instant::now();
+    let turn_number = engine.turn_number() + 1;
+    engine.set_turn_number(turn_number);
+
+    let state_before = engine.state_json();
+
+    // Create move request, explaining a rejected previous attempt
+    let mut request_state = state_before.clone();
+    if let Some(last_error) = last_error {
+        request_state["last_error"] = json!(last_error);
+    }
+    let move_request = MoveRequest {
+        turn_index: turn_number,
+        game_id: engine.game_id().to_string(),
+        state: request_state,
+        expected_move_schema: engine.move_schema(),
+        legal_moves: engine.legal_moves(),
+    };
+
+    // Get move from agent; one that never arrives is recorded as invalid
+    let api_start = Instant::now();
+    let (move_response, timeout_error) = match request_move(agent, &move_request, rules.move_timeout_ms).await {
+        Ok(response) => (response.map_err(TurnError::Agent)?, None),
+        Err(timed_out) => (MoveResponse::new(Value::Null), Some(timed_out)),
+    };
+    let api_latency = api_start.elapsed();
+
+    // Parse, validate and apply move; unparseable moves count as invalid
+    let mut move_data = move_response.chosen_move;
+    let reasoning = take_reasoning(&mut move_data);
+    let seat = E::SEATS[engine.current_seat()];
+    let applied = match timeout_error {
+        Some(timed_out) => Err(timed_out),
+        None => engine.parse_move(&move_data).and_then(|mv| {
+            let notation = format!("{}:{}", seat, engine.notation(&mv));
+            engine.apply_move(mv).map(|()| notation)
+        }),
+    };
+    let move_valid = applied.is_ok();
+    let error_message = applied.as_ref().err().cloned();
+    match &applied {
+        Ok(notation) => tracing::debug!("Turn {}: {} played {}", turn_number, agent.name(), notation),
+        Err(e) => tracing::debug!("Turn {}: {} sent {} ({})", turn_number, agent.name(), move_data, e),
+    }
+    if let Ok(notation) = applied {
+        stats.move_list.push(notation);
+    }
+
+    let state_after = if move_valid {
+        engine.state_json()
+    } else {
+        state_before.clone()
+    };
+
+    let api_latency_ms = api_latency.as_millis() as u64;
+    let processing_ms = (turn_start.elapsed() - api_latency).as_millis() as u64;
+    let turn = TurnStats {
+        turn_number,
+        player: agent.name().to_string(),
+        move_made: move_data,
+        time_taken_ms: api_latency_ms + processing_ms,
+        api_latency_ms,
+        processing_ms,
+        move_valid,
+        error_message: error_message.clone(),
+        state_before,
+        state_after,
+        diagnostics: move_response.diagnostics,
+        perturbation: move_response.perturbation,
+        replay_consistent: move_response.replay_consistent,
+        prompt: Some(move_request.user_prompt()),
+        system_prompt: agent.system_prompt().map(str::to_string),
+        usage: move_response.usage,
+        served_model: move_response.served_model,
+        reasoning,
+    };
+    if let Some(transcript) = transcript {
+        transcript.record(engine.game_id(), &turn);
+    }
+    stats.add_turn(turn);
+
+    match error_message {
+        Some(e) => Err(TurnError::Invalid(e)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::scripted::ScriptedAgent;
+    use crate::agents::socket::scripted_socket_agent;
+    use crate::games::tic_tac_toe::{TicTacToe, TicTacToeConfig};
+
+    #[tokio::test]
+    async fn test_play_needs_two_agents() {
+        let agent = AIAgent::Socket(scripted_socket_agent("solo", vec![json!({"row": 0, "col": 0})]).await);
+        let mut game = TicTacToe::new(TicTacToeConfig::default());
+
+        let err = play(&mut game, &[agent], None).await.unwrap_err();
+        assert_eq!(err.error, "Expected 2 agents, got 1");
+    }
+
+    fn scripted(name: &str, cells: &[(u32, u32)]) -> AIAgent {
+        let moves = cells.iter().map(|(row, col)| json!({"row": row, "col": col})).collect();
+        AIAgent::Scripted(ScriptedAgent::new(name, moves))
+    }
+
+    #[tokio::test]
+    async fn test_turn_time_splits_agent_wait_from_processing() {
+        use crate::agents::socket::delayed_socket_agent;
+
+        let delay = std::time::Duration::from_millis(50);
+        let agents = [
+            AIAgent::Socket(delayed_socket_agent("x", vec![json!({"row": 0, "col": 0})], delay).await),
+            AIAgent::Socket(delayed_socket_agent("o", vec![json!({"row": 9, "col": 9})], delay).await),
+        ];
+        let config = TicTacToeConfig {
+            rules: TurnRules {
+                max_retries: Some(0),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let stats = play(&mut TicTacToe::new(config), &agents, None).await.unwrap();
+
+        assert_eq!(stats.turns.len(), 2);
+        for turn in &stats.turns {
+            assert!(turn.api_latency_ms >= 50, "{} ms", turn.api_latency_ms);
+            assert_eq!(turn.time_taken_ms, turn.api_latency_ms + turn.processing_ms);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_agent_error_aborts_the_game() {
+        let config = TicTacToeConfig {
+            rules: TurnRules {
+                max_retries: Some(5),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        // O's script runs out on its second turn
+        let agents = [scripted("x", &[(0, 0), (1, 1), (2, 2)]), scripted("o", &[(0, 1)])];
+        let mut game = TicTacToe::new(config);
+
+        let aborted = play(&mut game, &agents, None).await.unwrap_err();
+        assert_eq!(aborted.error, "o failed: internal error: script exhausted after 1 move(s)");
+        assert_eq!(aborted.stats.total_turns(), 3);
+        assert_eq!(aborted.stats.invalid_moves, 0);
+        assert_eq!(aborted.stats.winner, None);
+    }
+
+    #[tokio::test]
+    async fn test_illegal_move_is_retried_then_forfeited() {
+        let config = TicTacToeConfig {
+            rules: TurnRules {
+                max_retries: Some(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        // O tries the taken corner, is re-asked, then plays legally
+        let agents = [scripted("x", &[(0, 0), (1, 1), (2, 2)]), scripted("o", &[(0, 0), (0, 1), (0, 2)])];
+        let mut game = TicTacToe::new(config.clone());
+        let stats = play(&mut game, &agents, None).await.unwrap();
+        assert_eq!(stats.winner.as_deref(), Some("x (X)"));
+        assert_eq!(stats.invalid_moves, 1);
+
+        // Two illegal tries in a row exhaust O's one retry
+        let agents = [scripted("x", &[(0, 0)]), scripted("o", &[(0, 0), (0, 0)])];
+        let mut game = TicTacToe::new(config);
+        let stats = play(&mut game, &agents, None).await.unwrap();
+        assert_eq!(stats.winner.as_deref(), Some("x (X) (opponent forfeited)"));
+        assert_eq!(stats.invalid_moves, 2);
+    }
+}
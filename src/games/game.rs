@@ -1,20 +1,153 @@
 use serde::{Deserialize, Serialize};
 
-use crate::agent_config::{AIAgentConfig, build_agents};
+use serde_json::Value;
+
+use crate::agent::{AIAgent, MoveRequest};
+use crate::agent_config::{AIAgentConfig, AgentKind, build_agents};
 
 use super::rock_paper_scissors::{RockPaperScissors, RockPaperScissorsConfig as GameRockPaperScissorsConfig};
-use super::tic_tac_toe::{TicTacToe, TicTacToeConfig as GameTicTacToeConfig};
-use super::connect_four::{ConnectFour, ConnectFourConfig as GameConnectFourConfig};
-use super::stats::GameStats;
+use super::tic_tac_toe::{self, TicTacToe, TicTacToeConfig as GameTicTacToeConfig};
+use super::connect_four::{self, ConnectFour, ConnectFourConfig as GameConnectFourConfig};
+use super::gomoku::{self, Gomoku, GomokuConfig as GameGomokuConfig};
+use super::checkers::{Checkers, CheckersConfig as GameCheckersConfig};
+use super::nim::{Nim, NimConfig as GameNimConfig};
+use super::reversi::{Reversi, ReversiConfig as GameReversiConfig};
+use super::display::BoardRepresentation;
+use super::judge::{Judge, RuleJudge, judge_result};
+use super::engine::{GameEngine, TurnBasedGame, TurnRules, check_player_count};
+use super::passes::{DEFAULT_MAX_CONSECUTIVE_PASSES, default_max_consecutive_passes};
+use super::registry::{self, DynGame, DynGameFactory};
+use super::rng::SeededRng;
+use super::stats::{FORFEIT_NOTE, GameStats};
+use super::transcript::TranscriptWriter;
+
+/// How many times `--probe-format` asks an agent before giving up on it
+pub const PROBE_ATTEMPTS: u32 = 2;
+
+/// Most cells a board may have for a `Minimax` agent to play on it
+pub const MINIMAX_MAX_CELLS: u32 = 9;
+
+/// Upper bounds on configured board sizes, checked before a board is
+/// allocated so a typo can't exhaust memory or produce a gigantic prompt
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BoardLimits {
+    /// Largest allowed row or column count
+    pub max_dimension: u32,
+    pub max_win_length: u32,
+}
+
+impl Default for BoardLimits {
+    fn default() -> Self {
+        BoardLimits {
+            max_dimension: 32,
+            max_win_length: 32,
+        }
+    }
+}
+
+impl BoardLimits {
+    fn check(&self, game: &str, dims: &[(&str, u32)], win_length: u32) -> Result<(), String> {
+        for (name, value) in dims {
+            if *value == 0 || *value > self.max_dimension {
+                return Err(format!(
+                    "{} {} must be between 1 and {}, got {}",
+                    game, name, self.max_dimension, value
+                ));
+            }
+        }
+        let smallest = dims.iter().map(|(_, v)| *v).min().unwrap_or(0);
+        if win_length == 0 || win_length > self.max_win_length.min(smallest) {
+            return Err(format!(
+                "{} win_length must be between 1 and {}, got {}",
+                game,
+                self.max_win_length.min(smallest),
+                win_length
+            ));
+        }
+        Ok(())
+    }
+}
 
-#[derive(Clone, Debug, Deserialize)]
+/// Overrides for a game's default config, e.g. from a CSV row; `None`
+/// keeps the default
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GameParams {
+    pub board_size: Option<u32>,
+    pub win_length: Option<u32>,
+    pub rows: Option<u32>,
+    pub cols: Option<u32>,
+    pub rounds: Option<u32>,
+}
+
+impl GameParams {
+    /// The parameters that are set, by name
+    fn set(&self) -> impl Iterator<Item = &'static str> {
+        [
+            ("board_size", self.board_size),
+            ("win_length", self.win_length),
+            ("rows", self.rows),
+            ("cols", self.cols),
+            ("rounds", self.rounds),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|_| name))
+    }
+}
+
+/// A game and its configuration. The built-in games are variants, so their
+/// configs can be read from batch files and their results keep their own
+/// shape; only games added at runtime go through the boxed `DynGame` path.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
 pub enum Game {
     TicTacToe(TicTacToeConfig),
     RockPaperScissors(RockPaperScissorsConfig),
     ConnectFour(ConnectFourConfig),
+    Gomoku(GomokuConfig),
+    Checkers(CheckersConfig),
+    Nim(NimConfig),
+    Reversi(ReversiConfig),
+    /// A game added through the registry; picked by name, not read from
+    /// batch files
+    #[serde(skip)]
+    Custom(CustomGame),
 }
 
-#[derive(Clone, Debug, Deserialize, Default)]
+/// A game added at runtime, see `registry::register_game`
+#[derive(Clone)]
+pub struct CustomGame {
+    name: String,
+    factory: DynGameFactory,
+    order: PlayerOrder,
+}
+
+impl CustomGame {
+    pub fn new(name: &str, factory: DynGameFactory) -> Self {
+        CustomGame {
+            name: name.to_string(),
+            factory,
+            order: PlayerOrder::default(),
+        }
+    }
+
+    /// A fresh game to play or probe
+    fn engine(&self) -> Box<dyn DynGame> {
+        (self.factory)()
+    }
+}
+
+impl std::fmt::Debug for CustomGame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomGame").field("name", &self.name).field("order", &self.order).finish()
+    }
+}
+
+impl PartialEq for CustomGame {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.order == other.order
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Default)]
 pub enum PlayerOrder {
     Random,
     Decending,
@@ -24,12 +157,38 @@ pub enum PlayerOrder {
     ReverseOrderInList,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize)]
 pub struct TicTacToeConfig {
     pub board_size: u32,
     pub win_length: u32,
     #[serde(default)]
     pub order: PlayerOrder,
+    /// Symbol that moves first, independent of which agent plays it
+    #[serde(default)]
+    pub first_player: tic_tac_toe::Player,
+    #[serde(default)]
+    pub board_representation: BoardRepresentation,
+    /// `max_retries`, `move_timeout_ms` and `max_invalid_moves`, set at the
+    /// top level of the config
+    #[serde(flatten)]
+    pub rules: TurnRules,
+}
+
+impl PlayerOrder {
+    /// Seat order for agents with sort keys `keys`: for each seat, the index
+    /// of the agent that takes it. Sorting is stable, so agents with equal
+    /// keys keep their list order; `Random` is a shuffle seeded by `seed`.
+    pub fn seating(&self, keys: &[String], seed: u64) -> Vec<usize> {
+        let mut seats: Vec<usize> = (0..keys.len()).collect();
+        match self {
+            PlayerOrder::OrderInList => {}
+            PlayerOrder::ReverseOrderInList => seats.reverse(),
+            PlayerOrder::Ascending => seats.sort_by(|a, b| keys[*a].cmp(&keys[*b])),
+            PlayerOrder::Decending => seats.sort_by(|a, b| keys[*b].cmp(&keys[*a])),
+            PlayerOrder::Random => SeededRng::new(seed).shuffle(&mut seats),
+        }
+        seats
+    }
 }
 
 impl Default for TicTacToeConfig {
@@ -38,15 +197,25 @@ impl Default for TicTacToeConfig {
             board_size: 3,
             win_length: 3,
             order: PlayerOrder::default(),
+            first_player: tic_tac_toe::Player::default(),
+            board_representation: BoardRepresentation::default(),
+            rules: TurnRules::default(),
         }
     }
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize)]
 pub struct RockPaperScissorsConfig {
     pub rounds: u32,
     #[serde(default)]
     pub order: PlayerOrder,
+    /// `move_timeout_ms` and `max_invalid_moves`, set at the top level; a
+    /// round is never replayed, so `max_retries` has no effect
+    #[serde(flatten)]
+    pub rules: TurnRules,
+    /// End the game once the leader can't be caught in the rounds left
+    #[serde(default)]
+    pub end_when_clinched: bool,
 }
 
 impl Default for RockPaperScissorsConfig {
@@ -54,17 +223,28 @@ impl Default for RockPaperScissorsConfig {
         RockPaperScissorsConfig {
             rounds: 3,
             order: PlayerOrder::default(),
+            rules: TurnRules::default(),
+            end_when_clinched: false,
         }
     }
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize)]
 pub struct ConnectFourConfig {
     pub rows: u32,
     pub cols: u32,
     pub win_length: u32,
     #[serde(default)]
     pub order: PlayerOrder,
+    /// Color that moves first, independent of which agent plays it
+    #[serde(default)]
+    pub first_player: connect_four::Player,
+    #[serde(default)]
+    pub board_representation: BoardRepresentation,
+    /// `max_retries`, `move_timeout_ms` and `max_invalid_moves`, set at the
+    /// top level of the config
+    #[serde(flatten)]
+    pub rules: TurnRules,
 }
 
 impl Default for ConnectFourConfig {
@@ -74,6 +254,121 @@ impl Default for ConnectFourConfig {
             cols: 7,
             win_length: 4,
             order: PlayerOrder::default(),
+            first_player: connect_four::Player::default(),
+            board_representation: BoardRepresentation::default(),
+            rules: TurnRules::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct GomokuConfig {
+    pub board_size: u32,
+    pub win_length: u32,
+    #[serde(default)]
+    pub order: PlayerOrder,
+    /// Color that moves first, independent of which agent plays it
+    #[serde(default)]
+    pub first_player: gomoku::Player,
+    #[serde(default)]
+    pub board_representation: BoardRepresentation,
+    /// `max_retries`, `move_timeout_ms` and `max_invalid_moves`, set at the
+    /// top level of the config
+    #[serde(flatten)]
+    pub rules: TurnRules,
+}
+
+impl Default for GomokuConfig {
+    fn default() -> Self {
+        GomokuConfig {
+            board_size: 15,
+            win_length: 5,
+            order: PlayerOrder::default(),
+            first_player: gomoku::Player::default(),
+            board_representation: BoardRepresentation::default(),
+            rules: TurnRules::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct CheckersConfig {
+    #[serde(default)]
+    pub order: PlayerOrder,
+    /// A player who can capture must, and must finish the capture chain
+    #[serde(default)]
+    pub forced_capture: bool,
+    /// Valid moves, counting both players, after which the game is drawn
+    pub max_moves: u32,
+    #[serde(default)]
+    pub board_representation: BoardRepresentation,
+    /// `max_retries`, `move_timeout_ms` and `max_invalid_moves`, set at the
+    /// top level of the config
+    #[serde(flatten)]
+    pub rules: TurnRules,
+}
+
+impl Default for CheckersConfig {
+    fn default() -> Self {
+        CheckersConfig {
+            order: PlayerOrder::default(),
+            forced_capture: false,
+            max_moves: 200,
+            board_representation: BoardRepresentation::default(),
+            rules: TurnRules::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct NimConfig {
+    #[serde(default)]
+    pub order: PlayerOrder,
+    /// Objects in each heap at the start
+    pub heaps: Vec<u32>,
+    /// The player who takes the last object loses instead of winning
+    #[serde(default)]
+    pub misere: bool,
+    /// `max_retries`, `move_timeout_ms` and `max_invalid_moves`, set at the
+    /// top level of the config
+    #[serde(flatten)]
+    pub rules: TurnRules,
+}
+
+impl Default for NimConfig {
+    fn default() -> Self {
+        NimConfig {
+            order: PlayerOrder::default(),
+            heaps: vec![3, 5, 7],
+            misere: false,
+            rules: TurnRules::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct ReversiConfig {
+    #[serde(default)]
+    pub order: PlayerOrder,
+    #[serde(default)]
+    pub board_representation: BoardRepresentation,
+    /// `max_retries`, `move_timeout_ms` and `max_invalid_moves`, set at the
+    /// top level of the config
+    #[serde(flatten)]
+    pub rules: TurnRules,
+    /// Passes in a row that end the game; 2 ends it once neither player
+    /// can move
+    #[serde(default = "default_max_consecutive_passes")]
+    pub max_consecutive_passes: u32,
+}
+
+impl Default for ReversiConfig {
+    fn default() -> Self {
+        ReversiConfig {
+            order: PlayerOrder::default(),
+            board_representation: BoardRepresentation::default(),
+            rules: TurnRules::default(),
+            max_consecutive_passes: DEFAULT_MAX_CONSECUTIVE_PASSES,
         }
     }
 }
@@ -83,6 +378,11 @@ pub enum TestResult {
     TicTacToe(TicTacToeResult),
     RockPaperScissors(RockPaperScissorsResult),
     ConnectFour(ConnectFourResult),
+    Gomoku(GomokuResult),
+    Checkers(CheckersResult),
+    Nim(NimResult),
+    Reversi(ReversiResult),
+    Custom(CustomResult),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -106,25 +406,317 @@ pub struct ConnectFourResult {
     pub error: Option<String>,
 }
 
-impl From<&str> for Game {
-    fn from(name: &str) -> Self {
-        match name {
-            "TicTacToe" => Game::TicTacToe(TicTacToeConfig::default()),
-            "RockPaperScissors" => Game::RockPaperScissors(RockPaperScissorsConfig::default()),
-            "ConnectFour" => Game::ConnectFour(ConnectFourConfig::default()),
-            _ => panic!("Unknown game name: {}", name),
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GomokuResult {
+    pub winner: Option<String>,
+    pub stats: GameStats,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CheckersResult {
+    pub winner: Option<String>,
+    pub stats: GameStats,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NimResult {
+    pub winner: Option<String>,
+    pub stats: GameStats,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReversiResult {
+    pub winner: Option<String>,
+    pub stats: GameStats,
+    pub error: Option<String>,
+}
+
+/// Result of a game added through the registry
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CustomResult {
+    /// Name the game was registered under
+    pub game: String,
+    /// Seat names in agent order, as used in recorded winners
+    pub seat_labels: Vec<String>,
+    pub winner: Option<String>,
+    pub stats: GameStats,
+    pub error: Option<String>,
+}
+
+impl TestResult {
+    pub fn winner(&self) -> Option<&String> {
+        match self {
+            TestResult::TicTacToe(r) => r.winner.as_ref(),
+            TestResult::RockPaperScissors(r) => r.winner.as_ref(),
+            TestResult::ConnectFour(r) => r.winner.as_ref(),
+            TestResult::Gomoku(r) => r.winner.as_ref(),
+            TestResult::Checkers(r) => r.winner.as_ref(),
+            TestResult::Nim(r) => r.winner.as_ref(),
+            TestResult::Reversi(r) => r.winner.as_ref(),
+            TestResult::Custom(r) => r.winner.as_ref(),
         }
     }
+
+    /// Index into the agent list of the winner. Winners are recorded as
+    /// "<agent> (<seat>)", and the first agent always holds X / Red / Black /
+    /// Player 1.
+    pub fn winner_seat(&self) -> Option<usize> {
+        let winner = self.winner()?;
+        let winner = winner.strip_suffix(FORFEIT_NOTE).unwrap_or(winner);
+        let seat = winner.rsplit_once(" (")?.1.strip_suffix(')')?;
+        match self {
+            TestResult::Custom(r) => r.seat_labels.iter().position(|label| label == seat),
+            _ => seat_index(seat),
+        }
+    }
+
+    /// Seat names in agent order, as used in recorded winners; one per
+    /// seat for games with more than two players
+    pub fn seat_labels(&self) -> Vec<String> {
+        let pair = match self {
+            TestResult::TicTacToe(_) => ["X", "O"],
+            TestResult::RockPaperScissors(_) => {
+                let seats = self.stats().seats.len().max(2);
+                return (1..=seats).map(|n| format!("Player {}", n)).collect();
+            }
+            TestResult::ConnectFour(_) => ["Red", "Yellow"],
+            TestResult::Gomoku(_) => ["Black", "White"],
+            TestResult::Checkers(_) => ["Black", "White"],
+            TestResult::Nim(_) => ["Player 1", "Player 2"],
+            TestResult::Reversi(_) => ["Black", "White"],
+            TestResult::Custom(r) => return r.seat_labels.clone(),
+        };
+        pair.map(String::from).into()
+    }
+
+    pub fn stats(&self) -> &GameStats {
+        match self {
+            TestResult::TicTacToe(r) => &r.stats,
+            TestResult::RockPaperScissors(r) => &r.stats,
+            TestResult::ConnectFour(r) => &r.stats,
+            TestResult::Gomoku(r) => &r.stats,
+            TestResult::Checkers(r) => &r.stats,
+            TestResult::Nim(r) => &r.stats,
+            TestResult::Reversi(r) => &r.stats,
+            TestResult::Custom(r) => &r.stats,
+        }
+    }
+
+    pub fn stats_mut(&mut self) -> &mut GameStats {
+        match self {
+            TestResult::TicTacToe(r) => &mut r.stats,
+            TestResult::RockPaperScissors(r) => &mut r.stats,
+            TestResult::ConnectFour(r) => &mut r.stats,
+            TestResult::Gomoku(r) => &mut r.stats,
+            TestResult::Checkers(r) => &mut r.stats,
+            TestResult::Nim(r) => &mut r.stats,
+            TestResult::Reversi(r) => &mut r.stats,
+            TestResult::Custom(r) => &mut r.stats,
+        }
+    }
+
+    /// The game this result came from, with its default configuration.
+    /// `None` for a custom game not registered in this process.
+    pub fn game(&self) -> Option<Game> {
+        Game::new(self.game_name())
+    }
+
+    /// Name of the game this result came from
+    pub fn game_name(&self) -> &str {
+        match self {
+            TestResult::TicTacToe(_) => "TicTacToe",
+            TestResult::RockPaperScissors(_) => "RockPaperScissors",
+            TestResult::ConnectFour(_) => "ConnectFour",
+            TestResult::Gomoku(_) => "Gomoku",
+            TestResult::Checkers(_) => "Checkers",
+            TestResult::Nim(_) => "Nim",
+            TestResult::Reversi(_) => "Reversi",
+            TestResult::Custom(r) => &r.game,
+        }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        match self {
+            TestResult::TicTacToe(r) => r.error.as_deref(),
+            TestResult::RockPaperScissors(r) => r.error.as_deref(),
+            TestResult::ConnectFour(r) => r.error.as_deref(),
+            TestResult::Gomoku(r) => r.error.as_deref(),
+            TestResult::Checkers(r) => r.error.as_deref(),
+            TestResult::Nim(r) => r.error.as_deref(),
+            TestResult::Reversi(r) => r.error.as_deref(),
+            TestResult::Custom(r) => r.error.as_deref(),
+        }
+    }
+}
+
+/// Agent index for a seat name such as "X", "Red", "Black" or "Player 3"
+pub fn seat_index(label: &str) -> Option<usize> {
+    match label {
+        "X" | "Red" | "Black" => Some(0),
+        "O" | "Yellow" | "White" => Some(1),
+        _ => label
+            .strip_prefix("Player ")?
+            .parse::<usize>()
+            .ok()?
+            .checked_sub(1),
+    }
+}
+
+/// Like `Game::new`, with an error naming the supported games
+impl TryFrom<&str> for Game {
+    type Error = String;
+
+    fn try_from(name: &str) -> Result<Self, String> {
+        Game::new(name)
+            .ok_or_else(|| format!("Unknown game name: {} (expected one of {})", name, Game::list().join(", ")))
+    }
 }
 
 impl Game {
+    /// Every game name in the registry: the built-in games, then any
+    /// registered at runtime
+    pub fn list() -> Vec<String> {
+        registry::global().read().unwrap().names()
+    }
+
+    /// The registered game called `name`, with its default configuration
     pub fn new(name: &str) -> Option<Self> {
-        match name {
-            "TicTacToe" => Some(Game::TicTacToe(TicTacToeConfig::default())),
-            "RockPaperScissors" => Some(Game::RockPaperScissors(RockPaperScissorsConfig::default())),
-            "ConnectFour" => Some(Game::ConnectFour(ConnectFourConfig::default())),
-            _ => None,
+        registry::global().read().unwrap().create(name)
+    }
+
+    /// This game with `params` applied over its config. A parameter the game
+    /// doesn't have, like `rounds` for TicTacToe, is an error.
+    pub fn with_params(mut self, params: &GameParams) -> Result<Game, String> {
+        let accepted: &[&str] = match self {
+            Game::TicTacToe(_) | Game::Gomoku(_) => &["board_size", "win_length"],
+            Game::ConnectFour(_) => &["rows", "cols", "win_length"],
+            Game::RockPaperScissors(_) => &["rounds"],
+            Game::Checkers(_) | Game::Nim(_) | Game::Reversi(_) | Game::Custom(_) => &[],
+        };
+        if let Some(name) = params.set().find(|name| !accepted.contains(name)) {
+            return Err(format!("{} has no {} setting", self.name(), name));
+        }
+
+        match &mut self {
+            Game::TicTacToe(config) => {
+                config.board_size = params.board_size.unwrap_or(config.board_size);
+                config.win_length = params.win_length.unwrap_or(config.win_length);
+            }
+            Game::Gomoku(config) => {
+                config.board_size = params.board_size.unwrap_or(config.board_size);
+                config.win_length = params.win_length.unwrap_or(config.win_length);
+            }
+            Game::ConnectFour(config) => {
+                config.rows = params.rows.unwrap_or(config.rows);
+                config.cols = params.cols.unwrap_or(config.cols);
+                config.win_length = params.win_length.unwrap_or(config.win_length);
+            }
+            Game::RockPaperScissors(config) => {
+                config.rounds = params.rounds.unwrap_or(config.rounds);
+            }
+            Game::Checkers(_) | Game::Nim(_) | Game::Reversi(_) | Game::Custom(_) => {}
         }
+        Ok(self)
+    }
+
+    /// Check the configured board against `limits` without building it
+    pub fn validate(&self, limits: &BoardLimits) -> Result<(), String> {
+        match self {
+            Game::TicTacToe(config) => limits.check(
+                "TicTacToe",
+                &[("board_size", config.board_size)],
+                config.win_length,
+            ),
+            Game::RockPaperScissors(_) => Ok(()),
+            Game::ConnectFour(config) => limits.check(
+                "ConnectFour",
+                &[("rows", config.rows), ("cols", config.cols)],
+                config.win_length,
+            ),
+            Game::Gomoku(config) => limits.check(
+                "Gomoku",
+                &[("board_size", config.board_size)],
+                config.win_length,
+            ),
+            Game::Checkers(config) if config.max_moves == 0 => {
+                Err("Checkers max_moves must be at least 1".to_string())
+            }
+            Game::Checkers(_) => Ok(()),
+            Game::Nim(config) if config.heaps.is_empty() || config.heaps.contains(&0) => {
+                Err("Nim needs at least one heap, and every heap needs at least 1 object".to_string())
+            }
+            Game::Nim(_) => Ok(()),
+            Game::Reversi(_) => Ok(()),
+            Game::Custom(_) => Ok(()),
+        }
+    }
+
+    /// Check this game can seat `agents`: built-in `Minimax` agents only
+    /// play TicTacToe and Gomoku, on boards of at most `MINIMAX_MAX_CELLS`
+    /// cells that are shown as a matrix
+    pub fn check_agents(&self, agents: &[AIAgentConfig]) -> Result<(), String> {
+        let minimax: Vec<&AIAgentConfig> = agents.iter().filter(|a| a.agent == AgentKind::Minimax).collect();
+        if minimax.is_empty() {
+            return Ok(());
+        }
+        for agent in &minimax {
+            agent.minimax_level()?;
+        }
+        let (board_size, representation) = match self {
+            Game::TicTacToe(config) => (config.board_size, config.board_representation),
+            Game::Gomoku(config) => (config.board_size, config.board_representation),
+            _ => return Err(format!("Minimax agents only play TicTacToe and Gomoku, not {}", self.name())),
+        };
+        let cells = board_size * board_size;
+        if cells > MINIMAX_MAX_CELLS {
+            return Err(format!(
+                "Minimax agents only play boards of up to {} cells; this {} board has {}",
+                MINIMAX_MAX_CELLS,
+                self.name(),
+                cells
+            ));
+        }
+        if !representation.includes_matrix() {
+            return Err(format!("Minimax agents need the board matrix; {} is set to a text-only board", self.name()));
+        }
+        Ok(())
+    }
+
+    /// Fewest agents this game can be played with
+    pub fn min_players(&self) -> usize {
+        match self {
+            Game::TicTacToe(_) => TicTacToe::MIN_PLAYERS,
+            Game::RockPaperScissors(_) => RockPaperScissors::MIN_PLAYERS,
+            Game::ConnectFour(_) => ConnectFour::MIN_PLAYERS,
+            Game::Gomoku(_) => Gomoku::MIN_PLAYERS,
+            Game::Checkers(_) => Checkers::MIN_PLAYERS,
+            Game::Nim(_) => Nim::MIN_PLAYERS,
+            Game::Reversi(_) => Reversi::MIN_PLAYERS,
+            Game::Custom(game) => game.engine().min_players(),
+        }
+    }
+
+    /// Most agents this game can be played with
+    pub fn max_players(&self) -> usize {
+        match self {
+            Game::TicTacToe(_) => TicTacToe::MAX_PLAYERS,
+            Game::RockPaperScissors(_) => RockPaperScissors::MAX_PLAYERS,
+            Game::ConnectFour(_) => ConnectFour::MAX_PLAYERS,
+            Game::Gomoku(_) => Gomoku::MAX_PLAYERS,
+            Game::Checkers(_) => Checkers::MAX_PLAYERS,
+            Game::Nim(_) => Nim::MAX_PLAYERS,
+            Game::Reversi(_) => Reversi::MAX_PLAYERS,
+            Game::Custom(game) => game.engine().max_players(),
+        }
+    }
+
+    /// Check this game can be played with `players` agents
+    pub fn check_player_count(&self, players: usize) -> Result<(), String> {
+        check_player_count(players, self.min_players(), self.max_players())
+            .map_err(|e| format!("{}: {}", self.name(), e))
     }
 
     pub fn name(&self) -> &str {
@@ -132,19 +724,75 @@ impl Game {
             Game::TicTacToe(_) => "TicTacToe",
             Game::RockPaperScissors(_) => "RockPaperScissors",
             Game::ConnectFour(_) => "ConnectFour",
+            Game::Gomoku(_) => "Gomoku",
+            Game::Checkers(_) => "Checkers",
+            Game::Nim(_) => "Nim",
+            Game::Reversi(_) => "Reversi",
+            Game::Custom(game) => &game.name,
         }
     }
 
     pub async fn play_game(&self, agents: Vec<AIAgentConfig>) -> TestResult {
+        let (seated, _) = self.seat_agents(agents, 0);
+        match build_agents(seated) {
+            Ok(agents) => self.play_with_agents(agents).await,
+            Err(e) => self.failed(format!("Failed to start: {}", e)),
+        }
+    }
+
+    pub fn player_order(&self) -> &PlayerOrder {
         match self {
+            Game::TicTacToe(config) => &config.order,
+            Game::RockPaperScissors(config) => &config.order,
+            Game::ConnectFour(config) => &config.order,
+            Game::Gomoku(config) => &config.order,
+            Game::Checkers(config) => &config.order,
+            Game::Nim(config) => &config.order,
+            Game::Reversi(config) => &config.order,
+            Game::Custom(game) => &game.order,
+        }
+    }
+
+    /// Put `agents` in seat order (first seat is X / Red / Black / Player 1)
+    /// per the configured `PlayerOrder`. Sorted orders go by model name, and
+    /// `Random` is seeded from the agents' seeds and `round`, so a replay seats
+    /// them the same way. Also returns, for each seat, the agent's index in `agents`.
+    pub fn seat_agents(&self, agents: Vec<AIAgentConfig>, round: u64) -> (Vec<AIAgentConfig>, Vec<usize>) {
+        let keys: Vec<String> = agents.iter().map(|a| a.model.clone()).collect();
+        let seed = agents
+            .iter()
+            .filter_map(|a| a.seed)
+            .fold(round, |acc, s| acc.wrapping_mul(31).wrapping_add(s));
+        let seating = self.player_order().seating(&keys, seed);
+        let seated = seating.iter().map(|&i| agents[i].clone()).collect();
+        (seated, seating)
+    }
+
+    /// Play one game with agents that are already connected, e.g. from the lobby
+    pub async fn play_with_agents(&self, agents: Vec<AIAgent>) -> TestResult {
+        self.play_recorded(agents, None, None).await
+    }
+
+    /// Like `play_with_agents`, also logging each turn to `transcript` as it
+    /// is played. A game that ends without a winner or a draw is decided by
+    /// `judge`, or failing that by `RuleJudge` from its final state.
+    pub async fn play_recorded(
+        &self,
+        mut agents: Vec<AIAgent>,
+        transcript: Option<&TranscriptWriter>,
+        judge: Option<&dyn Judge>,
+    ) -> TestResult {
+        let transcript = transcript.cloned();
+        for agent in &mut agents {
+            agent.join_game(self.name());
+        }
+        let players: Vec<String> = agents.iter().map(|agent| agent.name().to_string()).collect();
+        let result = match self {
             Game::TicTacToe(config) => {
-                let agents = build_agents(agents);
-                let game_config = GameTicTacToeConfig {
-                    board_size: config.board_size,
-                    win_length: config.win_length,
-                };
-                let game = TicTacToe::new(game_config);
-                let result = game.play_game(agents).await;
+                let result = TicTacToe::new(config.engine_config())
+                    .with_transcript(transcript)
+                    .play_game(agents)
+                    .await;
                 
                 TestResult::TicTacToe(TicTacToeResult {
                     winner: result.winner.clone(),
@@ -153,12 +801,10 @@ impl Game {
                 })
             }
             Game::RockPaperScissors(config) => {
-                let agents = build_agents(agents);
-                let game_config = GameRockPaperScissorsConfig {
-                    rounds: config.rounds,
-                };
-                let game = RockPaperScissors::new(game_config);
-                let result = game.play_game(agents).await;
+                let result = RockPaperScissors::new(config.engine_config())
+                    .with_transcript(transcript)
+                    .play_game(agents)
+                    .await;
                 
                 TestResult::RockPaperScissors(RockPaperScissorsResult {
                     winner: result.winner.clone(),
@@ -167,14 +813,10 @@ impl Game {
                 })
             }
             Game::ConnectFour(config) => {
-                let agents = build_agents(agents);
-                let game_config = GameConnectFourConfig {
-                    rows: config.rows,
-                    cols: config.cols,
-                    win_length: config.win_length,
-                };
-                let game = ConnectFour::new(game_config);
-                let result = game.play_game(agents).await;
+                let result = ConnectFour::new(config.engine_config())
+                    .with_transcript(transcript)
+                    .play_game(agents)
+                    .await;
                 
                 TestResult::ConnectFour(ConnectFourResult {
                     winner: result.winner.clone(),
@@ -182,6 +824,260 @@ impl Game {
                     error: result.error,
                 })
             }
+            Game::Gomoku(config) => {
+                let result = Gomoku::new(config.engine_config())
+                    .with_transcript(transcript)
+                    .play_game(agents)
+                    .await;
+
+                TestResult::Gomoku(GomokuResult {
+                    winner: result.winner.clone(),
+                    stats: result.stats,
+                    error: result.error,
+                })
+            }
+            Game::Checkers(config) => {
+                let result = Checkers::new(config.engine_config())
+                    .with_transcript(transcript)
+                    .play_game(agents)
+                    .await;
+
+                TestResult::Checkers(CheckersResult {
+                    winner: result.winner.clone(),
+                    stats: result.stats,
+                    error: result.error,
+                })
+            }
+            Game::Nim(config) => {
+                let result = Nim::new(config.engine_config())
+                    .with_transcript(transcript)
+                    .play_game(agents)
+                    .await;
+
+                TestResult::Nim(NimResult {
+                    winner: result.winner.clone(),
+                    stats: result.stats,
+                    error: result.error,
+                })
+            }
+            Game::Reversi(config) => {
+                let result = Reversi::new(config.engine_config())
+                    .with_transcript(transcript)
+                    .play_game(agents)
+                    .await;
+
+                TestResult::Reversi(ReversiResult {
+                    winner: result.winner.clone(),
+                    stats: result.stats,
+                    error: result.error,
+                })
+            }
+            Game::Custom(game) => {
+                let engine = game.engine();
+                let seat_labels = engine.seat_labels();
+                if let Err(e) = check_player_count(agents.len(), engine.min_players(), engine.max_players()) {
+                    return self.failed(e);
+                }
+                let (stats, error) = match engine.play(agents, transcript).await {
+                    Ok(stats) => (stats, None),
+                    Err(aborted) => (aborted.stats, Some(aborted.error)),
+                };
+
+                TestResult::Custom(CustomResult {
+                    game: game.name.clone(),
+                    seat_labels,
+                    winner: stats.winner.clone(),
+                    stats,
+                    error,
+                })
+            }
+        };
+        judge_result(result, &players, judge.unwrap_or(&RuleJudge)).await
+    }
+
+    /// Like `play_with_agents`, but first asks each agent for one move on the
+    /// opening position and skips the game if any agent can't produce a
+    /// parseable move within `PROBE_ATTEMPTS` tries. Probe moves are not
+    /// logged to `transcript`.
+    pub async fn play_with_probe(&self, agents: Vec<AIAgent>, transcript: Option<&TranscriptWriter>) -> TestResult {
+        for agent in &agents {
+            if let Err(e) = self.probe_format(agent).await {
+                tracing::warn!("Skipping game: {} failed format probe ({})", agent.name(), e);
+                return self.failed(format!("model failed format probe: {}: {}", agent.name(), e));
+            }
+        }
+        self.play_recorded(agents, transcript, None).await
+    }
+
+    async fn probe_format(&self, agent: &AIAgent) -> Result<(), String> {
+        let request = self.probe_request();
+        let mut last_error = String::new();
+        for _ in 0..PROBE_ATTEMPTS {
+            match agent.execute_turn(&request).await {
+                Ok(response) => match self.probe_check(&response.chosen_move) {
+                    Ok(()) => return Ok(()),
+                    Err(e) => last_error = e,
+                },
+                Err(e) => last_error = e.to_string(),
+            }
+        }
+        Err(last_error)
+    }
+
+    /// JSON schema of a move in this game, as sent to agents
+    pub fn move_schema(&self) -> Value {
+        self.probe_request().expected_move_schema
+    }
+
+    fn probe_request(&self) -> MoveRequest {
+        match self {
+            Game::TicTacToe(config) => TicTacToe::new(config.engine_config()).probe_request(),
+            Game::RockPaperScissors(config) => RockPaperScissors::new(config.engine_config()).probe_request(),
+            Game::ConnectFour(config) => ConnectFour::new(config.engine_config()).probe_request(),
+            Game::Gomoku(config) => Gomoku::new(config.engine_config()).probe_request(),
+            Game::Checkers(config) => Checkers::new(config.engine_config()).probe_request(),
+            Game::Nim(config) => Nim::new(config.engine_config()).probe_request(),
+            Game::Reversi(config) => Reversi::new(config.engine_config()).probe_request(),
+            Game::Custom(game) => game.engine().probe_request(),
+        }
+    }
+
+    fn probe_check(&self, move_data: &Value) -> Result<(), String> {
+        match self {
+            Game::TicTacToe(config) => TicTacToe::new(config.engine_config()).probe_check(move_data),
+            Game::RockPaperScissors(config) => RockPaperScissors::new(config.engine_config()).probe_check(move_data),
+            Game::ConnectFour(config) => ConnectFour::new(config.engine_config()).probe_check(move_data),
+            Game::Gomoku(config) => Gomoku::new(config.engine_config()).probe_check(move_data),
+            Game::Checkers(config) => Checkers::new(config.engine_config()).probe_check(move_data),
+            Game::Nim(config) => Nim::new(config.engine_config()).probe_check(move_data),
+            Game::Reversi(config) => Reversi::new(config.engine_config()).probe_check(move_data),
+            Game::Custom(game) => game.engine().probe_check(move_data),
+        }
+    }
+
+    /// A result for a game that never started
+    pub(crate) fn failed(&self, error: String) -> TestResult {
+        match self {
+            Game::TicTacToe(_) => TestResult::TicTacToe(TicTacToeResult {
+                winner: None,
+                stats: GameStats::new(),
+                error: Some(error),
+            }),
+            Game::RockPaperScissors(_) => TestResult::RockPaperScissors(RockPaperScissorsResult {
+                winner: None,
+                stats: GameStats::new(),
+                error: Some(error),
+            }),
+            Game::ConnectFour(_) => TestResult::ConnectFour(ConnectFourResult {
+                winner: None,
+                stats: GameStats::new(),
+                error: Some(error),
+            }),
+            Game::Gomoku(_) => TestResult::Gomoku(GomokuResult {
+                winner: None,
+                stats: GameStats::new(),
+                error: Some(error),
+            }),
+            Game::Checkers(_) => TestResult::Checkers(CheckersResult {
+                winner: None,
+                stats: GameStats::new(),
+                error: Some(error),
+            }),
+            Game::Nim(_) => TestResult::Nim(NimResult {
+                winner: None,
+                stats: GameStats::new(),
+                error: Some(error),
+            }),
+            Game::Reversi(_) => TestResult::Reversi(ReversiResult {
+                winner: None,
+                stats: GameStats::new(),
+                error: Some(error),
+            }),
+            Game::Custom(game) => TestResult::Custom(CustomResult {
+                game: game.name.clone(),
+                seat_labels: game.engine().seat_labels(),
+                winner: None,
+                stats: GameStats::new(),
+                error: Some(error),
+            }),
+        }
+    }
+}
+
+impl TicTacToeConfig {
+    fn engine_config(&self) -> GameTicTacToeConfig {
+        GameTicTacToeConfig {
+            board_size: self.board_size,
+            win_length: self.win_length,
+            first_player: self.first_player,
+            board_representation: self.board_representation,
+            rules: self.rules,
+        }
+    }
+}
+
+impl RockPaperScissorsConfig {
+    fn engine_config(&self) -> GameRockPaperScissorsConfig {
+        GameRockPaperScissorsConfig {
+            rounds: self.rounds,
+            rules: self.rules,
+            end_when_clinched: self.end_when_clinched,
+        }
+    }
+}
+
+impl ConnectFourConfig {
+    fn engine_config(&self) -> GameConnectFourConfig {
+        GameConnectFourConfig {
+            rows: self.rows,
+            cols: self.cols,
+            win_length: self.win_length,
+            first_player: self.first_player,
+            board_representation: self.board_representation,
+            rules: self.rules,
+        }
+    }
+}
+
+impl GomokuConfig {
+    fn engine_config(&self) -> GameGomokuConfig {
+        GameGomokuConfig {
+            board_size: self.board_size,
+            win_length: self.win_length,
+            first_player: self.first_player,
+            board_representation: self.board_representation,
+            rules: self.rules,
+        }
+    }
+}
+
+impl CheckersConfig {
+    fn engine_config(&self) -> GameCheckersConfig {
+        GameCheckersConfig {
+            forced_capture: self.forced_capture,
+            max_moves: self.max_moves,
+            board_representation: self.board_representation,
+            rules: self.rules,
+        }
+    }
+}
+
+impl NimConfig {
+    fn engine_config(&self) -> GameNimConfig {
+        GameNimConfig {
+            heaps: self.heaps.clone(),
+            misere: self.misere,
+            rules: self.rules,
+        }
+    }
+}
+
+impl ReversiConfig {
+    fn engine_config(&self) -> GameReversiConfig {
+        GameReversiConfig {
+            board_representation: self.board_representation,
+            rules: self.rules,
+            max_consecutive_passes: self.max_consecutive_passes,
         }
     }
 }
@@ -190,17 +1086,81 @@ impl Game {
 mod tests {
     use super::*;
 
+    fn config(model: &str, seed: u64) -> AIAgentConfig {
+        AIAgentConfig {
+            model: model.to_string(),
+            temp: 0.0,
+            seed: Some(seed),
+            agent: crate::agent_config::AgentKind::OpenAI,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_player_order_seating() {
+        let keys: Vec<String> = ["mistral", "gpt", "llama"].map(String::from).to_vec();
+        assert_eq!(PlayerOrder::OrderInList.seating(&keys, 0), [0, 1, 2]);
+        assert_eq!(PlayerOrder::ReverseOrderInList.seating(&keys, 0), [2, 1, 0]);
+        assert_eq!(PlayerOrder::Ascending.seating(&keys, 0), [1, 2, 0]);
+        assert_eq!(PlayerOrder::Decending.seating(&keys, 0), [0, 2, 1]);
+
+        let shuffled = PlayerOrder::Random.seating(&keys, 7);
+        assert_eq!(PlayerOrder::Random.seating(&keys, 7), shuffled);
+        let mut sorted = shuffled.clone();
+        sorted.sort();
+        assert_eq!(sorted, [0, 1, 2]);
+        // Some seed must put a different agent first
+        assert!((0..20).any(|seed| PlayerOrder::Random.seating(&keys, seed)[0] != 0));
+    }
+
+    #[tokio::test]
+    async fn test_reverse_order_flips_x_and_o() {
+        use crate::agents::socket::scripted_socket_agent;
+        use serde_json::json;
+
+        let game = Game::TicTacToe(TicTacToeConfig {
+            order: PlayerOrder::ReverseOrderInList,
+            ..Default::default()
+        });
+        let (seated, seating) = game.seat_agents(vec![config("first", 1), config("second", 2)], 0);
+        assert_eq!(seating, [1, 0]);
+
+        // Connect agents in seat order; whoever sits first plays X and wins
+        let x_moves = vec![json!({"row": 0, "col": 0}), json!({"row": 1, "col": 0}), json!({"row": 2, "col": 0})];
+        let o_moves = vec![json!({"row": 0, "col": 1}), json!({"row": 1, "col": 1})];
+        let x = scripted_socket_agent(&seated[0].model, x_moves).await;
+        let o = scripted_socket_agent(&seated[1].model, o_moves).await;
+        let result = game.play_with_agents(vec![AIAgent::Socket(x), AIAgent::Socket(o)]).await;
+
+        assert_eq!(result.winner().map(String::as_str), Some("second (X)"));
+        assert_eq!(result.stats().turns[1].player, "first");
+    }
+
+    #[test]
+    fn test_game_try_from_string() {
+        assert!(matches!(Game::try_from("TicTacToe"), Ok(Game::TicTacToe(_))));
+        assert!(matches!(Game::try_from("Reversi"), Ok(Game::Reversi(_))));
+    }
+
     #[test]
-    fn test_game_from_string() {
-        assert!(matches!(Game::from("TicTacToe"), Game::TicTacToe(_)));
-        assert!(matches!(Game::from("RockPaperScissors"), Game::RockPaperScissors(_)));
-        assert!(matches!(Game::from("ConnectFour"), Game::ConnectFour(_)));
+    fn test_game_try_from_invalid_string_lists_games() {
+        // Games registered by other tests may follow the built-in ones
+        let err = Game::try_from("InvalidGame").unwrap_err();
+        assert!(
+            err.starts_with(
+                "Unknown game name: InvalidGame (expected one of TicTacToe, RockPaperScissors, ConnectFour, Gomoku, \
+                 Checkers, Nim, Reversi"
+            ),
+            "{}",
+            err
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Unknown game name")]
-    fn test_game_from_invalid_string() {
-        let _ = Game::from("InvalidGame");
+    fn test_game_list_round_trips() {
+        for name in Game::list() {
+            assert_eq!(Game::new(&name).map(|game| game.name().to_string()), Some(name));
+        }
     }
 
     #[test]
@@ -208,14 +1168,22 @@ mod tests {
         assert!(matches!(Game::new("TicTacToe"), Some(Game::TicTacToe(_))));
         assert!(matches!(Game::new("RockPaperScissors"), Some(Game::RockPaperScissors(_))));
         assert!(matches!(Game::new("ConnectFour"), Some(Game::ConnectFour(_))));
+        assert!(matches!(Game::new("Gomoku"), Some(Game::Gomoku(_))));
+        assert!(matches!(Game::new("Checkers"), Some(Game::Checkers(_))));
+        assert!(matches!(Game::new("Nim"), Some(Game::Nim(_))));
+        assert!(matches!(Game::new("Reversi"), Some(Game::Reversi(_))));
         assert_eq!(Game::new("InvalidGame"), None);
     }
 
     #[test]
     fn test_game_name() {
-        assert_eq!(Game::from("TicTacToe").name(), "TicTacToe");
-        assert_eq!(Game::from("RockPaperScissors").name(), "RockPaperScissors");
-        assert_eq!(Game::from("ConnectFour").name(), "ConnectFour");
+        assert_eq!(Game::new("TicTacToe").unwrap().name(), "TicTacToe");
+        assert_eq!(Game::new("RockPaperScissors").unwrap().name(), "RockPaperScissors");
+        assert_eq!(Game::new("ConnectFour").unwrap().name(), "ConnectFour");
+        assert_eq!(Game::new("Gomoku").unwrap().name(), "Gomoku");
+        assert_eq!(Game::new("Checkers").unwrap().name(), "Checkers");
+        assert_eq!(Game::new("Nim").unwrap().name(), "Nim");
+        assert_eq!(Game::new("Reversi").unwrap().name(), "Reversi");
     }
 
     #[test]
@@ -239,10 +1207,190 @@ mod tests {
         assert_eq!(config.win_length, 4);
     }
 
+    #[test]
+    fn test_first_player_deserializes() {
+        let game: Game = serde_json::from_value(serde_json::json!({
+            "TicTacToe": {"board_size": 3, "win_length": 3, "first_player": "O"}
+        }))
+        .unwrap();
+        match game {
+            Game::TicTacToe(config) => assert_eq!(config.first_player, tic_tac_toe::Player::O),
+            other => panic!("unexpected game {:?}", other),
+        }
+        assert_eq!(ConnectFourConfig::default().first_player, connect_four::Player::Red);
+    }
+
+    #[test]
+    fn test_turn_rules_read_from_the_top_level() {
+        let game: Game = serde_json::from_value(serde_json::json!({
+            "Nim": {"heaps": [1, 2], "max_retries": 2, "move_timeout_ms": 500}
+        }))
+        .unwrap();
+        match game {
+            Game::Nim(config) => assert_eq!(
+                config.rules,
+                TurnRules {
+                    max_retries: Some(2),
+                    move_timeout_ms: Some(500),
+                    ..Default::default()
+                }
+            ),
+            other => panic!("unexpected game {:?}", other),
+        }
+        assert_eq!(NimConfig::default().rules.max_invalid_moves, Some(3));
+    }
+
     #[test]
     fn test_player_order_default() {
         let order = PlayerOrder::default();
         assert!(matches!(order, PlayerOrder::OrderInList));
     }
-}
 
+    #[tokio::test]
+    async fn test_probe_failure_skips_game() {
+        use crate::agents::socket::scripted_socket_agent;
+        use serde_json::json;
+
+        let x = scripted_socket_agent("x", vec![json!({"move": "center"}), json!({"move": "center"})]).await;
+        let o = scripted_socket_agent("o", vec![json!({"row": 1, "col": 0})]).await;
+
+        let result = Game::new("TicTacToe").unwrap()
+            .play_with_probe(vec![AIAgent::Socket(x), AIAgent::Socket(o)], None)
+            .await;
+
+        assert!(result.error().unwrap().starts_with("model failed format probe: x"));
+        assert!(result.stats().turns.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_probe_success_plays_game() {
+        use crate::agents::socket::scripted_socket_agent;
+        use serde_json::json;
+
+        let x = scripted_socket_agent(
+            "x",
+            vec![
+                json!({"row": 0, "col": 0}),
+                json!({"row": 0, "col": 0}),
+                json!({"row": 0, "col": 1}),
+                json!({"row": 0, "col": 2}),
+            ],
+        )
+        .await;
+        let o = scripted_socket_agent(
+            "o",
+            vec![json!({"row": 1, "col": 0}), json!({"row": 1, "col": 0}), json!({"row": 1, "col": 1})],
+        )
+        .await;
+
+        let result = Game::new("TicTacToe").unwrap()
+            .play_with_probe(vec![AIAgent::Socket(x), AIAgent::Socket(o)], None)
+            .await;
+
+        assert_eq!(result.error(), None);
+        assert_eq!(result.winner().map(String::as_str), Some("x (X)"));
+        assert_eq!(result.stats().turns.len(), 5);
+    }
+
+    #[test]
+    fn test_winner_seat() {
+        let result = |winner: &str| {
+            TestResult::ConnectFour(ConnectFourResult {
+                winner: Some(winner.to_string()),
+                stats: GameStats::new(),
+                error: None,
+            })
+        };
+        assert_eq!(result("OpenAI_1 (X)").winner_seat(), Some(0));
+        assert_eq!(result("Ollama_2 (Yellow)").winner_seat(), Some(1));
+        assert_eq!(result("my (odd) bot_1 (Player 1)").winner_seat(), Some(0));
+        assert_eq!(result("Ollama_2 (Yellow) (opponent forfeited)").winner_seat(), Some(1));
+        assert_eq!(result("Ollama_3 (Player 3)").winner_seat(), Some(2));
+        assert_eq!(result("Ollama_3 (Player 0)").winner_seat(), None);
+        assert_eq!(result("no seat").winner_seat(), None);
+    }
+
+    #[test]
+    fn test_oversized_board_is_rejected() {
+        let huge = Game::TicTacToe(TicTacToeConfig {
+            board_size: 10_000,
+            ..TicTacToeConfig::default()
+        });
+        let err = huge.validate(&BoardLimits::default()).unwrap_err();
+        assert_eq!(err, "TicTacToe board_size must be between 1 and 32, got 10000");
+
+        let wide = Game::ConnectFour(ConnectFourConfig {
+            cols: 10_000,
+            ..ConnectFourConfig::default()
+        });
+        assert!(wide.validate(&BoardLimits::default()).unwrap_err().contains("cols"));
+
+        // The limit is configurable
+        let limits = BoardLimits { max_dimension: 10_000, max_win_length: 5 };
+        assert!(huge.validate(&limits).is_ok());
+    }
+
+    #[test]
+    fn test_win_length_must_fit_board() {
+        let limits = BoardLimits::default();
+        for win_length in [0, 4] {
+            let game = Game::TicTacToe(TicTacToeConfig { win_length, ..TicTacToeConfig::default() });
+            assert!(game.validate(&limits).unwrap_err().contains("win_length"));
+        }
+        assert!(Game::new("TicTacToe").unwrap().validate(&limits).is_ok());
+        assert!(Game::new("ConnectFour").unwrap().validate(&limits).is_ok());
+        assert!(Game::new("RockPaperScissors").unwrap().validate(&limits).is_ok());
+    }
+
+    #[test]
+    fn test_minimax_agents_are_gated_to_small_boards() {
+        let minimax = |model: &str| AIAgentConfig {
+            agent: AgentKind::Minimax,
+            ..config(model, 0)
+        };
+        let agents = [config("gpt-4o-mini", 1), minimax("perfect")];
+
+        assert!(Game::new("TicTacToe").unwrap().check_agents(&agents).is_ok());
+        let small_gomoku = Game::Gomoku(GomokuConfig { board_size: 3, win_length: 3, ..Default::default() });
+        assert!(small_gomoku.check_agents(&agents).is_ok());
+        assert!(Game::new("ConnectFour").unwrap().check_agents(&[config("gpt-4o-mini", 1)]).is_ok());
+
+        assert_eq!(
+            Game::new("ConnectFour").unwrap().check_agents(&agents).unwrap_err(),
+            "Minimax agents only play TicTacToe and Gomoku, not ConnectFour"
+        );
+        assert_eq!(
+            Game::new("Gomoku").unwrap().check_agents(&agents).unwrap_err(),
+            "Minimax agents only play boards of up to 9 cells; this Gomoku board has 225"
+        );
+        let text_only = Game::TicTacToe(TicTacToeConfig {
+            board_representation: BoardRepresentation::Text,
+            ..Default::default()
+        });
+        assert!(text_only.check_agents(&agents).is_err());
+        assert!(Game::new("TicTacToe").unwrap().check_agents(&[minimax("deep")]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_minimax_agent_never_loses() {
+        use crate::agents::scripted::ScriptedAgent;
+        use serde_json::json;
+
+        let game = Game::new("TicTacToe").unwrap();
+        let perfect = AIAgentConfig {
+            agent: AgentKind::Minimax,
+            ..config("", 0)
+        };
+        let result = game.play_game(vec![perfect.clone(), perfect.clone()]).await;
+        assert!(result.stats().draw);
+        assert_eq!(result.stats().seats, ["Minimax_1:perfect", "Minimax_2:perfect"]);
+
+        // A model that opens in a corner, then ignores the threat on the diagonal
+        let cells = [(0, 0), (0, 1), (1, 0)];
+        let moves = cells.iter().map(|(row, col)| json!({"row": row, "col": col})).collect();
+        let mut agents = build_agents(vec![perfect]).unwrap();
+        agents.insert(0, AIAgent::Scripted(ScriptedAgent::new("model", moves)));
+        let result = game.play_with_agents(agents).await;
+        assert_eq!(result.winner().map(String::as_str), Some("Minimax_1:perfect (O)"));
+    }
+}
@@ -0,0 +1,424 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::agent::AIAgent;
+use crate::games::parsing::u32_field;
+use crate::games::display::{BoardRepresentation, render_board};
+use crate::games::engine::{
+    self, GameEngine, TurnBasedGame, TurnRules, with_reasoning,
+};
+use crate::games::judge::Outcome;
+use crate::games::stats::GameStats;
+use crate::games::transcript::TranscriptWriter;
+use crate::games::tic_tac_toe::{has_line, open_cells};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GomokuConfig {
+    pub board_size: u32,
+    /// Stones in a row needed to win; longer lines also count
+    pub win_length: u32,
+    /// Color that moves first; the first agent always plays Black
+    #[serde(default)]
+    pub first_player: Player,
+    /// Whether agents see the board as a matrix, ASCII text, or both
+    #[serde(default)]
+    pub board_representation: BoardRepresentation,
+    /// `max_retries`, `move_timeout_ms` and `max_invalid_moves`, set at the
+    /// top level of the config
+    #[serde(flatten)]
+    pub rules: TurnRules,
+}
+
+impl Default for GomokuConfig {
+    fn default() -> Self {
+        GomokuConfig {
+            board_size: 15,
+            win_length: 5,
+            first_player: Player::Black,
+            board_representation: BoardRepresentation::default(),
+            rules: TurnRules::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GomokuState {
+    pub board: Vec<Vec<Option<Player>>>,
+    pub current_player: Player,
+    pub turn_number: u32,
+    pub game_over: bool,
+    pub winner: Option<Player>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Player {
+    #[default]
+    Black,
+    White,
+}
+
+impl Player {
+    fn as_str(self) -> &'static str {
+        match self {
+            Player::Black => "Black",
+            Player::White => "White",
+        }
+    }
+
+    fn other(&self) -> Player {
+        match self {
+            Player::Black => Player::White,
+            Player::White => Player::Black,
+        }
+    }
+}
+
+impl std::fmt::Display for Player {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GomokuMove {
+    pub row: u32,
+    pub col: u32,
+}
+
+#[derive(Debug)]
+pub struct Gomoku {
+    config: GomokuConfig,
+    state: GomokuState,
+    game_id: String,
+    /// Where each turn is logged as it is played
+    transcript: Option<TranscriptWriter>,
+}
+
+impl Gomoku {
+    pub fn new(config: GomokuConfig) -> Self {
+        let board_size = config.board_size as usize;
+        let board = vec![vec![None; board_size]; board_size];
+        let first_player = config.first_player;
+
+        Self {
+            config,
+            state: GomokuState {
+                board,
+                current_player: first_player,
+                turn_number: 0,
+                game_over: false,
+                winner: None,
+            },
+            game_id: format!("gomoku_{}", &uuid::Uuid::new_v4().to_string()[..8]),
+            transcript: None,
+        }
+    }
+
+    /// Log every turn to `transcript` as the game is played
+    pub fn with_transcript(mut self, transcript: Option<TranscriptWriter>) -> Self {
+        self.transcript = transcript;
+        self
+    }
+
+    pub async fn play_game(mut self, agents: Vec<AIAgent>) -> GomokuResult {
+        let transcript = self.transcript.take();
+        match engine::play(&mut self, &agents, transcript.as_ref()).await {
+            Ok(stats) => GomokuResult {
+                winner: stats.winner.clone(),
+                stats,
+                error: None,
+            },
+            Err(aborted) => GomokuResult {
+                winner: None,
+                stats: aborted.stats,
+                error: Some(aborted.error),
+            },
+        }
+    }
+
+    fn parse_move(move_data: &Value) -> Result<GomokuMove, String> {
+        Ok(GomokuMove {
+            row: u32_field(move_data, "row")?,
+            col: u32_field(move_data, "col")?,
+        })
+    }
+
+    fn is_valid_move(&self, row: u32, col: u32) -> bool {
+        if row >= self.config.board_size || col >= self.config.board_size {
+            return false;
+        }
+        self.state.board[row as usize][col as usize].is_none()
+    }
+
+    /// Every intersection taken; invalid moves also advance turn_number, so
+    /// count stones rather than turns
+    fn is_board_full(&self) -> bool {
+        self.state.board.iter().flatten().all(Option::is_some)
+    }
+
+    fn check_win(&self) -> bool {
+        has_line(&self.state.board, &self.state.current_player, self.config.win_length as usize)
+    }
+
+    fn state_to_json(&self) -> Value {
+        let board: Vec<Vec<Option<String>>> = self
+            .state
+            .board
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| cell.map(|p| p.to_string()))
+                    .collect()
+            })
+            .collect();
+
+        let mut state = json!({
+            "current_player": self.state.current_player.to_string(),
+            "turn_number": self.state.turn_number,
+            "game_over": self.state.game_over,
+            "winner": self.state.winner.map(|p| p.to_string()),
+            "board_size": self.config.board_size,
+            "win_length": self.config.win_length,
+        });
+
+        let representation = self.config.board_representation;
+        if representation.includes_text() {
+            state["board_text"] = json!(render_board(&board));
+        }
+        if representation.includes_matrix() {
+            state["board"] = json!(board);
+        }
+        state
+    }
+}
+
+impl GameEngine for Gomoku {
+    type Move = GomokuMove;
+
+    fn game_id(&self) -> &str {
+        &self.game_id
+    }
+
+    fn move_schema(&self) -> Value {
+        with_reasoning(json!({
+            "type": "object",
+            "properties": {
+                "row": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "maximum": self.config.board_size - 1,
+                    "description": "Row index (0-indexed)"
+                },
+                "col": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "maximum": self.config.board_size - 1,
+                    "description": "Column index (0-indexed)"
+                }
+            },
+            "required": ["row", "col"]
+        }))
+    }
+
+    fn state_json(&self) -> Value {
+        self.state_to_json()
+    }
+
+    fn parse_move(&self, move_data: &Value) -> Result<GomokuMove, String> {
+        Gomoku::parse_move(move_data)
+    }
+
+    fn legal_moves(&self) -> Option<Value> {
+        Some(open_cells(&self.state.board))
+    }
+}
+
+impl TurnBasedGame for Gomoku {
+    const SEATS: [&'static str; 2] = ["Black", "White"];
+
+    fn rules(&self) -> TurnRules {
+        self.config.rules
+    }
+
+    fn max_turns(&self) -> u32 {
+        self.config.board_size * self.config.board_size
+    }
+
+    fn turn_number(&self) -> u32 {
+        self.state.turn_number
+    }
+
+    fn set_turn_number(&mut self, turn_number: u32) {
+        self.state.turn_number = turn_number;
+    }
+
+    fn current_seat(&self) -> usize {
+        match self.state.current_player {
+            Player::Black => 0,
+            Player::White => 1,
+        }
+    }
+
+    fn next_player(&mut self) {
+        self.state.current_player = self.state.current_player.other();
+    }
+
+    fn notation(&self, mv: &GomokuMove) -> String {
+        format!("{},{}", mv.row, mv.col)
+    }
+
+    fn apply_move(&mut self, GomokuMove { row, col }: GomokuMove) -> Result<(), String> {
+        if !self.is_valid_move(row, col) {
+            return Err(format!("Invalid move: row={}, col={}", row, col));
+        }
+        self.state.board[row as usize][col as usize] = Some(self.state.current_player);
+        Ok(())
+    }
+
+    fn is_over(&self) -> Option<Outcome> {
+        if self.check_win() {
+            Some(Outcome::Winner(self.current_seat()))
+        } else if self.is_board_full() {
+            Some(Outcome::Draw)
+        } else {
+            None
+        }
+    }
+
+    fn finish(&mut self, winner: Option<usize>) {
+        self.state.game_over = true;
+        self.state.winner = winner.map(|seat| [Player::Black, Player::White][seat]);
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GomokuResult {
+    pub winner: Option<String>,
+    pub stats: GameStats,
+    pub error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    /// A default board with `len` Black stones from (row, col) stepping (dr, dc)
+    fn with_line(row: usize, col: usize, (dr, dc): (isize, isize), len: usize) -> Gomoku {
+        let mut game = Gomoku::new(GomokuConfig::default());
+        for i in 0..len as isize {
+            let r = (row as isize + dr * i) as usize;
+            let c = (col as isize + dc * i) as usize;
+            game.state.board[r][c] = Some(Player::Black);
+        }
+        game.state.current_player = Player::Black;
+        game
+    }
+
+    #[test]
+    fn test_config_default() {
+        let config = GomokuConfig::default();
+        assert_eq!(config.board_size, 15);
+        assert_eq!(config.win_length, 5);
+        assert_eq!(config.first_player, Player::Black);
+    }
+
+    #[test]
+    fn test_gomoku_new() {
+        let game = Gomoku::new(GomokuConfig::default());
+
+        assert_eq!(game.state.board.len(), 15);
+        assert_eq!(game.state.board[0].len(), 15);
+        assert_eq!(game.state.current_player, Player::Black);
+        assert!(game.game_id.starts_with("gomoku_"));
+    }
+
+    #[test]
+    fn test_check_win_horizontal() {
+        assert!(with_line(7, 3, (0, 1), 5).check_win());
+        assert!(!with_line(7, 3, (0, 1), 4).check_win());
+    }
+
+    #[test]
+    fn test_check_win_vertical() {
+        assert!(with_line(10, 14, (1, 0), 5).check_win());
+        assert!(!with_line(10, 14, (1, 0), 4).check_win());
+    }
+
+    #[test]
+    fn test_check_win_diagonal_tl_br() {
+        assert!(with_line(9, 9, (1, 1), 5).check_win());
+        assert!(!with_line(9, 9, (1, 1), 4).check_win());
+    }
+
+    #[test]
+    fn test_check_win_diagonal_tr_bl() {
+        assert!(with_line(0, 14, (1, -1), 5).check_win());
+        assert!(!with_line(0, 14, (1, -1), 4).check_win());
+    }
+
+    #[test]
+    fn test_overline_wins() {
+        assert!(with_line(2, 0, (0, 1), 7).check_win());
+    }
+
+    #[test]
+    fn test_check_win_is_for_current_player() {
+        let mut game = with_line(7, 3, (0, 1), 5);
+        game.state.current_player = Player::White;
+        assert!(!game.check_win());
+    }
+
+    #[test]
+    fn test_full_board_scan_is_fast() {
+        // No five anywhere: colors alternate along rows and pair up down columns
+        let mut game = Gomoku::new(GomokuConfig::default());
+        for (r, row) in game.state.board.iter_mut().enumerate() {
+            for (c, cell) in row.iter_mut().enumerate() {
+                *cell = Some(if (r / 2 + c) % 2 == 0 { Player::Black } else { Player::White });
+            }
+        }
+
+        let start = Instant::now();
+        for _ in 0..1000 {
+            assert!(!game.check_win());
+        }
+        // About 5k cell reads per scan; a move's check stays well under a millisecond
+        assert!(start.elapsed().as_millis() < 1000, "took {:?}", start.elapsed());
+        assert_eq!(game.is_over(), Some(Outcome::Draw));
+    }
+
+    #[test]
+    fn test_apply_move_rejects_occupied_and_out_of_bounds() {
+        let mut game = Gomoku::new(GomokuConfig::default());
+        assert!(game.apply_move(GomokuMove { row: 7, col: 7 }).is_ok());
+        assert_eq!(
+            game.apply_move(GomokuMove { row: 7, col: 7 }).unwrap_err(),
+            "Invalid move: row=7, col=7"
+        );
+        assert!(game.apply_move(GomokuMove { row: 15, col: 0 }).is_err());
+    }
+
+    #[test]
+    fn test_move_schema_uses_row_and_col() {
+        let schema = Gomoku::new(GomokuConfig::default()).move_schema();
+        assert_eq!(schema["required"], json!(["row", "col"]));
+        assert_eq!(schema["properties"]["row"]["maximum"], 14);
+    }
+
+    #[tokio::test]
+    async fn test_first_agent_plays_black_and_wins() {
+        use crate::agents::socket::scripted_socket_agent;
+
+        let black = (0..5).map(|c| json!({"row": 7, "col": c})).collect();
+        let white = (0..4).map(|c| json!({"row": 8, "col": c})).collect();
+        let agents = vec![
+            AIAgent::Socket(scripted_socket_agent("black", black).await),
+            AIAgent::Socket(scripted_socket_agent("white", white).await),
+        ];
+
+        let result = Gomoku::new(GomokuConfig::default()).play_game(agents).await;
+        assert_eq!(result.winner.as_deref(), Some("black (Black)"));
+        assert_eq!(result.stats.total_turns(), 9);
+    }
+}
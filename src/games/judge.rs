@@ -0,0 +1,323 @@
+use futures::future::LocalBoxFuture;
+use serde_json::{Value, json};
+
+use crate::agent::{AIAgent, MoveRequest};
+
+use super::game::{TestResult, seat_index};
+use super::parsing::coerce_u32;
+use super::rock_paper_scissors::scores_in_state;
+
+/// Verdict on a finished game
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// Index of the winning agent
+    Winner(usize),
+    Draw,
+}
+
+/// Decides the outcome of a game from its full transcript. Used when the
+/// engine ends a game without a mechanical winner or draw.
+pub trait Judge {
+    /// `players` are the agent names in seat order
+    fn judge<'a>(&'a self, result: &'a TestResult, players: &'a [String]) -> LocalBoxFuture<'a, Result<Outcome, String>>;
+}
+
+/// Reads the outcome the rules recorded: the result's winner or draw, or
+/// failing that the winner or scores in the final state
+pub struct RuleJudge;
+
+impl Judge for RuleJudge {
+    fn judge<'a>(&'a self, result: &'a TestResult, _players: &'a [String]) -> LocalBoxFuture<'a, Result<Outcome, String>> {
+        Box::pin(async move { decide_by_rules(result) })
+    }
+}
+
+fn decide_by_rules(result: &TestResult) -> Result<Outcome, String> {
+    if let Some(seat) = result.winner_seat() {
+        return Ok(Outcome::Winner(seat));
+    }
+    if result.stats().draw {
+        return Ok(Outcome::Draw);
+    }
+
+    let state = result
+        .stats()
+        .turns
+        .last()
+        .map(|turn| &turn.state_after)
+        .ok_or("no turns to judge")?;
+
+    if let Some(label) = state.get("winner").and_then(Value::as_str) {
+        return seat_index(label)
+            .map(Outcome::Winner)
+            .ok_or_else(|| format!("unknown winner '{}'", label));
+    }
+    if state.get("game_over").and_then(Value::as_bool) != Some(true) {
+        return Err("game did not finish".to_string());
+    }
+    let scores = scores_in_state(state);
+    let best = scores.iter().max();
+    Ok(match best {
+        Some(best) if scores.iter().filter(|&score| score == best).count() == 1 => {
+            Outcome::Winner(scores.iter().position(|score| score == best).unwrap_or(0))
+        }
+        _ => Outcome::Draw,
+    })
+}
+
+/// Asks a model to pick the winner according to a rubric
+pub struct LlmJudge {
+    agent: AIAgent,
+    rubric: String,
+}
+
+impl LlmJudge {
+    pub fn new(agent: AIAgent, rubric: impl Into<String>) -> Self {
+        Self {
+            agent,
+            rubric: rubric.into(),
+        }
+    }
+
+    fn request(&self, result: &TestResult, players: &[String]) -> MoveRequest {
+        let transcript: Vec<Value> = result
+            .stats()
+            .turns
+            .iter()
+            .map(|turn| {
+                json!({
+                    "turn_number": turn.turn_number,
+                    "player": turn.player,
+                    "move": turn.move_made,
+                    "valid": turn.move_valid,
+                })
+            })
+            .collect();
+        let seats: Vec<Value> = players
+            .iter()
+            .zip(result.seat_labels())
+            .enumerate()
+            .map(|(i, (name, label))| json!({"index": i, "name": name, "seat": label}))
+            .collect();
+
+        MoveRequest {
+            turn_index: 0,
+            game_id: format!("judge_{}", result.game_name()),
+            state: json!({
+                "rubric": self.rubric,
+                "players": seats,
+                "transcript": transcript,
+                "final_state": result.stats().turns.last().map(|turn| &turn.state_after),
+            }),
+            expected_move_schema: json!({
+                "type": "object",
+                "properties": {
+                    "winner": {
+                        "type": ["integer", "null"],
+                        "minimum": 0,
+                        "maximum": players.len().saturating_sub(1),
+                        "description": "Index of the winning player, or null for a draw"
+                    }
+                },
+                "required": ["winner"]
+            }),
+            legal_moves: None,
+        }
+    }
+}
+
+impl Judge for LlmJudge {
+    fn judge<'a>(&'a self, result: &'a TestResult, players: &'a [String]) -> LocalBoxFuture<'a, Result<Outcome, String>> {
+        Box::pin(async move {
+            let response = self
+                .agent
+                .execute_turn(&self.request(result, players))
+                .await
+                .map_err(|e| format!("judge {}: {}", self.agent.name(), e))?;
+            match response.chosen_move.get("winner") {
+                None | Some(Value::Null) => Ok(Outcome::Draw),
+                Some(value) => coerce_u32(value)
+                    .map(|seat| seat as usize)
+                    .filter(|seat| *seat < players.len())
+                    .map(Outcome::Winner)
+                    .ok_or_else(|| format!("judge picked an unknown player: {}", value)),
+            }
+        })
+    }
+}
+
+/// Let `judge` decide a game that finished without a winner or a draw.
+/// Results with a mechanical outcome or an error are returned unchanged.
+pub async fn judge_result(mut result: TestResult, players: &[String], judge: &dyn Judge) -> TestResult {
+    if result.winner().is_some() || result.stats().draw || result.error().is_some() {
+        return result;
+    }
+
+    let verdict = judge.judge(&result, players).await;
+    let labels = result.seat_labels();
+    let (winner, stats) = match &mut result {
+        TestResult::TicTacToe(r) => (&mut r.winner, &mut r.stats),
+        TestResult::RockPaperScissors(r) => (&mut r.winner, &mut r.stats),
+        TestResult::ConnectFour(r) => (&mut r.winner, &mut r.stats),
+        TestResult::Gomoku(r) => (&mut r.winner, &mut r.stats),
+        TestResult::Checkers(r) => (&mut r.winner, &mut r.stats),
+        TestResult::Nim(r) => (&mut r.winner, &mut r.stats),
+        TestResult::Reversi(r) => (&mut r.winner, &mut r.stats),
+        TestResult::Custom(r) => (&mut r.winner, &mut r.stats),
+    };
+    match verdict {
+        Ok(Outcome::Winner(seat)) if seat < players.len() => {
+            let name = format!("{} ({})", players[seat], labels[seat]);
+            *winner = Some(name.clone());
+            stats.winner = Some(name);
+        }
+        Ok(Outcome::Winner(seat)) => tracing::warn!("Judge picked unknown seat {}; leaving game undecided", seat),
+        Ok(Outcome::Draw) => stats.draw = true,
+        Err(e) => tracing::warn!("Judge could not decide the game: {}", e),
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::scripted::ScriptedAgent;
+    use crate::agents::socket::scripted_socket_agent;
+    use crate::games::engine::Aborted;
+    use crate::games::game::{CustomGame, Game, TicTacToeResult};
+    use crate::games::registry::DynGame;
+    use crate::games::stats::GameStats;
+    use crate::games::transcript::TranscriptWriter;
+    use std::sync::Arc;
+
+    /// Always returns the same verdict and records that it was asked
+    struct FixedJudge {
+        outcome: Outcome,
+        calls: std::sync::atomic::AtomicU32,
+    }
+
+    impl FixedJudge {
+        fn new(outcome: Outcome) -> Self {
+            Self {
+                outcome,
+                calls: Default::default(),
+            }
+        }
+    }
+
+    impl Judge for FixedJudge {
+        fn judge<'a>(&'a self, _result: &'a TestResult, _players: &'a [String]) -> LocalBoxFuture<'a, Result<Outcome, String>> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Box::pin(async move { Ok(self.outcome) })
+        }
+    }
+
+    /// A game with no win condition: it ends after one round, undecided
+    struct Debate;
+
+    impl DynGame for Debate {
+        fn probe_request(&self) -> MoveRequest {
+            MoveRequest {
+                turn_index: 1,
+                game_id: "debate_test".to_string(),
+                state: json!({}),
+                expected_move_schema: json!({"type": "object"}),
+                legal_moves: None,
+            }
+        }
+
+        fn probe_check(&self, _move_data: &Value) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn play(
+            self: Box<Self>,
+            _agents: Vec<AIAgent>,
+            _transcript: Option<TranscriptWriter>,
+        ) -> LocalBoxFuture<'static, Result<GameStats, Aborted>> {
+            Box::pin(async { Ok(GameStats::new()) })
+        }
+    }
+
+    fn undecided() -> TestResult {
+        TestResult::TicTacToe(TicTacToeResult {
+            winner: None,
+            stats: GameStats::new(),
+            error: None,
+        })
+    }
+
+    fn players() -> Vec<String> {
+        vec!["x".to_string(), "o".to_string()]
+    }
+
+    #[tokio::test]
+    async fn test_judge_decides_undecided_game() {
+        let judge = FixedJudge::new(Outcome::Winner(1));
+        let result = judge_result(undecided(), &players(), &judge).await;
+
+        assert_eq!(result.winner().map(String::as_str), Some("o (O)"));
+        assert_eq!(result.winner_seat(), Some(1));
+        assert_eq!(result.stats().winner.as_deref(), Some("o (O)"));
+
+        let result = judge_result(undecided(), &players(), &FixedJudge::new(Outcome::Draw)).await;
+        assert_eq!(result.winner(), None);
+        assert!(result.stats().draw);
+    }
+
+    #[tokio::test]
+    async fn test_mechanical_outcome_is_not_judged() {
+        let x = scripted_socket_agent(
+            "x",
+            vec![json!({"row": 0, "col": 0}), json!({"row": 1, "col": 0}), json!({"row": 2, "col": 0})],
+        )
+        .await;
+        let o = scripted_socket_agent("o", vec![json!({"row": 0, "col": 1}), json!({"row": 1, "col": 1})]).await;
+        let judge = FixedJudge::new(Outcome::Winner(1));
+
+        let result = Game::new("TicTacToe")
+            .unwrap()
+            .play_recorded(vec![AIAgent::Socket(x), AIAgent::Socket(o)], None, Some(&judge))
+            .await;
+
+        assert_eq!(result.winner().map(String::as_str), Some("x (X)"));
+        assert_eq!(judge.calls.load(std::sync::atomic::Ordering::SeqCst), 0);
+        assert_eq!(RuleJudge.judge(&result, &players()).await, Ok(Outcome::Winner(0)));
+    }
+
+    #[tokio::test]
+    async fn test_played_game_without_outcome_goes_to_the_judge() {
+        let debate = Game::Custom(CustomGame::new("Debate", Arc::new(|| Box::new(Debate) as Box<dyn DynGame>)));
+        let agents = || {
+            vec![
+                AIAgent::Scripted(ScriptedAgent::new("pro", Vec::new())),
+                AIAgent::Scripted(ScriptedAgent::new("con", Vec::new())),
+            ]
+        };
+        let judge = FixedJudge::new(Outcome::Winner(1));
+
+        let result = debate.play_recorded(agents(), None, Some(&judge)).await;
+        assert_eq!(judge.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(result.winner().map(String::as_str), Some("con (Player 2)"));
+
+        // Without a judge of its own the game is left to RuleJudge, which
+        // can't decide a game that never finished
+        let result = debate.play_recorded(agents(), None, None).await;
+        assert_eq!(result.winner(), None);
+        assert!(!result.stats().draw);
+    }
+
+    #[tokio::test]
+    async fn test_rule_judge_needs_a_finished_game() {
+        assert!(RuleJudge.judge(&undecided(), &players()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_llm_judge_reads_winner_index() {
+        let model = scripted_socket_agent("judge", vec![json!({"winner": "1"}), json!({"winner": null})]).await;
+        let judge = LlmJudge::new(AIAgent::Socket(model), "Most creative play wins");
+
+        assert_eq!(judge.judge(&undecided(), &players()).await, Ok(Outcome::Winner(1)));
+        assert_eq!(judge.judge(&undecided(), &players()).await, Ok(Outcome::Draw));
+    }
+}
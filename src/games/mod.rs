@@ -1,10 +1,29 @@
 pub mod tic_tac_toe;
 pub mod rock_paper_scissors;
 pub mod connect_four;
+pub mod gomoku;
+pub mod checkers;
+pub mod nim;
+pub mod reversi;
 pub mod stats;
+pub mod parsing;
 pub mod game;
+pub mod registry;
 pub mod display;
+pub mod reliability;
+pub mod aggregate;
+pub mod svg;
+pub mod judge;
+pub mod passes;
+pub mod simultaneous;
+pub mod rng;
+pub mod timeout;
+pub mod engine;
+pub mod transcript;
+#[cfg(test)]
+mod test_support;
 
 pub use game::*;
-pub use display::print_game_stats;
+pub use display::{BoardRepresentation, print_game_stats, write_game_json};
+pub use stats::GameStats;
 
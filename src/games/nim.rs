@@ -0,0 +1,353 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::agent::AIAgent;
+use crate::games::engine::{
+    self, GameEngine, TurnBasedGame, TurnRules, with_reasoning,
+};
+use crate::games::judge::Outcome;
+use crate::games::parsing::u32_field;
+use crate::games::stats::GameStats;
+use crate::games::transcript::TranscriptWriter;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NimConfig {
+    /// Objects in each heap at the start
+    pub heaps: Vec<u32>,
+    /// The player who takes the last object loses instead of winning
+    #[serde(default)]
+    pub misere: bool,
+    /// `max_retries`, `move_timeout_ms` and `max_invalid_moves`, set at the
+    /// top level of the config
+    #[serde(flatten)]
+    pub rules: TurnRules,
+}
+
+impl Default for NimConfig {
+    fn default() -> Self {
+        NimConfig {
+            heaps: vec![3, 5, 7],
+            misere: false,
+            rules: TurnRules::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NimState {
+    pub heaps: Vec<u32>,
+    pub current_player: Player,
+    pub turn_number: u32,
+    pub game_over: bool,
+    pub winner: Option<Player>,
+}
+
+/// Player 1 moves first and always belongs to the first agent
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Player {
+    One,
+    Two,
+}
+
+impl Player {
+    fn other(&self) -> Player {
+        match self {
+            Player::One => Player::Two,
+            Player::Two => Player::One,
+        }
+    }
+}
+
+impl std::fmt::Display for Player {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Player::One => write!(f, "Player 1"),
+            Player::Two => write!(f, "Player 2"),
+        }
+    }
+}
+
+/// Take `count` objects from heap number `heap` (0-based)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NimMove {
+    pub heap: u32,
+    pub count: u32,
+}
+
+#[derive(Debug)]
+pub struct Nim {
+    config: NimConfig,
+    state: NimState,
+    game_id: String,
+    /// Where each turn is logged as it is played
+    transcript: Option<TranscriptWriter>,
+}
+
+impl Nim {
+    pub fn new(config: NimConfig) -> Self {
+        Self {
+            state: NimState {
+                heaps: config.heaps.clone(),
+                current_player: Player::One,
+                turn_number: 0,
+                game_over: false,
+                winner: None,
+            },
+            config,
+            game_id: format!("nim_{}", &uuid::Uuid::new_v4().to_string()[..8]),
+            transcript: None,
+        }
+    }
+
+    /// Log every turn to `transcript` as the game is played
+    pub fn with_transcript(mut self, transcript: Option<TranscriptWriter>) -> Self {
+        self.transcript = transcript;
+        self
+    }
+
+    pub async fn play_game(mut self, agents: Vec<AIAgent>) -> NimResult {
+        let transcript = self.transcript.take();
+        match engine::play(&mut self, &agents, transcript.as_ref()).await {
+            Ok(stats) => NimResult {
+                winner: stats.winner.clone(),
+                stats,
+                error: None,
+            },
+            Err(aborted) => NimResult {
+                winner: None,
+                stats: aborted.stats,
+                error: Some(aborted.error),
+            },
+        }
+    }
+
+    fn parse_move(move_data: &Value) -> Result<NimMove, String> {
+        Ok(NimMove {
+            heap: u32_field(move_data, "heap")?,
+            count: u32_field(move_data, "count")?,
+        })
+    }
+
+    fn state_to_json(&self) -> Value {
+        let last = if self.config.misere { "loses" } else { "wins" };
+        json!({
+            "heaps": self.state.heaps,
+            "current_player": self.state.current_player.to_string(),
+            "turn_number": self.state.turn_number,
+            "game_over": self.state.game_over,
+            "winner": self.state.winner.map(|p| p.to_string()),
+            "misere": self.config.misere,
+            "rules": format!(
+                "Heaps are numbered from 0. Take one or more objects from a single heap. \
+                 The player who takes the last object {}.",
+                last
+            ),
+        })
+    }
+}
+
+impl GameEngine for Nim {
+    type Move = NimMove;
+
+    fn game_id(&self) -> &str {
+        &self.game_id
+    }
+
+    fn move_schema(&self) -> Value {
+        with_reasoning(json!({
+            "type": "object",
+            "properties": {
+                "heap": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "maximum": self.state.heaps.len().saturating_sub(1),
+                    "description": "Heap to take from (0-indexed)"
+                },
+                "count": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Number of objects to take"
+                }
+            },
+            "required": ["heap", "count"]
+        }))
+    }
+
+    fn state_json(&self) -> Value {
+        self.state_to_json()
+    }
+
+    fn parse_move(&self, move_data: &Value) -> Result<NimMove, String> {
+        Nim::parse_move(move_data)
+    }
+
+    fn legal_moves(&self) -> Option<Value> {
+        let moves: Vec<NimMove> = (0..)
+            .zip(&self.state.heaps)
+            .flat_map(|(heap, &size)| (1..=size).map(move |count| NimMove { heap, count }))
+            .collect();
+        Some(json!(moves))
+    }
+}
+
+impl TurnBasedGame for Nim {
+    const SEATS: [&'static str; 2] = ["Player 1", "Player 2"];
+
+    fn rules(&self) -> TurnRules {
+        self.config.rules
+    }
+
+    /// Every valid move takes at least one object; allow as many invalid ones
+    fn max_turns(&self) -> u32 {
+        self.config.heaps.iter().sum::<u32>() * 2
+    }
+
+    fn turn_number(&self) -> u32 {
+        self.state.turn_number
+    }
+
+    fn set_turn_number(&mut self, turn_number: u32) {
+        self.state.turn_number = turn_number;
+    }
+
+    fn current_seat(&self) -> usize {
+        match self.state.current_player {
+            Player::One => 0,
+            Player::Two => 1,
+        }
+    }
+
+    fn next_player(&mut self) {
+        self.state.current_player = self.state.current_player.other();
+    }
+
+    fn notation(&self, mv: &NimMove) -> String {
+        format!("{},{}", mv.heap, mv.count)
+    }
+
+    fn apply_move(&mut self, mv: NimMove) -> Result<(), String> {
+        let heaps = self.state.heaps.len();
+        let size = self
+            .state
+            .heaps
+            .get_mut(mv.heap as usize)
+            .ok_or_else(|| format!("Invalid move: heap {} doesn't exist (heaps are 0 to {})", mv.heap, heaps - 1))?;
+        if mv.count == 0 {
+            return Err("Invalid move: must take at least one object".to_string());
+        }
+        if mv.count > *size {
+            return Err(format!("Invalid move: heap {} has only {} object(s), can't take {}", mv.heap, size, mv.count));
+        }
+        *size -= mv.count;
+        Ok(())
+    }
+
+    /// Once every heap is empty, the player who just moved took the last object
+    fn is_over(&self) -> Option<Outcome> {
+        if self.state.heaps.iter().any(|&h| h > 0) {
+            return None;
+        }
+        let mover = self.current_seat();
+        Some(Outcome::Winner(if self.config.misere { 1 - mover } else { mover }))
+    }
+
+    fn finish(&mut self, winner: Option<usize>) {
+        self.state.game_over = true;
+        self.state.winner = winner.map(|seat| [Player::One, Player::Two][seat]);
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NimResult {
+    pub winner: Option<String>,
+    pub stats: GameStats,
+    pub error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::scripted::ScriptedAgent;
+
+    fn take(heap: u32, count: u32) -> NimMove {
+        NimMove { heap, count }
+    }
+
+    #[test]
+    fn test_new_game() {
+        let game = Nim::new(NimConfig::default());
+        assert_eq!(game.state.heaps, vec![3, 5, 7]);
+        assert_eq!(game.state.current_player, Player::One);
+        assert!(game.game_id.starts_with("nim_"));
+    }
+
+    #[test]
+    fn test_valid_removal() {
+        let mut game = Nim::new(NimConfig::default());
+        assert!(game.apply_move(take(1, 5)).is_ok());
+        assert_eq!(game.state.heaps, vec![3, 0, 7]);
+        assert_eq!(game.is_over(), None);
+    }
+
+    #[test]
+    fn test_rejects_bad_removals() {
+        let mut game = Nim::new(NimConfig::default());
+        assert_eq!(
+            game.apply_move(take(0, 4)).unwrap_err(),
+            "Invalid move: heap 0 has only 3 object(s), can't take 4"
+        );
+        assert_eq!(game.apply_move(take(0, 0)).unwrap_err(), "Invalid move: must take at least one object");
+        assert_eq!(
+            game.apply_move(take(3, 1)).unwrap_err(),
+            "Invalid move: heap 3 doesn't exist (heaps are 0 to 2)"
+        );
+        assert_eq!(game.state.heaps, vec![3, 5, 7]);
+    }
+
+    #[test]
+    fn test_taking_the_last_object() {
+        let config = NimConfig {
+            heaps: vec![1, 2],
+            ..Default::default()
+        };
+        let mut game = Nim::new(config.clone());
+        game.apply_move(take(1, 2)).unwrap();
+        game.next_player();
+        game.apply_move(take(0, 1)).unwrap();
+        // Player 2 took the last object
+        assert_eq!(game.is_over(), Some(Outcome::Winner(1)));
+
+        let mut game = Nim::new(NimConfig { misere: true, ..config });
+        game.apply_move(take(0, 1)).unwrap();
+        game.apply_move(take(1, 2)).unwrap();
+        assert_eq!(game.is_over(), Some(Outcome::Winner(1)));
+    }
+
+    #[test]
+    fn test_parse_move() {
+        assert_eq!(Nim::parse_move(&json!({"heap": "2", "count": 3.0})).unwrap(), take(2, 3));
+        assert!(Nim::parse_move(&json!({"heap": 1})).unwrap_err().contains("'count'"));
+        assert!(Nim::parse_move(&json!({"heap": 1, "count": -1})).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_full_game() {
+        let config = NimConfig {
+            heaps: vec![2, 3],
+            ..Default::default()
+        };
+        // Player 1 over-removes once, then both play it out
+        let one = vec![json!({"heap": 0, "count": 3}), json!({"heap": 1, "count": 1}), json!({"heap": 0, "count": 2})];
+        let two = vec![json!({"heap": 1, "count": 2})];
+        let agents = vec![
+            AIAgent::Scripted(ScriptedAgent::new("one", one)),
+            AIAgent::Scripted(ScriptedAgent::new("two", two)),
+        ];
+        let result = Nim::new(config).play_game(agents).await;
+
+        assert_eq!(result.winner.as_deref(), Some("one (Player 1)"));
+        assert_eq!(result.stats.seats, ["one", "two"]);
+        assert_eq!(result.stats.invalid_moves, 1);
+        assert_eq!(result.stats.total_turns(), 4);
+    }
+}
@@ -0,0 +1,219 @@
+use serde_json::Value;
+
+/// Error recorded when an agent sends back the game state instead of a move
+pub const STATE_ECHO_ERROR: &str = "agent returned full state, expected move matching schema";
+
+/// Keys that only ever appear in an agent-facing game state, never in a move
+const STATE_ONLY_KEYS: &[&str] = &["board", "current_player", "round_history"];
+
+/// Heuristic for a model echoing the `state` it was given rather than a move
+pub fn looks_like_state(move_data: &Value) -> bool {
+    move_data
+        .as_object()
+        .is_some_and(|obj| STATE_ONLY_KEYS.iter().any(|key| obj.contains_key(*key)))
+}
+
+/// Error for a move object whose `field` is missing or malformed
+pub fn missing_field_error(move_data: &Value, field: &str) -> String {
+    if looks_like_state(move_data) {
+        STATE_ECHO_ERROR.to_string()
+    } else {
+        format!("Missing or invalid '{}' field", field)
+    }
+}
+
+/// Read a whole number out of a JSON value, accepting the forms models
+/// commonly send: `3`, `3.0` and `"3"`
+pub fn coerce_u32(value: &Value) -> Option<u32> {
+    let whole = |f: f64| (f.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(&f)).then_some(f as u32);
+    match value {
+        Value::Number(n) => match n.as_u64() {
+            Some(v) => u32::try_from(v).ok(),
+            None => n.as_f64().and_then(whole),
+        },
+        Value::String(s) => {
+            let s = s.trim();
+            s.parse::<u32>().ok().or_else(|| s.parse::<f64>().ok().and_then(whole))
+        }
+        _ => None,
+    }
+}
+
+/// Extract a non-negative integer field from an agent's move object,
+/// leniently (see `coerce_u32`)
+pub fn u32_field(move_data: &Value, field: &str) -> Result<u32, String> {
+    match move_data.get(field) {
+        Some(value) if !looks_like_state(move_data) => coerce_u32(value).ok_or_else(|| {
+            format!("Missing or invalid '{}' field: expected a whole number, got {}", field, value)
+        }),
+        _ => Err(missing_field_error(move_data, field)),
+    }
+}
+
+/// A board read back from a game's `state` JSON; `None` marks an empty cell
+pub type ParsedBoard = Vec<Vec<Option<String>>>;
+
+/// Read the `board` matrix out of a game state. Every consumer that needs to
+/// reason about an agent-facing state goes through here so malformed states
+/// are rejected the same way everywhere.
+pub fn parse_board(state: &Value) -> Result<ParsedBoard, String> {
+    let rows = state
+        .get("board")
+        .ok_or_else(|| "state has no 'board' field".to_string())?
+        .as_array()
+        .ok_or_else(|| "'board' must be an array of rows".to_string())?;
+    if rows.is_empty() {
+        return Err("'board' has no rows".to_string());
+    }
+
+    let mut board = Vec::with_capacity(rows.len());
+    let mut width = None;
+    for (r, row) in rows.iter().enumerate() {
+        let cells = row
+            .as_array()
+            .ok_or_else(|| format!("board row {} is not an array", r))?;
+        match width {
+            None if cells.is_empty() => return Err(format!("board row {} is empty", r)),
+            None => width = Some(cells.len()),
+            Some(w) if w != cells.len() => {
+                return Err(format!("board row {} has {} cells, expected {}", r, cells.len(), w));
+            }
+            Some(_) => {}
+        }
+
+        let parsed = cells
+            .iter()
+            .enumerate()
+            .map(|(c, cell)| match cell {
+                Value::Null => Ok(None),
+                Value::String(s) => Ok(Some(s.clone())),
+                other => Err(format!("board cell ({}, {}) must be null or a string, got {}", r, c, other)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        board.push(parsed);
+    }
+    Ok(board)
+}
+
+/// The symbol of the player to move, e.g. "X" or "Red"
+pub fn parse_current_player(state: &Value) -> Result<String, String> {
+    state
+        .get("current_player")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .ok_or_else(|| "state has no string 'current_player' field".to_string())
+}
+
+/// (rows, cols) of a parsed board
+pub fn board_dimensions(board: &ParsedBoard) -> (usize, usize) {
+    (board.len(), board.first().map_or(0, |row| row.len()))
+}
+
+/// Coordinates of every empty cell, in row-major order
+pub fn empty_cells<T>(board: &[Vec<Option<T>>]) -> Vec<(usize, usize)> {
+    board
+        .iter()
+        .enumerate()
+        .flat_map(|(r, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, cell)| cell.is_none())
+                .map(move |(c, _)| (r, c))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_u32_field_valid() {
+        assert_eq!(u32_field(&json!({"row": 2}), "row"), Ok(2));
+    }
+
+    #[test]
+    fn test_u32_field_missing() {
+        let err = u32_field(&json!({}), "row").unwrap_err();
+        assert_eq!(err, "Missing or invalid 'row' field");
+    }
+
+    #[test]
+    fn test_u32_field_state_echo() {
+        let state = json!({"board": [[null, "X"]], "current_player": "O", "turn_number": 2});
+        assert_eq!(u32_field(&state, "row").unwrap_err(), STATE_ECHO_ERROR);
+    }
+
+    #[test]
+    fn test_u32_field_rejects_out_of_range() {
+        assert!(u32_field(&json!({"row": -1}), "row").is_err());
+        assert!(u32_field(&json!({"row": u64::from(u32::MAX) + 1}), "row").is_err());
+        assert!(u32_field(&json!({"row": -1.0}), "row").is_err());
+    }
+
+    #[test]
+    fn test_u32_field_lenient_forms() {
+        assert_eq!(u32_field(&json!({"column": 3}), "column"), Ok(3));
+        assert_eq!(u32_field(&json!({"column": "3"}), "column"), Ok(3));
+        assert_eq!(u32_field(&json!({"column": " 3 "}), "column"), Ok(3));
+        assert_eq!(u32_field(&json!({"column": 3.0}), "column"), Ok(3));
+        assert_eq!(u32_field(&json!({"column": "3.0"}), "column"), Ok(3));
+    }
+
+    #[test]
+    fn test_u32_field_rejects_non_whole_numbers() {
+        assert_eq!(
+            u32_field(&json!({"column": 3.5}), "column").unwrap_err(),
+            "Missing or invalid 'column' field: expected a whole number, got 3.5"
+        );
+        assert_eq!(
+            u32_field(&json!({"column": "abc"}), "column").unwrap_err(),
+            "Missing or invalid 'column' field: expected a whole number, got \"abc\""
+        );
+        assert!(u32_field(&json!({"column": true}), "column").is_err());
+        assert!(u32_field(&json!({"column": [3]}), "column").is_err());
+    }
+
+    #[test]
+    fn test_parse_board_valid() {
+        let state = json!({"board": [["X", null], [null, "O"]], "current_player": "X"});
+        let board = parse_board(&state).unwrap();
+        assert_eq!(board, vec![
+            vec![Some("X".to_string()), None],
+            vec![None, Some("O".to_string())],
+        ]);
+        assert_eq!(board_dimensions(&board), (2, 2));
+        assert_eq!(empty_cells(&board), vec![(0, 1), (1, 0)]);
+        assert_eq!(parse_current_player(&state).unwrap(), "X");
+    }
+
+    #[test]
+    fn test_parse_board_non_square() {
+        let board = parse_board(&json!({"board": [[null, null, "Red"], [null, "Yellow", "Red"]]})).unwrap();
+        assert_eq!(board_dimensions(&board), (2, 3));
+    }
+
+    #[test]
+    fn test_parse_board_missing_field() {
+        assert_eq!(parse_board(&json!({})).unwrap_err(), "state has no 'board' field");
+    }
+
+    #[test]
+    fn test_parse_board_malformed_shapes() {
+        assert!(parse_board(&json!({"board": "X.O"})).unwrap_err().contains("array of rows"));
+        assert!(parse_board(&json!({"board": []})).unwrap_err().contains("no rows"));
+        assert!(parse_board(&json!({"board": [[]]})).unwrap_err().contains("row 0 is empty"));
+        assert!(parse_board(&json!({"board": [null]})).unwrap_err().contains("row 0 is not an array"));
+        assert_eq!(
+            parse_board(&json!({"board": [[null, null], [null]]})).unwrap_err(),
+            "board row 1 has 1 cells, expected 2"
+        );
+        assert!(parse_board(&json!({"board": [[null, 1]]})).unwrap_err().contains("cell (0, 1)"));
+    }
+
+    #[test]
+    fn test_parse_current_player_missing() {
+        assert!(parse_current_player(&json!({"current_player": 1})).is_err());
+    }
+}
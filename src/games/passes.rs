@@ -0,0 +1,82 @@
+/// Both players passing in a row ends the game
+pub const DEFAULT_MAX_CONSECUTIVE_PASSES: u32 = 2;
+
+/// Serde default for game configs' `max_consecutive_passes`
+pub fn default_max_consecutive_passes() -> u32 {
+    DEFAULT_MAX_CONSECUTIVE_PASSES
+}
+
+/// Tracks passes in games that allow them, so two agents that keep passing
+/// can't stall a game forever. The game records every move and pass and
+/// ends, scoring by its normal rule, once `record` returns true.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PassCounter {
+    max_consecutive: u32,
+    consecutive: u32,
+}
+
+impl Default for PassCounter {
+    fn default() -> Self {
+        PassCounter::new(DEFAULT_MAX_CONSECUTIVE_PASSES)
+    }
+}
+
+impl PassCounter {
+    /// A limit of 0 is treated as 1: any pass ends the game
+    pub fn new(max_consecutive: u32) -> Self {
+        PassCounter {
+            max_consecutive: max_consecutive.max(1),
+            consecutive: 0,
+        }
+    }
+
+    /// Record one turn. Returns true once the pass limit is reached.
+    pub fn record(&mut self, passed: bool) -> bool {
+        if passed {
+            self.consecutive += 1;
+        } else {
+            self.consecutive = 0;
+        }
+        self.limit_reached()
+    }
+
+    pub fn limit_reached(&self) -> bool {
+        self.consecutive >= self.max_consecutive
+    }
+
+    pub fn consecutive(&self) -> u32 {
+        self.consecutive
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_passes_end_the_game() {
+        let mut passes = PassCounter::default();
+        assert!(!passes.record(true));
+        assert!(passes.record(true));
+    }
+
+    #[test]
+    fn test_move_resets_the_count() {
+        let mut passes = PassCounter::default();
+        assert!(!passes.record(true));
+        assert!(!passes.record(false));
+        assert_eq!(passes.consecutive(), 0);
+        assert!(!passes.record(true));
+        assert!(passes.record(true));
+    }
+
+    #[test]
+    fn test_custom_limit() {
+        let mut passes = PassCounter::new(3);
+        assert!(!passes.record(true));
+        assert!(!passes.record(true));
+        assert!(passes.record(true));
+
+        assert!(PassCounter::new(0).record(true));
+    }
+}
@@ -0,0 +1,205 @@
+use std::sync::{Arc, LazyLock, RwLock};
+
+use futures::future::LocalBoxFuture;
+use serde_json::Value;
+
+use crate::agent::{AIAgent, MoveRequest};
+
+use super::engine::Aborted;
+use super::game::{
+    CheckersConfig, ConnectFourConfig, CustomGame, Game, GomokuConfig, NimConfig, ReversiConfig,
+    RockPaperScissorsConfig, TicTacToeConfig,
+};
+use super::stats::GameStats;
+use super::transcript::TranscriptWriter;
+
+/// One game of a type added at runtime, built fresh by its factory for
+/// every game played. Unlike `GameEngine` it can be boxed, so a crate
+/// embedding the arena can add games without touching `Game`.
+pub trait DynGame: Send {
+    /// Fewest agents the game can be played with
+    fn min_players(&self) -> usize {
+        2
+    }
+
+    /// Most agents the game can be played with
+    fn max_players(&self) -> usize {
+        2
+    }
+
+    /// Seat names in agent order, as used in recorded winners
+    fn seat_labels(&self) -> Vec<String> {
+        vec!["Player 1".to_string(), "Player 2".to_string()]
+    }
+
+    /// Request for the opening position, used to check an agent's output
+    /// format before committing to a whole game
+    fn probe_request(&self) -> MoveRequest;
+
+    /// Whether a probe response parses as a move for this game
+    fn probe_check(&self, move_data: &Value) -> Result<(), String>;
+
+    /// Play the game to the end with `agents` in seat order, also logging
+    /// each turn to `transcript`, as `engine::play` does
+    fn play(
+        self: Box<Self>,
+        agents: Vec<AIAgent>,
+        transcript: Option<TranscriptWriter>,
+    ) -> LocalBoxFuture<'static, Result<GameStats, Aborted>>;
+}
+
+/// Builds a game by name, with its default configuration
+pub type GameFactory = Arc<dyn Fn() -> Game + Send + Sync>;
+
+/// Builds one `DynGame` to play
+pub type DynGameFactory = Arc<dyn Fn() -> Box<dyn DynGame> + Send + Sync>;
+
+/// Every game that can be picked by name, in the order they are listed.
+/// Built-in games are listed by their `Game` variant; downstream games are
+/// played through `DynGame`.
+pub struct GameRegistry {
+    games: Vec<(String, GameFactory)>,
+}
+
+impl GameRegistry {
+    /// The games that ship with the arena
+    pub fn builtin() -> Self {
+        let builtin = |name: &str, factory: fn() -> Game| (name.to_string(), Arc::new(factory) as GameFactory);
+        GameRegistry {
+            games: vec![
+                builtin("TicTacToe", || Game::TicTacToe(TicTacToeConfig::default())),
+                builtin("RockPaperScissors", || Game::RockPaperScissors(RockPaperScissorsConfig::default())),
+                builtin("ConnectFour", || Game::ConnectFour(ConnectFourConfig::default())),
+                builtin("Gomoku", || Game::Gomoku(GomokuConfig::default())),
+                builtin("Checkers", || Game::Checkers(CheckersConfig::default())),
+                builtin("Nim", || Game::Nim(NimConfig::default())),
+                builtin("Reversi", || Game::Reversi(ReversiConfig::default())),
+            ],
+        }
+    }
+
+    /// Add `name`, built by `factory`. Names must be unique.
+    pub fn register(&mut self, name: &str, factory: impl Fn() -> Game + Send + Sync + 'static) -> Result<(), String> {
+        if self.games.iter().any(|(registered, _)| registered == name) {
+            return Err(format!("A game named {} is already registered", name));
+        }
+        self.games.push((name.to_string(), Arc::new(factory)));
+        Ok(())
+    }
+
+    /// Add `name`, played through the `DynGame` that `factory` builds
+    pub fn register_dyn(
+        &mut self,
+        name: &str,
+        factory: impl Fn() -> Box<dyn DynGame> + Send + Sync + 'static,
+    ) -> Result<(), String> {
+        let game = CustomGame::new(name, Arc::new(factory));
+        self.register(name, move || Game::Custom(game.clone()))
+    }
+
+    /// A new game called `name`, with its default configuration
+    pub fn create(&self, name: &str) -> Option<Game> {
+        self.games
+            .iter()
+            .find(|(registered, _)| registered == name)
+            .map(|(_, factory)| factory())
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.games.iter().map(|(name, _)| name.clone()).collect()
+    }
+}
+
+static GLOBAL: LazyLock<RwLock<GameRegistry>> = LazyLock::new(|| RwLock::new(GameRegistry::builtin()));
+
+/// Process-wide registry that `Game::new` and the command line look game
+/// names up in
+pub fn global() -> &'static RwLock<GameRegistry> {
+    &GLOBAL
+}
+
+/// Make a `DynGame` playable by `name` everywhere a game name is accepted
+pub fn register_game(name: &str, factory: impl Fn() -> Box<dyn DynGame> + Send + Sync + 'static) -> Result<(), String> {
+    global().write().unwrap().register_dyn(name, factory)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::scripted::ScriptedAgent;
+    use crate::games::engine::{self, GameEngine, TurnBasedGame};
+    use crate::games::tic_tac_toe::{TicTacToe, TicTacToeConfig as EngineConfig};
+    use crate::games::TestResult;
+    use serde_json::json;
+
+    /// TicTacToe again, under another name and through the boxed interface
+    struct BoxedTicTacToe(TicTacToe);
+
+    impl DynGame for BoxedTicTacToe {
+        fn seat_labels(&self) -> Vec<String> {
+            TicTacToe::SEATS.map(String::from).into()
+        }
+
+        fn probe_request(&self) -> MoveRequest {
+            self.0.probe_request()
+        }
+
+        fn probe_check(&self, move_data: &Value) -> Result<(), String> {
+            self.0.probe_check(move_data)
+        }
+
+        fn play(
+            self: Box<Self>,
+            agents: Vec<AIAgent>,
+            transcript: Option<TranscriptWriter>,
+        ) -> LocalBoxFuture<'static, Result<GameStats, Aborted>> {
+            let mut game = self.0;
+            Box::pin(async move { engine::play(&mut game, &agents, transcript.as_ref()).await })
+        }
+    }
+
+    #[test]
+    fn test_builtin_games_keep_their_names_and_order() {
+        assert_eq!(
+            GameRegistry::builtin().names(),
+            ["TicTacToe", "RockPaperScissors", "ConnectFour", "Gomoku", "Checkers", "Nim", "Reversi"]
+        );
+    }
+
+    #[test]
+    fn test_names_are_unique() {
+        let mut registry = GameRegistry::builtin();
+        let err = registry
+            .register_dyn("TicTacToe", || Box::new(BoxedTicTacToe(TicTacToe::new(EngineConfig::default()))))
+            .unwrap_err();
+        assert_eq!(err, "A game named TicTacToe is already registered");
+    }
+
+    #[tokio::test]
+    async fn test_registered_game_is_playable_by_name() {
+        // A local registry, so the game doesn't leak into other tests
+        let mut registry = GameRegistry::builtin();
+        registry
+            .register_dyn("BoxedTicTacToe", || Box::new(BoxedTicTacToe(TicTacToe::new(EngineConfig::default()))))
+            .unwrap();
+        assert!(registry.names().iter().any(|name| name == "BoxedTicTacToe"));
+        assert!(!Game::list().iter().any(|name| name == "BoxedTicTacToe"));
+
+        let game = registry.create("BoxedTicTacToe").unwrap();
+        assert_eq!(game.name(), "BoxedTicTacToe");
+        assert_eq!(game.move_schema(), Game::new("TicTacToe").unwrap().move_schema());
+
+        let scripted = |name: &str, cells: [(u32, u32); 3]| {
+            let moves = cells.iter().map(|(row, col)| json!({"row": row, "col": col})).collect();
+            AIAgent::Scripted(ScriptedAgent::new(name, moves))
+        };
+        let agents = vec![scripted("x", [(0, 0), (0, 1), (0, 2)]), scripted("o", [(1, 0), (1, 1), (2, 2)])];
+        let result = game.play_with_agents(agents).await;
+
+        assert!(matches!(&result, TestResult::Custom(r) if r.game == "BoxedTicTacToe"));
+        assert_eq!(result.winner().map(String::as_str), Some("x (X)"));
+        assert_eq!(result.winner_seat(), Some(0));
+        assert_eq!(result.seat_labels(), ["X", "O"]);
+        assert_eq!(result.game_name(), "BoxedTicTacToe");
+    }
+}
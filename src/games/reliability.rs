@@ -0,0 +1,316 @@
+use std::collections::BTreeMap;
+
+use tabled::{Table, Tabled, settings::{Alignment, Modify, Style, object::Rows}};
+
+use super::game::TestResult;
+
+/// How one agent fared across every game in a batch
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize)]
+pub struct AgentReliability {
+    pub agent: String,
+    pub games: u32,
+    pub wins: u32,
+    pub turns: u32,
+    pub invalid_moves: u32,
+    pub format_errors: u32,
+    pub total_time_ms: u64,
+    /// Turns that were requested twice to check temperature-0 determinism
+    pub replayed_turns: u32,
+    /// Replayed turns whose two answers differed
+    pub replay_mismatches: u32,
+}
+
+impl AgentReliability {
+    pub fn win_rate(&self) -> f64 {
+        ratio(self.wins as u64, self.games as u64)
+    }
+
+    pub fn invalid_move_rate(&self) -> f64 {
+        ratio(self.invalid_moves as u64, self.turns as u64)
+    }
+
+    /// Share of responses that parsed as a move, legal or not
+    pub fn format_compliance(&self) -> f64 {
+        1.0 - ratio(self.format_errors as u64, self.turns as u64)
+    }
+
+    pub fn average_latency_ms(&self) -> f64 {
+        ratio(self.total_time_ms, self.turns as u64)
+    }
+
+    /// Share of replayed turns answered the same way twice; `None` if the
+    /// agent was never replay-checked
+    pub fn temp0_stability(&self) -> Option<f64> {
+        (self.replayed_turns > 0)
+            .then(|| 1.0 - ratio(self.replay_mismatches as u64, self.replayed_turns as u64))
+    }
+}
+
+fn ratio(part: u64, whole: u64) -> f64 {
+    if whole == 0 { 0.0 } else { part as f64 / whole as f64 }
+}
+
+/// Per-agent reliability figures, sorted by agent name
+pub fn reliability_rows(results: &[TestResult]) -> Vec<AgentReliability> {
+    let mut agents: BTreeMap<String, AgentReliability> = BTreeMap::new();
+
+    for result in results {
+        let stats = result.stats();
+        let mut seen_this_game: Vec<&str> = Vec::new();
+
+        for turn in &stats.turns {
+            let row = agents.entry(turn.player.clone()).or_insert_with(|| AgentReliability {
+                agent: turn.player.clone(),
+                ..Default::default()
+            });
+            row.turns += 1;
+            row.total_time_ms += turn.time_taken_ms;
+            if !turn.move_valid {
+                row.invalid_moves += 1;
+            }
+            if turn.is_format_error() {
+                row.format_errors += 1;
+            }
+            if let Some(consistent) = turn.replay_consistent {
+                row.replayed_turns += 1;
+                if !consistent {
+                    row.replay_mismatches += 1;
+                }
+            }
+
+            if !seen_this_game.contains(&turn.player.as_str()) {
+                seen_this_game.push(&turn.player);
+                row.games += 1;
+                // Winners are recorded as "<agent> (<seat>)"
+                if result
+                    .winner()
+                    .is_some_and(|w| w.strip_prefix(turn.player.as_str()).is_some_and(|rest| rest.starts_with(" (")))
+                {
+                    row.wins += 1;
+                }
+            }
+        }
+    }
+
+    agents.into_values().collect()
+}
+
+/// Share of a matchup's games in which neither side made an invalid move
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize)]
+pub struct MatchupCleanRate {
+    /// Players in seat order, e.g. `OpenAI_1 vs Ollama_2`
+    pub matchup: String,
+    pub games: u32,
+    pub clean_games: u32,
+}
+
+impl MatchupCleanRate {
+    pub fn clean_rate(&self) -> f64 {
+        ratio(self.clean_games as u64, self.games as u64)
+    }
+}
+
+/// Clean-game rate per matchup, sorted by matchup
+pub fn clean_rate_rows(results: &[TestResult]) -> Vec<MatchupCleanRate> {
+    let mut matchups: BTreeMap<String, MatchupCleanRate> = BTreeMap::new();
+
+    for result in results {
+        let stats = result.stats();
+        let mut players: Vec<&str> = Vec::new();
+        for turn in &stats.turns {
+            if !players.contains(&turn.player.as_str()) {
+                players.push(&turn.player);
+            }
+        }
+        let matchup = players.join(" vs ");
+
+        let row = matchups.entry(matchup.clone()).or_insert_with(|| MatchupCleanRate {
+            matchup,
+            ..Default::default()
+        });
+        row.games += 1;
+        if stats.is_clean_game() {
+            row.clean_games += 1;
+        }
+    }
+
+    matchups.into_values().collect()
+}
+
+/// Batch-level table of clean-game rates per matchup
+pub fn clean_rate_report(results: &[TestResult]) -> Table {
+    #[derive(Tabled)]
+    struct CleanRateRow {
+        #[tabled(rename = "Matchup")]
+        matchup: String,
+        #[tabled(rename = "Games")]
+        games: String,
+        #[tabled(rename = "Clean Games")]
+        clean: String,
+    }
+
+    let rows: Vec<CleanRateRow> = clean_rate_rows(results)
+        .iter()
+        .map(|r| CleanRateRow {
+            matchup: r.matchup.clone(),
+            games: r.games.to_string(),
+            clean: format!("{} ({:.1}%)", r.clean_games, r.clean_rate() * 100.0),
+        })
+        .collect();
+
+    let mut table = Table::new(rows);
+    table
+        .with(Style::rounded())
+        .with(Modify::new(Rows::new(1..)).with(Alignment::left()));
+    table
+}
+
+#[derive(Tabled)]
+struct ReliabilityRow {
+    #[tabled(rename = "Agent")]
+    agent: String,
+    #[tabled(rename = "Games")]
+    games: String,
+    #[tabled(rename = "Win Rate")]
+    win_rate: String,
+    #[tabled(rename = "Invalid Moves")]
+    invalid_rate: String,
+    #[tabled(rename = "Format OK")]
+    format_compliance: String,
+    #[tabled(rename = "Avg Latency (ms)")]
+    latency: String,
+    #[tabled(rename = "Temp0 Stable")]
+    temp0_stability: String,
+}
+
+/// Batch-level dashboard of per-agent reliability
+pub fn reliability_report(results: &[TestResult]) -> Table {
+    let rows: Vec<ReliabilityRow> = reliability_rows(results)
+        .iter()
+        .map(|r| ReliabilityRow {
+            agent: r.agent.clone(),
+            games: r.games.to_string(),
+            win_rate: format!("{:.1}%", r.win_rate() * 100.0),
+            invalid_rate: format!("{} ({:.1}%)", r.invalid_moves, r.invalid_move_rate() * 100.0),
+            format_compliance: format!("{:.1}%", r.format_compliance() * 100.0),
+            latency: format!("{:.2}", r.average_latency_ms()),
+            temp0_stability: r
+                .temp0_stability()
+                .map_or_else(|| "-".to_string(), |s| format!("{:.1}%", s * 100.0)),
+        })
+        .collect();
+
+    let mut table = Table::new(rows);
+    table
+        .with(Style::rounded())
+        .with(Modify::new(Rows::new(1..)).with(Alignment::left()));
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::stats::{GameStats, TurnStats};
+    use crate::games::TicTacToeResult;
+    use serde_json::json;
+
+    fn turn(player: &str, time_taken_ms: u64, error: Option<&str>) -> TurnStats {
+        TurnStats {
+            player: player.to_string(),
+            move_made: json!({}),
+            time_taken_ms,
+            api_latency_ms: time_taken_ms,
+            move_valid: error.is_none(),
+            error_message: error.map(|e| e.to_string()),
+            state_before: json!({}),
+            state_after: json!({}),
+            ..Default::default()
+        }
+    }
+
+    fn game(winner: Option<&str>, turns: Vec<TurnStats>) -> TestResult {
+        let mut stats = GameStats::new();
+        for t in turns {
+            stats.add_turn(t);
+        }
+        TestResult::TicTacToe(TicTacToeResult {
+            winner: winner.map(|w| w.to_string()),
+            stats,
+            error: None,
+        })
+    }
+
+    #[test]
+    fn test_reliability_rows_from_synthetic_results() {
+        let results = vec![
+            game(
+                Some("OpenAI_1 (X)"),
+                vec![
+                    turn("OpenAI_1", 100, None),
+                    turn("Ollama_2", 300, Some("Missing or invalid 'row' field")),
+                    turn("Ollama_2", 300, None),
+                    turn("OpenAI_1", 100, None),
+                ],
+            ),
+            game(
+                Some("Ollama_2 (O)"),
+                vec![
+                    turn("OpenAI_1", 200, Some("Invalid move: row=0, col=0")),
+                    turn("Ollama_2", 200, None),
+                ],
+            ),
+        ];
+
+        let rows = reliability_rows(&results);
+        assert_eq!(rows.len(), 2);
+
+        let ollama = &rows[0];
+        assert_eq!(ollama.agent, "Ollama_2");
+        assert_eq!((ollama.games, ollama.wins, ollama.turns), (2, 1, 3));
+        assert_eq!(ollama.invalid_moves, 1);
+        assert_eq!(ollama.format_errors, 1);
+        assert!((ollama.format_compliance() - 2.0 / 3.0).abs() < 1e-9);
+
+        let openai = &rows[1];
+        assert_eq!(openai.agent, "OpenAI_1");
+        assert_eq!((openai.games, openai.wins, openai.turns), (2, 1, 3));
+        assert_eq!(openai.invalid_moves, 1);
+        assert_eq!(openai.format_errors, 0);
+        assert_eq!(openai.format_compliance(), 1.0);
+        assert!((openai.average_latency_ms() - 400.0 / 3.0).abs() < 1e-9);
+        assert_eq!(openai.win_rate(), 0.5);
+    }
+
+    #[test]
+    fn test_clean_rate_per_matchup() {
+        let results = vec![
+            game(None, vec![turn("A", 1, None), turn("B", 1, None)]),
+            game(None, vec![turn("A", 1, None), turn("B", 1, Some("Invalid move"))]),
+            game(None, vec![turn("B", 1, None), turn("A", 1, None)]),
+        ];
+
+        let rows = clean_rate_rows(&results);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].matchup, "A vs B");
+        assert_eq!((rows[0].games, rows[0].clean_games), (2, 1));
+        assert_eq!(rows[0].clean_rate(), 0.5);
+        assert_eq!(rows[1].matchup, "B vs A");
+        assert_eq!(rows[1].clean_rate(), 1.0);
+    }
+
+    #[test]
+    fn test_winner_prefix_does_not_match_longer_names() {
+        let results = vec![game(Some("OpenAI_10 (X)"), vec![turn("OpenAI_1", 1, None), turn("OpenAI_10", 1, None)])];
+        let rows = reliability_rows(&results);
+        assert_eq!(rows.iter().find(|r| r.agent == "OpenAI_1").unwrap().wins, 0);
+        assert_eq!(rows.iter().find(|r| r.agent == "OpenAI_10").unwrap().wins, 1);
+    }
+
+    #[test]
+    fn test_report_renders_a_row_per_agent() {
+        let results = vec![game(None, vec![turn("A", 10, None), turn("B", 20, None)])];
+        let rendered = reliability_report(&results).to_string();
+        assert!(rendered.contains("Format OK"));
+        assert!(rendered.contains("A") && rendered.contains("B"));
+    }
+}
@@ -0,0 +1,541 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::agent::AIAgent;
+use crate::games::parsing::u32_field;
+use crate::games::display::{BoardRepresentation, render_board};
+use crate::games::engine::{
+    self, GameEngine, TurnBasedGame, TurnRules, with_reasoning,
+};
+use crate::games::judge::Outcome;
+use crate::games::passes::{DEFAULT_MAX_CONSECUTIVE_PASSES, PassCounter, default_max_consecutive_passes};
+use crate::games::stats::GameStats;
+use crate::games::transcript::TranscriptWriter;
+
+pub const BOARD_SIZE: usize = 8;
+
+/// The eight directions a line of flipped pieces can run in
+const DIRECTIONS: [(isize, isize); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReversiConfig {
+    /// Whether agents see the board as a matrix, ASCII text, or both
+    #[serde(default)]
+    pub board_representation: BoardRepresentation,
+    /// `max_retries`, `move_timeout_ms` and `max_invalid_moves`, set at the
+    /// top level of the config
+    #[serde(flatten)]
+    pub rules: TurnRules,
+    /// Passes in a row that end the game, scored by piece count as usual.
+    /// The default of 2 ends it once neither player can move.
+    #[serde(default = "default_max_consecutive_passes")]
+    pub max_consecutive_passes: u32,
+}
+
+impl Default for ReversiConfig {
+    fn default() -> Self {
+        ReversiConfig {
+            board_representation: BoardRepresentation::default(),
+            rules: TurnRules::default(),
+            max_consecutive_passes: DEFAULT_MAX_CONSECUTIVE_PASSES,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReversiState {
+    pub board: Vec<Vec<Option<Player>>>,
+    pub current_player: Player,
+    pub turn_number: u32,
+    /// The other player had no legal move, so the current player moves again
+    pub opponent_passed: bool,
+    pub game_over: bool,
+    pub winner: Option<Player>,
+}
+
+/// Black moves first and always belongs to the first agent
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Player {
+    Black,
+    White,
+}
+
+impl Player {
+    fn other(&self) -> Player {
+        match self {
+            Player::Black => Player::White,
+            Player::White => Player::Black,
+        }
+    }
+}
+
+impl std::fmt::Display for Player {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Player::Black => write!(f, "Black"),
+            Player::White => write!(f, "White"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReversiMove {
+    pub row: u32,
+    pub col: u32,
+}
+
+#[derive(Debug)]
+pub struct Reversi {
+    config: ReversiConfig,
+    state: ReversiState,
+    game_id: String,
+    passes: PassCounter,
+    /// Where each turn is logged as it is played
+    transcript: Option<TranscriptWriter>,
+}
+
+impl Reversi {
+    pub fn new(config: ReversiConfig) -> Self {
+        let mut board = vec![vec![None; BOARD_SIZE]; BOARD_SIZE];
+        let mid = BOARD_SIZE / 2;
+        board[mid - 1][mid - 1] = Some(Player::White);
+        board[mid][mid] = Some(Player::White);
+        board[mid - 1][mid] = Some(Player::Black);
+        board[mid][mid - 1] = Some(Player::Black);
+
+        Self {
+            passes: PassCounter::new(config.max_consecutive_passes),
+            config,
+            state: ReversiState {
+                board,
+                current_player: Player::Black,
+                turn_number: 0,
+                opponent_passed: false,
+                game_over: false,
+                winner: None,
+            },
+            game_id: format!("reversi_{}", &uuid::Uuid::new_v4().to_string()[..8]),
+            transcript: None,
+        }
+    }
+
+    /// Log every turn to `transcript` as the game is played
+    pub fn with_transcript(mut self, transcript: Option<TranscriptWriter>) -> Self {
+        self.transcript = transcript;
+        self
+    }
+
+    pub async fn play_game(mut self, agents: Vec<AIAgent>) -> ReversiResult {
+        let transcript = self.transcript.take();
+        match engine::play(&mut self, &agents, transcript.as_ref()).await {
+            Ok(stats) => ReversiResult {
+                winner: stats.winner.clone(),
+                stats,
+                error: None,
+            },
+            Err(aborted) => ReversiResult {
+                winner: None,
+                stats: aborted.stats,
+                error: Some(aborted.error),
+            },
+        }
+    }
+
+    fn parse_move(move_data: &Value) -> Result<ReversiMove, String> {
+        Ok(ReversiMove {
+            row: u32_field(move_data, "row")?,
+            col: u32_field(move_data, "col")?,
+        })
+    }
+
+    /// Opponent pieces `player` would flip by playing the empty cell at
+    /// (`row`, `col`); empty if the move is illegal
+    fn flips(&self, player: Player, row: usize, col: usize) -> Vec<(usize, usize)> {
+        let board = &self.state.board;
+        if board[row][col].is_some() {
+            return Vec::new();
+        }
+        let mut flips = Vec::new();
+        for (dr, dc) in DIRECTIONS {
+            let mut line = Vec::new();
+            let (mut r, mut c) = (row as isize + dr, col as isize + dc);
+            while (0..BOARD_SIZE as isize).contains(&r) && (0..BOARD_SIZE as isize).contains(&c) {
+                match board[r as usize][c as usize] {
+                    Some(piece) if piece == player => {
+                        flips.extend(line);
+                        break;
+                    }
+                    Some(_) => line.push((r as usize, c as usize)),
+                    None => break,
+                }
+                r += dr;
+                c += dc;
+            }
+        }
+        flips
+    }
+
+    /// Cells `player` may play, in row-major order
+    pub fn legal_moves_for(&self, player: Player) -> Vec<ReversiMove> {
+        (0..BOARD_SIZE)
+            .flat_map(|row| (0..BOARD_SIZE).map(move |col| (row, col)))
+            .filter(|&(row, col)| !self.flips(player, row, col).is_empty())
+            .map(|(row, col)| ReversiMove {
+                row: row as u32,
+                col: col as u32,
+            })
+            .collect()
+    }
+
+    fn has_legal_move(&self, player: Player) -> bool {
+        !self.legal_moves_for(player).is_empty()
+    }
+
+    fn count(&self, player: Player) -> usize {
+        self.state.board.iter().flatten().filter(|&&cell| cell == Some(player)).count()
+    }
+
+    fn state_to_json(&self) -> Value {
+        let board: Vec<Vec<Option<String>>> = self
+            .state
+            .board
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.map(|p| p.to_string())).collect())
+            .collect();
+
+        let mut state = json!({
+            "current_player": self.state.current_player.to_string(),
+            "turn_number": self.state.turn_number,
+            "opponent_passed": self.state.opponent_passed,
+            "game_over": self.state.game_over,
+            "winner": self.state.winner.map(|p| p.to_string()),
+            "black_count": self.count(Player::Black),
+            "white_count": self.count(Player::White),
+            "rules": "Place a piece so that it and another of your pieces flank one or more straight \
+                      (horizontal, vertical or diagonal) lines of opponent pieces; every flanked piece \
+                      is flipped to your color. A player with no such move passes. When neither player \
+                      can move, the one with more pieces wins.",
+        });
+
+        let representation = self.config.board_representation;
+        if representation.includes_text() {
+            state["board_text"] = json!(render_board(&board));
+        }
+        if representation.includes_matrix() {
+            state["board"] = json!(board);
+        }
+        state
+    }
+}
+
+impl GameEngine for Reversi {
+    type Move = ReversiMove;
+
+    fn game_id(&self) -> &str {
+        &self.game_id
+    }
+
+    fn move_schema(&self) -> Value {
+        with_reasoning(json!({
+            "type": "object",
+            "properties": {
+                "row": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "maximum": BOARD_SIZE - 1,
+                    "description": "Row index (0-indexed)"
+                },
+                "col": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "maximum": BOARD_SIZE - 1,
+                    "description": "Column index (0-indexed)"
+                }
+            },
+            "required": ["row", "col"]
+        }))
+    }
+
+    fn state_json(&self) -> Value {
+        self.state_to_json()
+    }
+
+    fn parse_move(&self, move_data: &Value) -> Result<ReversiMove, String> {
+        Reversi::parse_move(move_data)
+    }
+
+    fn legal_moves(&self) -> Option<Value> {
+        Some(json!(self.legal_moves_for(self.state.current_player)))
+    }
+}
+
+impl TurnBasedGame for Reversi {
+    const SEATS: [&'static str; 2] = ["Black", "White"];
+
+    fn rules(&self) -> TurnRules {
+        self.config.rules
+    }
+
+    /// Every valid move fills one of the 60 empty cells; allow as many
+    /// invalid ones
+    fn max_turns(&self) -> u32 {
+        ((BOARD_SIZE * BOARD_SIZE - 4) * 2) as u32
+    }
+
+    fn turn_number(&self) -> u32 {
+        self.state.turn_number
+    }
+
+    fn set_turn_number(&mut self, turn_number: u32) {
+        self.state.turn_number = turn_number;
+    }
+
+    fn current_seat(&self) -> usize {
+        match self.state.current_player {
+            Player::Black => 0,
+            Player::White => 1,
+        }
+    }
+
+    /// A player with no legal move passes, and the mover goes again
+    fn next_player(&mut self) {
+        let next = self.state.current_player.other();
+        self.state.opponent_passed = !self.has_legal_move(next);
+        if !self.state.opponent_passed {
+            self.state.current_player = next;
+        }
+    }
+
+    fn notation(&self, mv: &ReversiMove) -> String {
+        format!("{},{}", mv.row, mv.col)
+    }
+
+    fn apply_move(&mut self, ReversiMove { row, col }: ReversiMove) -> Result<(), String> {
+        let (r, c) = (row as usize, col as usize);
+        if r >= BOARD_SIZE || c >= BOARD_SIZE {
+            return Err(format!("Invalid move: row={}, col={} is off the board", row, col));
+        }
+        if self.state.board[r][c].is_some() {
+            return Err(format!("Invalid move: row={}, col={} is already taken", row, col));
+        }
+        let player = self.state.current_player;
+        let flips = self.flips(player, r, c);
+        if flips.is_empty() {
+            return Err(format!(
+                "Invalid move: row={}, col={} doesn't flank any {} pieces",
+                row,
+                col,
+                player.other()
+            ));
+        }
+        self.state.board[r][c] = Some(player);
+        for (fr, fc) in flips {
+            self.state.board[fr][fc] = Some(player);
+        }
+        // Count the passes this move forces: the opponent's, then the mover's
+        self.passes.record(false);
+        if !self.has_legal_move(player.other()) {
+            self.passes.record(true);
+            if !self.has_legal_move(player) {
+                self.passes.record(true);
+            }
+        }
+        Ok(())
+    }
+
+    /// Over once neither player can move, or after `max_consecutive_passes`
+    /// passes in a row; the one with more pieces wins
+    fn is_over(&self) -> Option<Outcome> {
+        if !self.passes.limit_reached() && (self.has_legal_move(Player::Black) || self.has_legal_move(Player::White)) {
+            return None;
+        }
+        let (black, white) = (self.count(Player::Black), self.count(Player::White));
+        Some(match black.cmp(&white) {
+            std::cmp::Ordering::Greater => Outcome::Winner(0),
+            std::cmp::Ordering::Less => Outcome::Winner(1),
+            std::cmp::Ordering::Equal => Outcome::Draw,
+        })
+    }
+
+    fn finish(&mut self, winner: Option<usize>) {
+        self.state.game_over = true;
+        self.state.winner = winner.map(|seat| [Player::Black, Player::White][seat]);
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReversiResult {
+    pub winner: Option<String>,
+    pub stats: GameStats,
+    pub error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::scripted::ScriptedAgent;
+
+    fn at(row: u32, col: u32) -> ReversiMove {
+        ReversiMove { row, col }
+    }
+
+    /// An empty board with the given pieces, Black to move
+    fn with_pieces(pieces: &[(usize, usize, Player)]) -> Reversi {
+        let mut game = Reversi::new(ReversiConfig::default());
+        game.state.board = vec![vec![None; BOARD_SIZE]; BOARD_SIZE];
+        for &(row, col, player) in pieces {
+            game.state.board[row][col] = Some(player);
+        }
+        game
+    }
+
+    #[test]
+    fn test_new_game() {
+        let game = Reversi::new(ReversiConfig::default());
+        assert_eq!(game.state.board[3][3], Some(Player::White));
+        assert_eq!(game.state.board[4][4], Some(Player::White));
+        assert_eq!(game.state.board[3][4], Some(Player::Black));
+        assert_eq!(game.state.board[4][3], Some(Player::Black));
+        assert_eq!((game.count(Player::Black), game.count(Player::White)), (2, 2));
+        assert_eq!(game.state.current_player, Player::Black);
+        assert!(game.game_id.starts_with("reversi_"));
+    }
+
+    #[test]
+    fn test_opening_legal_moves() {
+        let game = Reversi::new(ReversiConfig::default());
+        assert_eq!(game.legal_moves_for(Player::Black), vec![at(2, 3), at(3, 2), at(4, 5), at(5, 4)]);
+        assert_eq!(game.legal_moves_for(Player::White), vec![at(2, 4), at(3, 5), at(4, 2), at(5, 3)]);
+    }
+
+    #[test]
+    fn test_move_flips_one_direction() {
+        let mut game = Reversi::new(ReversiConfig::default());
+        game.apply_move(at(2, 3)).unwrap();
+        assert_eq!(game.state.board[2][3], Some(Player::Black));
+        assert_eq!(game.state.board[3][3], Some(Player::Black));
+        // Nothing else changed
+        assert_eq!(game.state.board[4][4], Some(Player::White));
+        assert_eq!((game.count(Player::Black), game.count(Player::White)), (4, 1));
+    }
+
+    #[test]
+    fn test_move_flips_every_flanked_line() {
+        // Black at the center flanks a row, a column and a diagonal of two
+        let mut game = with_pieces(&[
+            (4, 1, Player::Black),
+            (4, 2, Player::White),
+            (4, 3, Player::White),
+            (1, 4, Player::Black),
+            (2, 4, Player::White),
+            (3, 4, Player::White),
+            (7, 7, Player::Black),
+            (6, 6, Player::White),
+            (5, 5, Player::White),
+            // Not flanked: the line runs into an empty cell
+            (4, 5, Player::White),
+        ]);
+        game.apply_move(at(4, 4)).unwrap();
+        assert_eq!((game.count(Player::Black), game.count(Player::White)), (10, 1));
+        assert_eq!(game.state.board[4][5], Some(Player::White));
+    }
+
+    #[test]
+    fn test_rejects_moves_that_flip_nothing() {
+        let mut game = Reversi::new(ReversiConfig::default());
+        assert_eq!(
+            game.apply_move(at(0, 0)).unwrap_err(),
+            "Invalid move: row=0, col=0 doesn't flank any White pieces"
+        );
+        assert_eq!(game.apply_move(at(3, 3)).unwrap_err(), "Invalid move: row=3, col=3 is already taken");
+        assert_eq!(game.apply_move(at(8, 0)).unwrap_err(), "Invalid move: row=8, col=0 is off the board");
+        assert_eq!((game.count(Player::Black), game.count(Player::White)), (2, 2));
+    }
+
+    #[test]
+    fn test_player_without_moves_passes() {
+        // White's only piece can't flank anything; Black can still take it
+        let mut game = with_pieces(&[(0, 0, Player::Black), (0, 1, Player::White), (7, 7, Player::Black)]);
+        assert!(game.legal_moves_for(Player::White).is_empty());
+        game.next_player();
+        assert_eq!(game.state.current_player, Player::Black);
+        assert!(game.state.opponent_passed);
+    }
+
+    /// Black moves 5,7 and White is left without a move; Black then moves
+    /// 3,2, which gives White moves again
+    fn pass_then_move(max_consecutive_passes: u32) -> Reversi {
+        let mut game = with_pieces(&[
+            (3, 0, Player::Black),
+            (3, 1, Player::White),
+            (2, 2, Player::White),
+            (6, 7, Player::White),
+            (7, 7, Player::Black),
+        ]);
+        game.config.max_consecutive_passes = max_consecutive_passes;
+        game.passes = PassCounter::new(max_consecutive_passes);
+        game
+    }
+
+    #[test]
+    fn test_two_passes_end_the_game() {
+        // After 0,2 White has no pieces left and Black has nothing to flank
+        let mut game = with_pieces(&[(0, 0, Player::Black), (0, 1, Player::White), (7, 7, Player::Black)]);
+        game.apply_move(at(0, 2)).unwrap();
+        assert_eq!(game.passes.consecutive(), 2);
+        assert_eq!(game.is_over(), Some(Outcome::Winner(0)));
+    }
+
+    #[test]
+    fn test_pass_then_move_resets_the_count() {
+        let mut game = pass_then_move(DEFAULT_MAX_CONSECUTIVE_PASSES);
+        game.apply_move(at(5, 7)).unwrap();
+        assert_eq!(game.passes.consecutive(), 1);
+        assert_eq!(game.is_over(), None);
+        game.next_player();
+        assert_eq!(game.state.current_player, Player::Black);
+
+        game.apply_move(at(3, 2)).unwrap();
+        assert_eq!(game.passes.consecutive(), 0);
+        assert_eq!(game.is_over(), None);
+        game.next_player();
+        assert_eq!(game.legal_moves_for(Player::White), vec![at(4, 0), at(4, 2)]);
+        assert_eq!(game.state.current_player, Player::White);
+    }
+
+    #[test]
+    fn test_pass_limit_ends_the_game_early() {
+        // Black could still move, but one pass is the limit
+        let mut game = pass_then_move(1);
+        game.apply_move(at(5, 7)).unwrap();
+        assert!(game.has_legal_move(Player::Black));
+        assert_eq!(game.is_over(), Some(Outcome::Winner(0)));
+    }
+
+    #[test]
+    fn test_game_over_scores_by_piece_count() {
+        let game = with_pieces(&[(0, 0, Player::Black), (0, 1, Player::Black), (7, 7, Player::White)]);
+        assert_eq!(game.is_over(), Some(Outcome::Winner(0)));
+        let game = with_pieces(&[(0, 0, Player::Black), (7, 7, Player::White)]);
+        assert_eq!(game.is_over(), Some(Outcome::Draw));
+        assert_eq!(Reversi::new(ReversiConfig::default()).is_over(), None);
+    }
+
+    #[tokio::test]
+    async fn test_full_game() {
+        // The shortest possible game: White is wiped out after nine moves
+        let black = [(2, 3), (2, 1), (0, 4), (6, 3), (3, 5)];
+        let white = [(2, 2), (1, 3), (5, 3), (2, 4)];
+        let moves = |cells: &[(u32, u32)]| cells.iter().map(|(row, col)| json!({"row": row, "col": col})).collect();
+        let agents = vec![
+            AIAgent::Scripted(ScriptedAgent::new("black", moves(&black))),
+            AIAgent::Scripted(ScriptedAgent::new("white", moves(&white))),
+        ];
+        let result = Reversi::new(ReversiConfig::default()).play_game(agents).await;
+
+        assert_eq!(result.error, None);
+        assert_eq!(result.winner.as_deref(), Some("black (Black)"));
+        assert_eq!(result.stats.total_turns(), 9);
+        assert_eq!(result.stats.invalid_moves, 0);
+    }
+}
@@ -0,0 +1,28 @@
+/// Small deterministic generator for seeded choices (shuffles, random
+/// baselines); not for anything security-related
+#[derive(Clone, Copy, Debug)]
+pub struct SeededRng(u64);
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        // xorshift gets stuck on zero
+        SeededRng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    /// Uniform-ish value in `0..bound` (0 when `bound` is 0)
+    pub fn next_below(&mut self, bound: usize) -> usize {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        (x % bound.max(1) as u64) as usize
+    }
+
+    /// Fisher-Yates shuffle
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.next_below(i + 1));
+        }
+    }
+}
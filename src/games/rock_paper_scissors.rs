@@ -3,16 +3,34 @@ use serde_json::{json, Value};
 use std::time::Instant;
 
 use crate::agent::{AIAgent, MoveRequest, MoveResponse};
-use crate::games::stats::{GameStats, TurnStats};
+use crate::games::engine::{
+    GameEngine, TurnRules, check_player_count, take_reasoning, with_reasoning,
+};
+use crate::games::parsing::missing_field_error;
+use crate::games::stats::{FORFEIT_NOTE, GameStats, TurnStats};
+use crate::games::simultaneous::query_agents;
+use crate::games::transcript::TranscriptWriter;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RockPaperScissorsConfig {
     pub rounds: u32,
+    /// `move_timeout_ms` and `max_invalid_moves`, set at the top level; a
+    /// round is never replayed, so `max_retries` has no effect
+    #[serde(flatten)]
+    pub rules: TurnRules,
+    /// Stop as soon as one player leads by more than the rounds left, rather
+    /// than only once someone has won a majority of rounds
+    #[serde(default)]
+    pub end_when_clinched: bool,
 }
 
 impl Default for RockPaperScissorsConfig {
     fn default() -> Self {
-        RockPaperScissorsConfig { rounds: 3 }
+        RockPaperScissorsConfig {
+            rounds: 3,
+            rules: TurnRules::default(),
+            end_when_clinched: false,
+        }
     }
 }
 
@@ -24,7 +42,7 @@ pub enum Choice {
 }
 
 impl Choice {
-    fn as_str(&self) -> &str {
+    fn as_str(self) -> &'static str {
         match self {
             Choice::Rock => "rock",
             Choice::Paper => "paper",
@@ -32,8 +50,44 @@ impl Choice {
         }
     }
 
-    fn to_string(&self) -> String {
-        self.as_str().to_string()
+    /// Read a choice from free text: the name, a common synonym or
+    /// singular, or the hand emoji, ignoring case, punctuation and filler
+    /// words ("I choose rock!"). `None` when the text names no choice or
+    /// more than one.
+    fn from_text(text: &str) -> Option<Choice> {
+        let mut found = None;
+        for word in text.split(|c: char| !c.is_alphanumeric() && Self::from_emoji(c).is_none()) {
+            let mut chars = word.chars();
+            let choice = match (chars.next().and_then(Self::from_emoji), chars.next()) {
+                (Some(choice), None) => Some(choice),
+                _ => Self::from_word(&word.to_lowercase()),
+            };
+            match (found, choice) {
+                (_, None) => {}
+                (None, Some(choice)) => found = Some(choice),
+                (Some(seen), Some(choice)) if seen != choice => return None,
+                _ => {}
+            }
+        }
+        found
+    }
+
+    fn from_word(word: &str) -> Option<Choice> {
+        match word {
+            "rock" | "rocks" | "stone" | "fist" => Some(Choice::Rock),
+            "paper" | "papers" | "sheet" => Some(Choice::Paper),
+            "scissors" | "scissor" | "shears" => Some(Choice::Scissors),
+            _ => None,
+        }
+    }
+
+    fn from_emoji(c: char) -> Option<Choice> {
+        match c {
+            '✊' | '👊' | '🪨' => Some(Choice::Rock),
+            '✋' | '🤚' | '🖐' | '📄' => Some(Choice::Paper),
+            '✌' | '✂' => Some(Choice::Scissors),
+            _ => None,
+        }
     }
 
     fn beats(&self, other: Choice) -> bool {
@@ -46,11 +100,17 @@ impl Choice {
     }
 }
 
+impl std::fmt::Display for Choice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RockPaperScissorsState {
     pub round: u32,
-    pub player_one_score: u32,
-    pub player_two_score: u32,
+    /// Rounds won so far, in seat order
+    pub scores: Vec<u32>,
     pub round_history: Vec<RoundResult>,
     pub game_over: bool,
 }
@@ -58,16 +118,55 @@ pub struct RockPaperScissorsState {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RoundResult {
     pub round_number: u32,
-    pub player_one_choice: Option<Choice>,
-    pub player_two_choice: Option<Choice>,
-    pub winner: Option<usize>, // 0 for player 1, 1 for player 2, None for tie
+    /// Each player's choice in seat order; `None` for an invalid choice or
+    /// a player who has forfeited
+    pub choices: Vec<Option<Choice>>,
+    /// Seats that won the round, empty for a tie
+    pub winners: Vec<usize>,
+}
+
+/// Seats that win a round. With exactly two different valid choices made,
+/// everyone who made the winning one scores; with one, or all three, the
+/// round is a tie.
+fn round_winners(choices: &[Option<Choice>]) -> Vec<usize> {
+    let made: Vec<Choice> = [Choice::Rock, Choice::Paper, Choice::Scissors]
+        .into_iter()
+        .filter(|choice| choices.contains(&Some(*choice)))
+        .collect();
+    let winning = match made.as_slice() {
+        [a, b] if a.beats(*b) => *a,
+        [_, b] => *b,
+        _ => return Vec::new(),
+    };
+    (0..choices.len()).filter(|&seat| choices[seat] == Some(winning)).collect()
+}
+
+/// Rounds won per seat in a recorded state, also reading results saved
+/// before games could seat more than two players
+pub fn scores_in_state(state: &Value) -> Vec<u64> {
+    match state.get("scores").and_then(Value::as_array) {
+        Some(scores) => scores.iter().filter_map(Value::as_u64).collect(),
+        None => ["player_one_score", "player_two_score"]
+            .iter()
+            .filter_map(|key| state.get(key).and_then(Value::as_u64))
+            .collect(),
+    }
+}
+
+/// Seat name used in recorded winners and errors
+fn seat_label(seat: usize) -> String {
+    format!("Player {}", seat + 1)
 }
 
 pub struct RockPaperScissors {
     config: RockPaperScissorsConfig,
     state: RockPaperScissorsState,
     stats: GameStats,
+    /// Invalid choices so far per player, for `max_invalid_moves`
+    invalid_moves: Vec<u32>,
     game_id: String,
+    /// Where each turn is logged as it is played
+    transcript: Option<TranscriptWriter>,
 }
 
 impl RockPaperScissors {
@@ -76,30 +175,38 @@ impl RockPaperScissors {
             config,
             state: RockPaperScissorsState {
                 round: 0,
-                player_one_score: 0,
-                player_two_score: 0,
+                scores: vec![0; 2],
                 round_history: Vec::new(),
                 game_over: false,
             },
             stats: GameStats::new(),
-            game_id: format!("rps_{}", uuid::Uuid::new_v4().to_string()[..8].to_string()),
+            invalid_moves: vec![0; 2],
+            game_id: format!("rps_{}", &uuid::Uuid::new_v4().to_string()[..8]),
+            transcript: None,
         }
     }
 
+    /// Log every turn to `transcript` as the game is played
+    pub fn with_transcript(mut self, transcript: Option<TranscriptWriter>) -> Self {
+        self.transcript = transcript;
+        self
+    }
+
     pub async fn play_game(mut self, agents: Vec<AIAgent>) -> RockPaperScissorsResult {
         let start_time = Instant::now();
 
-        // Ensure we have exactly 2 agents
-        if agents.len() != 2 {
+        if let Err(e) = check_player_count(agents.len(), Self::MIN_PLAYERS, Self::MAX_PLAYERS) {
             return RockPaperScissorsResult {
                 winner: None,
                 stats: self.stats,
-                error: Some(format!("Expected 2 agents, got {}", agents.len())),
+                error: Some(e),
             };
         }
 
-        let player_one_agent = &agents[0];
-        let player_two_agent = &agents[1];
+        self.state.scores = vec![0; agents.len()];
+        self.invalid_moves = vec![0; agents.len()];
+        self.stats.seats = agents.iter().map(|agent| agent.name().to_string()).collect();
+        self.stats.game_id = Some(self.game_id.clone());
 
         // Play rounds until someone wins or we run out of rounds
         let rounds_to_win = (self.config.rounds / 2) + 1;
@@ -107,50 +214,69 @@ impl RockPaperScissors {
         while !self.state.game_over && self.state.round < self.config.rounds {
             self.state.round += 1;
 
-            // Execute round - both players choose simultaneously
-            let round_result = match self.execute_round(player_one_agent, player_two_agent).await {
+            // Execute round - all players choose simultaneously
+            let round_result = match self.execute_round(&agents).await {
                 Ok(result) => result,
                 Err(e) => {
-                    eprintln!("Round error: {}", e);
+                    tracing::warn!("Round error: {}", e);
                     // Continue with a tie if there's an error
                     RoundResult {
                         round_number: self.state.round,
-                        player_one_choice: None,
-                        player_two_choice: None,
-                        winner: None,
+                        choices: vec![None; agents.len()],
+                        winners: Vec::new(),
                     }
                 }
             };
 
             // Update scores
-            match round_result.winner {
-                Some(0) => self.state.player_one_score += 1,
-                Some(1) => self.state.player_two_score += 1,
-                _ => {} // Tie, no score change
+            for &seat in &round_result.winners {
+                self.state.scores[seat] += 1;
             }
 
             self.state.round_history.push(round_result.clone());
 
-            // Check for game end
-            if self.state.player_one_score >= rounds_to_win {
+            // A player over `max_invalid_moves` forfeits and sits out the
+            // rest of the game. Once one player is left they win; if the
+            // last ones go over in the same round, nobody does.
+            let in_play = self.seats_in_play();
+            if in_play.len() < 2 {
                 self.state.game_over = true;
-                self.stats.winner = Some(format!("{} (Player 1)", player_one_agent.name()));
+                match in_play.as_slice() {
+                    [seat] => {
+                        self.stats.winner =
+                            Some(format!("{} ({}){}", agents[*seat].name(), seat_label(*seat), FORFEIT_NOTE));
+                    }
+                    _ => self.stats.draw = true,
+                }
                 break;
-            } else if self.state.player_two_score >= rounds_to_win {
+            }
+
+            // Check for game end
+            let lead = self.lead();
+            if let Some((seat, _)) = lead
+                && self.state.scores[seat] >= rounds_to_win
+            {
                 self.state.game_over = true;
-                self.stats.winner = Some(format!("{} (Player 2)", player_two_agent.name()));
+                self.stats.winner = Some(format!("{} ({})", agents[seat].name(), seat_label(seat)));
+                break;
+            }
+
+            // Ties can leave a lead the remaining rounds can't overturn;
+            // the scores below then decide the game
+            let remaining = self.config.rounds - self.state.round;
+            if self.config.end_when_clinched && lead.is_some_and(|(_, margin)| margin > remaining) {
+                tracing::debug!("Decided after round {} of {}", self.state.round, self.config.rounds);
                 break;
             }
         }
 
         // If game ended without a clear winner (all rounds played, tie)
         if !self.state.game_over {
-            if self.state.player_one_score > self.state.player_two_score {
-                self.stats.winner = Some(format!("{} (Player 1)", player_one_agent.name()));
-            } else if self.state.player_two_score > self.state.player_one_score {
-                self.stats.winner = Some(format!("{} (Player 2)", player_two_agent.name()));
-            } else {
-                self.stats.draw = true;
+            match self.lead() {
+                Some((seat, _)) => {
+                    self.stats.winner = Some(format!("{} ({})", agents[seat].name(), seat_label(seat)));
+                }
+                None => self.stats.draw = true,
             }
             self.state.game_over = true;
         }
@@ -165,136 +291,153 @@ impl RockPaperScissors {
         }
     }
 
-    async fn execute_round(
-        &mut self,
-        player_one_agent: &AIAgent,
-        player_two_agent: &AIAgent,
-    ) -> Result<RoundResult, String> {
-        let round_start = Instant::now();
+    /// Seats that haven't forfeited by going over `max_invalid_moves`
+    fn seats_in_play(&self) -> Vec<usize> {
+        (0..self.invalid_moves.len())
+            .filter(|&seat| self.config.rules.max_invalid_moves.is_none_or(|max| self.invalid_moves[seat] <= max))
+            .collect()
+    }
+
+    /// The player still in play with the most rounds won and their margin
+    /// over the next best, or `None` if the top score is shared
+    fn lead(&self) -> Option<(usize, u32)> {
+        let mut seats = self.seats_in_play();
+        seats.sort_by_key(|&seat| std::cmp::Reverse(self.state.scores[seat]));
+        match seats.as_slice() {
+            [first, second, ..] => {
+                let margin = self.state.scores[*first] - self.state.scores[*second];
+                (margin > 0).then_some((*first, margin))
+            }
+            [only] => Some((*only, self.state.scores[*only])),
+            [] => None,
+        }
+    }
 
+    async fn execute_round(&mut self, agents: &[AIAgent]) -> Result<RoundResult, String> {
+        let round_start = Instant::now();
         // Create game state JSON
         let state_json = self.state_to_json();
         let state_before = state_json.clone();
 
         // Create move schema
-        let move_schema = json!({
-            "type": "object",
-            "properties": {
-                "choice": {
-                    "type": "string",
-                    "enum": ["rock", "paper", "scissors"],
-                    "description": "Your choice for this round"
-                }
-            },
-            "required": ["choice"]
-        });
+        let move_schema = self.move_schema();
 
-        // Both players choose simultaneously
+        // Everyone still in play chooses simultaneously
         let turn_number = self.state.round;
+        let seats = self.seats_in_play();
+        let requests: Vec<MoveRequest> = seats
+            .iter()
+            .map(|_| MoveRequest {
+                turn_index: turn_number,
+                game_id: self.game_id.clone(),
+                state: state_json.clone(),
+                expected_move_schema: move_schema.clone(),
+                legal_moves: None,
+            })
+            .collect();
 
-        let move_request_one = MoveRequest {
-            turn_index: turn_number,
-            game_id: self.game_id.clone(),
-            state: state_json.clone(),
-            expected_move_schema: move_schema.clone(),
-        };
-
-        let move_request_two = MoveRequest {
-            turn_index: turn_number,
-            game_id: self.game_id.clone(),
-            state: state_json.clone(),
-            expected_move_schema: move_schema.clone(),
-        };
-
-        // Get moves from both agents (could be parallelized in the future)
-        let move_response_one: MoveResponse = player_one_agent
-            .execute_turn(&move_request_one)
-            .await
-            .map_err(|e| format!("Player 1 error: {}", e))?;
-
-        let move_response_two: MoveResponse = player_two_agent
-            .execute_turn(&move_request_two)
-            .await
-            .map_err(|e| format!("Player 2 error: {}", e))?;
-
-        let time_taken = round_start.elapsed();
+        // Ask every agent at once, timing each on its own so no one's
+        // latency shows up in another's stats
+        let seated: Vec<&AIAgent> = seats.iter().map(|&seat| &agents[seat]).collect();
+        let join_start = Instant::now();
+        let responses = query_agents(&seated, &requests, self.config.rules.move_timeout_ms, seats.len()).await;
+        let join_time = join_start.elapsed();
+        let mut answers = Vec::with_capacity(seats.len());
+        for ((seat, request), (response, time)) in seats.into_iter().zip(requests).zip(responses) {
+            let response: MoveResponse = response
+                .and_then(|response| response.map_err(|e| e.to_string()))
+                .map_err(|e| format!("{} error: {}", seat_label(seat), e))?;
+            answers.push((seat, request, response, None, time));
+        }
 
         // Parse choices
-        let choice_one_result = self.parse_choice(&move_response_one.chosen_move, "Player 1");
-        let choice_two_result = self.parse_choice(&move_response_two.chosen_move, "Player 2");
-        
-        let (choice_one, choice_one_valid, choice_one_error) = match choice_one_result {
-            Ok(Some(c)) => (Some(c), true, None),
-            Ok(None) => (None, false, Some("Invalid choice".to_string())),
-            Err(e) => (None, false, Some(e)),
-        };
-        
-        let (choice_two, choice_two_valid, choice_two_error) = match choice_two_result {
-            Ok(Some(c)) => (Some(c), true, None),
-            Ok(None) => (None, false, Some("Invalid choice".to_string())),
-            Err(e) => (None, false, Some(e)),
-        };
-
-        // Determine winner (only if both choices are valid)
-        let winner = if let (Some(c1), Some(c2)) = (choice_one, choice_two) {
-            if c1.beats(c2) {
-                Some(0) // Player 1 wins
-            } else if c2.beats(c1) {
-                Some(1) // Player 2 wins
-            } else {
-                None // Tie
+        let mut choices = vec![None; agents.len()];
+        let mut parsed = Vec::with_capacity(answers.len());
+        for (seat, _, response, reasoning, _) in &mut answers {
+            *reasoning = take_reasoning(&mut response.chosen_move);
+            let label = seat_label(*seat);
+            let (choice, valid, error) = match self.parse_choice(&response.chosen_move, &label) {
+                Ok(Some(c)) => (Some(c), true, None),
+                Ok(None) => (None, false, Some("Invalid choice".to_string())),
+                Err(e) => (None, false, Some(e)),
+            };
+            if !valid {
+                self.invalid_moves[*seat] += 1;
             }
-        } else {
-            None // Invalid moves result in no winner
-        };
+            tracing::debug!("Round {}: {} chose {}", turn_number, label, choice.map_or("an invalid move", Choice::as_str));
+            if let Some(choice) = choice {
+                self.stats.move_list.push(format!("{}:{}", label, choice.as_str()));
+            }
+            choices[*seat] = choice;
+            parsed.push((valid, error));
+        }
 
-        // Record turn stats for player 1
-        let turn_stats_one = TurnStats {
-            turn_number: turn_number * 2 - 1, // Odd numbers for player 1
-            player: player_one_agent.name().to_string(),
-            move_made: move_response_one.chosen_move.clone(),
-            time_taken_ms: time_taken.as_millis() as u64,
-            move_valid: choice_one_valid,
-            error_message: choice_one_error,
-            state_before: state_before.clone(),
-            state_after: self.state_to_json(),
-            diagnostics: move_response_one.diagnostics,
-        };
-        self.stats.add_turn(turn_stats_one);
-
-        // Record turn stats for player 2
-        let turn_stats_two = TurnStats {
-            turn_number: turn_number * 2, // Even numbers for player 2
-            player: player_two_agent.name().to_string(),
-            move_made: move_response_two.chosen_move.clone(),
-            time_taken_ms: time_taken.as_millis() as u64,
-            move_valid: choice_two_valid,
-            error_message: choice_two_error,
-            state_before: state_before.clone(),
-            state_after: self.state_to_json(),
-            diagnostics: move_response_two.diagnostics,
-        };
-        self.stats.add_turn(turn_stats_two);
+        // Invalid choices never win a round
+        let winners = round_winners(&choices);
+
+        // Players share the round's own work outside the requests
+        let processing_ms = (round_start.elapsed() - join_time).as_millis() as u64;
+
+        // Turns are numbered by seat within the round: 1, 2, ... for the
+        // first round, then on from there
+        let players = agents.len() as u32;
+        for ((seat, request, response, reasoning, time), (valid, error)) in answers.into_iter().zip(parsed) {
+            let turn_stats = TurnStats {
+                turn_number: (turn_number - 1) * players + seat as u32 + 1,
+                player: agents[seat].name().to_string(),
+                move_made: response.chosen_move.clone(),
+                time_taken_ms: time.as_millis() as u64 + processing_ms,
+                api_latency_ms: time.as_millis() as u64,
+                processing_ms,
+                move_valid: valid,
+                error_message: error,
+                state_before: state_before.clone(),
+                state_after: self.state_to_json(),
+                diagnostics: response.diagnostics,
+                perturbation: response.perturbation,
+                replay_consistent: response.replay_consistent,
+                prompt: Some(request.user_prompt()),
+                system_prompt: agents[seat].system_prompt().map(str::to_string),
+                usage: response.usage,
+                served_model: response.served_model,
+                reasoning,
+            };
+            self.record_turn(turn_stats);
+        }
 
         Ok(RoundResult {
             round_number: turn_number,
-            player_one_choice: choice_one,
-            player_two_choice: choice_two,
-            winner,
+            choices,
+            winners,
         })
     }
 
+    fn record_turn(&mut self, turn: TurnStats) {
+        if let Some(transcript) = &self.transcript {
+            transcript.record(&self.game_id, &turn);
+        }
+        self.stats.add_turn(turn);
+    }
+
     fn parse_choice(&self, move_data: &Value, player_name: &str) -> Result<Option<Choice>, String> {
         let choice_str = move_data
             .get("choice")
             .and_then(|v| v.as_str())
-            .ok_or_else(|| format!("{}: Missing or invalid 'choice' field", player_name))?;
+            .ok_or_else(|| format!("{}: {}", player_name, missing_field_error(move_data, "choice")))?;
+
+        // An unrecognized choice is invalid, but doesn't fail the round
+        Ok(Choice::from_text(choice_str))
+    }
 
-        match choice_str.to_lowercase().as_str() {
-            "rock" => Ok(Some(Choice::Rock)),
-            "paper" => Ok(Some(Choice::Paper)),
-            "scissors" => Ok(Some(Choice::Scissors)),
-            _ => Ok(None), // Invalid choice, but don't fail the round
+    /// Request for the current position, used to check an agent's output
+    /// format before committing to a whole game
+    pub fn probe_request(&self) -> MoveRequest {
+        MoveRequest {
+            turn_index: self.state.round,
+            game_id: self.game_id.clone(),
+            state: self.state_to_json(),
+            expected_move_schema: self.move_schema(),
+            legal_moves: self.legal_moves(),
         }
     }
 
@@ -306,17 +449,15 @@ impl RockPaperScissors {
             .map(|r| {
                 json!({
                     "round_number": r.round_number,
-                    "player_one_choice": r.player_one_choice.map(|c| c.to_string()),
-                    "player_two_choice": r.player_two_choice.map(|c| c.to_string()),
-                    "winner": r.winner,
+                    "choices": r.choices.iter().map(|c| c.map(|c| c.to_string())).collect::<Vec<_>>(),
+                    "winners": r.winners,
                 })
             })
             .collect();
 
         json!({
             "round": self.state.round,
-            "player_one_score": self.state.player_one_score,
-            "player_two_score": self.state.player_two_score,
+            "scores": self.state.scores,
             "round_history": round_history,
             "game_over": self.state.game_over,
             "total_rounds": self.config.rounds,
@@ -325,6 +466,41 @@ impl RockPaperScissors {
     }
 }
 
+/// Rounds are simultaneous, so RPS keeps its own round loop rather than
+/// going through `engine::play`
+impl GameEngine for RockPaperScissors {
+    type Move = Choice;
+
+    const MAX_PLAYERS: usize = 8;
+
+    fn game_id(&self) -> &str {
+        &self.game_id
+    }
+
+    fn move_schema(&self) -> Value {
+        with_reasoning(json!({
+            "type": "object",
+            "properties": {
+                "choice": {
+                    "type": "string",
+                    "enum": ["rock", "paper", "scissors"],
+                    "description": "Your choice for this round"
+                }
+            },
+            "required": ["choice"]
+        }))
+    }
+
+    fn state_json(&self) -> Value {
+        self.state_to_json()
+    }
+
+    fn parse_move(&self, move_data: &Value) -> Result<Choice, String> {
+        self.parse_choice(move_data, "Probe")?
+            .ok_or_else(|| "Invalid choice".to_string())
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RockPaperScissorsResult {
     pub winner: Option<String>,
@@ -376,15 +552,14 @@ mod tests {
         let game = RockPaperScissors::new(config);
         
         assert_eq!(game.state.round, 0);
-        assert_eq!(game.state.player_one_score, 0);
-        assert_eq!(game.state.player_two_score, 0);
+        assert_eq!(game.state.scores, [0, 0]);
         assert_eq!(game.state.round_history.len(), 0);
-        assert_eq!(game.state.game_over, false);
+        assert!(!game.state.game_over);
     }
 
     #[test]
     fn test_rock_paper_scissors_new_custom_rounds() {
-        let config = RockPaperScissorsConfig { rounds: 5 };
+        let config = RockPaperScissorsConfig { rounds: 5, ..Default::default() };
         let game = RockPaperScissors::new(config);
         
         assert_eq!(game.config.rounds, 5);
@@ -421,6 +596,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_choice_forgiving() {
+        let game = RockPaperScissors::new(RockPaperScissorsConfig::default());
+        let parse = |text: &str| game.parse_choice(&json!({"choice": text}), "Test").unwrap();
+
+        // Emoji, with and without the emoji-presentation selector
+        assert_eq!(parse("✊"), Some(Choice::Rock));
+        assert_eq!(parse("✋"), Some(Choice::Paper));
+        assert_eq!(parse("✌️"), Some(Choice::Scissors));
+        assert_eq!(parse("✂\u{fe0f}"), Some(Choice::Scissors));
+
+        // Punctuation, filler words, singulars and synonyms
+        assert_eq!(parse("Rock!"), Some(Choice::Rock));
+        assert_eq!(parse("  paper. "), Some(Choice::Paper));
+        assert_eq!(parse("i choose rock"), Some(Choice::Rock));
+        assert_eq!(parse("scissor"), Some(Choice::Scissors));
+        assert_eq!(parse("Stone"), Some(Choice::Rock));
+        assert_eq!(parse("rock ✊"), Some(Choice::Rock));
+
+        // Nothing recognizable, or more than one choice
+        assert_eq!(parse("rocket"), None);
+        assert_eq!(parse("rock or paper"), None);
+        assert_eq!(parse(""), None);
+    }
+
     #[test]
     fn test_parse_choice_invalid() {
         let config = RockPaperScissorsConfig::default();
@@ -446,5 +646,177 @@ mod tests {
         let config = RockPaperScissorsConfig::default();
         assert_eq!(config.rounds, 3);
     }
-}
 
+    #[tokio::test]
+    async fn test_both_choices_are_requested_at_once_and_timed_apart() {
+        use crate::agents::socket::{delayed_socket_agent, scripted_socket_agent};
+        use std::time::Duration;
+
+        let delay = Duration::from_millis(200);
+        let agents = vec![
+            AIAgent::Socket(delayed_socket_agent("slow", vec![json!({"choice": "rock"})], delay).await),
+            AIAgent::Socket(delayed_socket_agent("also slow", vec![json!({"choice": "paper"})], delay).await),
+        ];
+        let config = RockPaperScissorsConfig { rounds: 1, ..Default::default() };
+        let result = RockPaperScissors::new(config).play_game(agents).await;
+        let turns = &result.stats.turns;
+        assert_eq!(turns.len(), 2);
+        assert!(turns.iter().all(|t| t.api_latency_ms >= 200));
+        assert!(turns.iter().all(|t| t.time_taken_ms == t.api_latency_ms + t.processing_ms));
+        // Sequential calls would take at least twice the delay
+        assert!(result.stats.total_duration_ms < 400, "{} ms", result.stats.total_duration_ms);
+
+        let agents = vec![
+            AIAgent::Socket(delayed_socket_agent("slow", vec![json!({"choice": "rock"})], delay).await),
+            AIAgent::Socket(scripted_socket_agent("fast", vec![json!({"choice": "paper"})]).await),
+        ];
+        let config = RockPaperScissorsConfig { rounds: 1, ..Default::default() };
+        let result = RockPaperScissors::new(config).play_game(agents).await;
+        let turns = &result.stats.turns;
+        assert!(turns[0].time_taken_ms >= 200);
+        assert!(turns[1].time_taken_ms < 200, "{} ms", turns[1].time_taken_ms);
+    }
+
+    #[tokio::test]
+    async fn test_clinched_game_ends_early() {
+        use crate::agents::scripted::ScriptedAgent;
+
+        // One wins rounds 1 and 4 and ties 2 and 3: 2-0 with one round left
+        let moves_one = ["rock", "rock", "paper", "rock", "rock"];
+        let moves_two = ["scissors", "rock", "paper", "scissors", "paper"];
+        let play = |end_when_clinched| {
+            let agents = [("one", moves_one), ("two", moves_two)]
+                .map(|(name, moves)| {
+                    AIAgent::Scripted(ScriptedAgent::new(name, moves.iter().map(|c| json!({"choice": c})).collect()))
+                })
+                .into();
+            let config = RockPaperScissorsConfig { rounds: 5, end_when_clinched, ..Default::default() };
+            RockPaperScissors::new(config).play_game(agents)
+        };
+
+        let clinched = play(true).await;
+        assert_eq!(clinched.winner.as_deref(), Some("one (Player 1)"));
+        assert_eq!(clinched.stats.turns.len(), 8);
+
+        // Without the guard the last round is still played, and lost
+        let full = play(false).await;
+        assert_eq!(full.winner.as_deref(), Some("one (Player 1)"));
+        assert_eq!(full.stats.turns.len(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_three_players() {
+        use crate::agents::scripted::ScriptedAgent;
+
+        let scripted = |name: &str, moves: &[&str]| {
+            AIAgent::Scripted(ScriptedAgent::new(name, moves.iter().map(|c| json!({"choice": c})).collect()))
+        };
+        // Round 1: rock and rock beat scissors. Round 2: all three choices,
+        // a tie. Round 3: paper beats rock, and "lizard" is invalid.
+        let agents = vec![
+            scripted("one", &["rock", "rock", "paper"]),
+            scripted("two", &["rock", "paper", "rock"]),
+            scripted("three", &["scissors", "scissors", "lizard"]),
+        ];
+        let config = RockPaperScissorsConfig { rounds: 3, ..Default::default() };
+        let result = RockPaperScissors::new(config).play_game(agents).await;
+
+        assert_eq!(result.winner.as_deref(), Some("one (Player 1)"));
+        assert_eq!(result.stats.seats, ["one", "two", "three"]);
+        let turns: Vec<u32> = result.stats.turns.iter().map(|t| t.turn_number).collect();
+        assert_eq!(turns, (1..=9).collect::<Vec<_>>());
+        assert_eq!(result.stats.invalid_moves, 1);
+        let last = &result.stats.turns.last().unwrap().state_before;
+        assert_eq!(last["scores"], json!([1, 1, 0]));
+        assert_eq!(last["round_history"][0]["winners"], json!([0, 1]));
+        assert_eq!(last["round_history"][1]["winners"], json!([]));
+
+        let crowd = (0..9).map(|i| scripted(&format!("p{}", i), &["rock"])).collect();
+        let result = RockPaperScissors::new(RockPaperScissorsConfig::default()).play_game(crowd).await;
+        assert_eq!(result.error.as_deref(), Some("Expected 2 to 8 agents, got 9"));
+    }
+
+    #[test]
+    fn test_round_winners() {
+        use Choice::*;
+        assert_eq!(round_winners(&[Some(Rock), Some(Scissors)]), [0]);
+        assert_eq!(round_winners(&[Some(Rock), None]), Vec::<usize>::new());
+        assert_eq!(round_winners(&[Some(Paper), Some(Rock), Some(Paper)]), [0, 2]);
+        assert_eq!(round_winners(&[Some(Paper), Some(Rock), Some(Scissors)]), Vec::<usize>::new());
+        assert_eq!(round_winners(&[Some(Rock), Some(Rock), None]), Vec::<usize>::new());
+    }
+
+    #[tokio::test]
+    async fn test_too_many_invalid_choices_forfeit() {
+        use crate::agents::scripted::ScriptedAgent;
+
+        let agents = vec![
+            AIAgent::Scripted(ScriptedAgent::new("one", vec![json!({"choice": "lizard"}); 5])),
+            AIAgent::Scripted(ScriptedAgent::new("two", vec![json!({"choice": "rock"}); 5])),
+        ];
+        let config = RockPaperScissorsConfig {
+            rounds: 5,
+            rules: TurnRules {
+                max_invalid_moves: Some(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = RockPaperScissors::new(config).play_game(agents).await;
+
+        assert_eq!(result.winner.as_deref(), Some("two (Player 2) (opponent forfeited)"));
+        assert_eq!(result.stats.invalid_moves, 2);
+        assert_eq!(result.stats.turns.len(), 4);
+    }
+
+    mod properties {
+        use super::*;
+        use crate::games::test_support::arb_json;
+        use proptest::prelude::*;
+
+        proptest! {
+            #[test]
+            fn parse_choice_never_panics(value in arb_json()) {
+                let game = RockPaperScissors::new(RockPaperScissorsConfig::default());
+                match game.parse_choice(&value, "Test") {
+                    Ok(_) => prop_assert!(value["choice"].is_string()),
+                    Err(_) => prop_assert!(!value["choice"].is_string()),
+                }
+            }
+
+            #[test]
+            fn parse_choice_ignores_case(
+                choice in prop_oneof![Just(Choice::Rock), Just(Choice::Paper), Just(Choice::Scissors)],
+                mask in prop::collection::vec(any::<bool>(), 8),
+            ) {
+                let game = RockPaperScissors::new(RockPaperScissorsConfig::default());
+                let mixed: String = choice
+                    .as_str()
+                    .chars()
+                    .zip(mask.iter().cycle())
+                    .map(|(c, upper)| if *upper { c.to_ascii_uppercase() } else { c })
+                    .collect();
+                prop_assert_eq!(game.parse_choice(&json!({"choice": mixed}), "Test"), Ok(Some(choice)));
+            }
+
+            #[test]
+            fn from_text_finds_choice_among_filler(
+                choice in prop_oneof![Just(Choice::Rock), Just(Choice::Paper), Just(Choice::Scissors)],
+                mask in prop::collection::vec(any::<bool>(), 8),
+                before in "[a-zA-Z ,.!:]{0,20}",
+                after in "[a-zA-Z ,.!:]{0,20}",
+            ) {
+                // Filler that names a choice itself would make the text ambiguous
+                prop_assume!(Choice::from_text(&before).is_none() && Choice::from_text(&after).is_none());
+                let mixed: String = choice
+                    .as_str()
+                    .chars()
+                    .zip(mask.iter().cycle())
+                    .map(|(c, upper)| if *upper { c.to_ascii_uppercase() } else { c })
+                    .collect();
+                let text = format!("{} {} {}", before, mixed, after);
+                prop_assert_eq!(Choice::from_text(&text), Some(choice));
+            }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::time::{Duration, Instant};
+
+use futures::stream::{self, StreamExt};
+
+use crate::agent::{AIAgent, AgentResult, MoveRequest, MoveResponse};
+use crate::games::timeout::request_move;
+
+/// Run `f` on every item with at most `max_in_flight` calls pending at once.
+/// Results come back in input order, whichever call finishes first.
+pub async fn bounded_join<T, R>(
+    items: impl IntoIterator<Item = T>,
+    max_in_flight: usize,
+    f: impl AsyncFn(T) -> R,
+) -> Vec<R> {
+    stream::iter(items)
+        .map(|item| f(item))
+        .buffered(max_in_flight.max(1))
+        .collect()
+        .await
+}
+
+/// Ask each agent for its move in a simultaneous round, `requests[i]` going
+/// to `agents[i]` and each call giving up after `timeout_ms` as in
+/// `request_move`. Each response carries that agent's own response time.
+pub async fn query_agents(
+    agents: &[&AIAgent],
+    requests: &[MoveRequest],
+    timeout_ms: Option<u64>,
+    max_in_flight: usize,
+) -> Vec<(Result<AgentResult<MoveResponse>, String>, Duration)> {
+    bounded_join(agents.iter().zip(requests), max_in_flight, async |(agent, request)| {
+        let start = Instant::now();
+        let response = request_move(agent, request, timeout_ms).await;
+        (response, start.elapsed())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[tokio::test]
+    async fn test_bounded_join_limits_in_flight_and_keeps_order() {
+        let in_flight = Cell::new(0);
+        let peak = Cell::new(0);
+
+        // Earlier items take longer, so they finish last
+        let results = bounded_join(0..5u32, 3, async |i| {
+            in_flight.set(in_flight.get() + 1);
+            peak.set(peak.get().max(in_flight.get()));
+            for _ in 0..(5 - i) {
+                tokio::task::yield_now().await;
+            }
+            in_flight.set(in_flight.get() - 1);
+            i * 10
+        })
+        .await;
+
+        assert_eq!(results, vec![0, 10, 20, 30, 40]);
+        assert_eq!(peak.get(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_query_agents_in_agent_order() {
+        use crate::agents::socket::scripted_socket_agent;
+        use serde_json::{Value, json};
+
+        let mut agents = Vec::new();
+        for choice in ["rock", "paper", "scissors"] {
+            agents.push(AIAgent::Socket(scripted_socket_agent(choice, vec![json!({"choice": choice})]).await));
+        }
+        let requests: Vec<MoveRequest> = (0..agents.len())
+            .map(|_| MoveRequest {
+                turn_index: 1,
+                game_id: "rps_test".to_string(),
+                state: json!({}),
+                expected_move_schema: json!({}),
+                legal_moves: None,
+            })
+            .collect();
+
+        let refs: Vec<&AIAgent> = agents.iter().collect();
+        let responses = query_agents(&refs, &requests, None, 3).await;
+
+        let choices: Vec<Value> = responses
+            .into_iter()
+            .map(|(response, _)| response.unwrap().unwrap().chosen_move["choice"].clone())
+            .collect();
+        assert_eq!(choices, vec![json!("rock"), json!("paper"), json!("scissors")]);
+    }
+    #[tokio::test]
+    async fn test_query_agents_times_out_each_agent() {
+        use crate::agents::socket::{scripted_socket_agent, stalling_socket_agent};
+        use serde_json::json;
+
+        let agents = [
+            AIAgent::Socket(scripted_socket_agent("fast", vec![json!({"choice": "rock"})]).await),
+            AIAgent::Socket(stalling_socket_agent("hung", vec![]).await),
+        ];
+        let requests: Vec<MoveRequest> = (0..agents.len())
+            .map(|_| MoveRequest {
+                turn_index: 1,
+                game_id: "rps_test".to_string(),
+                state: json!({}),
+                expected_move_schema: json!({}),
+                legal_moves: None,
+            })
+            .collect();
+        let refs: Vec<&AIAgent> = agents.iter().collect();
+        let responses = query_agents(&refs, &requests, Some(50), 2).await;
+
+        assert!(responses[0].0.as_ref().unwrap().is_ok());
+        assert_eq!(responses[1].0.as_ref().unwrap_err(), "agent timed out after 50 ms");
+    }
+}
@@ -1,8 +1,12 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::agent::{ServedModel, TokenUsage};
+
 /// Statistics tracked for each turn in a game
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct TurnStats {
     /// The turn number (0-indexed)
     pub turn_number: u32,
@@ -10,8 +14,14 @@ pub struct TurnStats {
     pub player: String,
     /// The move that was made (as JSON)
     pub move_made: Value,
-    /// Time taken to make the move
+    /// Time taken to make the move, `api_latency_ms` plus `processing_ms`
     pub time_taken_ms: u64,
+    /// Time spent waiting on the agent for its move
+    #[serde(default)]
+    pub api_latency_ms: u64,
+    /// Time spent building the request and checking and applying the move
+    #[serde(default)]
+    pub processing_ms: u64,
     /// Whether the move was valid
     pub move_valid: bool,
     /// Error message if move was invalid
@@ -22,37 +32,110 @@ pub struct TurnStats {
     pub state_after: Value,
     /// Any diagnostics from the agent
     pub diagnostics: Option<String>,
+    /// Perturbation applied to the state the agent was shown, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub perturbation: Option<String>,
+    /// Whether a replay of the same request gave the same move (temp-0 check)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_consistent: Option<bool>,
+    /// User message the agent was sent; kept in memory for exporters and
+    /// not written to results files
+    #[serde(skip)]
+    pub prompt: Option<String>,
+    /// System message the agent was sent, for agents that send one; kept in
+    /// memory like `prompt`
+    #[serde(skip)]
+    pub system_prompt: Option<String>,
+    /// Tokens the move request consumed, if the provider reported them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TokenUsage>,
+    /// Model the provider reported serving the move request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub served_model: Option<ServedModel>,
+    /// Free-text `reasoning` the agent sent along with its move; kept but
+    /// never checked, and left out of `move_made`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<String>,
+}
+
+impl TurnStats {
+    /// The response couldn't be read as a move at all, as opposed to a
+    /// well-formed move that broke the rules ("Invalid move: ...")
+    pub fn is_format_error(&self) -> bool {
+        !self.move_valid
+            && !self
+                .error_message
+                .as_deref()
+                .is_some_and(|e| e.starts_with("Invalid"))
+    }
 }
 
+/// Follows `GameStats::winner` when the other player forfeited
+pub const FORFEIT_NOTE: &str = " (opponent forfeited)";
+
 /// Statistics for a complete game
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GameStats {
+    /// Id the game was played under, as in transcripts; `None` for games
+    /// that never started
+    #[serde(default)]
+    pub game_id: Option<String>,
     /// All turns in the game
     pub turns: Vec<TurnStats>,
     /// Total game duration in milliseconds
     pub total_duration_ms: u64,
     /// Number of invalid moves attempted
     pub invalid_moves: u32,
-    /// Winner of the game (None if draw or incomplete)
+    /// `invalid_moves` split by player, with 0 for players who made none.
+    /// Empty in results written before it was added.
+    #[serde(default)]
+    pub invalid_moves_by_player: BTreeMap<String, u32>,
+    /// Winner of the game (None if draw or incomplete), as
+    /// "<agent> (<seat>)", followed by `FORFEIT_NOTE` after a forfeit
     pub winner: Option<String>,
     /// Whether the game ended in a draw
     pub draw: bool,
+    /// Every valid move in order as `<seat>:<move>`, e.g. `X:1,1` or `Red:3`
+    #[serde(default)]
+    pub move_list: Vec<String>,
+    /// Agent in each seat, first mover first. Empty in results written
+    /// before it was added.
+    #[serde(default)]
+    pub seats: Vec<String>,
+    /// Prompt tokens over all turns, `None` if no turn reported usage
+    #[serde(default)]
+    pub total_prompt_tokens: Option<u64>,
+    /// Completion tokens over all turns, `None` if no turn reported usage
+    #[serde(default)]
+    pub total_completion_tokens: Option<u64>,
 }
 
 impl GameStats {
     pub fn new() -> Self {
         Self {
+            game_id: None,
             turns: Vec::new(),
             total_duration_ms: 0,
             invalid_moves: 0,
+            invalid_moves_by_player: BTreeMap::new(),
             winner: None,
             draw: false,
+            move_list: Vec::new(),
+            seats: Vec::new(),
+            total_prompt_tokens: None,
+            total_completion_tokens: None,
         }
     }
 
     pub fn add_turn(&mut self, turn: TurnStats) {
+        let by_player = self.invalid_moves_by_player.entry(turn.player.clone()).or_insert(0);
         if !turn.move_valid {
             self.invalid_moves += 1;
+            *by_player += 1;
+        }
+        if let Some(usage) = turn.usage {
+            *self.total_prompt_tokens.get_or_insert(0) += u64::from(usage.prompt_tokens);
+            *self.total_completion_tokens.get_or_insert(0) += u64::from(usage.completion_tokens);
         }
         self.turns.push(turn);
     }
@@ -65,9 +148,56 @@ impl GameStats {
         total as f64 / self.turns.len() as f64
     }
 
+    /// Turn time at or below which `p` percent of turns fall (nearest rank),
+    /// 0 with no turns. With few turns the upper percentiles are all the
+    /// slowest turn.
+    pub fn latency_percentile(&self, p: f64) -> u64 {
+        let times = self.sorted_turn_times();
+        if times.is_empty() {
+            return 0;
+        }
+        let rank = (p.clamp(0.0, 100.0) / 100.0 * times.len() as f64).ceil() as usize;
+        times[rank.clamp(1, times.len()) - 1]
+    }
+
+    /// Turn times in equal-width buckets from the fastest turn to the
+    /// slowest, as `(lowest_ms, highest_ms, turns)`. There are at most
+    /// `buckets` of them, and no more than there are turns.
+    pub fn latency_histogram(&self, buckets: usize) -> Vec<(u64, u64, u32)> {
+        let times = self.sorted_turn_times();
+        let (Some(&fastest), Some(&slowest)) = (times.first(), times.last()) else {
+            return Vec::new();
+        };
+        let buckets = buckets.clamp(1, times.len()) as u64;
+        let width = (slowest - fastest + buckets) / buckets;
+        let mut counts = vec![0; buckets as usize];
+        for time in times {
+            counts[((time - fastest) / width) as usize] += 1;
+        }
+        while counts.last() == Some(&0) {
+            counts.pop();
+        }
+        counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, turns)| (fastest + i as u64 * width, fastest + (i as u64 + 1) * width - 1, turns))
+            .collect()
+    }
+
+    fn sorted_turn_times(&self) -> Vec<u64> {
+        let mut times: Vec<u64> = self.turns.iter().map(|t| t.time_taken_ms).collect();
+        times.sort_unstable();
+        times
+    }
+
     pub fn total_turns(&self) -> u32 {
         self.turns.len() as u32
     }
+
+    /// True when no player made an invalid move
+    pub fn is_clean_game(&self) -> bool {
+        self.invalid_moves == 0
+    }
 }
 
 impl Default for GameStats {
@@ -81,6 +211,56 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn test_is_clean_game() {
+        let mut stats = GameStats::new();
+        let turn = |move_valid| TurnStats {
+            turn_number: 1,
+            player: "p".to_string(),
+            move_made: json!({}),
+            time_taken_ms: 1,
+            api_latency_ms: 1,
+            move_valid,
+            state_before: json!({}),
+            state_after: json!({}),
+            ..Default::default()
+        };
+
+        stats.add_turn(turn(true));
+        assert!(stats.is_clean_game());
+        stats.add_turn(turn(false));
+        assert!(!stats.is_clean_game());
+    }
+
+    #[test]
+    fn test_invalid_moves_by_player() {
+        let turn = |player: &str, move_valid| TurnStats {
+            turn_number: 1,
+            player: player.to_string(),
+            move_made: json!({}),
+            time_taken_ms: 1,
+            api_latency_ms: 1,
+            move_valid,
+            state_before: json!({}),
+            state_after: json!({}),
+            ..Default::default()
+        };
+
+        let mut stats = GameStats::new();
+        for (player, valid) in [("x", true), ("o", false), ("o", false), ("o", true), ("x", true), ("x", false)] {
+            stats.add_turn(turn(player, valid));
+        }
+        assert_eq!(stats.invalid_moves_by_player, BTreeMap::from([("o".to_string(), 2), ("x".to_string(), 1)]));
+        assert_eq!(stats.invalid_moves, 3);
+
+        let json = serde_json::to_value(&stats).unwrap();
+        assert_eq!(json["invalid_moves_by_player"], json!({"o": 2, "x": 1}));
+
+        let mut clean = GameStats::new();
+        clean.add_turn(turn("x", true));
+        assert_eq!(clean.invalid_moves_by_player, BTreeMap::from([("x".to_string(), 0)]));
+    }
+
     #[test]
     fn test_game_stats_new() {
         let stats = GameStats::new();
@@ -88,7 +268,34 @@ mod tests {
         assert_eq!(stats.total_duration_ms, 0);
         assert_eq!(stats.invalid_moves, 0);
         assert_eq!(stats.winner, None);
-        assert_eq!(stats.draw, false);
+        assert!(!stats.draw);
+    }
+
+    #[test]
+    fn test_token_totals_only_count_reported_usage() {
+        let turn = |usage| TurnStats {
+            turn_number: 1,
+            player: "p".to_string(),
+            move_made: json!({}),
+            time_taken_ms: 1,
+            api_latency_ms: 1,
+            move_valid: true,
+            state_before: json!({}),
+            state_after: json!({}),
+            usage,
+            ..Default::default()
+        };
+
+        let mut stats = GameStats::new();
+        stats.add_turn(turn(None));
+        assert_eq!(stats.total_prompt_tokens, None);
+        assert_eq!(stats.total_completion_tokens, None);
+
+        stats.add_turn(turn(Some(TokenUsage { prompt_tokens: 100, completion_tokens: 7 })));
+        stats.add_turn(turn(None));
+        stats.add_turn(turn(Some(TokenUsage { prompt_tokens: 120, completion_tokens: 9 })));
+        assert_eq!(stats.total_prompt_tokens, Some(220));
+        assert_eq!(stats.total_completion_tokens, Some(16));
     }
 
     #[test]
@@ -107,11 +314,11 @@ mod tests {
             player: "Player1".to_string(),
             move_made: json!({"row": 0, "col": 0}),
             time_taken_ms: 100,
+            api_latency_ms: 100,
             move_valid: true,
-            error_message: None,
             state_before: json!({}),
             state_after: json!({}),
-            diagnostics: None,
+            ..Default::default()
         };
         
         stats.add_turn(turn);
@@ -127,11 +334,11 @@ mod tests {
             player: "Player1".to_string(),
             move_made: json!({"row": 10, "col": 10}),
             time_taken_ms: 50,
-            move_valid: false,
+            api_latency_ms: 50,
             error_message: Some("Invalid move".to_string()),
             state_before: json!({}),
             state_after: json!({}),
-            diagnostics: None,
+            ..Default::default()
         };
         
         stats.add_turn(turn);
@@ -149,11 +356,12 @@ mod tests {
                 player: format!("Player{}", i % 2 + 1),
                 move_made: json!({"move": i}),
                 time_taken_ms: ((i + 1) * 10) as u64,
+                api_latency_ms: ((i + 1) * 10) as u64,
                 move_valid: i % 2 == 0, // Alternate valid/invalid
                 error_message: if i % 2 == 0 { None } else { Some("Invalid".to_string()) },
                 state_before: json!({}),
                 state_after: json!({}),
-                diagnostics: None,
+                ..Default::default()
             };
             stats.add_turn(turn);
         }
@@ -176,16 +384,69 @@ mod tests {
             player: "Player1".to_string(),
             move_made: json!({}),
             time_taken_ms: 100,
+            api_latency_ms: 100,
             move_valid: true,
-            error_message: None,
             state_before: json!({}),
             state_after: json!({}),
-            diagnostics: None,
+            ..Default::default()
         };
         stats.add_turn(turn);
         assert_eq!(stats.average_turn_time_ms(), 100.0);
     }
 
+    fn timed(times: &[u64]) -> GameStats {
+        let mut stats = GameStats::new();
+        for (i, &time_taken_ms) in times.iter().enumerate() {
+            stats.add_turn(TurnStats {
+                turn_number: i as u32 + 1,
+                player: "Player1".to_string(),
+                move_made: json!({}),
+                time_taken_ms,
+                api_latency_ms: time_taken_ms,
+                move_valid: true,
+                state_before: json!({}),
+                state_after: json!({}),
+                ..Default::default()
+            });
+        }
+        stats
+    }
+
+    #[test]
+    fn test_latency_percentiles() {
+        // 1..=100 ms, shuffled by turn order
+        let times: Vec<u64> = (1..=100).map(|i| (i * 37) % 100 + 1).collect();
+        let stats = timed(&times);
+        assert_eq!(stats.latency_percentile(50.0), 50);
+        assert_eq!(stats.latency_percentile(90.0), 90);
+        assert_eq!(stats.latency_percentile(99.0), 99);
+        assert_eq!(stats.latency_percentile(100.0), 100);
+        assert_eq!(stats.latency_percentile(0.0), 1);
+
+        let stats = timed(&[400, 100, 200, 300]);
+        assert_eq!(stats.latency_percentile(50.0), 200);
+        assert_eq!(stats.latency_percentile(90.0), 400);
+        assert_eq!(stats.latency_percentile(99.0), 400);
+    }
+
+    #[test]
+    fn test_latency_percentiles_with_few_turns() {
+        assert_eq!(GameStats::new().latency_percentile(50.0), 0);
+        let stats = timed(&[250]);
+        assert_eq!([50.0, 90.0, 99.0].map(|p| stats.latency_percentile(p)), [250, 250, 250]);
+    }
+
+    #[test]
+    fn test_latency_histogram() {
+        let stats = timed(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 95]);
+        assert_eq!(stats.latency_histogram(5), [(0, 19, 10), (20, 39, 0), (40, 59, 0), (60, 79, 0), (80, 99, 1)]);
+
+        // No more buckets than turns, and none left empty at the top
+        assert_eq!(timed(&[10, 30]).latency_histogram(5), [(10, 20, 1), (21, 31, 1)]);
+        assert_eq!(timed(&[7, 7, 7]).latency_histogram(5), [(7, 7, 3)]);
+        assert!(GameStats::new().latency_histogram(5).is_empty());
+    }
+
     #[test]
     fn test_average_turn_time_multiple() {
         let mut stats = GameStats::new();
@@ -195,11 +456,11 @@ mod tests {
                 player: "Player1".to_string(),
                 move_made: json!({}),
                 time_taken_ms: (i * 10) as u64,
+                api_latency_ms: (i * 10) as u64,
                 move_valid: true,
-                error_message: None,
                 state_before: json!({}),
                 state_after: json!({}),
-                diagnostics: None,
+                ..Default::default()
             };
             stats.add_turn(turn);
         }
@@ -218,11 +479,11 @@ mod tests {
                 player: "Player1".to_string(),
                 move_made: json!({}),
                 time_taken_ms: 100,
+                api_latency_ms: 100,
                 move_valid: true,
-                error_message: None,
                 state_before: json!({}),
                 state_after: json!({}),
-                diagnostics: None,
+                ..Default::default()
             };
             stats.add_turn(turn);
         }
@@ -0,0 +1,134 @@
+use serde_json::Value;
+
+use super::game::TestResult;
+use super::rock_paper_scissors::scores_in_state;
+
+const CELL: usize = 40;
+const BAR_WIDTH: usize = 300;
+const BAR_HEIGHT: usize = 30;
+
+fn piece_color(symbol: &str) -> &'static str {
+    match symbol {
+        "X" | "Red" => "#d33",
+        "O" | "Yellow" => "#e6b800",
+        "Black" | "b" | "B" => "#222",
+        "White" | "w" | "W" => "#eee",
+        _ => "#555",
+    }
+}
+
+/// SVG of a `board` matrix (rows of piece names or `null`): one `rect` per
+/// cell and a labelled `circle` per piece. `None` if `board` isn't a matrix.
+pub fn board_svg(board: &Value) -> Option<String> {
+    let rows = board.as_array()?;
+    let cols = rows.first()?.as_array()?.len();
+    let (width, height) = (cols * CELL, rows.len() * CELL);
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+        w = width,
+        h = height
+    );
+    for (r, row) in rows.iter().enumerate() {
+        for (c, cell) in row.as_array()?.iter().enumerate() {
+            let (x, y) = (c * CELL, r * CELL);
+            svg.push_str(&format!(
+                "<rect class=\"cell\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#fff\" stroke=\"#333\"/>\n",
+                x, y, CELL, CELL
+            ));
+            if let Some(symbol) = cell.as_str() {
+                let (cx, cy) = (x + CELL / 2, y + CELL / 2);
+                svg.push_str(&format!(
+                    "<circle class=\"piece\" cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>\n",
+                    cx,
+                    cy,
+                    CELL / 2 - 4,
+                    piece_color(symbol)
+                ));
+                svg.push_str(&format!(
+                    "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\" font-family=\"monospace\">{}</text>\n",
+                    cx,
+                    cy,
+                    symbol.chars().next().unwrap_or('?')
+                ));
+            }
+        }
+    }
+    svg.push_str("</svg>\n");
+    Some(svg)
+}
+
+/// SVG bar split between two players in proportion to their scores
+pub fn score_bar_svg(one: u64, two: u64) -> String {
+    let one_width = (BAR_WIDTH as u64 * one)
+        .checked_div(one + two)
+        .map_or(BAR_WIDTH / 2, |w| w as usize);
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n\
+         <rect class=\"score\" x=\"0\" y=\"0\" width=\"{one_w}\" height=\"{h}\" fill=\"#d33\"/>\n\
+         <rect class=\"score\" x=\"{one_w}\" y=\"0\" width=\"{two_w}\" height=\"{h}\" fill=\"#36c\"/>\n\
+         <text x=\"4\" y=\"{ty}\" font-family=\"monospace\">{one} - {two}</text>\n\
+         </svg>\n",
+        w = BAR_WIDTH,
+        h = BAR_HEIGHT,
+        one_w = one_width,
+        two_w = BAR_WIDTH - one_width,
+        ty = BAR_HEIGHT / 2 + 5,
+        one = one,
+        two = two,
+    )
+}
+
+/// SVG of a game's final position: the board for board games, including
+/// custom games whose state has a `board`, the score bar
+/// for two-player Rock Paper Scissors. `None` for Nim, which has no board,
+/// for Rock Paper Scissors with more players, or if no turn recorded a
+/// renderable state.
+pub fn final_state_svg(result: &TestResult) -> Option<String> {
+    let state = &result.stats().turns.last()?.state_after;
+    match result {
+        TestResult::RockPaperScissors(_) => match scores_in_state(state).as_slice() {
+            [one, two] => Some(score_bar_svg(*one, *two)),
+            _ => None,
+        },
+        TestResult::TicTacToe(_)
+        | TestResult::ConnectFour(_)
+        | TestResult::Gomoku(_)
+        | TestResult::Checkers(_)
+        | TestResult::Reversi(_)
+        | TestResult::Custom(_) => {
+            board_svg(state.get("board")?)
+        }
+        TestResult::Nim(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_board_svg_has_cell_and_piece_elements() {
+        let svg = board_svg(&json!([["X", null, null], [null, "O", null], [null, null, null]])).unwrap();
+
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<rect class=\"cell\"").count(), 9);
+        assert_eq!(svg.matches("<circle class=\"piece\"").count(), 2);
+        assert!(svg.contains("<circle class=\"piece\" cx=\"20\" cy=\"20\" r=\"16\" fill=\"#d33\"/>"));
+        assert!(svg.contains("<circle class=\"piece\" cx=\"60\" cy=\"60\" r=\"16\" fill=\"#e6b800\"/>"));
+        assert!(svg.contains(">O</text>"));
+    }
+
+    #[test]
+    fn test_board_svg_rejects_non_matrix() {
+        assert_eq!(board_svg(&json!("X..")), None);
+    }
+
+    #[test]
+    fn test_score_bar_svg() {
+        let svg = score_bar_svg(2, 1);
+        assert!(svg.contains("width=\"200\" height=\"30\" fill=\"#d33\""));
+        assert!(svg.contains(">2 - 1</text>"));
+    }
+}
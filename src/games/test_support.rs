@@ -0,0 +1,117 @@
+//! Shared proptest strategies and reference checks for the game test suites.
+
+use proptest::prelude::*;
+use serde_json::{Map, Value, json};
+
+/// The eight compass directions collapse to these four line directions
+pub const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+/// Arbitrary JSON values, biased towards the shapes agents actually send back
+pub fn arb_json() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Bool),
+        any::<i64>().prop_map(|n| json!(n)),
+        any::<u64>().prop_map(|n| json!(n)),
+        any::<f64>().prop_map(|n| json!(n)),
+        ".{0,12}".prop_map(Value::String),
+    ];
+    leaf.prop_recursive(3, 24, 4, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..4).prop_map(Value::Array),
+            prop::collection::vec((arb_key(), inner), 0..4)
+                .prop_map(|fields| Value::Object(fields.into_iter().collect::<Map<_, _>>())),
+        ]
+    })
+}
+
+/// Object keys that collide with real move fields often enough to matter
+fn arb_key() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just("row".to_string()),
+        Just("col".to_string()),
+        Just("column".to_string()),
+        Just("choice".to_string()),
+        "[a-z]{1,6}",
+    ]
+}
+
+/// A rows x cols board whose cells are drawn from `cell`
+pub fn arb_board<T: Clone + std::fmt::Debug>(
+    rows: usize,
+    cols: usize,
+    cell: impl Strategy<Value = Option<T>> + Clone,
+) -> impl Strategy<Value = Vec<Vec<Option<T>>>> {
+    prop::collection::vec(prop::collection::vec(cell, cols), rows)
+}
+
+/// Brute-force line detection used as the oracle for each game's `check_win`
+pub fn reference_has_line<T: PartialEq>(board: &[Vec<Option<T>>], who: &T, win_length: usize) -> bool {
+    let rows = board.len() as isize;
+    let cols = board.first().map_or(0, |r| r.len()) as isize;
+    for row in 0..rows {
+        for col in 0..cols {
+            for (dr, dc) in DIRECTIONS {
+                let all = (0..win_length as isize).all(|i| {
+                    let (r, c) = (row + dr * i, col + dc * i);
+                    r >= 0 && r < rows && c >= 0 && c < cols
+                        && board[r as usize][c as usize].as_ref() == Some(who)
+                });
+                if all {
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
+
+/// Map arbitrary seeds onto a start cell from which a `len`-long line in
+/// `dir` fits on a rows x cols board (requires len <= rows and len <= cols).
+pub fn line_start(
+    rows: usize,
+    cols: usize,
+    (dr, dc): (isize, isize),
+    len: usize,
+    (seed_r, seed_c): (usize, usize),
+) -> (usize, usize) {
+    let span = |extent: usize, step: isize, seed: usize| match step {
+        0 => seed % extent,
+        1 => seed % (extent - len + 1),
+        _ => len - 1 + seed % (extent - len + 1),
+    };
+    (span(rows, dr, seed_r), span(cols, dc, seed_c))
+}
+
+/// Overwrite `len` cells starting at (row, col) in `dir` with `who`.
+/// Returns false without touching the board when the line doesn't fit.
+pub fn plant_line<T: Clone>(
+    board: &mut [Vec<Option<T>>],
+    who: &T,
+    (row, col): (usize, usize),
+    (dr, dc): (isize, isize),
+    len: usize,
+) -> bool {
+    let rows = board.len() as isize;
+    let cols = board.first().map_or(0, |r| r.len()) as isize;
+    let end_r = row as isize + dr * (len as isize - 1);
+    let end_c = col as isize + dc * (len as isize - 1);
+    let in_bounds = |r: isize, c: isize| r >= 0 && r < rows && c >= 0 && c < cols;
+    if len == 0 || !in_bounds(row as isize, col as isize) || !in_bounds(end_r, end_c) {
+        return false;
+    }
+    for i in 0..len as isize {
+        let (r, c) = (row as isize + dr * i, col as isize + dc * i);
+        board[r as usize][c as usize] = Some(who.clone());
+    }
+    true
+}
+
+#[test]
+fn test_reference_has_line_and_plant_line_agree() {
+    let mut board: Vec<Vec<Option<u8>>> = vec![vec![None; 4]; 4];
+    assert!(!reference_has_line(&board, &1, 3));
+    assert!(plant_line(&mut board, &1, (0, 3), (1, -1), 3));
+    assert!(reference_has_line(&board, &1, 3));
+    assert!(!plant_line(&mut board, &1, (3, 3), (0, 1), 3));
+}
@@ -1,14 +1,30 @@
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::time::Instant;
 
-use crate::agent::{AIAgent, MoveRequest, MoveResponse};
-use crate::games::stats::{GameStats, TurnStats};
+use crate::agent::AIAgent;
+use crate::games::parsing::u32_field;
+use crate::games::display::{BoardRepresentation, render_board};
+use crate::games::engine::{
+    self, GameEngine, TurnBasedGame, TurnRules, with_reasoning,
+};
+use crate::games::judge::Outcome;
+use crate::games::stats::GameStats;
+use crate::games::transcript::TranscriptWriter;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TicTacToeConfig {
     pub board_size: u32,
     pub win_length: u32,
+    /// Symbol that moves first; the first agent always plays X
+    #[serde(default)]
+    pub first_player: Player,
+    /// Whether agents see the board as a matrix, ASCII text, or both
+    #[serde(default)]
+    pub board_representation: BoardRepresentation,
+    /// `max_retries`, `move_timeout_ms` and `max_invalid_moves`, set at the
+    /// top level of the config
+    #[serde(flatten)]
+    pub rules: TurnRules,
 }
 
 impl Default for TicTacToeConfig {
@@ -16,6 +32,9 @@ impl Default for TicTacToeConfig {
         TicTacToeConfig {
             board_size: 3,
             win_length: 3,
+            first_player: Player::X,
+            board_representation: BoardRepresentation::default(),
+            rules: TurnRules::default(),
         }
     }
 }
@@ -29,24 +48,21 @@ pub struct TicTacToeState {
     pub winner: Option<Player>,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Player {
+    #[default]
     X,
     O,
 }
 
 impl Player {
-    fn as_str(&self) -> &str {
+    fn as_str(self) -> &'static str {
         match self {
             Player::X => "X",
             Player::O => "O",
         }
     }
 
-    fn to_string(&self) -> String {
-        self.as_str().to_string()
-    }
-
     fn other(&self) -> Player {
         match self {
             Player::X => Player::O,
@@ -55,115 +71,128 @@ impl Player {
     }
 }
 
+impl std::fmt::Display for Player {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TicTacToeMove {
     pub row: u32,
     pub col: u32,
 }
 
+#[derive(Debug)]
 pub struct TicTacToe {
     config: TicTacToeConfig,
     state: TicTacToeState,
-    stats: GameStats,
     game_id: String,
+    /// Where each turn is logged as it is played
+    transcript: Option<TranscriptWriter>,
 }
 
 impl TicTacToe {
     pub fn new(config: TicTacToeConfig) -> Self {
         let board_size = config.board_size as usize;
         let board = vec![vec![None; board_size]; board_size];
+        let first_player = config.first_player;
         
         Self {
             config,
             state: TicTacToeState {
                 board,
-                current_player: Player::X,
+                current_player: first_player,
                 turn_number: 0,
                 game_over: false,
                 winner: None,
             },
-            stats: GameStats::new(),
-            game_id: format!("ttt_{}", uuid::Uuid::new_v4().to_string()[..8].to_string()),
+            game_id: format!("ttt_{}", &uuid::Uuid::new_v4().to_string()[..8]),
+            transcript: None,
         }
     }
 
+    /// Log every turn to `transcript` as the game is played
+    pub fn with_transcript(mut self, transcript: Option<TranscriptWriter>) -> Self {
+        self.transcript = transcript;
+        self
+    }
+
     pub async fn play_game(mut self, agents: Vec<AIAgent>) -> TicTacToeResult {
-        let start_time = Instant::now();
-        
-        // Ensure we have exactly 2 agents
-        if agents.len() != 2 {
-            return TicTacToeResult {
+        let transcript = self.transcript.take();
+        match engine::play(&mut self, &agents, transcript.as_ref()).await {
+            Ok(stats) => TicTacToeResult {
+                winner: stats.winner.clone(),
+                stats,
+                error: None,
+            },
+            Err(aborted) => TicTacToeResult {
                 winner: None,
-                stats: self.stats,
-                error: Some(format!("Expected 2 agents, got {}", agents.len())),
-            };
+                stats: aborted.stats,
+                error: Some(aborted.error),
+            },
         }
+    }
 
-        let player_x_agent = &agents[0];
-        let player_o_agent = &agents[1];
-        
-        // Map players to agents
-        let agent_map: Vec<(&AIAgent, Player)> = vec![
-            (player_x_agent, Player::X),
-            (player_o_agent, Player::O),
-        ];
+    fn parse_move(move_data: &Value) -> Result<TicTacToeMove, String> {
+        Ok(TicTacToeMove {
+            row: u32_field(move_data, "row")?,
+            col: u32_field(move_data, "col")?,
+        })
+    }
 
-        while !self.state.game_over && self.state.turn_number < (self.config.board_size * self.config.board_size) {
-            let current_agent_idx = match self.state.current_player {
-                Player::X => 0,
-                Player::O => 1,
-            };
-            
-            let (agent, player) = &agent_map[current_agent_idx];
-            
-            // Execute turn
-            match self.execute_turn(agent, *player).await {
-                Ok(()) => {
-                    // Check for win condition
-                    if self.check_win() {
-                        self.state.game_over = true;
-                        self.state.winner = Some(self.state.current_player);
-                        self.stats.winner = Some(format!("{} ({})", agent.name(), self.state.current_player.as_str()));
-                        break;
-                    }
-                    
-                    // Check for draw
-                    if self.state.turn_number >= (self.config.board_size * self.config.board_size) {
-                        self.state.game_over = true;
-                        self.stats.draw = true;
-                        break;
-                    }
-                    
-                    // Switch player
-                    self.state.current_player = self.state.current_player.other();
-                }
-                Err(e) => {
-                    // Invalid move - game continues but stats are tracked
-                    eprintln!("Turn error: {}", e);
-                }
-            }
+    fn is_valid_move(&self, row: u32, col: u32) -> bool {
+        if row >= self.config.board_size || col >= self.config.board_size {
+            return false;
         }
+        self.state.board[row as usize][col as usize].is_none()
+    }
+
+    fn check_win(&self) -> bool {
+        has_line(&self.state.board, &self.state.current_player, self.config.win_length as usize)
+    }
 
-        let total_duration = start_time.elapsed();
-        self.stats.total_duration_ms = total_duration.as_millis() as u64;
+    fn state_to_json(&self) -> Value {
+        let board: Vec<Vec<Option<String>>> = self
+            .state
+            .board
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| cell.map(|p| p.to_string()))
+                    .collect()
+            })
+            .collect();
 
-        TicTacToeResult {
-            winner: self.stats.winner.clone(),
-            stats: self.stats,
-            error: None,
+        let mut state = json!({
+            "current_player": self.state.current_player.to_string(),
+            "turn_number": self.state.turn_number,
+            "game_over": self.state.game_over,
+            "winner": self.state.winner.map(|p| p.to_string()),
+            "board_size": self.config.board_size,
+            "win_length": self.config.win_length,
+        });
+
+        let representation = self.config.board_representation;
+        if representation.includes_text() {
+            state["board_text"] = json!(render_board(&board));
+        }
+        if representation.includes_matrix() {
+            state["board"] = json!(board);
         }
+        state
     }
+}
 
-    async fn execute_turn(&mut self, agent: &AIAgent, player: Player) -> Result<(), String> {
-        let turn_start = Instant::now();
-        self.state.turn_number += 1;
+impl GameEngine for TicTacToe {
+    type Move = TicTacToeMove;
 
-        // Create game state JSON
-        let state_json = self.state_to_json();
-        let state_before = state_json.clone();
+    fn game_id(&self) -> &str {
+        &self.game_id
+    }
 
-        // Create move schema
-        let move_schema = json!({
+    fn move_schema(&self) -> Value {
+        with_reasoning(json!({
             "type": "object",
             "properties": {
                 "row": {
@@ -180,178 +209,172 @@ impl TicTacToe {
                 }
             },
             "required": ["row", "col"]
-        });
+        }))
+    }
 
-        // Create move request
-        let move_request = MoveRequest {
-            turn_index: self.state.turn_number,
-            game_id: self.game_id.clone(),
-            state: state_json,
-            expected_move_schema: move_schema,
-        };
+    fn state_json(&self) -> Value {
+        self.state_to_json()
+    }
 
-        // Get move from agent
-        let move_response: MoveResponse = agent
-            .execute_turn(&move_request)
-            .await
-            .map_err(|e| format!("Agent error: {}", e))?;
-
-        let time_taken = turn_start.elapsed();
-
-        // Parse move
-        let move_data = move_response.chosen_move;
-        let row = move_data
-            .get("row")
-            .and_then(|v| v.as_u64())
-            .ok_or_else(|| "Missing or invalid 'row' field".to_string())? as u32;
-        let col = move_data
-            .get("col")
-            .and_then(|v| v.as_u64())
-            .ok_or_else(|| "Missing or invalid 'col' field".to_string())? as u32;
-
-        // Validate move
-        let move_valid = self.is_valid_move(row, col);
-        let error_message = if !move_valid {
-            Some(format!("Invalid move: row={}, col={}", row, col))
-        } else {
-            None
-        };
+    fn parse_move(&self, move_data: &Value) -> Result<TicTacToeMove, String> {
+        TicTacToe::parse_move(move_data)
+    }
 
-        // Apply move if valid
-        let state_after = if move_valid {
-            self.state.board[row as usize][col as usize] = Some(player);
-            self.state_to_json()
-        } else {
-            state_before.clone()
-        };
+    fn legal_moves(&self) -> Option<Value> {
+        Some(open_cells(&self.state.board))
+    }
+}
 
-        // Record turn stats
-        let turn_stats = TurnStats {
-            turn_number: self.state.turn_number,
-            player: agent.name().to_string(),
-            move_made: move_data.clone(),
-            time_taken_ms: time_taken.as_millis() as u64,
-            move_valid,
-            error_message: error_message.clone(),
-            state_before,
-            state_after,
-            diagnostics: move_response.diagnostics,
-        };
+impl TurnBasedGame for TicTacToe {
+    const SEATS: [&'static str; 2] = ["X", "O"];
+
+    fn rules(&self) -> TurnRules {
+        self.config.rules
+    }
 
-        self.stats.add_turn(turn_stats);
+    fn max_turns(&self) -> u32 {
+        self.config.board_size * self.config.board_size
+    }
+
+    fn turn_number(&self) -> u32 {
+        self.state.turn_number
+    }
 
-        if !move_valid {
-            return Err(error_message.unwrap_or_else(|| "Invalid move".to_string()));
+    fn set_turn_number(&mut self, turn_number: u32) {
+        self.state.turn_number = turn_number;
+    }
+
+    fn current_seat(&self) -> usize {
+        match self.state.current_player {
+            Player::X => 0,
+            Player::O => 1,
         }
+    }
+
+    fn next_player(&mut self) {
+        self.state.current_player = self.state.current_player.other();
+    }
+
+    fn notation(&self, mv: &TicTacToeMove) -> String {
+        format!("{},{}", mv.row, mv.col)
+    }
 
+    fn apply_move(&mut self, TicTacToeMove { row, col }: TicTacToeMove) -> Result<(), String> {
+        if !self.is_valid_move(row, col) {
+            return Err(format!("Invalid move: row={}, col={}", row, col));
+        }
+        self.state.board[row as usize][col as usize] = Some(self.state.current_player);
         Ok(())
     }
 
-    fn is_valid_move(&self, row: u32, col: u32) -> bool {
-        if row >= self.config.board_size || col >= self.config.board_size {
-            return false;
+    fn is_over(&self) -> Option<Outcome> {
+        if self.check_win() {
+            Some(Outcome::Winner(self.current_seat()))
+        } else if self.state.turn_number >= self.max_turns() {
+            Some(Outcome::Draw)
+        } else {
+            None
         }
-        self.state.board[row as usize][col as usize].is_none()
     }
 
-    fn check_win(&self) -> bool {
-        let board_size = self.config.board_size as usize;
-        let win_length = self.config.win_length as usize;
-        let player = self.state.current_player;
+    fn finish(&mut self, winner: Option<usize>) {
+        self.state.game_over = true;
+        self.state.winner = winner.map(|seat| [Player::X, Player::O][seat]);
+    }
+}
+
+/// Every empty cell as a `{row, col}` move, in row-major order. Also used
+/// by Gomoku.
+pub(crate) fn open_cells<T>(board: &[Vec<Option<T>>]) -> Value {
+    let cells: Vec<Value> = board
+        .iter()
+        .enumerate()
+        .flat_map(|(row, cells)| {
+            cells
+                .iter()
+                .enumerate()
+                .filter(|(_, cell)| cell.is_none())
+                .map(move |(col, _)| json!({"row": row, "col": col}))
+        })
+        .collect();
+    json!(cells)
+}
+
+/// Whether `player` has `win_length` in a row on a square board, in any
+/// direction. Also used by Gomoku.
+pub(crate) fn has_line<T: PartialEq>(board: &[Vec<Option<T>>], player: &T, win_length: usize) -> bool {
+    let board_size = board.len();
+    let player = Some(player);
+
+    // Check rows
+    for row in board {
+        let mut count = 0;
+        for cell in row {
+            if cell.as_ref() == player {
+                count += 1;
+                if count >= win_length {
+                    return true;
+                }
+            } else {
+                count = 0;
+            }
+        }
+    }
+
+    // Check columns
+    for col in 0..board_size {
+        let mut count = 0;
+        for row in board {
+            if row[col].as_ref() == player {
+                count += 1;
+                if count >= win_length {
+                    return true;
+                }
+            } else {
+                count = 0;
+            }
+        }
+    }
 
-        // Check rows
-        for row in 0..board_size {
+    // Check diagonals (top-left to bottom-right)
+    for start_row in 0..=board_size.saturating_sub(win_length) {
+        for start_col in 0..=board_size.saturating_sub(win_length) {
             let mut count = 0;
-            for col in 0..board_size {
-                if self.state.board[row][col] == Some(player) {
+            for i in 0..win_length {
+                let row = start_row + i;
+                let col = start_col + i;
+                if row < board_size && col < board_size && board[row][col].as_ref() == player {
                     count += 1;
                     if count >= win_length {
                         return true;
                     }
                 } else {
-                    count = 0;
+                    break;
                 }
             }
         }
+    }
 
-        // Check columns
-        for col in 0..board_size {
+    // Check diagonals (top-right to bottom-left)
+    for start_row in 0..=board_size.saturating_sub(win_length) {
+        for start_col in (win_length - 1)..board_size {
             let mut count = 0;
-            for row in 0..board_size {
-                if self.state.board[row][col] == Some(player) {
+            for i in 0..win_length {
+                let row = start_row + i;
+                let col = start_col.saturating_sub(i);
+                if row < board_size && col < board_size && board[row][col].as_ref() == player {
                     count += 1;
                     if count >= win_length {
                         return true;
                     }
                 } else {
-                    count = 0;
-                }
-            }
-        }
-
-        // Check diagonals (top-left to bottom-right)
-        for start_row in 0..=board_size.saturating_sub(win_length) {
-            for start_col in 0..=board_size.saturating_sub(win_length) {
-                let mut count = 0;
-                for i in 0..win_length {
-                    let row = start_row + i;
-                    let col = start_col + i;
-                    if row < board_size && col < board_size && self.state.board[row][col] == Some(player) {
-                        count += 1;
-                        if count >= win_length {
-                            return true;
-                        }
-                    } else {
-                        break;
-                    }
-                }
-            }
-        }
-
-        // Check diagonals (top-right to bottom-left)
-        for start_row in 0..=board_size.saturating_sub(win_length) {
-            for start_col in (win_length - 1)..board_size {
-                let mut count = 0;
-                for i in 0..win_length {
-                    let row = start_row + i;
-                    let col = start_col.saturating_sub(i);
-                    if row < board_size && col < board_size && self.state.board[row][col] == Some(player) {
-                        count += 1;
-                        if count >= win_length {
-                            return true;
-                        }
-                    } else {
-                        break;
-                    }
+                    break;
                 }
             }
         }
-
-        false
     }
 
-    fn state_to_json(&self) -> Value {
-        let board: Vec<Vec<Option<String>>> = self
-            .state
-            .board
-            .iter()
-            .map(|row| {
-                row.iter()
-                    .map(|cell| cell.map(|p| p.to_string()))
-                    .collect()
-            })
-            .collect();
-
-        json!({
-            "board": board,
-            "current_player": self.state.current_player.to_string(),
-            "turn_number": self.state.turn_number,
-            "game_over": self.state.game_over,
-            "winner": self.state.winner.map(|p| p.to_string()),
-            "board_size": self.config.board_size,
-            "win_length": self.config.win_length,
-        })
-    }
+    false
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -364,6 +387,8 @@ pub struct TicTacToeResult {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::games::stats::TurnStats;
+    use crate::games::engine::DEFAULT_MAX_INVALID_MOVES;
 
     #[test]
     fn test_player_as_str() {
@@ -392,7 +417,7 @@ mod tests {
         assert_eq!(game.state.board[0].len(), 3);
         assert_eq!(game.state.current_player, Player::X);
         assert_eq!(game.state.turn_number, 0);
-        assert_eq!(game.state.game_over, false);
+        assert!(!game.state.game_over);
         assert_eq!(game.state.winner, None);
     }
 
@@ -401,6 +426,7 @@ mod tests {
         let config = TicTacToeConfig {
             board_size: 5,
             win_length: 4,
+            ..Default::default()
         };
         let game = TicTacToe::new(config);
         
@@ -516,6 +542,7 @@ mod tests {
         let config = TicTacToeConfig {
             board_size: 5,
             win_length: 4,
+            ..Default::default()
         };
         let mut game = TicTacToe::new(config);
         
@@ -543,11 +570,313 @@ mod tests {
         assert_eq!(json["win_length"], 3);
     }
 
+    #[test]
+    fn test_state_to_json_parses_as_board() {
+        let mut game = TicTacToe::new(TicTacToeConfig::default());
+        game.state.board[1][2] = Some(Player::O);
+
+        let board = crate::games::parsing::parse_board(&game.state_to_json()).unwrap();
+        assert_eq!(board.len(), 3);
+        assert_eq!(board[1][2].as_deref(), Some("O"));
+        assert_eq!(crate::games::parsing::empty_cells(&board).len(), 8);
+    }
+
+    #[test]
+    fn test_board_representation_both() {
+        let mut game = TicTacToe::new(TicTacToeConfig {
+            board_representation: BoardRepresentation::Both,
+            ..Default::default()
+        });
+        game.state.board[0][0] = Some(Player::X);
+        game.state.board[1][2] = Some(Player::O);
+
+        let state = game.state_to_json();
+        assert_eq!(crate::games::parsing::parse_board(&state).unwrap()[1][2].as_deref(), Some("O"));
+        assert_eq!(state["board_text"], "  0 1 2\n0 X . .\n1 . . O\n2 . . .");
+    }
+
+    #[test]
+    fn test_board_representation_defaults_to_matrix() {
+        let state = TicTacToe::new(TicTacToeConfig::default()).state_to_json();
+        assert!(state["board"].is_array());
+        assert!(state.get("board_text").is_none());
+    }
+
+    #[test]
+    fn test_new_honors_first_player() {
+        let config = TicTacToeConfig {
+            first_player: Player::O,
+            ..Default::default()
+        };
+        let game = TicTacToe::new(config);
+        assert_eq!(game.state.current_player, Player::O);
+    }
+
+    #[tokio::test]
+    async fn test_first_player_o_agent_takes_first_turn() {
+        use crate::agents::socket::scripted_socket_agent;
+
+        let x = scripted_socket_agent("x", vec![json!({"row": 1, "col": 0}), json!({"row": 1, "col": 1})]).await;
+        let o = scripted_socket_agent(
+            "o",
+            vec![json!({"row": 0, "col": 0}), json!({"row": 0, "col": 1}), json!({"row": 0, "col": 2})],
+        )
+        .await;
+        let config = TicTacToeConfig {
+            first_player: Player::O,
+            ..Default::default()
+        };
+
+        let result = TicTacToe::new(config)
+            .play_game(vec![AIAgent::Socket(x), AIAgent::Socket(o)])
+            .await;
+
+        let players: Vec<&str> = result.stats.turns.iter().map(|t| t.player.as_str()).collect();
+        assert_eq!(players, vec!["o", "x", "o", "x", "o"]);
+        assert_eq!(result.winner.as_deref(), Some("o (O)"));
+    }
+
+    #[tokio::test]
+    async fn test_retry_resends_turn_with_last_error() {
+        use crate::agents::socket::scripted_socket_agent;
+
+        let x = scripted_socket_agent(
+            "x",
+            vec![json!({"row": 5, "col": 5}), json!({"row": 0, "col": 0}), json!({"row": 0, "col": 1}), json!({"row": 0, "col": 2})],
+        )
+        .await;
+        let o = scripted_socket_agent("o", vec![json!({"row": 1, "col": 0}), json!({"row": 1, "col": 1})]).await;
+        let config = TicTacToeConfig {
+            rules: TurnRules {
+                max_retries: Some(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let result = TicTacToe::new(config)
+            .play_game(vec![AIAgent::Socket(x), AIAgent::Socket(o)])
+            .await;
+
+        let (rejected, retry) = (&result.stats.turns[0], &result.stats.turns[1]);
+        assert!(!rejected.move_valid);
+        assert_eq!(retry.turn_number, rejected.turn_number);
+        let prompt: Value = serde_json::from_str(retry.prompt.as_deref().unwrap()).unwrap();
+        assert_eq!(prompt["state"]["last_error"], json!("Invalid move: row=5, col=5"));
+        // Only the rejected attempt was told about an error
+        assert!(!result.stats.turns[2].prompt.as_deref().unwrap().contains("last_error"));
+        assert_eq!(result.winner.as_deref(), Some("x (X)"));
+        // The rejected move is left out
+        assert_eq!(result.stats.move_list, ["X:0,0", "O:1,0", "X:0,1", "O:1,1", "X:0,2"]);
+    }
+
+    #[tokio::test]
+    async fn test_requests_list_open_cells() {
+        use crate::agents::socket::scripted_socket_agent;
+
+        let x = scripted_socket_agent("x", vec![json!({"row": 0, "col": 0}), json!({"row": 1, "col": 1}), json!({"row": 2, "col": 2})]).await;
+        let o = scripted_socket_agent("o", vec![json!({"row": 0, "col": 1}), json!({"row": 0, "col": 2})]).await;
+        let result = TicTacToe::new(TicTacToeConfig::default())
+            .play_game(vec![AIAgent::Socket(x), AIAgent::Socket(o)])
+            .await;
+
+        // X's second move, with (0, 0) and (0, 1) taken
+        let prompt: Value = serde_json::from_str(result.stats.turns[2].prompt.as_deref().unwrap()).unwrap();
+        let open: Vec<Value> = [(0, 2), (1, 0), (1, 1), (1, 2), (2, 0), (2, 1), (2, 2)]
+            .iter()
+            .map(|(row, col)| json!({"row": row, "col": col}))
+            .collect();
+        assert_eq!(prompt["legal_moves"], json!(open));
+    }
+
+    #[tokio::test]
+    async fn test_too_many_invalid_moves_forfeit() {
+        use crate::agents::scripted::ScriptedAgent;
+
+        // X only ever plays off the board; O never gets a turn
+        let agents = vec![
+            AIAgent::Scripted(ScriptedAgent::new("x", vec![json!({"row": 5, "col": 5}); 10])),
+            AIAgent::Scripted(ScriptedAgent::new("o", Vec::new())),
+        ];
+        let result = TicTacToe::new(TicTacToeConfig::default()).play_game(agents).await;
+
+        assert_eq!(result.winner.as_deref(), Some("o (O) (opponent forfeited)"));
+        assert_eq!(result.stats.invalid_moves, DEFAULT_MAX_INVALID_MOVES + 1);
+        assert!(result.stats.turns.iter().all(|t| t.player == "x" && !t.move_valid));
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_retries_forfeit_the_game() {
+        use crate::agents::socket::scripted_socket_agent;
+
+        let x = scripted_socket_agent("x", vec![json!({"row": 5, "col": 5}); 3]).await;
+        let o = scripted_socket_agent("o", vec![]).await;
+        let config = TicTacToeConfig {
+            rules: TurnRules {
+                max_retries: Some(2),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let result = TicTacToe::new(config)
+            .play_game(vec![AIAgent::Socket(x), AIAgent::Socket(o)])
+            .await;
+
+        assert_eq!(result.stats.invalid_moves, 3);
+        assert_eq!(result.winner.as_deref(), Some("o (O) (opponent forfeited)"));
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_move_is_invalid_and_retried() {
+        use crate::agents::socket::{scripted_socket_agent, stalling_socket_agent};
+
+        // O answers once, then hangs until it forfeits
+        let x = scripted_socket_agent("x", vec![json!({"row": 0, "col": 0}), json!({"row": 0, "col": 1})]).await;
+        let o = stalling_socket_agent("o", vec![json!({"row": 1, "col": 1})]).await;
+        let config = TicTacToeConfig {
+            rules: TurnRules {
+                max_retries: Some(1),
+                move_timeout_ms: Some(50),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let result = TicTacToe::new(config)
+            .play_game(vec![AIAgent::Socket(x), AIAgent::Socket(o)])
+            .await;
+
+        let timed_out: Vec<&TurnStats> = result.stats.turns.iter().filter(|t| !t.move_valid).collect();
+        assert_eq!(timed_out.len(), 2);
+        assert_eq!(timed_out[0].error_message.as_deref(), Some("agent timed out after 50 ms"));
+        assert_eq!(timed_out[0].move_made, Value::Null);
+        assert_eq!(result.winner.as_deref(), Some("x (X) (opponent forfeited)"));
+    }
+
+    #[tokio::test]
+    async fn test_state_echo_is_recorded_as_invalid_move() {
+        use crate::agents::socket::scripted_socket_agent;
+        use crate::games::parsing::STATE_ECHO_ERROR;
+
+        let echoed_state = TicTacToe::new(TicTacToeConfig::default()).state_to_json();
+        let x = scripted_socket_agent(
+            "x",
+            vec![echoed_state, json!({"row": 0, "col": 0}), json!({"row": 0, "col": 1}), json!({"row": 0, "col": 2})],
+        )
+        .await;
+        let o = scripted_socket_agent("o", vec![json!({"row": 1, "col": 0}), json!({"row": 1, "col": 1})]).await;
+
+        let result = TicTacToe::new(TicTacToeConfig::default())
+            .play_game(vec![AIAgent::Socket(x), AIAgent::Socket(o)])
+            .await;
+
+        let first = &result.stats.turns[0];
+        assert!(!first.move_valid);
+        assert_eq!(first.error_message.as_deref(), Some(STATE_ECHO_ERROR));
+        assert_eq!(result.stats.invalid_moves, 1);
+        assert_eq!(result.winner.as_deref(), Some("x (X)"));
+    }
+
+    #[tokio::test]
+    async fn test_served_model_is_recorded_per_turn() {
+        use crate::agents::socket::{scripted_socket_agent, scripted_socket_replies};
+
+        let served = json!({"model": "gpt-4o-2024-08-06", "system_fingerprint": "fp_abc123"});
+        let x = scripted_socket_replies(
+            "x",
+            [(0, 0), (1, 0), (2, 0)]
+                .iter()
+                .map(|(row, col)| json!({"chosen_move": {"row": row, "col": col}, "served_model": served}))
+                .collect(),
+        )
+        .await;
+        let o = scripted_socket_agent("o", vec![json!({"row": 0, "col": 1}), json!({"row": 1, "col": 1})]).await;
+
+        let result = TicTacToe::new(TicTacToeConfig::default())
+            .play_game(vec![AIAgent::Socket(x), AIAgent::Socket(o)])
+            .await;
+
+        let first = &result.stats.turns[0];
+        assert_eq!(first.served_model.as_ref().map(|m| m.model.as_str()), Some("gpt-4o-2024-08-06"));
+        assert_eq!(result.stats.turns[1].served_model, None);
+        let saved = serde_json::to_value(&result.stats).unwrap();
+        assert_eq!(saved["turns"][0]["served_model"], served);
+    }
+
     #[test]
     fn test_config_default() {
         let config = TicTacToeConfig::default();
         assert_eq!(config.board_size, 3);
         assert_eq!(config.win_length, 3);
     }
-}
 
+    mod properties {
+        use super::*;
+        use crate::games::parsing::coerce_u32;
+        use crate::games::test_support::{arb_board, arb_json, line_start, plant_line, reference_has_line, DIRECTIONS};
+        use proptest::prelude::*;
+
+        fn arb_cell() -> impl Strategy<Value = Option<Player>> + Clone {
+            prop_oneof![Just(None), Just(Some(Player::X)), Just(Some(Player::O))]
+        }
+
+        fn arb_player() -> impl Strategy<Value = Player> {
+            prop_oneof![Just(Player::X), Just(Player::O)]
+        }
+
+        fn arb_game() -> impl Strategy<Value = TicTacToe> {
+            (3u32..7)
+                .prop_flat_map(|n| (Just(n), 2..=n, arb_board(n as usize, n as usize, arb_cell())))
+                .prop_map(|(board_size, win_length, board)| {
+                    let mut game = TicTacToe::new(TicTacToeConfig { board_size, win_length, ..Default::default() });
+                    game.state.board = board;
+                    game
+                })
+        }
+
+        proptest! {
+            #[test]
+            fn parse_move_never_panics(value in arb_json()) {
+                if let Ok(mv) = TicTacToe::parse_move(&value) {
+                    prop_assert_eq!(coerce_u32(&value["row"]), Some(mv.row));
+                    prop_assert_eq!(coerce_u32(&value["col"]), Some(mv.col));
+                }
+            }
+
+            #[test]
+            fn parse_move_accepts_well_formed(row in any::<u32>(), col in any::<u32>()) {
+                let mv = TicTacToe::parse_move(&json!({"row": row, "col": col})).unwrap();
+                prop_assert_eq!((mv.row, mv.col), (row, col));
+            }
+
+            #[test]
+            fn parse_move_rejects_values_past_u32(row in (u32::MAX as u64 + 1)..) {
+                let move_data = json!({"row": row, "col": 0});
+                prop_assert!(TicTacToe::parse_move(&move_data).is_err());
+            }
+
+            #[test]
+            fn check_win_matches_reference(mut game in arb_game(), player in arb_player()) {
+                game.state.current_player = player;
+                let expected = reference_has_line(&game.state.board, &player, game.config.win_length as usize);
+                prop_assert_eq!(game.check_win(), expected);
+            }
+
+            #[test]
+            fn planted_line_always_wins(
+                mut game in arb_game(),
+                player in arb_player(),
+                seed in (any::<usize>(), any::<usize>()),
+                dir in 0usize..4,
+            ) {
+                let win_length = game.config.win_length as usize;
+                let (rows, cols) = (game.state.board.len(), game.state.board[0].len());
+                let start = line_start(rows, cols, DIRECTIONS[dir], win_length, seed);
+                prop_assert!(plant_line(&mut game.state.board, &player, start, DIRECTIONS[dir], win_length));
+                game.state.current_player = player;
+                prop_assert!(game.check_win());
+            }
+        }
+    }
+}
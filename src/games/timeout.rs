@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use crate::agent::{AIAgent, AgentResult, MoveRequest, MoveResponse};
+
+/// Error recorded for a move that didn't arrive in time
+pub fn timeout_message(timeout_ms: u64) -> String {
+    format!("agent timed out after {} ms", timeout_ms)
+}
+
+/// Ask `agent` for a move, giving up after `timeout_ms` if one is set so a
+/// hung call can't stall the game. The outer `Err` is the timeout message.
+pub async fn request_move(
+    agent: &AIAgent,
+    request: &MoveRequest,
+    timeout_ms: Option<u64>,
+) -> Result<AgentResult<MoveResponse>, String> {
+    let Some(timeout_ms) = timeout_ms else {
+        return Ok(agent.execute_turn(request).await);
+    };
+    tokio::time::timeout(Duration::from_millis(timeout_ms), agent.execute_turn(request))
+        .await
+        .map_err(|_| timeout_message(timeout_ms))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::socket::{scripted_socket_agent, stalling_socket_agent};
+    use serde_json::json;
+
+    fn request() -> MoveRequest {
+        MoveRequest {
+            turn_index: 1,
+            game_id: "ttt_test".to_string(),
+            state: json!({}),
+            expected_move_schema: json!({}),
+            legal_moves: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prompt_reply_is_returned() {
+        let agent = AIAgent::Socket(scripted_socket_agent("fast", vec![json!({"row": 0, "col": 0})]).await);
+        let response = request_move(&agent, &request(), Some(5_000)).await.unwrap().unwrap();
+        assert_eq!(response.chosen_move, json!({"row": 0, "col": 0}));
+    }
+
+    #[tokio::test]
+    async fn test_silent_agent_times_out() {
+        let agent = AIAgent::Socket(stalling_socket_agent("hung", vec![]).await);
+        let err = request_move(&agent, &request(), Some(20)).await.unwrap_err();
+        assert_eq!(err, "agent timed out after 20 ms");
+    }
+}
@@ -0,0 +1,102 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use serde_json::json;
+
+use super::stats::TurnStats;
+
+/// Append-only JSONL log of every turn as it is played. Each line is flushed
+/// straight away, so a batch that crashes still leaves a usable log. Clones
+/// share the same file.
+#[derive(Clone, Debug)]
+pub struct TranscriptWriter {
+    file: Arc<Mutex<BufWriter<File>>>,
+}
+
+impl TranscriptWriter {
+    /// Open `path` for appending, creating it if needed
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        Ok(Self { file: Arc::new(Mutex::new(BufWriter::new(file))) })
+    }
+
+    /// Append one turn of game `game_id`
+    pub fn record(&self, game_id: &str, turn: &TurnStats) {
+        let line = json!({
+            "game_id": game_id,
+            "turn_number": turn.turn_number,
+            "player": turn.player,
+            "move_made": turn.move_made,
+            "state_before": turn.state_before,
+            "state_after": turn.state_after,
+            "diagnostics": turn.diagnostics,
+        });
+        let mut file = self.file.lock().unwrap();
+        if let Err(e) = writeln!(file, "{}", line).and_then(|_| file.flush()) {
+            tracing::error!("Failed to write transcript: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::AIAgent;
+    use crate::agents::socket::scripted_socket_agent;
+    use crate::games::Game;
+    use serde_json::Value;
+
+    #[tokio::test]
+    async fn test_every_turn_is_logged_as_it_is_played() {
+        let path = std::env::temp_dir().join(format!("ai_arena_transcript_{}.jsonl", uuid::Uuid::new_v4()));
+        let transcript = TranscriptWriter::open(&path).unwrap();
+
+        let x_moves = vec![json!({"row": 0, "col": 0}), json!({"row": 0, "col": 1}), json!({"row": 0, "col": 2})];
+        // O's second move is on an occupied cell and still logged
+        let o_moves = vec![json!({"row": 1, "col": 0}), json!({"row": 0, "col": 0}), json!({"row": 1, "col": 1})];
+        let x = scripted_socket_agent("x", x_moves).await;
+        let o = scripted_socket_agent("o", o_moves).await;
+        let result = Game::new("TicTacToe").unwrap()
+            .play_recorded(vec![AIAgent::Socket(x), AIAgent::Socket(o)], Some(&transcript), None)
+            .await;
+        assert_eq!(result.winner().map(String::as_str), Some("x (X)"));
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let lines: Vec<Value> = contents.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), result.stats().turns.len());
+        assert_eq!(lines[0]["player"], "x");
+        assert_eq!(lines[0]["move_made"], json!({"row": 0, "col": 0}));
+        assert_eq!(lines[0]["state_after"]["board"][0][0], "X");
+        assert!(lines[0]["game_id"].as_str().unwrap().starts_with("ttt_"));
+        assert!(lines.iter().all(|l| l["game_id"] == lines[0]["game_id"]));
+        assert_eq!(lines[3]["state_before"], lines[3]["state_after"]);
+    }
+
+    #[tokio::test]
+    async fn test_rock_paper_scissors_logs_both_players() {
+        let path = std::env::temp_dir().join(format!("ai_arena_transcript_{}.jsonl", uuid::Uuid::new_v4()));
+        let transcript = TranscriptWriter::open(&path).unwrap();
+
+        let one = scripted_socket_agent("one", vec![json!({"choice": "rock"}); 2]).await;
+        let two = scripted_socket_agent("two", vec![json!({"choice": "scissors"}); 2]).await;
+        Game::new("RockPaperScissors").unwrap()
+            .play_recorded(vec![AIAgent::Socket(one), AIAgent::Socket(two)], Some(&transcript), None)
+            .await;
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let players: Vec<Value> = contents
+            .lines()
+            .map(|l| serde_json::from_str::<Value>(l).unwrap()["player"].clone())
+            .collect();
+        assert_eq!(players, ["one", "two", "one", "two"]);
+    }
+}
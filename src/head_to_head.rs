@@ -0,0 +1,193 @@
+use std::fmt;
+
+use crate::agent_config::AIAgentConfig;
+use crate::games::aggregate::AggregateStats;
+use crate::games::{Game, TestResult};
+use crate::stopping::{WilsonInterval, Z_95, wilson_interval};
+use crate::tournament::{credit_entrants, entrant_label};
+
+/// Two models' record against each other over games with alternating seats
+#[derive(Clone, Debug, PartialEq)]
+pub struct HeadToHead {
+    pub game: String,
+    /// Entrant labels of model A then model B
+    pub labels: [String; 2],
+    /// Totals with every turn and win credited to A or B rather than a seat
+    pub stats: AggregateStats,
+}
+
+impl HeadToHead {
+    /// Games won by A, then by B
+    pub fn wins(&self) -> [u32; 2] {
+        self.labels.clone().map(|label| self.stats.wins.get(&label).copied().unwrap_or(0))
+    }
+
+    /// Games that finished without an error
+    pub fn decided_games(&self) -> u32 {
+        self.stats.games - self.stats.errors
+    }
+
+    /// A's score per finished game, counting a draw as half a win
+    pub fn score_rate(&self) -> f64 {
+        self.points() / self.decided_games().max(1) as f64
+    }
+
+    /// 95% Wilson interval on A's score rate
+    pub fn interval(&self) -> WilsonInterval {
+        wilson_interval(self.points(), self.decided_games(), Z_95)
+    }
+
+    fn points(&self) -> f64 {
+        self.wins()[0] as f64 + 0.5 * self.stats.draws as f64
+    }
+
+    /// Which model the games favour, if the interval leaves out an even match
+    pub fn verdict(&self) -> String {
+        let ci = self.interval();
+        if self.decided_games() == 0 {
+            "No games finished, so there is nothing to compare".to_string()
+        } else if ci.lower > 0.5 {
+            format!("{} is stronger (95% CI excludes an even match)", self.labels[0])
+        } else if ci.upper < 0.5 {
+            format!("{} is stronger (95% CI excludes an even match)", self.labels[1])
+        } else {
+            "No significant difference at 95%; play more games to separate them".to_string()
+        }
+    }
+}
+
+impl fmt::Display for HeadToHead {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b] = &self.labels;
+        let [a_wins, b_wins] = self.wins();
+        let ci = self.interval();
+        writeln!(f, "{}: {} vs {} over {} game(s)", self.game, a, b, self.stats.games)?;
+        writeln!(f, "Wins: {} {}, {} {}", a, a_wins, b, b_wins)?;
+        writeln!(f, "Draws: {}", self.stats.draws)?;
+        if self.stats.errors > 0 {
+            writeln!(f, "Errors: {}", self.stats.errors)?;
+        }
+        writeln!(f, "Avg turns: {:.1}", self.stats.average_turns())?;
+        for label in &self.labels {
+            writeln!(
+                f,
+                "Invalid moves by {}: {} of {} turns ({:.1}%)",
+                label,
+                self.stats.agent_invalid_moves.get(label).copied().unwrap_or(0),
+                self.stats.agent_turns.get(label).copied().unwrap_or(0),
+                self.stats.agent_invalid_move_rate(label) * 100.0
+            )?;
+        }
+        writeln!(
+            f,
+            "{} score rate: {:.2}, 95% CI [{:.2}, {:.2}]",
+            a,
+            self.score_rate(),
+            ci.lower,
+            ci.upper
+        )?;
+        write!(f, "{}", self.verdict())
+    }
+}
+
+/// Play `models` (A then B) against each other `games` times at `game`,
+/// with A moving first in even-numbered games and B in odd ones, using
+/// `play` for each game with the agents in seat order
+pub async fn run_head_to_head(
+    models: [AIAgentConfig; 2],
+    game: &Game,
+    games: u32,
+    play: impl AsyncFn(&Game, Vec<AIAgentConfig>) -> TestResult,
+) -> Result<HeadToHead, String> {
+    let labels = models.clone().map(|model| entrant_label(&model));
+    if labels[0] == labels[1] {
+        return Err(format!("Can't compare {} with itself", labels[0]));
+    }
+    let mut stats = AggregateStats::default();
+    for number in 0..games {
+        let (seated, entrants) = if number % 2 == 0 {
+            (models.to_vec(), labels.to_vec())
+        } else {
+            (vec![models[1].clone(), models[0].clone()], vec![labels[1].clone(), labels[0].clone()])
+        };
+        tracing::info!("Game {} of {}: {} moves first", number + 1, games, entrants[0]);
+        let mut result = play(game, seated.clone()).await;
+        credit_entrants(&mut result, &seated, &entrants);
+        stats.add(&result);
+    }
+    Ok(HeadToHead {
+        game: game.name().to_string(),
+        labels,
+        stats,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::AIAgent;
+    use crate::agents::scripted::ScriptedAgent;
+    use crate::tournament::parse_entrant;
+    use serde_json::json;
+
+    fn script(cells: &[(u32, u32)]) -> Vec<serde_json::Value> {
+        cells.iter().map(|(row, col)| json!({"row": row, "col": col})).collect()
+    }
+
+    #[tokio::test]
+    async fn test_alternates_seats_and_credits_each_model() {
+        let models = [parse_entrant("openai:a").unwrap(), parse_entrant("ollama:b").unwrap()];
+        let seatings = std::cell::RefCell::new(Vec::new());
+        // The first mover always wins; B's first move is invalid when it moves second
+        let report = run_head_to_head(models, &Game::new("TicTacToe").unwrap(), 4, async |game: &Game, seated| {
+            seatings.borrow_mut().push(seated[0].model.clone());
+            let second = if seated[1].model == "b" { vec![(0, 0), (1, 0), (1, 1)] } else { vec![(1, 0), (1, 1)] };
+            let agents = vec![
+                AIAgent::Scripted(ScriptedAgent::new(seated[0].agent_name(0), script(&[(0, 0), (0, 1), (0, 2)]))),
+                AIAgent::Scripted(ScriptedAgent::new(seated[1].agent_name(1), script(&second))),
+            ];
+            game.play_with_agents(agents).await
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(*seatings.borrow(), ["a", "b", "a", "b"]);
+        assert_eq!(report.wins(), [2, 2]);
+        assert_eq!(report.stats.draws, 0);
+        assert_eq!(report.stats.agent_invalid_moves.get("b (Ollama)"), Some(&2));
+        assert!(report.stats.agent_invalid_move_rate("b (Ollama)") > 0.0);
+        assert_eq!(report.stats.agent_invalid_move_rate("a (OpenAI)"), 0.0);
+        assert_eq!(report.score_rate(), 0.5);
+        assert!(report.verdict().starts_with("No significant difference"));
+        assert!(report.to_string().contains("Wins: a (OpenAI) 2, b (Ollama) 2"));
+    }
+
+    #[test]
+    fn test_verdict_names_the_stronger_model() {
+        let labels = ["a (OpenAI)".to_string(), "b (Ollama)".to_string()];
+        let report = |a_wins, b_wins| HeadToHead {
+            game: "ConnectFour".to_string(),
+            labels: labels.clone(),
+            stats: AggregateStats {
+                games: a_wins + b_wins,
+                wins: [(labels[0].clone(), a_wins), (labels[1].clone(), b_wins)].into(),
+                ..Default::default()
+            },
+        };
+        assert!(report(18, 2).verdict().starts_with("a (OpenAI) is stronger"));
+        assert!(report(2, 18).verdict().starts_with("b (Ollama) is stronger"));
+        assert!(report(6, 4).verdict().starts_with("No significant difference"));
+        assert!(report(0, 0).verdict().starts_with("No games finished"));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_the_same_model_twice() {
+        let model = parse_entrant("openai:a").unwrap();
+        let err = run_head_to_head([model.clone(), model], &Game::new("TicTacToe").unwrap(), 2, async |game: &Game, _| {
+            game.failed("not played".to_string())
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(err, "Can't compare a (OpenAI) with itself");
+    }
+}
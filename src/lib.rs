@@ -0,0 +1,24 @@
+pub mod agent;
+pub mod agents;
+pub mod batch_progress;
+pub mod budget;
+pub mod agent_config;
+pub mod games;
+pub mod head_to_head;
+pub mod cooldown;
+pub mod csv_runner;
+pub mod curriculum;
+pub mod decathlon;
+pub mod lobby;
+pub mod logging;
+pub mod progress_bar;
+pub mod rating;
+pub mod replay_diff;
+pub mod result_db;
+pub mod result_sink;
+pub mod results_file;
+pub mod schedule;
+pub mod secrets;
+pub mod standings;
+pub mod stopping;
+pub mod tournament;
@@ -0,0 +1,252 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::net::TcpListener;
+
+use crate::agent::{AIAgent, AgentError};
+use crate::agents::socket::SocketAgent;
+use crate::games::{Game, TestResult, print_game_stats};
+
+/// How long a new connection has to send its registration line
+const REGISTRATION_TIMEOUT: Duration = Duration::from_secs(10);
+/// Longest registration line accepted, newline included
+const MAX_REGISTRATION_BYTES: u64 = 4096;
+
+/// First line an external agent sends after connecting
+#[derive(Debug, Deserialize)]
+pub struct Registration {
+    pub name: String,
+}
+
+/// Listens on a TCP socket and seats external processes as agents
+pub struct Lobby {
+    listener: TcpListener,
+    registered: u32,
+    registration_timeout: Duration,
+}
+
+impl Lobby {
+    pub async fn bind(addr: &str) -> Result<Self, AgentError> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .map_err(|e| AgentError::Internal(format!("failed to bind lobby on {}: {}", addr, e)))?;
+        Ok(Self {
+            listener,
+            registered: 0,
+            registration_timeout: REGISTRATION_TIMEOUT,
+        })
+    }
+
+    pub fn with_registration_timeout(mut self, timeout: Duration) -> Self {
+        self.registration_timeout = timeout;
+        self
+    }
+
+    pub fn local_addr(&self) -> Result<String, AgentError> {
+        self.listener
+            .local_addr()
+            .map(|addr| addr.to_string())
+            .map_err(|e| AgentError::Internal(format!("lobby address: {}", e)))
+    }
+
+    /// Wait for the next connection and read its registration line. A
+    /// connection that is too slow to register, or whose line is too long,
+    /// is dropped.
+    pub async fn accept_agent(&mut self) -> Result<SocketAgent, AgentError> {
+        let (stream, peer) = self
+            .listener
+            .accept()
+            .await
+            .map_err(|e| AgentError::Internal(format!("lobby accept failed: {}", e)))?;
+
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        let mut limited = (&mut reader).take(MAX_REGISTRATION_BYTES);
+        tokio::time::timeout(self.registration_timeout, limited.read_line(&mut line))
+            .await
+            .map_err(|_| AgentError::InvalidRequest(format!("{} did not register in time", peer)))?
+            .map_err(|e| AgentError::Internal(format!("registration read from {} failed: {}", peer, e)))?;
+        if !line.ends_with('\n') && line.len() as u64 == MAX_REGISTRATION_BYTES {
+            return Err(AgentError::InvalidRequest(format!(
+                "registration from {} is longer than {} bytes",
+                peer, MAX_REGISTRATION_BYTES
+            )));
+        }
+        let registration: Registration = serde_json::from_str(line.trim()).map_err(|e| {
+            AgentError::InvalidRequest(format!("bad registration from {}: {}", peer, e))
+        })?;
+
+        self.registered += 1;
+        let name = format!("{}_{}", registration.name, self.registered);
+        Ok(SocketAgent::from_reader(name, reader))
+    }
+
+    /// Wait until `count` agents have registered, in arrival order
+    pub async fn wait_for_agents(&mut self, count: usize) -> Result<Vec<AIAgent>, AgentError> {
+        let mut agents = Vec::with_capacity(count);
+        while agents.len() < count {
+            match self.accept_agent().await {
+                Ok(agent) => {
+                    tracing::info!("Agent registered: {}", agent.name());
+                    agents.push(AIAgent::Socket(agent));
+                }
+                Err(e) => tracing::warn!("Lobby: {}", e),
+            }
+        }
+        Ok(agents)
+    }
+}
+
+/// Serve `matches` games of `game`, pairing agents as they connect
+pub async fn run_lobby(addr: &str, game: Game, matches: u32) -> Result<Vec<TestResult>, String> {
+    let mut lobby = Lobby::bind(addr).await.map_err(|e| e.to_string())?;
+    println!("Lobby listening on {} for {}", lobby.local_addr().map_err(|e| e.to_string())?, game.name());
+
+    let mut results = Vec::new();
+    for match_idx in 0..matches {
+        println!("\n[Match {} of {}] waiting for 2 agents...", match_idx + 1, matches);
+        let agents = lobby.wait_for_agents(2).await.map_err(|e| e.to_string())?;
+        let result = game.play_with_agents(agents).await;
+        print_game_stats(game.name(), &result, None, false);
+        results.push(result);
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpStream;
+
+    /// Connects to the lobby, registers, then answers each request with the next scripted move
+    async fn scripted_client(addr: String, name: &'static str, moves: Vec<Value>) {
+        let mut stream = BufReader::new(TcpStream::connect(&addr).await.unwrap());
+        let hello = format!("{}\n", json!({"name": name}));
+        stream.get_mut().write_all(hello.as_bytes()).await.unwrap();
+
+        for chosen_move in moves {
+            let mut line = String::new();
+            if stream.read_line(&mut line).await.unwrap() == 0 {
+                return;
+            }
+            let reply = format!("{}\n", json!({"chosen_move": chosen_move}));
+            stream.get_mut().write_all(reply.as_bytes()).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lobby_registers_and_round_trips() {
+        let mut lobby = Lobby::bind("127.0.0.1:0").await.unwrap();
+        let addr = lobby.local_addr().unwrap();
+        let client = tokio::spawn(scripted_client(addr, "echo", vec![json!({"column": 3})]));
+
+        let agent = lobby.accept_agent().await.unwrap();
+        assert_eq!(agent.name(), "echo_1");
+
+        let request = crate::agent::MoveRequest {
+            turn_index: 1,
+            game_id: "c4_test".to_string(),
+            state: json!({}),
+            expected_move_schema: json!({}),
+            legal_moves: None,
+        };
+        let response = agent.execute_turn(&request).await.unwrap();
+        assert_eq!(response.chosen_move, json!({"column": 3}));
+        client.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_lobby_plays_full_game_between_socket_agents() {
+        let mut lobby = Lobby::bind("127.0.0.1:0").await.unwrap();
+        let addr = lobby.local_addr().unwrap();
+
+        let x_moves = vec![json!({"row": 0, "col": 0}), json!({"row": 0, "col": 1}), json!({"row": 0, "col": 2})];
+        let o_moves = vec![json!({"row": 1, "col": 0}), json!({"row": 1, "col": 1})];
+        let x = tokio::spawn(scripted_client(addr.clone(), "x", x_moves));
+        // Make sure X registers first so it is seated first
+        let first = lobby.accept_agent().await.unwrap();
+        let o = tokio::spawn(scripted_client(addr, "o", o_moves));
+        let second = lobby.accept_agent().await.unwrap();
+
+        let game = Game::new("TicTacToe").unwrap();
+        let result = game
+            .play_with_agents(vec![AIAgent::Socket(first), AIAgent::Socket(second)])
+            .await;
+
+        assert_eq!(result.winner().map(String::as_str), Some("x_1 (X)"));
+        assert_eq!(result.stats().total_turns(), 5);
+        x.await.unwrap();
+        o.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_lobby_rejects_bad_registration() {
+        let mut lobby = Lobby::bind("127.0.0.1:0").await.unwrap();
+        let addr = lobby.local_addr().unwrap();
+        let client = tokio::spawn(async move {
+            let mut stream = TcpStream::connect(&addr).await.unwrap();
+            stream.write_all(b"not json\n").await.unwrap();
+        });
+
+        let err = lobby.accept_agent().await.err().unwrap();
+        assert!(matches!(err, AgentError::InvalidRequest(_)));
+        client.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_lobby_keeps_bytes_sent_after_registration() {
+        let mut lobby = Lobby::bind("127.0.0.1:0").await.unwrap();
+        let addr = lobby.local_addr().unwrap();
+        let client = tokio::spawn(async move {
+            let mut stream = TcpStream::connect(&addr).await.unwrap();
+            // The first move arrives in the same write as the registration
+            let lines = format!("{}\n{}\n", json!({"name": "eager"}), json!({"chosen_move": {"column": 2}}));
+            stream.write_all(lines.as_bytes()).await.unwrap();
+            stream
+        });
+
+        let agent = lobby.accept_agent().await.unwrap();
+        let _stream = client.await.unwrap();
+        let request = crate::agent::MoveRequest {
+            turn_index: 1,
+            game_id: "c4_test".to_string(),
+            state: json!({}),
+            expected_move_schema: json!({}),
+            legal_moves: None,
+        };
+        let response = agent.execute_turn(&request).await.unwrap();
+        assert_eq!(response.chosen_move, json!({"column": 2}));
+    }
+
+    #[tokio::test]
+    async fn test_lobby_drops_silent_connection() {
+        let lobby = Lobby::bind("127.0.0.1:0").await.unwrap();
+        let mut lobby = lobby.with_registration_timeout(Duration::from_millis(50));
+        let addr = lobby.local_addr().unwrap();
+        let mut stream = TcpStream::connect(&addr).await.unwrap();
+
+        let err = lobby.accept_agent().await.err().unwrap();
+        assert!(err.to_string().contains("did not register in time"));
+        // The lobby hung up on it
+        let mut buf = [0u8; 1];
+        assert_eq!(stream.read(&mut buf).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_lobby_rejects_oversized_registration() {
+        let mut lobby = Lobby::bind("127.0.0.1:0").await.unwrap();
+        let addr = lobby.local_addr().unwrap();
+        let client = tokio::spawn(async move {
+            let mut stream = TcpStream::connect(&addr).await.unwrap();
+            // Never sends a newline; the lobby must stop reading anyway
+            let _ = stream.write_all(&vec![b'a'; 1 << 20]).await;
+        });
+
+        let err = lobby.accept_agent().await.err().unwrap();
+        assert!(err.to_string().contains("longer than 4096 bytes"));
+        client.await.unwrap();
+    }
+}
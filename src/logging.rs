@@ -0,0 +1,77 @@
+use std::io::{self, IsTerminal, Write};
+
+use clap::ValueEnum;
+use tracing::level_filters::LevelFilter;
+
+use crate::progress_bar;
+
+/// How much a run logs. Logs go to stderr; results and tables always go to
+/// stdout, so redirecting stdout to a file keeps just the results.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum LogLevel {
+    /// Only failures that cost a game or a result
+    Error,
+    /// Also invalid moves, skipped rows, retries and other warnings
+    Warn,
+    /// Also progress through a batch: test cases, repetitions, rate limits
+    #[default]
+    Info,
+    /// Also every move played and every provider request
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub fn filter(self) -> LevelFilter {
+        match self {
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+/// Send logs at `level` and above to stderr, clearing any batch progress
+/// bar out of the way first. Does nothing if a subscriber is already
+/// installed, e.g. by an embedding program.
+pub fn init(level: LogLevel) {
+    let _ = tracing_subscriber::fmt()
+        .with_max_level(level.filter())
+        .with_writer(|| LogWriter)
+        .with_ansi(std::io::stderr().is_terminal())
+        .with_target(false)
+        .try_init();
+}
+
+/// Stderr, written around the batch progress bar if one is drawn there
+struct LogWriter;
+
+impl Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        progress_bar::suspend(|| io::stderr().write(buf))
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        progress_bar::suspend(|| io::stderr().write_all(buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels_widen_in_order() {
+        let levels = [LogLevel::Error, LogLevel::Warn, LogLevel::Info, LogLevel::Debug, LogLevel::Trace];
+        for pair in levels.windows(2) {
+            assert!(pair[0].filter() < pair[1].filter(), "{:?} should log less than {:?}", pair[0], pair[1]);
+        }
+        assert_eq!(LogLevel::default(), LogLevel::Info);
+    }
+}
@@ -1,22 +1,239 @@
-mod agent;
-mod agents;
-mod agent_config;
-mod games;
-mod csv_runner;
-mod secrets;
-
 use clap::Parser;
-use games::{Game, print_game_stats};
-use agent_config::{AIAgentConfig, AgentKind};
-use csv_runner::run_csv_batch;
+use std::io::IsTerminal;
+use std::time::Duration;
+use ai_arena::games::{BoardLimits, Game, TestResult, print_game_stats, write_game_json};
+use ai_arena::games::transcript::TranscriptWriter;
+use ai_arena::agent::AIAgent;
+use ai_arena::agent_config::{AIAgentConfig, AgentKind, build_agents, build_agents_with};
+use ai_arena::agents::baseline::{BaselineAgent, BaselineLevel};
+use ai_arena::budget::{Budget, Pricing};
+use ai_arena::cooldown;
+use ai_arena::agents::perturbed::{Perturbation, PerturbationKind};
+use ai_arena::csv_runner::{
+    BatchOptions, ConfiguredCase, check_csv_file, play_configured, run_configured_cases, run_csv_batch,
+};
+use ai_arena::curriculum::{DEFAULT_PASS_SCORE, run_curriculum};
+use ai_arena::decathlon::run_decathlon;
+use ai_arena::head_to_head::run_head_to_head;
+use ai_arena::lobby::run_lobby;
+use ai_arena::logging::{self, LogLevel};
+use ai_arena::replay_diff::replay_diff;
+use ai_arena::result_db::SqliteSink;
+use ai_arena::result_sink::{BatchSummary, ChatJsonlSink, JsonArraySink, ResultSink, SinkDispatcher, SvgSink};
+use ai_arena::schedule::ScheduleStrategy;
+use ai_arena::secrets::{SecretsCache, SecretsManager};
+use ai_arena::stopping::AdaptiveStop;
+use ai_arena::tournament::{parse_entrant, run_round_robin};
+use ai_arena::results_file::ResultsFile;
 
 #[derive(Parser, Debug)]
-#[command(name = "ai_arena")]
+#[command(name = "ai_arena", args_conflicts_with_subcommands = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
     #[clap(flatten)]
     test_case: Option<ClapTestCase>,
     #[arg(long, short = 'f')]
     test_file: Option<String>,
+    /// Check the --test-file rows, game names and secret profiles and report
+    /// problems without playing any games
+    #[arg(long, requires = "test_file")]
+    check: bool,
+    /// Run the test cases in a JSON batch file, whose games can carry their
+    /// full configuration
+    #[arg(long, conflicts_with = "test_file")]
+    batch_file: Option<String>,
+    /// Listen on this address and seat external agents that connect over TCP
+    #[arg(long)]
+    lobby: Option<String>,
+    /// Game played between agents seated by the lobby
+    #[arg(long, default_value = "TicTacToe")]
+    lobby_game: String,
+    /// Number of matches the lobby serves before exiting
+    #[arg(long, default_value_t = 1)]
+    lobby_matches: u32,
+    /// Play two models, each `<kind>:<model>`, against each other with
+    /// alternating seats and report which is stronger
+    #[arg(long, num_args = 2, value_names = ["A", "B"], value_parser = parse_entrant)]
+    compare: Option<Vec<AIAgentConfig>>,
+    /// Game played by --compare
+    #[arg(long, default_value = "TicTacToe")]
+    compare_game: String,
+    /// Games played by --compare
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    compare_games: u32,
+    /// Reuse results of identical temperature-0 test cases in a batch instead of replaying them
+    #[arg(long)]
+    dedupe: bool,
+    /// Seconds to pause a provider after it reports rate-limit exhaustion without a retry-after
+    #[arg(long, default_value_t = 60)]
+    rate_limit_cooldown: u64,
+    /// Send each agent one probe move request first and skip games whose agents can't answer in the expected format
+    #[arg(long)]
+    probe_format: bool,
+    /// Play the two agents through every game in a comma-separated --game-name list and report a combined scorecard
+    #[arg(long)]
+    decathlon: bool,
+    /// In batch runs, keep repeating each case until the 95% interval on agent one's win rate is at most this wide
+    #[arg(long)]
+    ci_width: Option<f64>,
+    /// Cap on games per case when --ci-width is set
+    #[arg(long, default_value_t = 100)]
+    max_repetitions: u32,
+    /// In batch runs, replay drawn games with seats swapped up to this many times
+    #[arg(long, visible_alias = "repeat-until-decisive")]
+    rematch_on_draw: Option<u32>,
+    /// In batch runs, add the repetition index to each agent's seed so repetitions are independent samples
+    #[arg(long)]
+    seed_sweep: bool,
+    /// Robustness testing: show one agent a perturbed state (moves are still validated against the true state)
+    #[arg(value_enum, long)]
+    perturb: Option<PerturbationKind>,
+    /// Which agent (1 or 2) --perturb applies to
+    #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u32).range(1..=2))]
+    perturb_agent: u32,
+    /// Order in which batch test cases are started
+    #[arg(value_enum, long, default_value_t = ScheduleStrategy::InOrder)]
+    schedule: ScheduleStrategy,
+    /// Ask temperature-0 agents for every move twice and report how often the answers differ
+    #[arg(long)]
+    check_temp0: bool,
+    /// Largest board rows/columns (and win length) a game may be configured with
+    #[arg(long, default_value_t = BoardLimits::default().max_dimension)]
+    max_board_size: u32,
+    /// Write an SVG of each batch game's final position into this directory
+    #[arg(long)]
+    render_images: Option<String>,
+    /// Write every batch turn as a chat-completion record (system/user/assistant) to this JSONL file
+    #[arg(long)]
+    chat_jsonl: Option<String>,
+    /// Create a private secrets file template at the default location and exit
+    #[arg(long)]
+    init_secrets: bool,
+    /// Re-read the secrets file between games when it changes, to pick up rotated keys
+    #[arg(long)]
+    reload_secrets: bool,
+    /// Stop starting batch games once the estimated spend would exceed this many USD
+    #[arg(long)]
+    budget_usd: Option<f64>,
+    /// USD per 1k prompt tokens, for --budget-usd and the cost column in player summaries
+    #[arg(long, default_value_t = 0.0)]
+    price_per_1k_prompt: f64,
+    /// USD per 1k completion tokens, for --budget-usd and the cost column in player summaries
+    #[arg(long, default_value_t = 0.0)]
+    price_per_1k_completion: f64,
+    /// Only run batch rows (or decathlon events) for this game, e.g. `ConnectFour`
+    #[arg(long)]
+    only_game: Option<String>,
+    /// Also write each game's full result as JSON to this file: a results
+    /// file for batch runs, one object per line otherwise
+    #[arg(long)]
+    json_output: Option<String>,
+    /// In batch runs, also insert every game into this SQLite database
+    #[arg(long)]
+    db: Option<String>,
+    /// K-factor for the Elo ratings printed after a batch run
+    #[arg(long)]
+    elo_k_factor: Option<f64>,
+    /// Play up to this many repetitions of a batch row at once
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    concurrency: u32,
+    /// Append every turn to this JSONL file as it is played
+    #[arg(long)]
+    transcript: Option<String>,
+    /// Show the board after every turn in the turn table
+    #[arg(long)]
+    show_boards: bool,
+    /// How much to log to stderr; results and tables always go to stdout
+    #[arg(value_enum, long, default_value_t = LogLevel::Info)]
+    log_level: LogLevel,
+    /// Same as --log-level debug: also log every move and provider request
+    #[arg(long, short, conflicts_with_all = ["log_level", "quiet"])]
+    verbose: bool,
+    /// Log only errors, and print only a batch's final results
+    #[arg(long, short, conflicts_with = "log_level")]
+    quiet: bool,
+    /// Skip the --test-file repetitions an interrupted earlier run already finished
+    #[arg(long, requires = "test_file", conflicts_with = "ci_width")]
+    resume: bool,
+    /// Start no more games once one ends in an error, e.g. a rejected API key
+    #[arg(long)]
+    fail_fast: bool,
+    /// Print every game's tables during a --test-file batch instead of
+    /// drawing a progress bar on stderr
+    #[arg(long)]
+    no_progress: bool,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Upgrade a results JSON file written by an older version to the current schema
+    Convert {
+        /// Results file to read
+        input: String,
+        /// Where to write the upgraded file (defaults to overwriting the input)
+        #[arg(long, short)]
+        output: Option<String>,
+    },
+    /// Replay one player's recorded positions against a live agent and report
+    /// how often it picks the same move
+    ReplayDiff {
+        /// Results file to read the recorded games from
+        input: String,
+        /// Recorded player whose positions are replayed, e.g. `OpenAI_1:gpt-4o-mini`
+        #[arg(long)]
+        player: String,
+        /// Model of the substitute agent
+        #[arg(long)]
+        model: String,
+        #[arg(value_enum, long)]
+        kind: AgentKind,
+        #[arg(long, default_value_t = 0.0)]
+        temp: f32,
+        #[arg(long)]
+        secret_profile: Option<String>,
+    },
+    /// Play a model at TicTacToe against ever stronger baseline agents and
+    /// report the hardest level it still holds its own against
+    Curriculum {
+        #[arg(long)]
+        model: String,
+        #[arg(value_enum, long)]
+        kind: AgentKind,
+        #[arg(long, default_value_t = 0.0)]
+        temp: f32,
+        #[arg(long)]
+        secret_profile: Option<String>,
+        /// Games against each level
+        #[arg(long, default_value_t = 4)]
+        games_per_level: u32,
+        /// Seed for the random baseline levels
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+    /// Play every pair of models against each other, in both seatings, and
+    /// print a leaderboard
+    Tournament {
+        /// Entrant as `<kind>:<model>`, e.g. `openai:gpt-4o-mini`; repeat for each model
+        #[arg(long = "entrant", required = true, value_parser = parse_entrant)]
+        entrants: Vec<AIAgentConfig>,
+        #[arg(long, short, default_value = "TicTacToe")]
+        game_name: String,
+        /// Games per ordered pairing
+        #[arg(long, short, default_value_t = 1)]
+        repetitions: u32,
+        /// Temperature for every entrant
+        #[arg(long, default_value_t = 0.0)]
+        temp: f32,
+        #[arg(long)]
+        secret_profile: Option<String>,
+        /// Save standings here after every game and resume from it if it exists
+        #[arg(long)]
+        checkpoint: Option<String>,
+        /// Replay drawn games with seats swapped up to this many times
+        #[arg(long, visible_alias = "repeat-until-decisive", default_value_t = 0)]
+        rematch_on_draw: u32,
+    },
 }
 
 #[derive(Clone, Debug, clap::Args)]
@@ -52,6 +269,21 @@ pub struct ClapAgentConfig {
     agent_two_kind: AgentKind,
     #[arg(long)]
     agent_two_secret_profile: Option<String>,
+    /// Per-turn temperatures for agent one, e.g. `0.9,0.5,0`
+    #[arg(long, value_delimiter = ',')]
+    agent_one_temp_schedule: Option<Vec<f32>>,
+    /// Per-turn temperatures for agent two
+    #[arg(long, value_delimiter = ',')]
+    agent_two_temp_schedule: Option<Vec<f32>>,
+    /// Cap on tokens generated per move request, for both agents
+    #[arg(long)]
+    max_tokens: Option<u32>,
+    /// Nucleus sampling cutoff for both agents (Ollama only)
+    #[arg(long)]
+    top_p: Option<f32>,
+    /// Sequence that ends generation, for both agents (Ollama only); repeat for more
+    #[arg(long)]
+    stop: Option<Vec<String>>,
 }
 
 
@@ -63,6 +295,11 @@ fn clap_agents_to_real_agents(agents: ClapAgentConfig) -> Vec<AIAgentConfig> {
             seed: Some(agents.agent_one_seed),
             agent: agents.agent_one_kind,
             secret_profile: agents.agent_one_secret_profile,
+            max_tokens: agents.max_tokens,
+            temp_schedule: agents.agent_one_temp_schedule,
+            top_p: agents.top_p,
+            stop: agents.stop.clone(),
+            ..Default::default()
         },
         AIAgentConfig {
             model: agents.agent_two_model,
@@ -70,37 +307,456 @@ fn clap_agents_to_real_agents(agents: ClapAgentConfig) -> Vec<AIAgentConfig> {
             seed: Some(agents.agent_two_seed),
             agent: agents.agent_two_kind,
             secret_profile: agents.agent_two_secret_profile,
+            max_tokens: agents.max_tokens,
+            temp_schedule: agents.agent_two_temp_schedule,
+            top_p: agents.top_p,
+            stop: agents.stop,
+            ..Default::default()
         },
     ]
 }
 
+/// Exporters requested on the command line for a batch run
+fn build_sinks(args: &Args) -> Result<Vec<Box<dyn ResultSink>>, String> {
+    let mut sinks: Vec<Box<dyn ResultSink>> = Vec::new();
+    if let Some(dir) = &args.render_images {
+        sinks.push(Box::new(SvgSink::new(dir)?));
+    }
+    if let Some(path) = &args.chat_jsonl {
+        sinks.push(Box::new(ChatJsonlSink::create(path)?));
+    }
+    if let Some(path) = &args.db {
+        sinks.push(Box::new(SqliteSink::open(path)?));
+    }
+    if let Some(path) = &args.json_output {
+        sinks.push(Box::new(JsonArraySink::create(path)?));
+    }
+    Ok(sinks)
+}
+
+/// Per-game JSON file for --json-output outside batch runs
+fn create_json_output(path: Option<&str>) -> Option<std::fs::File> {
+    let path = path?;
+    Some(std::fs::File::create(path).unwrap_or_else(|e| {
+        tracing::error!("Failed to create {}: {}", path, e);
+        std::process::exit(1);
+    }))
+}
+
+fn write_json_output(file: Option<&std::fs::File>, result: &TestResult) {
+    if let Some(file) = file
+        && let Err(e) = write_game_json(result, file)
+    {
+        tracing::error!("Error writing JSON output: {}", e);
+    }
+}
+
 #[tokio::main]
 async fn main() {
     let args = Args::parse();
-    if let Some(test_file) = args.test_file {
+    logging::init(match (args.quiet, args.verbose) {
+        (true, _) => LogLevel::Error,
+        (_, true) => LogLevel::Debug,
+        _ => args.log_level,
+    });
+    cooldown::global().set_default_cooldown(Duration::from_secs(args.rate_limit_cooldown));
+    match args.command {
+        Some(Command::Convert { input, output }) => {
+            if let Err(e) = convert_results(&input, output.as_deref().unwrap_or(&input)) {
+                tracing::error!("Error converting results: {}", e);
+                std::process::exit(1);
+            }
+            return;
+        }
+        Some(Command::ReplayDiff { input, player, model, kind, temp, secret_profile }) => {
+            let substitute = AIAgentConfig {
+                model,
+                temp,
+                agent: kind,
+                secret_profile,
+                ..Default::default()
+            };
+            if let Err(e) = run_replay_diff(&input, &player, substitute).await {
+                tracing::error!("Error running replay diff: {}", e);
+                std::process::exit(1);
+            }
+            return;
+        }
+        Some(Command::Curriculum { model, kind, temp, secret_profile, games_per_level, seed }) => {
+            let config = AIAgentConfig {
+                model,
+                temp,
+                agent: kind,
+                secret_profile,
+                ..Default::default()
+            };
+            run_curriculum_case(config, games_per_level, seed).await;
+            return;
+        }
+        Some(Command::Tournament { entrants, game_name, repetitions, temp, secret_profile, checkpoint, rematch_on_draw }) => {
+            let entrants = entrants
+                .into_iter()
+                .map(|config| AIAgentConfig { temp, secret_profile: secret_profile.clone(), ..config })
+                .collect();
+            if let Err(e) = run_tournament(entrants, &game_name, repetitions, rematch_on_draw, checkpoint.as_deref()).await {
+                tracing::error!("Error running tournament: {}", e);
+                std::process::exit(1);
+            }
+            return;
+        }
+        None => {}
+    }
+
+    if args.init_secrets {
+        let path = SecretsManager::default_config_path();
+        if let Err(e) = SecretsManager::init(&path) {
+            tracing::error!("Error creating secrets file: {}", e);
+            std::process::exit(1);
+        }
+        println!("🔑 Wrote secrets template to {}", path.display());
+        return;
+    }
+
+    let pricing = Pricing {
+        prompt_per_1k: args.price_per_1k_prompt,
+        completion_per_1k: args.price_per_1k_completion,
+    };
+    let options = BatchOptions {
+        verbose: true,
+        dedupe: args.dedupe,
+        probe_format: args.probe_format,
+        adaptive_stop: args.ci_width.map(|max_width| AdaptiveStop {
+            max_width,
+            max_games: args.max_repetitions,
+        }),
+        rematch_on_draw: args.rematch_on_draw,
+        seed_sweep: args.seed_sweep,
+        perturb: args.perturb.map(|kind| Perturbation {
+            kind,
+            seat: args.perturb_agent as usize - 1,
+        }),
+        schedule: args.schedule,
+        check_temp0: args.check_temp0,
+        board_limits: BoardLimits {
+            max_dimension: args.max_board_size,
+            max_win_length: args.max_board_size,
+        },
+        reload_secrets: args.reload_secrets,
+        budget: args.budget_usd.map(|limit_usd| Budget { limit_usd, pricing }),
+        pricing: (pricing != Pricing::default()).then_some(pricing),
+        only_game: args.only_game.clone(),
+        elo_k_factor: args.elo_k_factor,
+        concurrency: args.concurrency,
+        transcript: args.transcript.as_deref().map(|path| {
+            TranscriptWriter::open(path).unwrap_or_else(|e| {
+                tracing::error!("{}", e);
+                std::process::exit(1);
+            })
+        }),
+        show_boards: args.show_boards,
+        quiet: args.quiet,
+        progress: args.test_file.is_some() && !args.no_progress && std::io::stderr().is_terminal(),
+        resume: args.resume,
+        fail_fast: args.fail_fast,
+        checkpoint: None,
+    };
+
+    if args.check
+        && let Some(test_file) = &args.test_file
+    {
+        match check_csv_file(test_file, &SecretsManager::load_or_empty()) {
+            Ok(problems) if problems.is_empty() => println!("✅ {} is ready to run", test_file),
+            Ok(problems) => {
+                println!("❌ {} problem(s) in {}:", problems.len(), test_file);
+                for problem in problems {
+                    println!("  {}", problem);
+                }
+                std::process::exit(1);
+            }
+            Err(e) => {
+                tracing::error!("Error checking CSV file: {}", e);
+                std::process::exit(1);
+            }
+        }
+    } else if let Some(test_file) = &args.test_file {
         // Run CSV batch file
-        if let Err(e) = run_csv_batch(&test_file, true).await {
-            eprintln!("Error running CSV batch: {}", e);
+        let sinks = build_sinks(&args).unwrap_or_else(|e| {
+            tracing::error!("{}", e);
+            std::process::exit(1);
+        });
+        match run_csv_batch(test_file, &options, &sinks).await {
+            Ok(summary) => exit_on_errored_games(&summary),
+            Err(e) => {
+                tracing::error!("Error running CSV batch: {}", e);
+                std::process::exit(1);
+            }
+        }
+    } else if let Some(batch_file) = &args.batch_file {
+        let sinks = build_sinks(&args).unwrap_or_else(|e| {
+            tracing::error!("{}", e);
+            std::process::exit(1);
+        });
+        match run_batch_file(batch_file, &options, &sinks).await {
+            Ok(summary) => exit_on_errored_games(&summary),
+            Err(e) => {
+                tracing::error!("Error running batch file: {}", e);
+                std::process::exit(1);
+            }
+        }
+    } else if let Some(models) = args.compare.clone() {
+        let sinks = build_sinks(&args).unwrap_or_else(|e| {
+            tracing::error!("{}", e);
+            std::process::exit(1);
+        });
+        match run_compare(models, &args.compare_game, args.compare_games, &options, &sinks).await {
+            Ok(summary) => exit_on_errored_games(&summary),
+            Err(e) => {
+                tracing::error!("Error running comparison: {}", e);
+                std::process::exit(1);
+            }
+        }
+    } else if let Some(addr) = args.lobby {
+        let game = Game::try_from(args.lobby_game.as_str()).unwrap_or_else(|e| {
+            tracing::error!("{}", e);
+            std::process::exit(1);
+        });
+        if let Err(e) = run_lobby(&addr, game, args.lobby_matches).await {
+            tracing::error!("Error running lobby: {}", e);
             std::process::exit(1);
         }
     } else if let Some(test_case) = args.test_case {
-        let case: TestCase = test_case.into();
+        if args.decathlon {
+            let json_output = create_json_output(args.json_output.as_deref());
+            if let Err(e) = run_decathlon_case(test_case, &options, json_output.as_ref()).await {
+                tracing::error!("Error running decathlon: {}", e);
+                std::process::exit(1);
+            }
+            return;
+        }
+        let case = TestCase::try_from(test_case).unwrap_or_else(|e| {
+            tracing::error!("{}", e);
+            std::process::exit(1);
+        });
         let game = case.game_name;
         let game_name = game.name();
-        let result = game.play_game(case.agents.clone()).await;
-        
+        let secrets = SecretsCache::load_default(options.reload_secrets);
+        let json_output = create_json_output(args.json_output.as_deref());
+        let mut dispatcher = SinkDispatcher::new(&[]);
+        let (seated, _) = game.seat_agents(case.agents.clone(), 0);
+        let result = play_configured(&game, seated, &options, &secrets).await;
+
         // Print formatted statistics
-        print_game_stats(game_name, &result);
+        print_game_stats(game_name, &result, options.pricing, options.show_boards);
+        write_json_output(json_output.as_ref(), &result);
+        dispatcher.game_complete(&result);
+        exit_on_errored_games(&dispatcher.finish());
     } else {
         println!("No test case or test file provided.");
     }
 }
 
+async fn run_batch_file(path: &str, options: &BatchOptions, sinks: &[Box<dyn ResultSink>]) -> Result<BatchSummary, String> {
+    let contents = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let mut cases: Vec<ConfiguredCase> = TestBatch::from_json(&contents, path)?.cases.into_iter().map(Into::into).collect();
+    // Prompt files are relative to the batch file, as in CSV batches
+    let base_dir = std::path::Path::new(path).parent().unwrap_or(std::path::Path::new(""));
+    for agent in cases.iter_mut().flat_map(|case| &mut case.agents) {
+        agent.load_system_prompt_file(base_dir)?;
+    }
+    let secrets = SecretsCache::load_default(options.reload_secrets);
+    run_configured_cases(&cases, options, sinks, async |game: &Game, agents| {
+        play_configured(game, agents, options, &secrets).await
+    })
+    .await
+}
+
+/// Exit with the batch's status if any of its games ended in an error
+fn exit_on_errored_games(summary: &BatchSummary) {
+    let code = summary.exit_code();
+    if code != 0 {
+        tracing::error!("{} of {} game(s) ended in an error", summary.errored_games, summary.total_games);
+        std::process::exit(code);
+    }
+}
+
+async fn run_decathlon_case(
+    test_case: ClapTestCase,
+    options: &BatchOptions,
+    json_output: Option<&std::fs::File>,
+) -> Result<(), String> {
+    let mut games = test_case
+        .game_name
+        .split(',')
+        .map(|name| Game::try_from(name.trim()))
+        .collect::<Result<Vec<_>, _>>()?;
+    if let Some(only) = &options.only_game {
+        if Game::new(only).is_none() {
+            return Err(format!("Unknown game for --only-game: {}", only));
+        }
+        games.retain(|game| game.name() == only);
+        if games.is_empty() {
+            return Err(format!("{} is not one of the decathlon's games", only));
+        }
+    }
+    let agents = clap_agents_to_real_agents(test_case.agent_config);
+    let secrets = SecretsCache::load_default(options.reload_secrets);
+
+    let decathlon = run_decathlon(&games, agents, test_case.repetitions, async |game: &Game, agents| {
+        let result = play_configured(game, agents, options, &secrets).await;
+        print_game_stats(game.name(), &result, options.pricing, options.show_boards);
+        write_json_output(json_output, &result);
+        result
+    })
+    .await?;
+    decathlon.print_scorecard();
+    Ok(())
+}
+
+async fn run_tournament(
+    entrants: Vec<AIAgentConfig>,
+    game_name: &str,
+    repetitions: u32,
+    max_rematches: u32,
+    checkpoint: Option<&str>,
+) -> Result<(), String> {
+    let game = Game::try_from(game_name)?;
+    let options = BatchOptions::default();
+    let secrets = SecretsCache::load_default(false);
+    let standings = run_round_robin(
+        entrants,
+        &game,
+        repetitions,
+        max_rematches,
+        checkpoint.map(std::path::Path::new),
+        async |game: &Game, agents| {
+            let result = play_configured(game, agents, &options, &secrets).await;
+            print_game_stats(game.name(), &result, None, false);
+            result
+        },
+    )
+    .await?;
+
+    println!("\n{}", "=".repeat(80));
+    println!("TOURNAMENT STANDINGS: {}", game.name());
+    println!("{}", "=".repeat(80));
+    println!("{}", standings.leaderboard_table());
+    Ok(())
+}
+
+async fn run_compare(
+    models: Vec<AIAgentConfig>,
+    game_name: &str,
+    games: u32,
+    options: &BatchOptions,
+    sinks: &[Box<dyn ResultSink>],
+) -> Result<BatchSummary, String> {
+    let game = Game::try_from(game_name)?;
+    let models: [AIAgentConfig; 2] = models.try_into().map_err(|_| "--compare takes exactly two models".to_string())?;
+    let secrets = SecretsCache::load_default(options.reload_secrets);
+    let dispatcher = std::cell::RefCell::new(SinkDispatcher::new(sinks));
+    let report = run_head_to_head(models, &game, games, async |game: &Game, agents| {
+        let result = play_configured(game, agents, options, &secrets).await;
+        if !options.quiet {
+            print_game_stats(game.name(), &result, options.pricing, options.show_boards);
+        }
+        dispatcher.borrow_mut().game_complete(&result);
+        result
+    })
+    .await?;
+
+    println!("\n{}", "=".repeat(80));
+    println!("HEAD TO HEAD");
+    println!("{}", "=".repeat(80));
+    println!("{}", report);
+    Ok(dispatcher.into_inner().finish())
+}
+
+async fn run_curriculum_case(config: AIAgentConfig, games_per_level: u32, seed: u64) {
+    let game = Game::new("TicTacToe").unwrap();
+    let secrets = SecretsCache::load_default(false);
+    let games_played = std::cell::Cell::new(0);
+    let report = run_curriculum(&BaselineLevel::ladder(), games_per_level, DEFAULT_PASS_SCORE, async |level| {
+        let mut agents = build_agents_with(vec![config.clone()], &secrets.current()).unwrap_or_else(|e| {
+            tracing::error!("{}", e);
+            std::process::exit(1);
+        });
+        agents.push(AIAgent::Baseline(BaselineAgent::new(level, seed + games_played.get())));
+        games_played.set(games_played.get() + 1);
+        let result = game.play_with_agents(agents).await;
+        print_game_stats(game.name(), &result, None, false);
+        result
+    })
+    .await;
+
+    println!("\n{}", "=".repeat(80));
+    println!("CURRICULUM: {} ({:?})", config.model, config.agent);
+    println!("{}", "=".repeat(80));
+    println!("{}", report.table());
+    match (report.ceiling(), report.stopped_at()) {
+        (_, None) => println!("🏔️  Cleared every level"),
+        (Some(ceiling), Some(stopped)) => {
+            println!("🏔️  Skill ceiling: {} (stopped winning at {})", ceiling.name(), stopped.name())
+        }
+        (None, Some(stopped)) => println!("🏔️  Skill ceiling: none (stopped winning at {})", stopped.name()),
+    }
+}
+
+async fn run_replay_diff(input: &str, player: &str, substitute: AIAgentConfig) -> Result<(), String> {
+    let (file, _) = ResultsFile::read_from(input)?;
+    let agent = build_agents(vec![substitute])?.pop().ok_or("failed to build substitute agent")?;
+    let diff = replay_diff(&file.results, player, &agent).await;
+    if diff.diffs.is_empty() {
+        return Err(format!("no turns by '{}' found in {}", player, input));
+    }
+
+    println!("{}", diff.report());
+    println!(
+        "Agreement with {}: {}/{} ({:.1}%)",
+        player,
+        diff.diffs.iter().filter(|d| d.agrees()).count(),
+        diff.diffs.len(),
+        diff.agreement_rate() * 100.0
+    );
+    Ok(())
+}
+
+fn convert_results(input: &str, output: &str) -> Result<(), String> {
+    let (file, changes) = ResultsFile::read_from(input)?;
+    if changes.is_empty() {
+        println!("{} is already at schema v{}", input, file.schema_version);
+    }
+    for change in &changes {
+        println!("  {}", change);
+    }
+    file.write_to(output)?;
+    println!("Wrote schema v{} results to {}", file.schema_version, output);
+    Ok(())
+}
+
 #[derive(Debug, serde::Deserialize)]
 struct TestBatch {
     cases: Vec<TestCase>,
 }
 
+impl TestBatch {
+    /// Parse a JSON batch; `source` names the file in errors
+    fn from_json(contents: &str, source: &str) -> Result<Self, String> {
+        let batch: TestBatch = serde_json::from_str(contents)
+            .map_err(|e| format!("Invalid batch file {}: {}", source, e))?;
+        if batch.cases.is_empty() {
+            return Err(format!("no test cases found in {}", source));
+        }
+        for case in &batch.cases {
+            case.game_name
+                .validate(&BoardLimits::default())
+                .and_then(|()| case.game_name.check_agents(&case.agents))
+                .and_then(|()| case.game_name.check_player_count(case.agents.len()))
+                .map_err(|e| format!("Invalid batch file {}: {}", source, e))?;
+        }
+        Ok(batch)
+    }
+}
+
 #[derive(Clone, Debug, serde::Deserialize)]
 struct TestCase {
     game_name: Game,
@@ -109,18 +765,108 @@ struct TestCase {
     repetitions: u32,
 }
 
-impl From<ClapTestCase> for TestCase {
-    fn from(config: ClapTestCase) -> Self {
+impl TryFrom<ClapTestCase> for TestCase {
+    type Error = String;
+
+    fn try_from(config: ClapTestCase) -> Result<Self, String> {
+        let game_name = Game::try_from(config.game_name.as_str())?;
         let agents = clap_agents_to_real_agents(config.agent_config);
-        TestCase {
-            // Need to make a new game here
-            game_name: Game::from(config.game_name.as_str()),
+        game_name.check_agents(&agents)?;
+        Ok(TestCase {
+            game_name,
             description: "manual run".to_string(),
-            agents: agents,
+            agents,
             repetitions: config.repetitions,
+        })
+    }
+}
+
+impl From<TestCase> for ConfiguredCase {
+    fn from(case: TestCase) -> Self {
+        ConfiguredCase {
+            game: case.game_name,
+            description: case.description,
+            agents: case.agents,
+            repetitions: case.repetitions,
         }
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_definition_is_valid() {
+        use clap::CommandFactory;
+        Args::command().debug_assert();
+    }
+
+    #[test]
+    fn test_empty_batch_file_is_an_error() {
+        let err = TestBatch::from_json(r#"{"cases": []}"#, "batch.json").unwrap_err();
+        assert_eq!(err, "no test cases found in batch.json");
+    }
+
+    #[test]
+    fn test_batch_file_with_oversized_board_is_rejected() {
+        let err = TestBatch::from_json(
+            r#"{"cases": [{
+                "game_name": {"TicTacToe": {"board_size": 10000, "win_length": 3}},
+                "description": "typo",
+                "agents": [],
+                "repetitions": 1
+            }]}"#,
+            "batch.json",
+        )
+        .unwrap_err();
+        assert!(err.contains("board_size must be between 1 and 32"), "{}", err);
+    }
 
+    #[test]
+    fn test_batch_file_with_too_many_players_is_rejected() {
+        let agent = r#"{"model": "llama3", "temp": 0.0, "seed": 1, "agent": "Ollama", "secret_profile": null}"#;
+        let batch = |game: &str| {
+            format!(
+                r#"{{"cases": [{{"game_name": {}, "description": "crowd", "agents": [{agent}, {agent}, {agent}], "repetitions": 1}}]}}"#,
+                game
+            )
+        };
+        let err = TestBatch::from_json(&batch(r#"{"TicTacToe": {"board_size": 3, "win_length": 3}}"#), "batch.json")
+            .unwrap_err();
+        assert_eq!(err, "Invalid batch file batch.json: TicTacToe: Expected 2 agents, got 3");
+        assert!(TestBatch::from_json(&batch(r#"{"RockPaperScissors": {"rounds": 3}}"#), "batch.json").is_ok());
+    }
 
+    #[test]
+    fn test_batch_file_with_cases_parses() {
+        let batch = TestBatch::from_json(
+            r#"{"cases": [{
+                "game_name": {"TicTacToe": {"board_size": 3, "win_length": 3}},
+                "description": "smoke",
+                "agents": [
+                    {"model": "gpt-4o-mini", "temp": 0.0, "seed": 1, "agent": "OpenAI", "secret_profile": null},
+                    {"model": "llama3", "temp": 0.0, "seed": 2, "agent": "Ollama", "secret_profile": null}
+                ],
+                "repetitions": 2
+            }]}"#,
+            "batch.json",
+        )
+        .unwrap();
+        assert_eq!(batch.cases.len(), 1);
+        assert_eq!(batch.cases[0].repetitions, 2);
+    }
+
+    #[test]
+    fn test_example_batch_file_parses() {
+        let batch = TestBatch::from_json(include_str!("../examples/test_batch.json"), "test_batch.json").unwrap();
+        let cases: Vec<ConfiguredCase> = batch.cases.into_iter().map(Into::into).collect();
+        assert_eq!(cases.len(), 3);
+        assert!(matches!(&cases[0].game, Game::TicTacToe(config) if config.board_size == 4 && config.win_length == 3));
+        assert!(matches!(&cases[1].game, Game::ConnectFour(config) if config.rows == 8 && config.cols == 9));
+        assert!(matches!(&cases[2].game, Game::Nim(config) if config.misere));
+        assert_eq!(cases[1].agents[0].model, "claude-3-7-sonnet-latest");
+        assert_eq!(cases[1].agents[1].secret_profile.as_deref(), Some("work"));
+        assert_eq!(cases[2].repetitions, 3);
+    }
+}
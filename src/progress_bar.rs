@@ -0,0 +1,152 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use indicatif::{HumanDuration, ProgressBar, ProgressStyle};
+
+/// Games whose pace the time-remaining estimate follows
+const ROLLING_GAMES: usize = 10;
+
+/// The bar being drawn on stderr, if any, so logs can step around it
+static ACTIVE: Mutex<Option<ProgressBar>> = Mutex::new(None);
+
+/// Run `write` with any batch progress bar cleared from the terminal, so
+/// what it writes to stderr doesn't land in the middle of the bar
+pub fn suspend<R>(write: impl FnOnce() -> R) -> R {
+    let active = ACTIVE.lock().unwrap().clone();
+    match active {
+        Some(bar) => bar.suspend(write),
+        None => write(),
+    }
+}
+
+/// Games finished out of those planned for a batch, drawn on stderr with
+/// the test case being played and an estimate of the time left. The bar is
+/// cleared when this is dropped.
+pub struct BatchProgressBar {
+    bar: ProgressBar,
+    case: String,
+    /// How long each of the last few played games took, oldest first
+    recent: VecDeque<Duration>,
+    last_finished: Instant,
+}
+
+impl BatchProgressBar {
+    /// Draw a bar on stderr for a batch of `total_games` games
+    pub fn start(total_games: u64) -> Self {
+        Self::with_bar(ProgressBar::new(total_games))
+    }
+
+    fn with_bar(bar: ProgressBar) -> Self {
+        bar.set_style(
+            ProgressStyle::with_template("{bar:30} {pos}/{len} games  {msg}")
+                .expect("progress bar template is valid"),
+        );
+        *ACTIVE.lock().unwrap() = Some(bar.clone());
+        BatchProgressBar {
+            bar,
+            case: String::new(),
+            recent: VecDeque::new(),
+            last_finished: Instant::now(),
+        }
+    }
+
+    /// Show `case` as the test case now being played
+    pub fn start_case(&mut self, case: &str) {
+        self.case = case.to_string();
+        self.refresh();
+    }
+
+    /// Count a game that was just played
+    pub fn game_played(&mut self) {
+        let now = Instant::now();
+        self.recent.push_back(now - self.last_finished);
+        if self.recent.len() > ROLLING_GAMES {
+            self.recent.pop_front();
+        }
+        self.last_finished = now;
+        self.bar.inc(1);
+        self.refresh();
+    }
+
+    /// Count games whose results were reused rather than played; they take
+    /// no time, so they don't count towards the pace
+    pub fn games_reused(&mut self, games: u64) {
+        self.last_finished = Instant::now();
+        self.bar.inc(games);
+        self.refresh();
+    }
+
+    /// Take back planned games that won't be played, e.g. when a test case
+    /// is skipped or stops early
+    pub fn games_skipped(&mut self, games: u64) {
+        let total = self.bar.length().unwrap_or(0);
+        self.bar.set_length(total.saturating_sub(games));
+        self.refresh();
+    }
+
+    pub fn position(&self) -> u64 {
+        self.bar.position()
+    }
+
+    fn refresh(&self) {
+        let remaining = self.bar.length().unwrap_or(0).saturating_sub(self.bar.position());
+        let pace = match (average(&self.recent), estimate_remaining(&self.recent, remaining)) {
+            (Some(average), Some(eta)) => {
+                format!(" | ~{:.1}s/game, ETA {}", average.as_secs_f64(), HumanDuration(eta))
+            }
+            _ => String::new(),
+        };
+        self.bar.set_message(format!("{}{}", self.case, pace));
+    }
+}
+
+impl Drop for BatchProgressBar {
+    fn drop(&mut self) {
+        self.bar.finish_and_clear();
+        *ACTIVE.lock().unwrap() = None;
+    }
+}
+
+fn average(recent: &VecDeque<Duration>) -> Option<Duration> {
+    if recent.is_empty() {
+        return None;
+    }
+    Some(recent.iter().sum::<Duration>() / recent.len() as u32)
+}
+
+/// Time left to play `remaining` games at the average pace of `recent` ones
+pub fn estimate_remaining(recent: &VecDeque<Duration>, remaining: u64) -> Option<Duration> {
+    average(recent).map(|average| average.saturating_mul(remaining.min(u32::MAX as u64) as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_follows_recent_pace() {
+        let recent: VecDeque<Duration> = [2, 4, 6].into_iter().map(Duration::from_secs).collect();
+        assert_eq!(estimate_remaining(&recent, 5), Some(Duration::from_secs(20)));
+        assert_eq!(estimate_remaining(&recent, 0), Some(Duration::ZERO));
+        assert_eq!(estimate_remaining(&VecDeque::new(), 5), None);
+    }
+
+    #[test]
+    fn test_counts_played_reused_and_skipped_games() {
+        let mut bar = BatchProgressBar::with_bar(ProgressBar::hidden());
+        bar.bar.set_length(10);
+        bar.start_case("RPS mirror match");
+        bar.game_played();
+        bar.games_reused(3);
+        bar.games_skipped(2);
+        assert_eq!((bar.position(), bar.bar.length()), (4, Some(8)));
+        // Only the played game counts towards the pace
+        assert_eq!(bar.recent.len(), 1);
+        for _ in 0..2 * ROLLING_GAMES {
+            bar.game_played();
+        }
+        assert_eq!(bar.recent.len(), ROLLING_GAMES);
+        assert!(bar.bar.message().starts_with("RPS mirror match | ~"));
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use tabled::{Table, Tabled, settings::{Alignment, Modify, Style, object::Rows}};
+
+use crate::games::judge::Outcome;
+use crate::standings::{RankKey, rank_cmp};
+
+/// Rating every player starts from
+pub const INITIAL_RATING: f64 = 1500.0;
+
+/// How far one game can move a rating
+pub const DEFAULT_K_FACTOR: f64 = 32.0;
+
+/// Outcome for the two players given their `game_points`, or `None` for a
+/// game that produced no result
+pub fn outcome_from_points(points: [f64; 2]) -> Option<Outcome> {
+    match points {
+        [a, b] if a > b => Some(Outcome::Winner(0)),
+        [a, b] if b > a => Some(Outcome::Winner(1)),
+        [a, _] if a > 0.0 => Some(Outcome::Draw),
+        _ => None,
+    }
+}
+
+/// Elo ratings per model name, updated one game at a time
+#[derive(Clone, Debug)]
+pub struct EloTracker {
+    k_factor: f64,
+    ratings: HashMap<String, f64>,
+    games: HashMap<String, u32>,
+}
+
+impl Default for EloTracker {
+    fn default() -> Self {
+        EloTracker::new(DEFAULT_K_FACTOR)
+    }
+}
+
+impl EloTracker {
+    pub fn new(k_factor: f64) -> Self {
+        EloTracker {
+            k_factor,
+            ratings: HashMap::new(),
+            games: HashMap::new(),
+        }
+    }
+
+    /// Update both ratings for one game; `Outcome::Winner(0)` means
+    /// `player_a` won. A player against itself is not rated.
+    pub fn record_game(&mut self, player_a: &str, player_b: &str, outcome: Outcome) {
+        if player_a == player_b {
+            return;
+        }
+        let score_a = match outcome {
+            Outcome::Winner(0) => 1.0,
+            Outcome::Winner(_) => 0.0,
+            Outcome::Draw => 0.5,
+        };
+        let rating_a = self.rating(player_a);
+        let rating_b = self.rating(player_b);
+        let expected_a = 1.0 / (1.0 + 10f64.powf((rating_b - rating_a) / 400.0));
+        let delta = self.k_factor * (score_a - expected_a);
+        self.ratings.insert(player_a.to_string(), rating_a + delta);
+        self.ratings.insert(player_b.to_string(), rating_b - delta);
+        for player in [player_a, player_b] {
+            *self.games.entry(player.to_string()).or_default() += 1;
+        }
+    }
+
+    /// Current rating, `INITIAL_RATING` for players not yet seen
+    pub fn rating(&self, player: &str) -> f64 {
+        self.ratings.get(player).copied().unwrap_or(INITIAL_RATING)
+    }
+
+    pub fn ratings(&self) -> HashMap<String, f64> {
+        self.ratings.clone()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ratings.is_empty()
+    }
+
+    /// Ratings highest first; equal ratings are listed by name
+    pub fn table(&self) -> Table {
+        #[derive(Tabled)]
+        struct RatingRow {
+            #[tabled(rename = "#")]
+            rank: usize,
+            #[tabled(rename = "Model")]
+            name: String,
+            #[tabled(rename = "Elo")]
+            rating: String,
+            #[tabled(rename = "Games")]
+            games: u32,
+        }
+
+        let mut players: Vec<(&str, f64)> = self.ratings.iter().map(|(name, r)| (name.as_str(), *r)).collect();
+        players.sort_by(|(a, ra), (b, rb)| {
+            rank_cmp(
+                &RankKey { name: a, score: *ra, invalid_rate: 0.0 },
+                &RankKey { name: b, score: *rb, invalid_rate: 0.0 },
+            )
+        });
+        let mut table = Table::new(players.into_iter().enumerate().map(|(i, (name, rating))| RatingRow {
+            rank: i + 1,
+            name: name.to_string(),
+            rating: format!("{:.0}", rating),
+            games: self.games.get(name).copied().unwrap_or(0),
+        }));
+        table
+            .with(Style::rounded())
+            .with(Modify::new(Rows::new(1..)).with(Alignment::left()));
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equal_players_move_by_half_k() {
+        let mut elo = EloTracker::default();
+        elo.record_game("a", "b", Outcome::Winner(0));
+        assert_eq!(elo.rating("a"), 1516.0);
+        assert_eq!(elo.rating("b"), 1484.0);
+    }
+
+    #[test]
+    fn test_draw_pulls_ratings_together() {
+        let mut elo = EloTracker::new(20.0);
+        elo.record_game("a", "b", Outcome::Winner(1));
+        elo.record_game("a", "b", Outcome::Draw);
+        let (a, b) = (elo.rating("a"), elo.rating("b"));
+        assert!(a > 1490.0 && b < 1510.0);
+        // Rating points are only ever exchanged
+        assert!((a + b - 2.0 * INITIAL_RATING).abs() < 1e-9);
+        assert_eq!(elo.ratings().len(), 2);
+    }
+
+    #[test]
+    fn test_upset_moves_ratings_more() {
+        let mut elo = EloTracker::default();
+        for _ in 0..5 {
+            elo.record_game("strong", "weak", Outcome::Winner(0));
+        }
+        let before = elo.rating("strong");
+        elo.record_game("strong", "weak", Outcome::Winner(1));
+        assert!(before - elo.rating("strong") > 16.0);
+    }
+
+    #[test]
+    fn test_outcome_from_points() {
+        assert_eq!(outcome_from_points([1.0, 0.0]), Some(Outcome::Winner(0)));
+        assert_eq!(outcome_from_points([0.0, 1.0]), Some(Outcome::Winner(1)));
+        assert_eq!(outcome_from_points([0.5, 0.5]), Some(Outcome::Draw));
+        assert_eq!(outcome_from_points([0.0, 0.0]), None);
+    }
+
+    #[test]
+    fn test_self_play_is_not_rated() {
+        let mut elo = EloTracker::default();
+        elo.record_game("a", "a", Outcome::Winner(0));
+        assert!(elo.is_empty());
+    }
+}
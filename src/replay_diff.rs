@@ -0,0 +1,148 @@
+use serde_json::Value;
+use tabled::{Table, Tabled, settings::{Alignment, Modify, Style, object::Rows}};
+
+use crate::agent::{AIAgent, MoveRequest};
+use crate::games::TestResult;
+
+/// A recorded move next to what the substitute agent chose in the same position
+#[derive(Clone, Debug, PartialEq)]
+pub struct MoveDiff {
+    pub turn_number: u32,
+    pub recorded: Value,
+    /// `None` if the substitute agent failed to answer
+    pub replayed: Option<Value>,
+    pub error: Option<String>,
+}
+
+impl MoveDiff {
+    pub fn agrees(&self) -> bool {
+        self.replayed.as_ref() == Some(&self.recorded)
+    }
+}
+
+/// How a substitute agent's moves compare to one recorded player's
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ReplayDiff {
+    pub player: String,
+    pub diffs: Vec<MoveDiff>,
+}
+
+impl ReplayDiff {
+    /// Share of replayed positions where the substitute chose the recorded move
+    pub fn agreement_rate(&self) -> f64 {
+        if self.diffs.is_empty() {
+            return 0.0;
+        }
+        self.diffs.iter().filter(|d| d.agrees()).count() as f64 / self.diffs.len() as f64
+    }
+
+    pub fn report(&self) -> Table {
+        #[derive(Tabled)]
+        struct DiffRow {
+            #[tabled(rename = "Turn")]
+            turn: String,
+            #[tabled(rename = "Recorded")]
+            recorded: String,
+            #[tabled(rename = "Substitute")]
+            replayed: String,
+            #[tabled(rename = "Same")]
+            same: String,
+        }
+
+        let rows: Vec<DiffRow> = self
+            .diffs
+            .iter()
+            .map(|d| DiffRow {
+                turn: d.turn_number.to_string(),
+                recorded: d.recorded.to_string(),
+                replayed: match (&d.replayed, &d.error) {
+                    (Some(replayed), _) => replayed.to_string(),
+                    (None, Some(e)) => format!("error: {}", e),
+                    (None, None) => "-".to_string(),
+                },
+                same: if d.agrees() { "✓".to_string() } else { "✗".to_string() },
+            })
+            .collect();
+
+        let mut table = Table::new(rows);
+        table
+            .with(Style::rounded())
+            .with(Modify::new(Rows::new(1..)).with(Alignment::left()));
+        table
+    }
+}
+
+/// Show `substitute` every position `player` faced in `results` and record
+/// whether it picks the same move. Positions come from each turn's
+/// `state_before`; the move schema is that of the game's default
+/// configuration, or null for a custom game that isn't registered.
+pub async fn replay_diff(results: &[TestResult], player: &str, substitute: &AIAgent) -> ReplayDiff {
+    let mut diff = ReplayDiff {
+        player: player.to_string(),
+        diffs: Vec::new(),
+    };
+
+    for (game_idx, result) in results.iter().enumerate() {
+        let schema = result.game().map_or(Value::Null, |game| game.move_schema());
+        for turn in result.stats().turns.iter().filter(|t| t.player == player) {
+            let request = MoveRequest {
+                turn_index: turn.turn_number,
+                game_id: format!("replay_diff_{}", game_idx + 1),
+                state: turn.state_before.clone(),
+                expected_move_schema: schema.clone(),
+                legal_moves: None,
+            };
+            let (replayed, error) = match substitute.execute_turn(&request).await {
+                Ok(response) => (Some(response.chosen_move), None),
+                Err(e) => (None, Some(e.to_string())),
+            };
+            diff.diffs.push(MoveDiff {
+                turn_number: turn.turn_number,
+                recorded: turn.move_made.clone(),
+                replayed,
+                error,
+            });
+        }
+    }
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::socket::scripted_socket_agent;
+    use crate::games::Game;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_diff_against_recorded_game() {
+        // Record a game: x wins down the first column
+        let x = scripted_socket_agent(
+            "x",
+            vec![json!({"row": 0, "col": 0}), json!({"row": 1, "col": 0}), json!({"row": 2, "col": 0})],
+        )
+        .await;
+        let o = scripted_socket_agent("o", vec![json!({"row": 0, "col": 1}), json!({"row": 1, "col": 1})]).await;
+        let recorded = Game::new("TicTacToe").unwrap()
+            .play_with_agents(vec![AIAgent::Socket(x), AIAgent::Socket(o)])
+            .await;
+
+        // The substitute agrees on the first move only
+        let substitute = scripted_socket_agent(
+            "sub",
+            vec![json!({"row": 0, "col": 0}), json!({"row": 2, "col": 2}), json!({"row": 2, "col": 0})],
+        )
+        .await;
+        let diff = replay_diff(&[recorded], "x", &AIAgent::Socket(substitute)).await;
+
+        assert_eq!(diff.player, "x");
+        assert_eq!(diff.diffs.len(), 3);
+        let agreements: Vec<bool> = diff.diffs.iter().map(MoveDiff::agrees).collect();
+        assert_eq!(agreements, [true, false, true]);
+        assert_eq!(diff.diffs[1].turn_number, 3);
+        assert_eq!(diff.diffs[1].recorded, json!({"row": 1, "col": 0}));
+        assert!((diff.agreement_rate() - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(diff.report().count_rows(), 4);
+    }
+}
@@ -0,0 +1,187 @@
+use std::path::Path;
+use std::sync::Mutex;
+
+use rusqlite::{Connection, params};
+
+use crate::agent_config::split_agent_name;
+use crate::games::TestResult;
+use crate::result_sink::{BatchSummary, ResultSink};
+
+/// Tables `SqliteSink` writes to; created if missing, so a database can
+/// collect several batches
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS games (
+    id INTEGER PRIMARY KEY,
+    game_id TEXT,
+    game_type TEXT NOT NULL,
+    winner TEXT,
+    winner_seat INTEGER,
+    draw INTEGER NOT NULL,
+    error TEXT,
+    duration_ms INTEGER NOT NULL,
+    total_turns INTEGER NOT NULL,
+    invalid_moves INTEGER NOT NULL,
+    move_list TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS agents (
+    game INTEGER NOT NULL REFERENCES games(id),
+    seat INTEGER NOT NULL,
+    seat_label TEXT NOT NULL,
+    name TEXT NOT NULL,
+    kind TEXT,
+    model TEXT
+);
+CREATE TABLE IF NOT EXISTS turns (
+    game INTEGER NOT NULL REFERENCES games(id),
+    turn_number INTEGER NOT NULL,
+    player TEXT NOT NULL,
+    move TEXT NOT NULL,
+    valid INTEGER NOT NULL,
+    error TEXT,
+    time_taken_ms INTEGER NOT NULL,
+    api_latency_ms INTEGER NOT NULL,
+    processing_ms INTEGER NOT NULL,
+    prompt_tokens INTEGER,
+    completion_tokens INTEGER
+);
+";
+
+/// Inserts every finished game into a SQLite database: one `games` row, an
+/// `agents` row per seat (0 moves first) and a `turns` row per turn, so
+/// results can be queried with SQL across batches
+pub struct SqliteSink {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteSink {
+    /// Open or create the database at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let conn = Connection::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        Self::with_connection(conn)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self, String> {
+        conn.execute_batch(SCHEMA).map_err(|e| format!("Failed to create results tables: {}", e))?;
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+
+    fn insert(&self, result: &TestResult) -> rusqlite::Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let stats = result.stats();
+        tx.execute(
+            "INSERT INTO games (game_id, game_type, winner, winner_seat, draw, error, duration_ms, total_turns, invalid_moves, move_list)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                stats.game_id,
+                result.game_name(),
+                result.winner(),
+                result.winner_seat(),
+                stats.draw,
+                result.error(),
+                stats.total_duration_ms,
+                stats.total_turns(),
+                stats.invalid_moves,
+                stats.move_list.join(" "),
+            ],
+        )?;
+        let game = tx.last_insert_rowid();
+
+        for ((seat, name), label) in stats.seats.iter().enumerate().zip(result.seat_labels()) {
+            let (kind, model) = split_agent_name(name).unzip();
+            tx.execute(
+                "INSERT INTO agents (game, seat, seat_label, name, kind, model) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![game, seat, label, name, kind, model],
+            )?;
+        }
+
+        for turn in &stats.turns {
+            tx.execute(
+                "INSERT INTO turns (game, turn_number, player, move, valid, error, time_taken_ms, api_latency_ms, processing_ms, prompt_tokens, completion_tokens)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    game,
+                    turn.turn_number,
+                    turn.player,
+                    turn.move_made.to_string(),
+                    turn.move_valid,
+                    turn.error_message,
+                    turn.time_taken_ms,
+                    turn.api_latency_ms,
+                    turn.processing_ms,
+                    turn.usage.map(|u| u.prompt_tokens),
+                    turn.usage.map(|u| u.completion_tokens),
+                ],
+            )?;
+        }
+        tx.commit()
+    }
+}
+
+impl ResultSink for SqliteSink {
+    fn on_game_complete(&self, result: &TestResult) {
+        if let Err(e) = self.insert(result) {
+            tracing::error!("Failed to store result in the database: {}", e);
+        }
+    }
+
+    fn on_batch_complete(&self, _summary: &BatchSummary) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::scripted::ScriptedAgent;
+    use crate::agent::AIAgent;
+    use crate::games::Game;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_game_round_trips() {
+        let script = |cells: &[(u32, u32)]| cells.iter().map(|(row, col)| json!({"row": row, "col": col})).collect();
+        let agents = vec![
+            AIAgent::Scripted(ScriptedAgent::new("OpenAI_1:gpt-4o-mini", script(&[(0, 0), (0, 1), (0, 2)]))),
+            AIAgent::Scripted(ScriptedAgent::new("Ollama_2:llama3", script(&[(9, 9), (1, 0), (1, 1)]))),
+        ];
+        let result = Game::new("TicTacToe").unwrap().play_with_agents(agents).await;
+
+        let sink = SqliteSink::with_connection(Connection::open_in_memory().unwrap()).unwrap();
+        sink.on_game_complete(&result);
+        let conn = sink.conn.lock().unwrap();
+
+        let game: (String, String, Option<String>, Option<usize>, bool, u32, u32, String) = conn
+            .query_row(
+                "SELECT game_id, game_type, winner, winner_seat, draw, total_turns, invalid_moves, move_list FROM games",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?, row.get(7)?)),
+            )
+            .unwrap();
+        assert_eq!(Some(&game.0), result.stats().game_id.as_ref());
+        assert_eq!(
+            (&game.1[..], game.2.as_deref(), game.3, game.4, game.5, game.6, &game.7[..]),
+            ("TicTacToe", Some("OpenAI_1:gpt-4o-mini (X)"), Some(0), false, 6, 1, "X:0,0 O:1,0 X:0,1 O:1,1 X:0,2")
+        );
+
+        // Win rate of a model as first player
+        let first_seat_wins: u32 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM games JOIN agents ON agents.game = games.id
+                 WHERE agents.model = 'gpt-4o-mini' AND agents.seat = 0 AND games.winner_seat = 0",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(first_seat_wins, 1);
+
+        let turns: Vec<(String, bool, Option<String>)> = conn
+            .prepare("SELECT player, valid, error FROM turns ORDER BY rowid")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(turns.len(), 6);
+        assert_eq!(turns[1], ("Ollama_2:llama3".to_string(), false, Some("Invalid move: row=9, col=9".to_string())));
+    }
+}
@@ -0,0 +1,401 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use serde_json::{Value, json};
+
+use crate::games::{TestResult, write_game_json};
+use crate::games::stats::TurnStats;
+use crate::games::svg::final_state_svg;
+use crate::results_file::RESULTS_SCHEMA_VERSION;
+
+/// Totals reported to sinks once every game in a batch has finished
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BatchSummary {
+    /// Number of games the batch attempted
+    pub total_games: u32,
+    /// Number of games that ran to completion
+    pub completed_games: u32,
+    /// Number of games whose result carries an error
+    pub errored_games: u32,
+}
+
+/// Exit status of a batch that ran but had games end in an error, set apart
+/// from the 1 of a batch that couldn't run at all
+pub const EXIT_GAMES_ERRORED: i32 = 2;
+
+impl BatchSummary {
+    /// Exit status for a finished batch: 0 if every game ended without an
+    /// error, else `EXIT_GAMES_ERRORED`
+    pub fn exit_code(&self) -> i32 {
+        if self.errored_games == 0 { 0 } else { EXIT_GAMES_ERRORED }
+    }
+}
+
+/// Receives results as a batch runs, so exporters and custom integrations
+/// can be plugged into the runner without adding a new flag per destination.
+pub trait ResultSink: Send + Sync {
+    /// Called once for every game as soon as it finishes
+    fn on_game_complete(&self, result: &TestResult);
+
+    /// Called once after the last game of the batch
+    fn on_batch_complete(&self, summary: &BatchSummary);
+}
+
+/// Fans results out to a list of sinks while keeping the batch totals
+pub struct SinkDispatcher<'a> {
+    sinks: &'a [Box<dyn ResultSink>],
+    summary: BatchSummary,
+}
+
+impl<'a> SinkDispatcher<'a> {
+    pub fn new(sinks: &'a [Box<dyn ResultSink>]) -> Self {
+        Self {
+            sinks,
+            summary: BatchSummary::default(),
+        }
+    }
+
+    pub fn game_complete(&mut self, result: &TestResult) {
+        self.summary.total_games += 1;
+        self.summary.completed_games += 1;
+        if result.error().is_some() {
+            self.summary.errored_games += 1;
+        }
+        for sink in self.sinks {
+            sink.on_game_complete(result);
+        }
+    }
+
+    pub fn summary(&self) -> &BatchSummary {
+        &self.summary
+    }
+
+    pub fn finish(self) -> BatchSummary {
+        for sink in self.sinks {
+            sink.on_batch_complete(&self.summary);
+        }
+        self.summary
+    }
+}
+
+/// Writes an SVG of each game's final position into a directory
+pub struct SvgSink {
+    dir: PathBuf,
+    games: AtomicU32,
+}
+
+impl SvgSink {
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self, String> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        Ok(Self {
+            dir,
+            games: AtomicU32::new(0),
+        })
+    }
+}
+
+impl ResultSink for SvgSink {
+    fn on_game_complete(&self, result: &TestResult) {
+        let n = self.games.fetch_add(1, Ordering::Relaxed) + 1;
+        let Some(svg) = final_state_svg(result) else {
+            return;
+        };
+        let kind = match result {
+            TestResult::TicTacToe(_) => "tictactoe",
+            TestResult::RockPaperScissors(_) => "rps",
+            TestResult::ConnectFour(_) => "connectfour",
+            TestResult::Gomoku(_) => "gomoku",
+            TestResult::Checkers(_) => "checkers",
+            TestResult::Nim(_) => "nim",
+            TestResult::Reversi(_) => "reversi",
+            TestResult::Custom(_) => "custom",
+        };
+        let path = self.dir.join(format!("game_{:04}_{}.svg", n, kind));
+        if let Err(e) = std::fs::write(&path, svg) {
+            tracing::error!("Failed to write {}: {}", path.display(), e);
+        }
+    }
+
+    fn on_batch_complete(&self, _summary: &BatchSummary) {}
+}
+
+/// One turn as a chat-completion training record: the system and user
+/// messages the agent was sent and its raw reply. `None` for turns recorded
+/// without them, e.g. by agents that aren't chat models or loaded from a
+/// results file.
+pub fn chat_record(turn: &TurnStats) -> Option<Value> {
+    let system_prompt = turn.system_prompt.as_deref()?;
+    let prompt = turn.prompt.as_deref()?;
+    let reply = turn.diagnostics.as_deref()?;
+    Some(json!({
+        "messages": [
+            {"role": "system", "content": system_prompt},
+            {"role": "user", "content": prompt},
+            {"role": "assistant", "content": reply},
+        ]
+    }))
+}
+
+/// Appends every turn to a JSONL file in chat-completion format, for
+/// fine-tuning or prompt analysis
+pub struct ChatJsonlSink {
+    file: Mutex<File>,
+}
+
+impl ChatJsonlSink {
+    pub fn create(path: impl Into<PathBuf>) -> Result<Self, String> {
+        let path = path.into();
+        let file = File::create(&path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        Ok(Self { file: Mutex::new(file) })
+    }
+}
+
+impl ResultSink for ChatJsonlSink {
+    fn on_game_complete(&self, result: &TestResult) {
+        let mut file = self.file.lock().unwrap();
+        for record in result.stats().turns.iter().filter_map(chat_record) {
+            if let Err(e) = writeln!(file, "{}", record) {
+                tracing::error!("Failed to write chat record: {}", e);
+                return;
+            }
+        }
+    }
+
+    fn on_batch_complete(&self, _summary: &BatchSummary) {
+        let _ = self.file.lock().unwrap().flush();
+    }
+}
+
+/// Writes every game's full result into one results file (see
+/// `results_file::ResultsFile`), for downstream analysis. Games are streamed
+/// into its `results` array, which is closed when the batch completes.
+pub struct JsonArraySink {
+    file: Mutex<(File, u32)>,
+}
+
+impl JsonArraySink {
+    pub fn create(path: impl Into<PathBuf>) -> Result<Self, String> {
+        let path = path.into();
+        let mut file = File::create(&path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        write!(file, "{{\n\"schema_version\": {},\n\"results\": [", RESULTS_SCHEMA_VERSION).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        Ok(Self { file: Mutex::new((file, 0)) })
+    }
+}
+
+impl ResultSink for JsonArraySink {
+    fn on_game_complete(&self, result: &TestResult) {
+        let mut guard = self.file.lock().unwrap();
+        let (file, games) = &mut *guard;
+        let separator = if *games == 0 { "\n" } else { ",\n" };
+        *games += 1;
+        let written = write!(file, "{}", separator).map_err(|e| e.to_string());
+        if let Err(e) = written.and_then(|()| write_game_json(result, &mut *file)) {
+            tracing::error!("Failed to write JSON result: {}", e);
+        }
+    }
+
+    fn on_batch_complete(&self, _summary: &BatchSummary) {
+        let (file, _) = &mut *self.file.lock().unwrap();
+        if let Err(e) = writeln!(file, "\n]}}").and_then(|_| file.flush()) {
+            tracing::error!("Failed to finish JSON results: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::{GameStats, TicTacToeResult};
+    use crate::results_file::ResultsFile;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct Recorded {
+        games: Vec<Option<String>>,
+        batches: Vec<BatchSummary>,
+    }
+
+    struct RecordingSink {
+        recorded: Arc<Mutex<Recorded>>,
+    }
+
+    impl ResultSink for RecordingSink {
+        fn on_game_complete(&self, result: &TestResult) {
+            self.recorded.lock().unwrap().games.push(result.winner().cloned());
+        }
+
+        fn on_batch_complete(&self, summary: &BatchSummary) {
+            self.recorded.lock().unwrap().batches.push(summary.clone());
+        }
+    }
+
+    fn result(winner: Option<&str>, error: Option<&str>) -> TestResult {
+        TestResult::TicTacToe(TicTacToeResult {
+            winner: winner.map(|w| w.to_string()),
+            stats: GameStats::new(),
+            error: error.map(|e| e.to_string()),
+        })
+    }
+
+    #[test]
+    fn test_sink_receives_each_game_and_one_batch_complete() {
+        let recorded = Arc::new(Mutex::new(Recorded::default()));
+        let sinks: Vec<Box<dyn ResultSink>> = vec![Box::new(RecordingSink {
+            recorded: recorded.clone(),
+        })];
+
+        let mut dispatcher = SinkDispatcher::new(&sinks);
+        dispatcher.game_complete(&result(Some("OpenAI_1 (X)"), None));
+        dispatcher.game_complete(&result(None, None));
+        dispatcher.game_complete(&result(None, Some("boom")));
+        let summary = dispatcher.finish();
+
+        let recorded = recorded.lock().unwrap();
+        assert_eq!(recorded.games.len(), 3);
+        assert_eq!(recorded.games[0], Some("OpenAI_1 (X)".to_string()));
+        assert_eq!(recorded.batches.len(), 1);
+        assert_eq!(recorded.batches[0], summary);
+        assert_eq!(summary.total_games, 3);
+        assert_eq!(summary.completed_games, 3);
+        assert_eq!(summary.errored_games, 1);
+    }
+
+    #[test]
+    fn test_dispatch_to_multiple_sinks() {
+        let first = Arc::new(Mutex::new(Recorded::default()));
+        let second = Arc::new(Mutex::new(Recorded::default()));
+        let sinks: Vec<Box<dyn ResultSink>> = vec![
+            Box::new(RecordingSink { recorded: first.clone() }),
+            Box::new(RecordingSink { recorded: second.clone() }),
+        ];
+
+        let mut dispatcher = SinkDispatcher::new(&sinks);
+        dispatcher.game_complete(&result(None, None));
+        dispatcher.finish();
+
+        for recorded in [first, second] {
+            let recorded = recorded.lock().unwrap();
+            assert_eq!(recorded.games.len(), 1);
+            assert_eq!(recorded.batches.len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_chat_jsonl_records_have_chat_roles() {
+        let path = std::env::temp_dir().join(format!("ai_arena_chat_{}.jsonl", uuid::Uuid::new_v4()));
+        let mut stats = GameStats::new();
+        stats.add_turn(TurnStats {
+            turn_number: 1,
+            player: "OpenAI_1".to_string(),
+            move_made: json!({"row": 1, "col": 1}),
+            time_taken_ms: 5,
+            api_latency_ms: 5,
+            move_valid: true,
+            state_before: json!({}),
+            state_after: json!({}),
+            prompt: Some(r#"{"turn_index":1}"#.to_string()),
+            system_prompt: Some("You are a cautious player.".to_string()),
+            diagnostics: Some("```json\n{\"row\": 1, \"col\": 1}\n```".to_string()),
+            ..Default::default()
+        });
+        // A scripted agent's turn has a prompt but no system message or reply
+        stats.add_turn(TurnStats {
+            turn_number: 2,
+            player: "Scripted_2".to_string(),
+            move_made: json!({"row": 0, "col": 0}),
+            move_valid: true,
+            prompt: Some(r#"{"turn_index":2}"#.to_string()),
+            ..Default::default()
+        });
+        let game = TestResult::TicTacToe(TicTacToeResult {
+            winner: None,
+            stats,
+            error: None,
+        });
+
+        let sinks: Vec<Box<dyn ResultSink>> = vec![Box::new(ChatJsonlSink::create(&path).unwrap())];
+        let mut dispatcher = SinkDispatcher::new(&sinks);
+        dispatcher.game_complete(&game);
+        // Results without prompts contribute nothing
+        dispatcher.game_complete(&result(None, None));
+        dispatcher.finish();
+        drop(sinks);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let lines: Vec<Value> = contents.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 1);
+
+        let messages = lines[0]["messages"].as_array().unwrap();
+        let roles: Vec<&str> = messages.iter().map(|m| m["role"].as_str().unwrap()).collect();
+        assert_eq!(roles, ["system", "user", "assistant"]);
+        assert_eq!(messages[0]["content"], "You are a cautious player.");
+        assert_eq!(messages[1]["content"], r#"{"turn_index":1}"#);
+        assert_eq!(messages[2]["content"], "```json\n{\"row\": 1, \"col\": 1}\n```");
+    }
+
+    #[test]
+    fn test_json_array_holds_every_game() {
+        let path = std::env::temp_dir().join(format!("ai_arena_results_{}.json", uuid::Uuid::new_v4()));
+        let sinks: Vec<Box<dyn ResultSink>> = vec![Box::new(JsonArraySink::create(&path).unwrap())];
+        let mut dispatcher = SinkDispatcher::new(&sinks);
+        dispatcher.game_complete(&result(Some("OpenAI_1 (X)"), None));
+        dispatcher.game_complete(&result(None, Some("boom")));
+        dispatcher.finish();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let (file, changes) = ResultsFile::read_from(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        // Already current, so reading it back upgrades nothing
+        assert!(changes.is_empty());
+        assert_eq!(file.schema_version, RESULTS_SCHEMA_VERSION);
+        let games = file.results;
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].winner().map(String::as_str), Some("OpenAI_1 (X)"));
+        assert_eq!(games[1].error(), Some("boom"));
+
+        let raw: Value = serde_json::from_str(&contents).unwrap();
+        let stats = &raw["results"][0]["TicTacToe"]["stats"];
+        assert!(stats["turns"].is_array());
+    }
+
+    #[test]
+    fn test_empty_batch_writes_empty_results() {
+        let path = std::env::temp_dir().join(format!("ai_arena_results_{}.json", uuid::Uuid::new_v4()));
+        let sinks: Vec<Box<dyn ResultSink>> = vec![Box::new(JsonArraySink::create(&path).unwrap())];
+        SinkDispatcher::new(&sinks).finish();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&contents).unwrap(),
+            serde_json::to_value(ResultsFile::new(Vec::new())).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_no_sinks_still_counts() {
+        let sinks: Vec<Box<dyn ResultSink>> = Vec::new();
+        let mut dispatcher = SinkDispatcher::new(&sinks);
+        dispatcher.game_complete(&result(None, None));
+        assert_eq!(dispatcher.summary().completed_games, 1);
+        assert_eq!(dispatcher.finish().total_games, 1);
+    }
+
+    #[test]
+    fn test_exit_code_fails_on_any_errored_game() {
+        let sinks: Vec<Box<dyn ResultSink>> = Vec::new();
+        let mut dispatcher = SinkDispatcher::new(&sinks);
+        assert_eq!(dispatcher.summary().exit_code(), 0);
+        dispatcher.game_complete(&result(Some("a (X)"), None));
+        dispatcher.game_complete(&result(None, None));
+        assert_eq!(dispatcher.summary().exit_code(), 0);
+        dispatcher.game_complete(&result(None, Some("401 Unauthorized")));
+        dispatcher.game_complete(&result(Some("a (X)"), None));
+        assert_eq!(dispatcher.finish().exit_code(), EXIT_GAMES_ERRORED);
+    }
+}
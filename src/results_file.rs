@@ -0,0 +1,321 @@
+use serde_json::{Value, json};
+use std::fs;
+use std::path::Path;
+
+use crate::games::TestResult;
+
+/// Version written by this build. Bump it together with a new upgrade step
+/// in `upgrade` whenever the shape of `TestResult` changes.
+pub const RESULTS_SCHEMA_VERSION: u32 = 3;
+
+/// On-disk results export
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ResultsFile {
+    pub schema_version: u32,
+    pub results: Vec<TestResult>,
+}
+
+impl ResultsFile {
+    pub fn new(results: Vec<TestResult>) -> Self {
+        Self {
+            schema_version: RESULTS_SCHEMA_VERSION,
+            results,
+        }
+    }
+
+    /// Read a results file of any known version, upgrading it in memory
+    pub fn read_from<P: AsRef<Path>>(path: P) -> Result<(Self, Vec<String>), String> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let value: Value = serde_json::from_str(&contents)
+            .map_err(|e| format!("Invalid JSON in {}: {}", path.display(), e))?;
+        let (value, changes) = upgrade(value)?;
+        let file = serde_json::from_value(value)
+            .map_err(|e| format!("Results do not match schema v{}: {}", RESULTS_SCHEMA_VERSION, e))?;
+        Ok((file, changes))
+    }
+
+    pub fn write_to<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        let path = path.as_ref();
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize results: {}", e))?;
+        fs::write(path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+/// Version of a results document. Files without `schema_version` predate
+/// versioning and are version 1 (a bare array of results).
+pub fn schema_version(value: &Value) -> Result<u32, String> {
+    match value {
+        Value::Array(_) => Ok(1),
+        Value::Object(map) => map
+            .get("schema_version")
+            .and_then(Value::as_u64)
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| "Missing or invalid 'schema_version' field".to_string()),
+        _ => Err("Results must be a JSON array or object".to_string()),
+    }
+}
+
+/// Upgrade a results document to `RESULTS_SCHEMA_VERSION`, returning the
+/// upgraded document and a description of every change made
+pub fn upgrade(mut value: Value) -> Result<(Value, Vec<String>), String> {
+    let mut changes = Vec::new();
+    let mut version = schema_version(&value)?;
+    if version > RESULTS_SCHEMA_VERSION {
+        return Err(format!(
+            "Results use schema v{}, but this build only understands up to v{}",
+            version, RESULTS_SCHEMA_VERSION
+        ));
+    }
+
+    while version < RESULTS_SCHEMA_VERSION {
+        value = match version {
+            1 => v1_to_v2(value, &mut changes),
+            2 => v2_to_v3(value, &mut changes),
+            _ => unreachable!("no upgrade step from schema v{}", version),
+        };
+        version += 1;
+    }
+
+    Ok((value, changes))
+}
+
+/// v2 wraps the results in a versioned object and adds `draw` to game stats
+/// and `diagnostics` to turns
+fn v1_to_v2(value: Value, changes: &mut Vec<String>) -> Value {
+    let mut results = match value {
+        Value::Array(results) => results,
+        _ => Vec::new(),
+    };
+    changes.push("v1 -> v2: wrapped results in a versioned object".to_string());
+
+    for (idx, result) in results.iter_mut().enumerate() {
+        // Each result is an externally tagged enum: {"TicTacToe": {...}}
+        let Some(stats) = result
+            .as_object_mut()
+            .and_then(|tagged| tagged.values_mut().next())
+            .and_then(|inner| inner.get_mut("stats"))
+            .and_then(Value::as_object_mut)
+        else {
+            continue;
+        };
+
+        if !stats.contains_key("draw") {
+            stats.insert("draw".to_string(), json!(false));
+            changes.push(format!("result {}: added stats.draw = false", idx + 1));
+        }
+
+        let mut filled = 0;
+        if let Some(turns) = stats.get_mut("turns").and_then(Value::as_array_mut) {
+            for turn in turns.iter_mut().filter_map(Value::as_object_mut) {
+                if !turn.contains_key("diagnostics") {
+                    turn.insert("diagnostics".to_string(), Value::Null);
+                    filled += 1;
+                }
+            }
+        }
+        if filled > 0 {
+            changes.push(format!("result {}: added diagnostics = null to {} turn(s)", idx + 1, filled));
+        }
+    }
+
+    json!({ "schema_version": 2, "results": results })
+}
+
+/// v3 adds to game stats the `seats`, `move_list`, token totals and
+/// `invalid_moves_by_player`, and splits each turn's `time_taken_ms` into
+/// `api_latency_ms` and `processing_ms`. Seats and moves can't be recovered
+/// so are left empty, invalid moves are counted from the turns, and the
+/// whole of each turn's time is put down to the API.
+fn v2_to_v3(mut value: Value, changes: &mut Vec<String>) -> Value {
+    let mut results = value
+        .get_mut("results")
+        .and_then(Value::as_array_mut)
+        .map(std::mem::take)
+        .unwrap_or_default();
+
+    for (idx, result) in results.iter_mut().enumerate() {
+        let Some(stats) = result
+            .as_object_mut()
+            .and_then(|tagged| tagged.values_mut().next())
+            .and_then(|inner| inner.get_mut("stats"))
+            .and_then(Value::as_object_mut)
+        else {
+            continue;
+        };
+
+        let mut added = Vec::new();
+        for (field, empty) in [
+            ("game_id", Value::Null),
+            ("seats", json!([])),
+            ("move_list", json!([])),
+            ("total_prompt_tokens", Value::Null),
+            ("total_completion_tokens", Value::Null),
+        ] {
+            if !stats.contains_key(field) {
+                stats.insert(field.to_string(), empty);
+                added.push(field);
+            }
+        }
+        if !added.is_empty() {
+            changes.push(format!("result {}: added empty stats.{}", idx + 1, added.join(", stats.")));
+        }
+
+        let mut split = 0;
+        let mut invalid_by_player = serde_json::Map::new();
+        if let Some(turns) = stats.get_mut("turns").and_then(Value::as_array_mut) {
+            for turn in turns.iter_mut().filter_map(Value::as_object_mut) {
+                if let Some(player) = turn.get("player").and_then(Value::as_str) {
+                    let invalid = turn.get("move_valid") == Some(&json!(false));
+                    let count = invalid_by_player.entry(player.to_string()).or_insert(json!(0));
+                    *count = json!(count.as_u64().unwrap_or(0) + u64::from(invalid));
+                }
+                if !turn.contains_key("api_latency_ms") {
+                    let time_taken = turn.get("time_taken_ms").cloned().unwrap_or(json!(0));
+                    turn.insert("api_latency_ms".to_string(), time_taken);
+                    turn.insert("processing_ms".to_string(), json!(0));
+                    split += 1;
+                }
+            }
+        }
+        if split > 0 {
+            changes.push(format!(
+                "result {}: set api_latency_ms = time_taken_ms, processing_ms = 0 on {} turn(s)",
+                idx + 1,
+                split
+            ));
+        }
+        if !stats.contains_key("invalid_moves_by_player") {
+            stats.insert("invalid_moves_by_player".to_string(), Value::Object(invalid_by_player));
+            changes.push(format!("result {}: counted stats.invalid_moves_by_player from the turns", idx + 1));
+        }
+    }
+
+    json!({ "schema_version": 3, "results": results })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V1_FIXTURE: &str = r#"[
+        {"TicTacToe": {
+            "winner": "OpenAI_1 (X)",
+            "error": null,
+            "stats": {
+                "turns": [{
+                    "turn_number": 1,
+                    "player": "OpenAI_1",
+                    "move_made": {"row": 1, "col": 1},
+                    "time_taken_ms": 12,
+                    "move_valid": true,
+                    "error_message": null,
+                    "state_before": {},
+                    "state_after": {}
+                }],
+                "total_duration_ms": 12,
+                "invalid_moves": 0,
+                "winner": "OpenAI_1 (X)"
+            }
+        }}
+    ]"#;
+
+    #[test]
+    fn test_upgrade_v1_fixture() {
+        let value: Value = serde_json::from_str(V1_FIXTURE).unwrap();
+        let (upgraded, changes) = upgrade(value).unwrap();
+
+        assert_eq!(schema_version(&upgraded).unwrap(), RESULTS_SCHEMA_VERSION);
+        // Three changes to reach v2, then three more to reach v3
+        assert_eq!(changes.len(), 6);
+
+        let file: ResultsFile = serde_json::from_value(upgraded).unwrap();
+        assert_eq!(file.results.len(), 1);
+        let stats = file.results[0].stats();
+        assert!(!stats.draw);
+        assert_eq!(stats.turns[0].diagnostics, None);
+        assert_eq!(file.results[0].winner(), Some(&"OpenAI_1 (X)".to_string()));
+    }
+
+    /// Written by the v2 build: no seats, move list, token totals, per-player
+    /// invalid moves or latency split
+    const V2_FIXTURE: &str = r#"{
+        "schema_version": 2,
+        "results": [
+            {"ConnectFour": {
+                "winner": "OpenAI_1:gpt-4o-mini (Red)",
+                "error": null,
+                "stats": {
+                    "turns": [
+                        {
+                            "turn_number": 1,
+                            "player": "OpenAI_1:gpt-4o-mini",
+                            "move_made": {"column": 3},
+                            "time_taken_ms": 410,
+                            "move_valid": true,
+                            "error_message": null,
+                            "state_before": {},
+                            "state_after": {},
+                            "diagnostics": null
+                        },
+                        {
+                            "turn_number": 2,
+                            "player": "Ollama_2:llama3",
+                            "move_made": {"column": 9},
+                            "time_taken_ms": 120,
+                            "move_valid": false,
+                            "error_message": "Invalid move: column=9 (column full or out of bounds)",
+                            "state_before": {},
+                            "state_after": {},
+                            "diagnostics": null
+                        }
+                    ],
+                    "total_duration_ms": 530,
+                    "invalid_moves": 1,
+                    "winner": "OpenAI_1:gpt-4o-mini (Red)",
+                    "draw": false
+                }
+            }}
+        ]
+    }"#;
+
+    #[test]
+    fn test_upgrade_v2_fixture() {
+        let value: Value = serde_json::from_str(V2_FIXTURE).unwrap();
+        let (upgraded, changes) = upgrade(value).unwrap();
+
+        assert_eq!(schema_version(&upgraded).unwrap(), RESULTS_SCHEMA_VERSION);
+        assert_eq!(changes, [
+            "result 1: added empty stats.game_id, stats.seats, stats.move_list, stats.total_prompt_tokens, stats.total_completion_tokens",
+            "result 1: set api_latency_ms = time_taken_ms, processing_ms = 0 on 2 turn(s)",
+            "result 1: counted stats.invalid_moves_by_player from the turns",
+        ]);
+
+        let file: ResultsFile = serde_json::from_value(upgraded).unwrap();
+        let stats = file.results[0].stats();
+        assert_eq!(
+            stats.invalid_moves_by_player,
+            [("OpenAI_1:gpt-4o-mini".to_string(), 0), ("Ollama_2:llama3".to_string(), 1)].into()
+        );
+        assert_eq!((stats.turns[0].api_latency_ms, stats.turns[0].processing_ms), (410, 0));
+        assert!(stats.seats.is_empty() && stats.move_list.is_empty());
+        assert_eq!(stats.total_prompt_tokens, None);
+        assert_eq!(file.results[0].winner().map(String::as_str), Some("OpenAI_1:gpt-4o-mini (Red)"));
+    }
+
+    #[test]
+    fn test_upgrade_current_is_noop() {
+        let value = serde_json::to_value(ResultsFile::new(Vec::new())).unwrap();
+        let (upgraded, changes) = upgrade(value.clone()).unwrap();
+        assert_eq!(upgraded, value);
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn test_upgrade_rejects_newer_version() {
+        let value = json!({ "schema_version": RESULTS_SCHEMA_VERSION + 1, "results": [] });
+        assert!(upgrade(value).unwrap_err().contains("only understands"));
+    }
+}
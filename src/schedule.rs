@@ -0,0 +1,94 @@
+use crate::agent_config::AgentKind;
+use crate::csv_runner::CsvTestCase;
+
+/// Order in which a batch's test cases are started
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ScheduleStrategy {
+    /// Rows run in file order
+    #[default]
+    InOrder,
+    /// Round-robin across provider pairings, so a run of cases against one
+    /// slow provider can't hold up everyone queued behind it
+    InterleaveProviders,
+}
+
+fn providers(case: &CsvTestCase) -> (AgentKind, AgentKind) {
+    (case.agent_one_kind, case.agent_two_kind)
+}
+
+/// Indices into `cases` in the order they should run
+pub fn schedule_order(cases: &[CsvTestCase], strategy: ScheduleStrategy) -> Vec<usize> {
+    match strategy {
+        ScheduleStrategy::InOrder => (0..cases.len()).collect(),
+        ScheduleStrategy::InterleaveProviders => {
+            // Queues per provider pairing, in order of first appearance
+            let mut queues: Vec<((AgentKind, AgentKind), Vec<usize>)> = Vec::new();
+            for (idx, case) in cases.iter().enumerate() {
+                let key = providers(case);
+                match queues.iter_mut().find(|(k, _)| *k == key) {
+                    Some((_, queue)) => queue.push(idx),
+                    None => queues.push((key, vec![idx])),
+                }
+            }
+
+            let longest = queues.iter().map(|(_, q)| q.len()).max().unwrap_or(0);
+            (0..longest)
+                .flat_map(|round| queues.iter().filter_map(move |(_, q)| q.get(round).copied()))
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::GameParams;
+
+    fn case(one: AgentKind, two: AgentKind) -> CsvTestCase {
+        CsvTestCase {
+            game_name: "TicTacToe".to_string(),
+            game_params: GameParams::default(),
+            agent_one_kind: one,
+            agent_one_model: "m".to_string(),
+            agent_one_temp: 0.0,
+            agent_one_seed: 0,
+            agent_one_secret_profile: None,
+            agent_one_max_tokens: None,
+            agent_one_temp_schedule: None,
+            agent_one_system_prompt: None,
+            agent_one_system_prompt_file: None,
+            agent_two_kind: two,
+            agent_two_model: "m".to_string(),
+            agent_two_temp: 0.0,
+            agent_two_seed: 0,
+            agent_two_secret_profile: None,
+            agent_two_max_tokens: None,
+            agent_two_temp_schedule: None,
+            agent_two_system_prompt: None,
+            agent_two_system_prompt_file: None,
+            repetitions: 1,
+            description: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_interleaves_providers() {
+        use AgentKind::*;
+        let cases = vec![
+            case(Ollama, Ollama),
+            case(Ollama, Ollama),
+            case(Ollama, Ollama),
+            case(OpenAI, OpenAI),
+            case(OpenAI, OpenAI),
+            case(Anthropic, OpenAI),
+        ];
+
+        assert_eq!(schedule_order(&cases, ScheduleStrategy::InOrder), vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(schedule_order(&cases, ScheduleStrategy::InterleaveProviders), vec![0, 3, 5, 1, 4, 2]);
+    }
+
+    #[test]
+    fn test_empty_schedule() {
+        assert!(schedule_order(&[], ScheduleStrategy::InterleaveProviders).is_empty());
+    }
+}
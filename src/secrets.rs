@@ -1,51 +1,107 @@
 use std::collections::HashMap;
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+#[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
 
+use crate::agent_config::AgentKind;
+
 #[derive(Debug, Clone)]
 pub struct SecretsManager {
     secrets: SecretsConfig,
     config_path: PathBuf,
 }
 
-#[derive(Debug, Clone, serde::Deserialize, Default)]
+/// Written by `SecretsManager::init`: every table commented out, so the
+/// file parses as empty until keys are filled in
+const SECRETS_TEMPLATE: &str = r#"# ai_arena secrets. Keep this file private (chmod 600).
+# Uncomment a profile and fill in its key; `default` is used when a test case
+# names no profile.
+
+# [secrets.openai.default]
+# api_key = "sk-..."
+# Optional, for OpenAI-compatible servers such as vLLM or LM Studio
+# base_url = "http://localhost:8000/v1"
+
+# [secrets.anthropic.default]
+# api_key = "sk-ant-..."
+
+# [secrets.ollama.default]
+# base_url = "http://localhost:11434"
+
+# Optional provider defaults, used when a CSV row leaves the model or temp empty.
+# [defaults.openai]
+# model = "gpt-4o-mini"
+# temp = 0.7
+"#;
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize, Default)]
 struct SecretsConfig {
     #[serde(default)]
     secrets: SecretsSection,
+    #[serde(default)]
+    defaults: ModelDefaults,
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize, Default)]
 struct SecretsSection {
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     openai: HashMap<String, OpenAISecret>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     anthropic: HashMap<String, AnthropicSecret>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     ollama: HashMap<String, OllamaSecret>,
 }
 
-impl Default for SecretsSection {
-    fn default() -> Self {
-        Self {
-            openai: HashMap::new(),
-            anthropic: HashMap::new(),
-            ollama: HashMap::new(),
+/// Model settings for one provider, used when a test case leaves them out
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize, Default, PartialEq)]
+pub struct ProviderDefaults {
+    pub model: Option<String>,
+    pub temp: Option<f32>,
+}
+
+/// For agents that aren't a provider, such as scripted ones
+const NO_DEFAULTS: ProviderDefaults = ProviderDefaults { model: None, temp: None };
+
+/// Per-provider defaults from the `[defaults.<provider>]` tables
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize, Default)]
+pub struct ModelDefaults {
+    #[serde(default)]
+    pub openai: ProviderDefaults,
+    #[serde(default)]
+    pub anthropic: ProviderDefaults,
+    #[serde(default)]
+    pub ollama: ProviderDefaults,
+}
+
+impl ModelDefaults {
+    pub fn for_kind(&self, kind: AgentKind) -> &ProviderDefaults {
+        match kind {
+            AgentKind::OpenAI => &self.openai,
+            AgentKind::Anthropic => &self.anthropic,
+            AgentKind::Ollama => &self.ollama,
+            AgentKind::Scripted | AgentKind::Minimax => &NO_DEFAULTS,
         }
     }
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 pub struct OpenAISecret {
     pub api_key: String,
+    /// API base of an OpenAI-compatible server, replacing api.openai.com
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 pub struct AnthropicSecret {
     pub api_key: String,
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 pub struct OllamaSecret {
     pub base_url: String,
 }
@@ -96,27 +152,12 @@ impl SecretsManager {
         if !path.exists() {
             // Return empty manager if file doesn't exist (will fall back to env vars)
             return Ok(Self {
-                secrets: SecretsConfig {
-                    secrets: SecretsSection {
-                        openai: HashMap::new(),
-                        anthropic: HashMap::new(),
-                        ollama: HashMap::new(),
-                    },
-                },
+                secrets: SecretsConfig::default(),
                 config_path: path.to_path_buf(),
             });
         }
 
-        // Check file permissions (warn if world-readable)
-        let metadata = fs::metadata(path)
-            .map_err(|e| SecretsError::IoError(format!("Failed to read metadata: {}", e)))?;
-        let permissions = metadata.permissions();
-        let mode = permissions.mode();
-        
-        // Check if file is readable by others (permission bits 004, 005, 006, 007)
-        if (mode & 0o007) != 0 {
-            eprintln!("⚠️  Warning: Secrets file is readable by others. Consider running: chmod 600 {}", path.display());
-        }
+        warn_if_readable_by_others(path)?;
 
         // Read and parse the file
         let contents = fs::read_to_string(path)
@@ -131,6 +172,60 @@ impl SecretsManager {
         })
     }
 
+    /// Create a secrets file at `path` from a commented-out template, readable
+    /// only by the owner. Refuses to overwrite an existing file.
+    pub fn init(path: &Path) -> Result<Self, SecretsError> {
+        if path.exists() {
+            return Err(SecretsError::IoError(format!("{} already exists", path.display())));
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| SecretsError::IoError(format!("Failed to create {}: {}", dir.display(), e)))?;
+        }
+        write_private(path, SECRETS_TEMPLATE)?;
+        Self::load_from_path(path)
+    }
+
+    /// Write the current secrets back to the file they were loaded from,
+    /// readable only by the owner. Comments in the file are not kept.
+    pub fn save(&self) -> Result<(), SecretsError> {
+        let contents = toml::to_string(&self.secrets)
+            .map_err(|e| SecretsError::InvalidFormat(format!("Failed to serialize TOML: {}", e)))?;
+        write_private(&self.config_path, &contents)
+    }
+
+    /// Add or replace an OpenAI profile. Call `save` to persist it.
+    pub fn set_openai(&mut self, profile: &str, api_key: &str) {
+        let secret = OpenAISecret {
+            api_key: api_key.to_string(),
+            base_url: None,
+        };
+        self.secrets.secrets.openai.insert(profile.to_string(), secret);
+    }
+
+    /// Add or replace an Anthropic profile. Call `save` to persist it.
+    pub fn set_anthropic(&mut self, profile: &str, api_key: &str) {
+        let secret = AnthropicSecret { api_key: api_key.to_string() };
+        self.secrets.secrets.anthropic.insert(profile.to_string(), secret);
+    }
+
+    /// Add or replace an Ollama profile. Call `save` to persist it.
+    pub fn set_ollama(&mut self, profile: &str, base_url: &str) {
+        let secret = OllamaSecret { base_url: base_url.to_string() };
+        self.secrets.secrets.ollama.insert(profile.to_string(), secret);
+    }
+
+    /// Load secrets from the default location, warning and falling back to
+    /// an empty manager (env vars only) if the file can't be read
+    pub fn load_or_empty() -> Self {
+        load_or_warn(&Self::default_config_path())
+    }
+
+    /// Provider-level model/temperature defaults
+    pub fn model_defaults(&self) -> &ModelDefaults {
+        &self.secrets.defaults
+    }
+
     /// Get OpenAI secret by profile name
     pub fn get_openai(&self, profile: &str) -> Result<&OpenAISecret, SecretsError> {
         self.secrets
@@ -161,10 +256,10 @@ impl SecretsManager {
     /// Resolve OpenAI API key with fallback to environment variable
     pub fn resolve_openai_key(&self, profile: Option<&str>) -> Result<String, SecretsError> {
         // Try secret profile first
-        if let Some(profile_name) = profile {
-            if let Ok(secret) = self.get_openai(profile_name) {
-                return Ok(secret.api_key.clone());
-            }
+        if let Some(profile_name) = profile
+            && let Ok(secret) = self.get_openai(profile_name)
+        {
+            return Ok(secret.api_key.clone());
         }
 
         // Fallback to environment variable
@@ -182,13 +277,29 @@ impl SecretsManager {
         ))
     }
 
+    /// Resolve the OpenAI API base with the same precedence as the key;
+    /// `None` means api.openai.com
+    pub fn resolve_openai_base_url(&self, profile: Option<&str>) -> Option<String> {
+        if let Some(profile_name) = profile
+            && let Ok(secret) = self.get_openai(profile_name)
+        {
+            return secret.base_url.clone();
+        }
+
+        if let Ok(url) = std::env::var("OPENAI_BASE_URL") {
+            return Some(url);
+        }
+
+        self.get_openai("default").ok().and_then(|secret| secret.base_url.clone())
+    }
+
     /// Resolve Anthropic API key with fallback to environment variable
     pub fn resolve_anthropic_key(&self, profile: Option<&str>) -> Result<String, SecretsError> {
         // Try secret profile first
-        if let Some(profile_name) = profile {
-            if let Ok(secret) = self.get_anthropic(profile_name) {
-                return Ok(secret.api_key.clone());
-            }
+        if let Some(profile_name) = profile
+            && let Ok(secret) = self.get_anthropic(profile_name)
+        {
+            return Ok(secret.api_key.clone());
         }
 
         // Fallback to environment variable
@@ -209,10 +320,10 @@ impl SecretsManager {
     /// Resolve Ollama base URL with fallback to environment variable
     pub fn resolve_ollama_base_url(&self, profile: Option<&str>) -> Result<String, SecretsError> {
         // Try secret profile first
-        if let Some(profile_name) = profile {
-            if let Ok(secret) = self.get_ollama(profile_name) {
-                return Ok(secret.base_url.clone());
-            }
+        if let Some(profile_name) = profile
+            && let Ok(secret) = self.get_ollama(profile_name)
+        {
+            return Ok(secret.base_url.clone());
         }
 
         // Fallback to environment variable
@@ -226,7 +337,7 @@ impl SecretsManager {
         }
 
         // Default fallback
-        Ok("http://localhost:11434".to_string())
+        Ok(crate::agents::ollama::DEFAULT_BASE_URL.to_string())
     }
 
     /// Get the config path
@@ -235,3 +346,172 @@ impl SecretsManager {
     }
 }
 
+/// Secrets shared by every game of a batch. Loaded once by default; with
+/// `reload` set, the file is re-read whenever its modification time changes,
+/// so rotated keys and new profiles are picked up mid-batch.
+#[derive(Debug)]
+pub struct SecretsCache {
+    path: PathBuf,
+    reload: bool,
+    cached: Mutex<(Option<SystemTime>, SecretsManager)>,
+}
+
+impl SecretsCache {
+    pub fn new(path: impl Into<PathBuf>, reload: bool) -> Self {
+        let path = path.into();
+        let manager = load_or_warn(&path);
+        Self {
+            cached: Mutex::new((modified(&path), manager)),
+            path,
+            reload,
+        }
+    }
+
+    /// Cache over the default secrets file
+    pub fn load_default(reload: bool) -> Self {
+        Self::new(SecretsManager::default_config_path(), reload)
+    }
+
+    /// Secrets to build the next game's agents with
+    pub fn current(&self) -> SecretsManager {
+        let mut cached = self.cached.lock().unwrap();
+        if self.reload {
+            let mtime = modified(&self.path);
+            if mtime != cached.0 {
+                tracing::info!("Secrets file changed, reloading {}", self.path.display());
+                *cached = (mtime, load_or_warn(&self.path));
+            }
+        }
+        cached.1.clone()
+    }
+}
+
+/// Warn if others can access the secrets file. Only unix has permission bits
+/// to check; elsewhere the file is trusted as-is.
+#[cfg(unix)]
+fn warn_if_readable_by_others(path: &Path) -> Result<(), SecretsError> {
+    let metadata = fs::metadata(path)
+        .map_err(|e| SecretsError::IoError(format!("Failed to read metadata: {}", e)))?;
+    let mode = metadata.permissions().mode();
+
+    // Check if file is readable by others (permission bits 004, 005, 006, 007)
+    if (mode & 0o007) != 0 {
+        tracing::warn!("Secrets file is readable by others. Consider running: chmod 600 {}", path.display());
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn warn_if_readable_by_others(_path: &Path) -> Result<(), SecretsError> {
+    Ok(())
+}
+
+/// Write `contents` to `path`, restricting it to owner read/write where the
+/// platform has unix permissions
+fn write_private(path: &Path, contents: &str) -> Result<(), SecretsError> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .map_err(|e| SecretsError::IoError(format!("Failed to open {}: {}", path.display(), e)))?;
+    // `mode` only applies when the file is created
+    #[cfg(unix)]
+    file.set_permissions(fs::Permissions::from_mode(0o600))
+        .map_err(|e| SecretsError::PermissionError(format!("Failed to chmod {}: {}", path.display(), e)))?;
+    file.write_all(contents.as_bytes())
+        .map_err(|e| SecretsError::IoError(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn load_or_warn(path: &Path) -> SecretsManager {
+    SecretsManager::load_from_path(path).unwrap_or_else(|e| {
+        tracing::warn!("Could not load secrets file: {}. Falling back to environment variables.", e);
+        SecretsManager {
+            secrets: SecretsConfig::default(),
+            config_path: path.to_path_buf(),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn write_key(path: &Path, key: &str, mtime: SystemTime) {
+        fs::write(path, format!("[secrets.openai.rotating]\napi_key = \"{}\"\n", key)).unwrap();
+        fs::File::options().write(true).open(path).unwrap().set_modified(mtime).unwrap();
+    }
+
+    #[test]
+    fn test_reload_picks_up_changed_file() {
+        let dir = std::env::temp_dir().join(format!("ai_arena_secrets_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("secrets.toml");
+        let start = SystemTime::now();
+        write_key(&path, "sk-old", start);
+
+        let reloading = SecretsCache::new(&path, true);
+        let cached = SecretsCache::new(&path, false);
+        assert_eq!(reloading.current().get_openai("rotating").unwrap().api_key, "sk-old");
+
+        // Key rotated between two games
+        write_key(&path, "sk-new", start + Duration::from_secs(5));
+        assert_eq!(reloading.current().get_openai("rotating").unwrap().api_key, "sk-new");
+        assert_eq!(cached.current().get_openai("rotating").unwrap().api_key, "sk-old");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_openai_profile_base_url() {
+        let dir = std::env::temp_dir().join(format!("ai_arena_secrets_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("secrets.toml");
+        fs::write(
+            &path,
+            "[secrets.openai.vllm]\napi_key = \"none\"\nbase_url = \"http://localhost:8000/v1\"\n\n\
+             [secrets.openai.work]\napi_key = \"sk-work\"\n",
+        )
+        .unwrap();
+
+        let manager = SecretsManager::load_from_path(&path).unwrap();
+        assert_eq!(manager.resolve_openai_base_url(Some("vllm")).as_deref(), Some("http://localhost:8000/v1"));
+        assert_eq!(manager.resolve_openai_base_url(Some("work")), None);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_init_then_set_round_trips() {
+        let dir = std::env::temp_dir().join(format!("ai_arena_secrets_{}", uuid::Uuid::new_v4()));
+        let path = dir.join("nested").join("secrets.toml");
+
+        let mut manager = SecretsManager::init(&path).unwrap();
+        assert!(manager.get_openai("default").is_err());
+        #[cfg(unix)]
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert!(SecretsManager::init(&path).is_err());
+
+        manager.set_openai("work", "sk-work");
+        manager.set_ollama("local", "http://localhost:11434");
+        manager.save().unwrap();
+
+        let reloaded = SecretsManager::load_from_path(&path).unwrap();
+        assert_eq!(reloaded.get_openai("work").unwrap().api_key, "sk-work");
+        assert_eq!(reloaded.get_ollama("local").unwrap().base_url, "http://localhost:11434");
+        assert!(reloaded.get_anthropic("default").is_err());
+        #[cfg(unix)]
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,332 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use tabled::{Table, Tabled, settings::{Alignment, Modify, Style, object::Rows}};
+
+use crate::decathlon::game_points;
+use crate::games::TestResult;
+
+/// One scheduled game of a tournament: who sits in each seat, and which
+/// repetition of that seating it is
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Pairing {
+    pub agents: [String; 2],
+    pub repetition: u32,
+}
+
+/// Win/loss/draw tally for one agent
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Record {
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    /// Games that ended in an error and count for neither side
+    pub no_results: u32,
+    /// Turns the agent took, and how many of them were invalid moves
+    #[serde(default)]
+    pub turns: u32,
+    #[serde(default)]
+    pub invalid_moves: u32,
+}
+
+impl Record {
+    /// 1 per win and 0.5 per draw, as in `game_points`
+    pub fn points(&self) -> f64 {
+        self.wins as f64 + 0.5 * self.draws as f64
+    }
+
+    pub fn invalid_rate(&self) -> f64 {
+        if self.turns == 0 {
+            0.0
+        } else {
+            self.invalid_moves as f64 / self.turns as f64
+        }
+    }
+}
+
+/// What a leaderboard ranks an agent by
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RankKey<'a> {
+    pub name: &'a str,
+    pub score: f64,
+    pub invalid_rate: f64,
+}
+
+/// Leaderboard order: higher score first, then lower invalid-move rate,
+/// then name, so tied agents print in the same order every run
+pub fn rank_cmp(a: &RankKey, b: &RankKey) -> Ordering {
+    b.score
+        .total_cmp(&a.score)
+        .then(a.invalid_rate.total_cmp(&b.invalid_rate))
+        .then(a.name.cmp(b.name))
+}
+
+/// Tournament progress, persisted after every pairing so a long tournament
+/// can be resumed where it stopped
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Standings {
+    pub completed: Vec<Pairing>,
+    pub records: BTreeMap<String, Record>,
+}
+
+impl Standings {
+    /// Load standings from `path`, or start empty if the file doesn't exist yet
+    pub fn load_or_new<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&contents).map_err(|e| format!("Invalid standings in {}: {}", path.display(), e))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        let path = path.as_ref();
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize standings: {}", e))?;
+        fs::write(path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    pub fn is_completed(&self, pairing: &Pairing) -> bool {
+        self.completed.contains(pairing)
+    }
+
+    /// Count the outcome of `pairing` towards both agents' records
+    pub fn record(&mut self, pairing: &Pairing, result: &TestResult) {
+        // A rematch may have swapped the seats; the game says who sat where
+        let [one, two] = match result.stats().seats.as_slice() {
+            [first, second] if *first == pairing.agents[1] && *second == pairing.agents[0] => [first, second],
+            _ => [&pairing.agents[0], &pairing.agents[1]],
+        };
+        let (a, b) = match game_points(result) {
+            [x, y] if x > y => (Outcome::Win, Outcome::Loss),
+            [x, y] if y > x => (Outcome::Loss, Outcome::Win),
+            [x, _] if x > 0.0 => (Outcome::Draw, Outcome::Draw),
+            _ => (Outcome::NoResult, Outcome::NoResult),
+        };
+        a.tally(self.records.entry(one.clone()).or_default());
+        b.tally(self.records.entry(two.clone()).or_default());
+        for turn in &result.stats().turns {
+            if let Some(record) = self.records.get_mut(&turn.player) {
+                record.turns += 1;
+                record.invalid_moves += u32::from(!turn.move_valid);
+            }
+        }
+        self.completed.push(pairing.clone());
+    }
+
+    /// Agents in leaderboard order, see `rank_cmp`
+    pub fn leaderboard(&self) -> Vec<(&str, &Record)> {
+        let mut rows: Vec<(&str, &Record)> = self.records.iter().map(|(name, r)| (name.as_str(), r)).collect();
+        rows.sort_by(|(a, ra), (b, rb)| {
+            rank_cmp(
+                &RankKey { name: a, score: ra.points(), invalid_rate: ra.invalid_rate() },
+                &RankKey { name: b, score: rb.points(), invalid_rate: rb.invalid_rate() },
+            )
+        });
+        rows
+    }
+
+    pub fn leaderboard_table(&self) -> Table {
+        #[derive(Tabled)]
+        struct StandingRow {
+            #[tabled(rename = "#")]
+            rank: usize,
+            #[tabled(rename = "Agent")]
+            name: String,
+            #[tabled(rename = "Points")]
+            points: String,
+            #[tabled(rename = "W-L-D")]
+            record: String,
+            #[tabled(rename = "Invalid %")]
+            invalid: String,
+        }
+
+        let rows = self.leaderboard().into_iter().enumerate().map(|(i, (name, r))| StandingRow {
+            rank: i + 1,
+            name: name.to_string(),
+            points: format!("{:.1}", r.points()),
+            record: format!("{}-{}-{}", r.wins, r.losses, r.draws),
+            invalid: format!("{:.1}", r.invalid_rate() * 100.0),
+        });
+        let mut table = Table::new(rows);
+        table
+            .with(Style::rounded())
+            .with(Modify::new(Rows::new(1..)).with(Alignment::left()));
+        table
+    }
+}
+
+enum Outcome {
+    Win,
+    Loss,
+    Draw,
+    NoResult,
+}
+
+impl Outcome {
+    fn tally(self, record: &mut Record) {
+        match self {
+            Outcome::Win => record.wins += 1,
+            Outcome::Loss => record.losses += 1,
+            Outcome::Draw => record.draws += 1,
+            Outcome::NoResult => record.no_results += 1,
+        }
+    }
+}
+
+/// Play every pairing in `schedule` that `standings` hasn't recorded yet,
+/// saving to `checkpoint` (if given) after each one. Returns how many
+/// pairings were played.
+pub async fn play_remaining(
+    standings: &mut Standings,
+    schedule: &[Pairing],
+    checkpoint: Option<&Path>,
+    play: impl AsyncFn(&Pairing) -> TestResult,
+) -> Result<usize, String> {
+    let remaining: Vec<&Pairing> = schedule.iter().filter(|p| !standings.is_completed(p)).collect();
+    if remaining.len() < schedule.len() {
+        tracing::info!(
+            "Resuming tournament: {} of {} pairings already played",
+            schedule.len() - remaining.len(),
+            schedule.len()
+        );
+    }
+
+    for pairing in &remaining {
+        let result = play(pairing).await;
+        standings.record(pairing, &result);
+        if let Some(path) = checkpoint {
+            standings.save(path)?;
+        }
+    }
+    Ok(remaining.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::{GameStats, TicTacToeResult};
+    use std::cell::RefCell;
+
+    fn pairing(one: &str, two: &str) -> Pairing {
+        Pairing {
+            agents: [one.to_string(), two.to_string()],
+            repetition: 0,
+        }
+    }
+
+    fn x_wins() -> TestResult {
+        TestResult::TicTacToe(TicTacToeResult {
+            winner: Some("seat one (X)".to_string()),
+            stats: GameStats::new(),
+            error: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_resume_plays_only_remaining_pairings() {
+        let path = std::env::temp_dir().join(format!("ai_arena_standings_{}.json", uuid::Uuid::new_v4()));
+        let schedule = vec![pairing("a", "b"), pairing("b", "a"), pairing("a", "c"), pairing("c", "a")];
+
+        // First run is interrupted after two pairings
+        let mut standings = Standings::load_or_new(&path).unwrap();
+        play_remaining(&mut standings, &schedule[..2], Some(&path), async |_: &Pairing| x_wins())
+            .await
+            .unwrap();
+
+        let mut resumed = Standings::load_or_new(&path).unwrap();
+        assert_eq!(resumed, standings);
+
+        let played = RefCell::new(Vec::new());
+        let count = play_remaining(&mut resumed, &schedule, Some(&path), async |p: &Pairing| {
+            played.borrow_mut().push(p.clone());
+            x_wins()
+        })
+        .await
+        .unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(count, 2);
+        assert_eq!(*played.borrow(), schedule[2..].to_vec());
+        assert_eq!(resumed.completed, schedule);
+        let a = resumed.records["a"];
+        assert_eq!((a.wins, a.losses), (2, 2));
+        assert_eq!(resumed.records["c"].wins, 1);
+    }
+
+    #[test]
+    fn test_record_draw_and_error() {
+        let mut standings = Standings::default();
+        let draw = TestResult::TicTacToe(TicTacToeResult {
+            winner: None,
+            stats: GameStats { draw: true, ..GameStats::new() },
+            error: None,
+        });
+        let errored = TestResult::TicTacToe(TicTacToeResult {
+            winner: None,
+            stats: GameStats::new(),
+            error: Some("boom".to_string()),
+        });
+
+        standings.record(&pairing("a", "b"), &draw);
+        standings.record(&pairing("b", "a"), &errored);
+
+        let expected = Record { wins: 0, losses: 0, draws: 1, no_results: 1, ..Record::default() };
+        assert_eq!(standings.records["a"], expected);
+        assert_eq!(standings.records["b"], expected);
+    }
+
+    fn record(wins: u32, draws: u32, turns: u32, invalid_moves: u32) -> Record {
+        Record { wins, draws, turns, invalid_moves, ..Record::default() }
+    }
+
+    #[test]
+    fn test_leaderboard_tie_break() {
+        let mut standings = Standings::default();
+        // "zed" and "amy" tie on points and invalid rate; "bob" ties on
+        // points but is less reliable; "cat" scores more with one draw
+        standings.records.insert("zed".to_string(), record(1, 0, 10, 1));
+        standings.records.insert("bob".to_string(), record(1, 0, 10, 3));
+        standings.records.insert("cat".to_string(), record(1, 1, 10, 5));
+        standings.records.insert("amy".to_string(), record(0, 2, 20, 2));
+
+        let order: Vec<&str> = standings.leaderboard().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(order, ["cat", "amy", "zed", "bob"]);
+
+        let printed = standings.leaderboard_table().to_string();
+        for _ in 0..5 {
+            assert_eq!(standings.clone().leaderboard_table().to_string(), printed);
+        }
+    }
+
+    #[test]
+    fn test_record_counts_invalid_moves() {
+        use crate::games::stats::TurnStats;
+        use serde_json::json;
+
+        let turn = |player: &str, move_valid| TurnStats {
+            player: player.to_string(),
+            move_made: json!({}),
+            move_valid,
+            state_before: json!({}),
+            state_after: json!({}),
+            ..Default::default()
+        };
+        let mut stats = GameStats::new();
+        stats.turns = vec![turn("a", true), turn("b", false), turn("a", true)];
+        let result = TestResult::TicTacToe(TicTacToeResult {
+            winner: Some("a (X)".to_string()),
+            stats,
+            error: None,
+        });
+
+        let mut standings = Standings::default();
+        standings.record(&pairing("a", "b"), &result);
+        assert_eq!((standings.records["a"].turns, standings.records["a"].invalid_moves), (2, 0));
+        assert_eq!(standings.records["b"].invalid_rate(), 1.0);
+    }
+}
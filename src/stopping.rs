@@ -0,0 +1,89 @@
+/// z-score for a 95% confidence interval
+pub const Z_95: f64 = 1.96;
+
+/// Confidence interval for a win rate
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WilsonInterval {
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl WilsonInterval {
+    pub fn width(&self) -> f64 {
+        self.upper - self.lower
+    }
+}
+
+/// Wilson score interval for `successes` out of `trials`. Successes may be
+/// fractional so a draw can count as half a win.
+pub fn wilson_interval(successes: f64, trials: u32, z: f64) -> WilsonInterval {
+    if trials == 0 {
+        return WilsonInterval { lower: 0.0, upper: 1.0 };
+    }
+    let n = trials as f64;
+    let p = successes / n;
+    let z2 = z * z;
+    let denom = 1.0 + z2 / n;
+    let centre = (p + z2 / (2.0 * n)) / denom;
+    let margin = z * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / denom;
+    WilsonInterval {
+        lower: (centre - margin).max(0.0),
+        upper: (centre + margin).min(1.0),
+    }
+}
+
+/// Keep repeating a matchup until the 95% interval on the first agent's win
+/// rate is at most `max_width` wide, or `max_games` have been played
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AdaptiveStop {
+    pub max_width: f64,
+    pub max_games: u32,
+}
+
+impl AdaptiveStop {
+    /// `points` is the first agent's score so far (1 per win, 0.5 per draw);
+    /// at least `min_games` are always played
+    pub fn should_stop(&self, points: f64, games: u32, min_games: u32) -> bool {
+        if games >= self.max_games {
+            return true;
+        }
+        games >= min_games.max(1) && wilson_interval(points, games, Z_95).width() <= self.max_width
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wilson_interval_known_value() {
+        // 50 wins out of 100 at 95%: roughly [0.404, 0.596]
+        let ci = wilson_interval(50.0, 100, Z_95);
+        assert!((ci.lower - 0.4038).abs() < 1e-3);
+        assert!((ci.upper - 0.5962).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_stops_once_interval_is_narrow_enough() {
+        let stop = AdaptiveStop { max_width: 0.2, max_games: 100 };
+
+        // An agent that wins every game: width is z^2 / (n + z^2), which first
+        // drops to 0.2 at n = 16
+        let games_played = (1..=100).find(|&n| stop.should_stop(n as f64, n, 1)).unwrap();
+        assert_eq!(games_played, 16);
+    }
+
+    #[test]
+    fn test_close_matchups_run_to_the_cap() {
+        let stop = AdaptiveStop { max_width: 0.1, max_games: 40 };
+        let games_played = (1..=100).find(|&n| stop.should_stop(n as f64 / 2.0, n, 1)).unwrap();
+        assert_eq!(games_played, 40);
+    }
+
+    #[test]
+    fn test_respects_min_games() {
+        let stop = AdaptiveStop { max_width: 0.99, max_games: 100 };
+        assert!(!stop.should_stop(3.0, 3, 5));
+        assert!(stop.should_stop(5.0, 5, 5));
+    }
+}
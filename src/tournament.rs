@@ -0,0 +1,236 @@
+use std::path::Path;
+
+use clap::ValueEnum;
+
+use crate::agent_config::{AIAgentConfig, AgentKind};
+use crate::csv_runner::{Rematched, play_until_decisive};
+use crate::games::{Game, TestResult};
+use crate::standings::{Pairing, Standings, play_remaining};
+
+/// Name a model is listed under in the standings, e.g. `gpt-4o (OpenAI)`
+pub fn entrant_label(config: &AIAgentConfig) -> String {
+    format!("{} ({:?})", config.model, config.agent)
+}
+
+/// Parse an entrant written as `<kind>:<model>`, e.g. `openai:gpt-4o-mini`
+pub fn parse_entrant(s: &str) -> Result<AIAgentConfig, String> {
+    let (kind, model) = s
+        .split_once(':')
+        .ok_or_else(|| format!("Entrant '{}' must look like <kind>:<model>", s))?;
+    // Accept the CLI spelling (`open-ai`) as well as the plain name (`openai`)
+    let agent = AgentKind::value_variants()
+        .iter()
+        .copied()
+        .find(|k| format!("{:?}", k).eq_ignore_ascii_case(kind) || AgentKind::from_str(kind, true) == Ok(*k))
+        .ok_or_else(|| format!("Unknown agent kind '{}' in '{}'", kind, s))?;
+    Ok(AIAgentConfig {
+        model: model.to_string(),
+        temp: 0.0,
+        agent,
+        ..Default::default()
+    })
+}
+
+/// Credit each turn of `result` to the entrant rather than its per-game
+/// agent name, and list the entrants as the game's seats. `seated` are the
+/// configs the game was played with and `entrants` their labels, in seat order.
+pub fn credit_entrants(result: &mut TestResult, seated: &[AIAgentConfig], entrants: &[String]) {
+    let stats = result.stats_mut();
+    for turn in &mut stats.turns {
+        if let Some(seat) = (0..seated.len()).find(|&seat| seated[seat].agent_name(seat) == turn.player) {
+            turn.player = entrants[seat].clone();
+        }
+    }
+    stats.seats = entrants.to_vec();
+}
+
+/// Every ordered pair of distinct entrants, `repetitions` times each, so
+/// every entrant moves first against every other
+pub fn round_robin_schedule(names: &[String], repetitions: u32) -> Vec<Pairing> {
+    let mut schedule = Vec::new();
+    for one in names {
+        for two in names.iter().filter(|two| *two != one) {
+            for repetition in 0..repetitions {
+                schedule.push(Pairing {
+                    agents: [one.clone(), two.clone()],
+                    repetition,
+                });
+            }
+        }
+    }
+    schedule
+}
+
+/// Play every ordered pair of `models` at `game`, `repetitions` games per
+/// pairing, using `play` for each game with the agents in seat order. A
+/// drawn game is replayed with seats swapped, up to `max_rematches` times.
+/// Progress is saved to `checkpoint` (if given) so an interrupted
+/// tournament resumes where it stopped.
+pub async fn run_round_robin(
+    models: Vec<AIAgentConfig>,
+    game: &Game,
+    repetitions: u32,
+    max_rematches: u32,
+    checkpoint: Option<&Path>,
+    play: impl AsyncFn(&Game, Vec<AIAgentConfig>) -> TestResult,
+) -> Result<Standings, String> {
+    if models.len() < 2 {
+        return Err(format!("A round robin needs at least 2 models, got {}", models.len()));
+    }
+    let labels: Vec<String> = models.iter().map(entrant_label).collect();
+    if let Some(dup) = labels.iter().enumerate().find_map(|(i, l)| labels[..i].contains(l).then_some(l)) {
+        return Err(format!("{} is entered more than once", dup));
+    }
+
+    let schedule = round_robin_schedule(&labels, repetitions);
+    let mut standings = match checkpoint {
+        Some(path) => Standings::load_or_new(path)?,
+        None => Standings::default(),
+    };
+    play_remaining(&mut standings, &schedule, checkpoint, async |pairing: &Pairing| {
+        let mut seated: Vec<AIAgentConfig> = pairing
+            .agents
+            .iter()
+            .map(|label| models[labels.iter().position(|l| l == label).unwrap()].clone())
+            .collect();
+        println!("\n🏟️  {} vs {}", pairing.agents[0], pairing.agents[1]);
+        let Rematched { mut result, swapped, .. } = play_until_decisive(game, &seated, max_rematches, &play).await;
+        let mut entrants = pairing.agents.clone();
+        if swapped {
+            seated.reverse();
+            entrants.reverse();
+        }
+        credit_entrants(&mut result, &seated, &entrants);
+        result
+    })
+    .await?;
+    Ok(standings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::stats::{GameStats, TurnStats};
+    use crate::games::TicTacToeResult;
+    use serde_json::json;
+    use std::cell::RefCell;
+
+    fn turn(player: &str, move_valid: bool) -> TurnStats {
+        TurnStats {
+            turn_number: 1,
+            player: player.to_string(),
+            move_made: json!({}),
+            move_valid,
+            state_before: json!({}),
+            state_after: json!({}),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_schedule_covers_both_seatings() {
+        let names: Vec<String> = ["a", "b", "c"].map(String::from).to_vec();
+        let schedule = round_robin_schedule(&names, 2);
+        assert_eq!(schedule.len(), 3 * 2 * 2);
+        for (one, two) in [("a", "b"), ("b", "a"), ("c", "a")] {
+            let games = schedule.iter().filter(|p| p.agents == [one.to_string(), two.to_string()]).count();
+            assert_eq!(games, 2);
+        }
+        assert!(schedule.iter().all(|p| p.agents[0] != p.agents[1]));
+    }
+
+    #[test]
+    fn test_parse_entrant() {
+        let config = parse_entrant("openai:gpt-4o-mini").unwrap();
+        assert_eq!(config.agent, AgentKind::OpenAI);
+        assert_eq!(config.model, "gpt-4o-mini");
+        assert_eq!(parse_entrant("open-ai:gpt-4o").unwrap().agent, AgentKind::OpenAI);
+        assert!(parse_entrant("gpt-4o").is_err());
+        assert!(parse_entrant("mystery:gpt-4o").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_round_robin_standings() {
+        let models = ["openai:strong", "ollama:weak", "openai:middling"]
+            .map(|s| parse_entrant(s).unwrap())
+            .to_vec();
+        let rank = |model: &str| ["weak", "middling", "strong"].iter().position(|m| *m == model).unwrap();
+        let games = RefCell::new(0);
+
+        // The higher-ranked model always wins; seat two makes one invalid move
+        let standings = run_round_robin(models, &Game::new("TicTacToe").unwrap(), 1, 0, None, async |_: &Game, seated| {
+            *games.borrow_mut() += 1;
+            let mut stats = GameStats::new();
+            stats.add_turn(turn(&seated[0].agent_name(0), true));
+            stats.add_turn(turn(&seated[1].agent_name(1), false));
+            let seat = if rank(&seated[0].model) > rank(&seated[1].model) { "X" } else { "O" };
+            TestResult::TicTacToe(TicTacToeResult {
+                winner: Some(format!("someone ({})", seat)),
+                stats,
+                error: None,
+            })
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(*games.borrow(), 6);
+        let order: Vec<(&str, u32)> = standings.leaderboard().into_iter().map(|(name, r)| (name, r.wins)).collect();
+        assert_eq!(order, [("strong (OpenAI)", 4), ("middling (OpenAI)", 2), ("weak (Ollama)", 0)]);
+        let weak = standings.records["weak (Ollama)"];
+        assert_eq!((weak.turns, weak.invalid_moves), (4, 2));
+    }
+
+    #[tokio::test]
+    async fn test_drawn_games_are_replayed_until_decisive() {
+        use crate::agent::AIAgent;
+        use crate::agents::scripted::ScriptedAgent;
+
+        let script = |cells: &[(u32, u32)]| cells.iter().map(|(row, col)| json!({"row": row, "col": col})).collect();
+        // Moves for X and O: a full-board draw, then a game each seat can win
+        let draw = [
+            script(&[(0, 0), (0, 2), (1, 0), (1, 1), (2, 1)]),
+            script(&[(0, 1), (1, 2), (2, 0), (2, 2)]),
+        ];
+        let x_wins = [script(&[(0, 0), (0, 1), (0, 2)]), script(&[(1, 0), (1, 1)])];
+        let o_wins = [script(&[(0, 0), (0, 1), (2, 2)]), script(&[(1, 0), (1, 1), (1, 2)])];
+
+        let models = vec![parse_entrant("openai:a").unwrap(), parse_entrant("openai:b").unwrap()];
+        let games = RefCell::new(0);
+        // Every pairing's first game is drawn; in the rematch "a" wins from either seat
+        let standings = run_round_robin(models, &Game::new("TicTacToe").unwrap(), 1, 2, None, async |game: &Game, seated| {
+            *games.borrow_mut() += 1;
+            let [x, o] = match *games.borrow() % 2 {
+                1 => draw.clone(),
+                _ if seated[0].model == "a" => x_wins.clone(),
+                _ => o_wins.clone(),
+            };
+            let agents = vec![
+                AIAgent::Scripted(ScriptedAgent::new(seated[0].agent_name(0), x)),
+                AIAgent::Scripted(ScriptedAgent::new(seated[1].agent_name(1), o)),
+            ];
+            game.play_with_agents(agents).await
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(*games.borrow(), 4);
+        let a = standings.records["a (OpenAI)"];
+        let b = standings.records["b (OpenAI)"];
+        assert_eq!((a.wins, a.draws, b.wins, b.draws), (2, 0, 0, 0));
+        // Only the deciding games' turns are counted
+        assert_eq!(a.turns + b.turns, 5 + 6);
+    }
+
+    #[tokio::test]
+    async fn test_round_robin_rejects_duplicates_and_singletons() {
+        let play = async |_: &Game, _| unreachable!();
+        let game = Game::new("TicTacToe").unwrap();
+
+        let one = vec![parse_entrant("openai:a").unwrap()];
+        assert!(run_round_robin(one, &game, 1, 0, None, play).await.is_err());
+
+        let twice = vec![parse_entrant("openai:a").unwrap(), parse_entrant("OpenAI:a").unwrap()];
+        let err = run_round_robin(twice, &game, 1, 0, None, play).await.unwrap_err();
+        assert_eq!(err, "a (OpenAI) is entered more than once");
+    }
+}